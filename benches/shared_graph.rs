@@ -0,0 +1,127 @@
+// Writer latency under concurrent readers, snapshot publishing versus a plain RwLock.
+//
+//     cargo bench --bench shared_graph
+//
+// One writer applies the full ticker payload from tick_data.txt over and over, as the engine
+// would at full `!ticker@arr` load, making the result visible once per batch. Reader threads
+// meanwhile run what execution and validation code does: a full detection pass over the
+// graph plus rate lookups. With the RwLock every pass holds the lock, so the writer waits
+// behind it; with SharedGraph the writer only ever swaps a pointer.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use hft3::graph::Graph;
+use hft3::recorder::load_session;
+use hft3::shared_graph::SharedGraph;
+use hft3::ticker::{apply_ticker_data, TickerData};
+
+const BATCHES: usize = 2_000;
+const MAX_READERS: usize = 4;
+
+// One reader per spare core, so the writer measures lock waits rather than preemption
+fn reader_count() -> usize {
+    let cores = thread::available_parallelism().map_or(1, |n| n.get());
+    if cores < 2 {
+        println!("warning: only one core, writer latencies include time preempted by readers");
+    }
+    cores.saturating_sub(1).clamp(1, MAX_READERS)
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p) as usize]
+}
+
+fn report(name: &str, mut samples: Vec<Duration>, reads: u64) {
+    samples.sort_unstable();
+    println!(
+        "{:<12} writer p50 {:>9.1?}  p99 {:>9.1?}  max {:>9.1?}  ({} reader passes)",
+        name,
+        percentile(&samples, 0.5),
+        percentile(&samples, 0.99),
+        samples[samples.len() - 1],
+        reads
+    );
+}
+
+// Spawns readers running `read` until the writer finishes; returns the number of passes
+fn with_readers(
+    readers: usize,
+    read: impl Fn() + Send + Sync + 'static,
+    write: impl FnOnce() -> Vec<Duration>,
+) -> (Vec<Duration>, u64) {
+    let read = Arc::new(read);
+    let done = Arc::new(AtomicBool::new(false));
+    let readers: Vec<_> = (0..readers)
+        .map(|_| {
+            let (read, done) = (read.clone(), done.clone());
+            thread::spawn(move || {
+                let mut passes = 0;
+                while !done.load(Ordering::Relaxed) {
+                    read();
+                    passes += 1;
+                }
+                passes
+            })
+        })
+        .collect();
+    let samples = write();
+    done.store(true, Ordering::Relaxed);
+    let passes = readers.into_iter().map(|r| r.join().unwrap()).sum();
+    (samples, passes)
+}
+
+fn reader_pass(graph: &Graph) {
+    let cycles = graph.find_cycles(usize::MAX, 3);
+    let rate = graph.rate("BTC", "USDT");
+    std::hint::black_box((cycles, rate));
+}
+
+fn main() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tick_data.txt");
+    let tickers: Vec<TickerData> = load_session(path.as_ref()).expect("tick_data.txt").remove(0).tickers;
+    let mut seed = Graph::new();
+    apply_ticker_data(&mut seed, &tickers);
+    let readers = reader_count();
+    println!("{} symbols, {} batches, {} readers", tickers.len(), BATCHES, readers);
+
+    // Baseline: readers lock the graph for the whole pass
+    let locked = Arc::new(RwLock::new(seed.clone()));
+    let reader_graph = locked.clone();
+    let (samples, passes) = with_readers(
+        readers,
+        move || reader_pass(&reader_graph.read().unwrap()),
+        || {
+            (0..BATCHES)
+                .map(|_| {
+                    let start = Instant::now();
+                    apply_ticker_data(&mut locked.write().unwrap(), &tickers);
+                    start.elapsed()
+                })
+                .collect()
+        },
+    );
+    report("rwlock", samples, passes);
+
+    // Snapshots: the writer owns its graph and publishes after each batch
+    let shared = Arc::new(SharedGraph::new(seed.clone()));
+    let reader_shared = shared.clone();
+    let mut graph = seed;
+    let (samples, passes) = with_readers(
+        readers,
+        move || reader_pass(&reader_shared.load()),
+        || {
+            (0..BATCHES)
+                .map(|_| {
+                    let start = Instant::now();
+                    apply_ticker_data(&mut graph, &tickers);
+                    shared.publish(&graph);
+                    start.elapsed()
+                })
+                .collect()
+        },
+    );
+    report("shared-graph", samples, passes);
+}
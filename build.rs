@@ -0,0 +1,8 @@
+// Generates the gRPC service from proto/hft3.proto. The proto is parsed in Rust (protox),
+// so building doesn't need protoc installed.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!("cargo:rerun-if-changed=proto/hft3.proto");
+    let descriptors = protox::compile(["proto/hft3.proto"], ["proto"])?;
+    tonic_prost_build::configure().compile_fds(descriptors)?;
+    Ok(())
+}
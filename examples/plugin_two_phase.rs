@@ -0,0 +1,40 @@
+// A strategy plugin for `hft3 --plugin`: runs the two-phase triangle detector in its
+// own process. Build with `cargo build --release --example plugin_two_phase`; rebuilding
+// while hft3 runs swaps it in without restarting the feed.
+
+use std::io::{self, BufRead, Write};
+
+use hft3::prelude::*;
+use hft3::TickerData;
+use serde_json::json;
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum HostMessage {
+    Tickers { tickers: Vec<TickerData> },
+    SymbolRemoved { symbol: String },
+}
+
+fn main() {
+    let mut strategy = TwoPhaseStrategy::default();
+    let graph = Graph::new(); // The detector keeps its own price cache
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    writeln!(out, "{}", json!({ "type": "log", "message": "two-phase plugin ready" })).unwrap();
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        let event = match serde_json::from_str(&line) {
+            Ok(HostMessage::Tickers { tickers }) => MarketEvent::Tickers(tickers),
+            Ok(HostMessage::SymbolRemoved { symbol }) => MarketEvent::SymbolRemoved(symbol),
+            Err(_) => continue,
+        };
+        for opportunity in strategy.on_event(&graph, &event) {
+            let message = json!({ "type": "opportunity", "path": opportunity.path, "profit": opportunity.profit });
+            if writeln!(out, "{}", message).is_err() {
+                return;
+            }
+        }
+        let _ = out.flush();
+    }
+}
@@ -0,0 +1,105 @@
+// A detection-only signal service built on the hft3 library: streams Binance tickers,
+// runs the negative-cycle detector and POSTs every opportunity to a webhook.
+//
+//     WEBHOOK_SECRET=... cargo run --example signal_webhook -- https://example.com/hook
+//
+// Each request carries `X-Signal-Timestamp` (unix ms) and `X-Signal-Signature`, the hex
+// HMAC-SHA256 of "<timestamp>.<body>" under WEBHOOK_SECRET, so the receiver can check
+// both origin and freshness. Failed deliveries are retried with exponential backoff.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use hft3::prelude::*;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tokio::sync::broadcast::error::RecvError;
+
+const MAX_ATTEMPTS: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(250);
+
+struct Signer {
+    http: reqwest::Client,
+    url: String,
+    secret: Vec<u8>,
+}
+
+impl Signer {
+    fn sign(&self, timestamp: u128, body: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC accepts any key length");
+        mac.update(format!("{}.{}", timestamp, body).as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    // Retries network errors and 5xx/429 responses; other 4xx mean the receiver rejected it
+    async fn deliver(&self, body: String) {
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=MAX_ATTEMPTS {
+            // Re-signed per attempt so the timestamp stays fresh
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+            let result = self
+                .http
+                .post(&self.url)
+                .header("Content-Type", "application/json")
+                .header("X-Signal-Timestamp", timestamp.to_string())
+                .header("X-Signal-Signature", self.sign(timestamp, &body))
+                .body(body.clone())
+                .send()
+                .await;
+            let retryable = match result {
+                Ok(response) if response.status().is_success() => return,
+                Ok(response) => {
+                    let status = response.status();
+                    eprintln!("webhook attempt {} returned {}", attempt, status);
+                    status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                }
+                Err(e) => {
+                    eprintln!("webhook attempt {} failed: {}", attempt, e);
+                    true
+                }
+            };
+            if !retryable || attempt == MAX_ATTEMPTS {
+                break;
+            }
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+        }
+        eprintln!("dropping signal after {} attempts", MAX_ATTEMPTS);
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let Some(url) = std::env::args().nth(1) else {
+        eprintln!("Usage: WEBHOOK_SECRET=<secret> signal_webhook <url>");
+        std::process::exit(2);
+    };
+    let secret = std::env::var("WEBHOOK_SECRET").unwrap_or_else(|_| {
+        eprintln!("WEBHOOK_SECRET must be set");
+        std::process::exit(2);
+    });
+    let signer = Signer {
+        http: reqwest::Client::builder().timeout(Duration::from_secs(5)).build().expect("Failed to build HTTP client"),
+        url,
+        secret: secret.into_bytes(),
+    };
+
+    let feed = BinanceFeed::connect(&["!ticker@arr".to_string()]).await.expect("Failed to connect");
+    let mut engine = Engine::new(feed).with_strategy(NegativeCycleStrategy::new());
+    let mut events = engine.subscribe();
+
+    // Deliveries run on their own task so a slow receiver never stalls detection
+    let delivery = tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(EngineEvent::Opportunity(opportunity)) => signer.deliver(opportunity.to_json().to_string()).await,
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => eprintln!("skipped {} signals while delivering", skipped),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+
+    engine.run().await;
+    drop(engine);
+    let _ = delivery.await;
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeSet;
+use std::fmt::Write;
+
+use rust_decimal::Decimal;
+
+use crate::ledger::Fill;
+use crate::order::Side;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountingFormat {
+    Beancount,
+    Ledger, // ledger-cli / hledger
+}
+
+impl AccountingFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "beancount" => Some(AccountingFormat::Beancount),
+            "ledger" | "hledger" => Some(AccountingFormat::Ledger),
+            _ => None,
+        }
+    }
+}
+
+// Holdings live under Assets:Exchanges:<Venue>:<ASSET>, fees under Expenses:Trading:Fees:<Venue>
+fn asset_account(venue: &str, asset: &str) -> String {
+    format!("Assets:Exchanges:{}:{}", account_component(venue), account_component(asset))
+}
+
+fn fee_account(venue: &str) -> String {
+    format!("Expenses:Trading:Fees:{}", account_component(venue))
+}
+
+// Account components must start with a capital letter and contain only letters, digits and dashes
+fn account_component(name: &str) -> String {
+    let cleaned: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect();
+    let mut chars = cleaned.chars();
+    match chars.next() {
+        Some(first) if first.is_ascii_alphabetic() => first.to_ascii_uppercase().to_string() + chars.as_str(),
+        _ => format!("X{}", cleaned),
+    }
+}
+
+// Beancount commodities must start with a letter; ledger quotes anything that isn't plain letters
+fn commodity(asset: &str, format: AccountingFormat) -> String {
+    match format {
+        AccountingFormat::Beancount if !asset.starts_with(|c: char| c.is_ascii_alphabetic()) => format!("X{}", asset),
+        AccountingFormat::Ledger if !asset.chars().all(|c| c.is_ascii_alphabetic()) => format!("\"{}\"", asset),
+        _ => asset.to_string(),
+    }
+}
+
+// Exact amount without trailing zeros
+fn number(value: Decimal) -> String {
+    let value = value.normalize();
+    if value.is_zero() { "0".to_string() } else { value.to_string() }
+}
+
+// Converts fills into plain-text accounting entries, one transaction per fill. The quote
+// posting is left for the tool to balance so rounding never makes a transaction unbalanced.
+pub fn export(fills: &[Fill], format: AccountingFormat) -> String {
+    let mut out = String::new();
+    let mut fills: Vec<&Fill> = fills.iter().collect();
+    fills.sort_by_key(|fill| fill.time);
+
+    if format == AccountingFormat::Beancount {
+        // Every account needs an open directive dated on or before its first posting
+        let mut opened = BTreeSet::new();
+        for fill in &fills {
+            let date = fill.time.format("%Y-%m-%d");
+            let mut accounts = vec![asset_account(&fill.venue, &fill.base), asset_account(&fill.venue, &fill.quote)];
+            if !fill.fee.is_zero() {
+                accounts.push(asset_account(&fill.venue, fill.fee_asset_or_quote()));
+                accounts.push(fee_account(&fill.venue));
+            }
+            for account in accounts {
+                if opened.insert(account.clone()) {
+                    let _ = writeln!(out, "{} open {}", date, account);
+                }
+            }
+        }
+        out.push('\n');
+    }
+
+    for fill in fills {
+        let base = commodity(&fill.base, format);
+        let quote = commodity(&fill.quote, format);
+        let quantity = match fill.side {
+            Side::Buy => fill.quantity,
+            Side::Sell => -fill.quantity,
+        };
+        let description = format!("{} {} {} @ {}", fill.side.as_str(), number(fill.quantity), fill.symbol, number(fill.price));
+        let _ = match format {
+            AccountingFormat::Beancount => writeln!(
+                out,
+                "{} * \"{}\" \"{}\"",
+                fill.time.format("%Y-%m-%d"),
+                fill.venue,
+                description
+            ),
+            AccountingFormat::Ledger => writeln!(out, "{} {} {}", fill.time.format("%Y/%m/%d"), fill.venue, description),
+        };
+        if let Some(order_id) = &fill.order_id {
+            let _ = match format {
+                AccountingFormat::Beancount => writeln!(out, "  order_id: \"{}\"", order_id),
+                AccountingFormat::Ledger => writeln!(out, "  ; order_id: {}", order_id),
+            };
+        }
+        let _ = writeln!(
+            out,
+            "  {}  {} {} @ {} {}",
+            asset_account(&fill.venue, &fill.base),
+            number(quantity),
+            base,
+            number(fill.price),
+            quote
+        );
+        if !fill.fee.is_zero() {
+            let fee_asset = fill.fee_asset_or_quote();
+            let fee_commodity = commodity(fee_asset, format);
+            let _ = writeln!(out, "  {}  {} {}", fee_account(&fill.venue), number(fill.fee), fee_commodity);
+            if fee_asset != fill.quote {
+                let _ = writeln!(out, "  {}  {} {}", asset_account(&fill.venue, fee_asset), number(-fill.fee), fee_commodity);
+            }
+        }
+        let _ = writeln!(out, "  {}", asset_account(&fill.venue, &fill.quote));
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,143 @@
+use std::sync::{Arc, RwLock};
+
+use futures_util::future::{join_all, BoxFuture};
+use rust_decimal::Decimal;
+
+use crate::credentials::CredentialSource;
+use crate::events::{Opportunity, Signal};
+use crate::executor::Executor;
+use crate::user_stream::AccountState;
+
+// Which account gets a cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccountPolicy {
+    #[default]
+    RoundRobin, // Each in turn, so order rate limits are spent evenly
+    MostFree, // The one with the most of the cycle's starting asset free, so capital is drawn down evenly
+}
+
+impl AccountPolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "round-robin" => Some(AccountPolicy::RoundRobin),
+            "most-free" => Some(AccountPolicy::MostFree),
+            _ => None,
+        }
+    }
+}
+
+// An execution account and where its API keys come from
+#[derive(Debug, Clone)]
+pub struct AccountConfig {
+    pub name: String,                  // Shown in logs
+    pub credentials: CredentialSource, // Same forms as --credentials
+}
+
+impl AccountConfig {
+    // `<name>=<source>`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (name, source) = value.split_once('=')?;
+        let credentials = CredentialSource::parse(source)?;
+        (!name.is_empty()).then(|| AccountConfig { name: name.to_string(), credentials })
+    }
+}
+
+struct Account {
+    name: String,
+    executor: Box<dyn Executor>,
+    balances: Option<Arc<RwLock<AccountState>>>, // Live from the account's user data stream, when it has one
+}
+
+impl Account {
+    // Free balance of `asset`; None when the account's balances aren't tracked
+    fn free(&self, asset: &str) -> Option<Decimal> {
+        let state = self.balances.as_ref()?.read().unwrap();
+        Some(if state.connected { state.free(asset) } else { Decimal::ZERO })
+    }
+}
+
+// Spreads execution over several accounts (sub-accounts, separate keys or other venues'
+// executors), each with its own executor and so its own order rate limits. Every cycle
+// goes to one account chosen by `policy` among those that hold its starting asset;
+// accounts whose balances aren't tracked are always eligible, and one whose balance
+// stream is down never is. Signals go round robin.
+pub struct MultiAccountExecutor {
+    accounts: Vec<Account>,
+    policy: AccountPolicy,
+    next: usize, // Round robin position
+}
+
+impl MultiAccountExecutor {
+    pub fn new(policy: AccountPolicy) -> Self {
+        MultiAccountExecutor {
+            accounts: Vec::new(),
+            policy,
+            next: 0,
+        }
+    }
+
+    // Adds an account executing through `executor`, with its live balances if tracked
+    pub fn with_account(
+        mut self,
+        name: impl Into<String>,
+        executor: impl Executor + 'static,
+        balances: Option<Arc<RwLock<AccountState>>>,
+    ) -> Self {
+        self.accounts.push(Account { name: name.into(), executor: Box::new(executor), balances });
+        self
+    }
+
+    // Index of the account to run a cycle starting from `asset`, None if none can
+    fn choose(&mut self, asset: &str) -> Option<usize> {
+        let count = self.accounts.len();
+        let mut rotation = (0..count).map(|i| (self.next + i) % count);
+        let chosen = match self.policy {
+            AccountPolicy::RoundRobin => rotation
+                .find(|&i| self.accounts[i].free(asset).is_none_or(|free| free > Decimal::ZERO)),
+            // Untracked accounts rank below any that shows a balance; ties go in turn
+            AccountPolicy::MostFree => rotation
+                .filter_map(|i| match self.accounts[i].free(asset) {
+                    Some(free) if free > Decimal::ZERO => Some((i, Some(free))),
+                    Some(_) => None,
+                    None => Some((i, None)),
+                })
+                .reduce(|best, candidate| if candidate.1 > best.1 { candidate } else { best })
+                .map(|(i, _)| i),
+        }?;
+        self.next = (chosen + 1) % count;
+        Some(chosen)
+    }
+}
+
+impl Executor for MultiAccountExecutor {
+    fn execute(&mut self, opportunity: &Opportunity) {
+        let Some(start) = opportunity.path.first() else {
+            return;
+        };
+        match self.choose(start) {
+            Some(i) => {
+                let account = &mut self.accounts[i];
+                tracing::debug!(account = %account.name, cycle = %opportunity.cycle_key(), "Cycle routed to account");
+                account.executor.execute(opportunity);
+            }
+            None => tracing::info!(asset = %start, cycle = %opportunity.cycle_key(), "No account has the asset free, cycle skipped"),
+        }
+    }
+
+    fn submit(&mut self, signal: &Signal) {
+        if self.accounts.is_empty() {
+            return;
+        }
+        let i = self.next % self.accounts.len();
+        self.next = (i + 1) % self.accounts.len();
+        self.accounts[i].executor.submit(signal);
+    }
+
+    // Every account's executor unwinds at once
+    fn shutdown(&mut self) -> BoxFuture<'static, ()> {
+        let shutdowns: Vec<_> = self.accounts.iter_mut().map(|account| account.executor.shutdown()).collect();
+        Box::pin(async move {
+            join_all(shutdowns).await;
+        })
+    }
+}
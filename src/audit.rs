@@ -0,0 +1,115 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::SystemTime;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use crate::events::MarketEvent;
+
+// How much of an existing log is read back to find where its sequence numbers left off
+const TAIL_BYTES: u64 = 64 * 1024;
+
+struct Record {
+    kind: &'static str,
+    at: SystemTime,
+    details: Value,
+}
+
+// Append-only audit trail of what the engine saw, decided and did, one JSON object per
+// line, each with a `seq` number, a `ts` timestamp and its `kind`. Sequence numbers are
+// assigned by the writer in the order records were made and carry on from the last one
+// already in the file, so a gap or a step backwards in a log means lost or altered
+// lines. Records are written on a thread of their own and flushed whenever it runs out
+// of work, so recording never waits on the disk. Clones share the same log and numbering.
+#[derive(Clone)]
+pub struct AuditLog {
+    records: mpsc::Sender<Record>,
+}
+
+impl AuditLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = OpenOptions::new().create(true).read(true).append(true).open(path)?;
+        let (last, complete) = last_seq(&mut file)?;
+        if !complete {
+            // Start on a line of our own rather than finishing a torn one
+            file.write_all(b"\n")?;
+        }
+        let next_seq = last.map_or(1, |seq| seq + 1);
+        let (records, receiver) = mpsc::channel();
+        thread::Builder::new()
+            .name("audit-log".to_string())
+            .spawn(move || write_records(BufWriter::new(file), receiver, next_seq))?;
+        Ok(AuditLog { records })
+    }
+
+    // Queues a record; `details` should be an object, whose fields are merged into the line
+    pub fn record(&self, kind: &'static str, details: Value) {
+        let _ = self.records.send(Record { kind, at: SystemTime::now(), details });
+    }
+}
+
+fn write_records(mut writer: BufWriter<File>, records: mpsc::Receiver<Record>, mut seq: u64) {
+    while let Ok(first) = records.recv() {
+        let mut next = Some(first);
+        while let Some(record) = next {
+            let at: DateTime<Utc> = record.at.into();
+            let mut line = json!({
+                "seq": seq,
+                "ts": at.to_rfc3339_opts(SecondsFormat::Micros, true),
+                "kind": record.kind,
+            });
+            if let (Some(line), Value::Object(details)) = (line.as_object_mut(), record.details) {
+                line.extend(details);
+            }
+            if let Err(e) = serde_json::to_writer(&mut writer, &line).map_err(io::Error::from).and_then(|_| writer.write_all(b"\n")) {
+                tracing::error!(seq, error = %e, "Failed to write audit record");
+            }
+            seq += 1;
+            next = records.try_recv().ok();
+        }
+        if let Err(e) = writer.flush() {
+            tracing::error!(error = %e, "Failed to flush audit log");
+        }
+    }
+}
+
+// Sequence number of the last complete record in the file, if any, and whether the
+// file ends on a line break
+fn last_seq(file: &mut File) -> io::Result<(Option<u64>, bool)> {
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(TAIL_BYTES)))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    let complete = tail.last().is_none_or(|&byte| byte == b'\n');
+    let tail = String::from_utf8_lossy(&tail);
+    // A crash can leave the last line half written
+    let last = tail
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .find_map(|record| record.get("seq").and_then(Value::as_u64));
+    Ok((last, complete))
+}
+
+// Kind and size of a market event, for "update" records. The prices themselves are left
+// to --record, which keeps the raw messages.
+pub fn market_summary(event: &MarketEvent) -> Value {
+    let (kind, count) = match event {
+        MarketEvent::Tickers(tickers) => ("tickers", tickers.len()),
+        MarketEvent::Quotes(quotes) => ("quotes", quotes.len()),
+        MarketEvent::MarkPrices(marks) => ("mark_prices", marks.len()),
+        MarketEvent::Depth(depths) => ("depth", depths.len()),
+        MarketEvent::SymbolRemoved(symbol) => return json!({ "event": "symbol_removed", "symbol": symbol }),
+        MarketEvent::FeedState { venue, degraded } => {
+            return json!({ "event": "feed_state", "venue": venue, "degraded": degraded });
+        }
+        MarketEvent::FeedStalled { venue, silent_ms } => {
+            return json!({ "event": "feed_stalled", "venue": venue, "silent_ms": silent_ms });
+        }
+    };
+    json!({ "event": kind, "count": count })
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::events::{MarketEvent, Opportunity, Signal};
+use crate::graph::Graph;
+use crate::perp::MarkPrice;
+use crate::strategy::Strategy;
+
+const YEAR: Duration = Duration::from_secs(365 * 24 * 60 * 60);
+
+/// Settings for [`BasisStrategy`].
+#[derive(Debug, Clone)]
+pub struct BasisConfig {
+    /// Report a perpetual once its funding, annualized, reaches this many percent a year
+    /// in either direction.
+    pub min_apr_pct: f64,
+    /// Time between funding payments; USD-M perpetuals mostly settle every eight hours.
+    pub funding_interval: Duration,
+}
+
+impl Default for BasisConfig {
+    fn default() -> Self {
+        BasisConfig {
+            min_apr_pct: 10.0,
+            funding_interval: Duration::from_secs(8 * 60 * 60),
+        }
+    }
+}
+
+/// Cash-and-carry detector across spot and USD-M perpetuals.
+///
+/// Pairs each perpetual's mark price and funding rate (from [`MarketEvent::MarkPrices`])
+/// with the spot price of the same symbol. When the current funding rate, annualized,
+/// reaches `min_apr_pct`, it emits a `perp_basis` [`Signal`] valued at that rate in percent:
+/// positive when longs pay, so buying spot and shorting the perpetual collects funding,
+/// negative for the reverse. The basis between mark and spot goes along in the details,
+/// since it is the cost of entering. A symbol reports again once it has dropped below
+/// the threshold and come back, or when funding changes sides.
+pub struct BasisStrategy {
+    config: BasisConfig,
+    spot: HashMap<String, f64>,       // Symbol -> latest spot mid (or last) price
+    marks: HashMap<String, MarkPrice>, // Symbol -> latest mark price update
+    alerting: HashMap<String, bool>,  // Symbol -> whether longs were paying when it reported
+    signals: Vec<Signal>,
+}
+
+impl BasisStrategy {
+    pub fn new(config: BasisConfig) -> Self {
+        BasisStrategy {
+            config,
+            spot: HashMap::new(),
+            marks: HashMap::new(),
+            alerting: HashMap::new(),
+            signals: Vec::new(),
+        }
+    }
+
+    fn periods_per_year(&self) -> f64 {
+        YEAR.as_secs_f64() / self.config.funding_interval.as_secs_f64().max(1.0)
+    }
+
+    fn evaluate(&mut self, symbol: &str) {
+        let (Some(&spot), Some(mark)) = (self.spot.get(symbol), self.marks.get(symbol)) else {
+            return;
+        };
+        let (Ok(mark_price), Ok(funding_rate)) = (mark.mark_price.parse::<f64>(), mark.funding_rate.parse::<f64>()) else {
+            return;
+        };
+        if spot <= 0.0 || mark_price <= 0.0 {
+            return;
+        }
+        let apr_pct = funding_rate * self.periods_per_year() * 100.0;
+        if apr_pct.abs() < self.config.min_apr_pct {
+            self.alerting.remove(symbol);
+            return;
+        }
+        let longs_pay = funding_rate > 0.0;
+        if self.alerting.insert(symbol.to_string(), longs_pay) == Some(longs_pay) {
+            return;
+        }
+        let basis_bps = (mark_price / spot - 1.0) * 10_000.0;
+        let direction = if longs_pay { "long_spot_short_perp" } else { "short_spot_long_perp" };
+        let signal = Signal::new("perp_basis", vec![symbol.to_string()], apr_pct)
+            .with_detail("direction", direction)
+            .with_detail("funding_rate", funding_rate)
+            .with_detail("basis_bps", basis_bps)
+            .with_detail("spot", spot)
+            .with_detail("mark", mark_price)
+            .with_detail("next_funding_time", mark.next_funding_time);
+        self.signals.push(signal);
+    }
+}
+
+impl Strategy for BasisStrategy {
+    fn name(&self) -> &str {
+        "basis"
+    }
+
+    // Prices come from events; the graph has no futures in it
+    fn on_update(&mut self, _graph: &Graph) -> Vec<Opportunity> {
+        Vec::new()
+    }
+
+    fn on_event(&mut self, _graph: &Graph, event: &MarketEvent) -> Vec<Opportunity> {
+        let mut touched = Vec::new();
+        match event {
+            MarketEvent::Tickers(tickers) => {
+                for ticker in tickers {
+                    let number = |v: &Option<String>| v.as_deref().and_then(|v| v.parse::<f64>().ok());
+                    let price = match (number(&ticker.b), number(&ticker.a)) {
+                        (Some(bid), Some(ask)) if bid > 0.0 && ask > 0.0 => (bid + ask) / 2.0,
+                        _ => match ticker.c.parse() {
+                            Ok(last) => last,
+                            Err(_) => continue,
+                        },
+                    };
+                    self.spot.insert(ticker.s.clone(), price);
+                    if self.marks.contains_key(&ticker.s) {
+                        touched.push(ticker.s.clone());
+                    }
+                }
+            }
+            MarketEvent::MarkPrices(marks) => {
+                for mark in marks {
+                    self.marks.insert(mark.symbol.clone(), mark.clone());
+                    touched.push(mark.symbol.clone());
+                }
+            }
+            MarketEvent::SymbolRemoved(symbol) => {
+                self.spot.remove(symbol);
+                self.alerting.remove(symbol);
+            }
+            _ => {}
+        }
+        for symbol in touched {
+            self.evaluate(&symbol);
+        }
+        Vec::new()
+    }
+
+    fn take_signals(&mut self) -> Vec<Signal> {
+        std::mem::take(&mut self.signals)
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::cbbo::VenueQuote;
+use crate::depth::BookDepth;
+use crate::events::MarketEvent;
+use crate::perp::MarkPrice;
+use crate::ticker::TickerData;
+
+#[derive(Debug, Clone)]
+pub struct ThrottleConfig {
+    pub interval: Duration, // Minimum time between detection passes
+    pub max_updates: usize, // Run early once this many symbol updates are pending
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        ThrottleConfig {
+            interval: Duration::from_millis(50),
+            max_updates: 1000,
+        }
+    }
+}
+
+// Coalesces market events between detection passes, keeping only the latest update per
+// symbol (or per venue and pair for quotes). Graph updates still apply immediately;
+// only strategy runs are deferred.
+pub struct UpdateBatch {
+    config: ThrottleConfig,
+    tickers: Vec<TickerData>,
+    ticker_index: HashMap<String, usize>,
+    quotes: Vec<VenueQuote>,
+    quote_index: HashMap<(String, String, String), usize>,
+    marks: Vec<MarkPrice>,
+    mark_index: HashMap<String, usize>,
+    depths: Vec<BookDepth>,
+    depth_index: HashMap<String, usize>,
+    removed: Vec<String>,
+    updates: usize,
+    first_received: Option<Instant>, // Arrival of the oldest pending update
+    last_pass: Instant,
+}
+
+impl UpdateBatch {
+    pub fn new(config: ThrottleConfig) -> Self {
+        UpdateBatch {
+            config,
+            tickers: Vec::new(),
+            ticker_index: HashMap::new(),
+            quotes: Vec::new(),
+            quote_index: HashMap::new(),
+            marks: Vec::new(),
+            mark_index: HashMap::new(),
+            depths: Vec::new(),
+            depth_index: HashMap::new(),
+            removed: Vec::new(),
+            updates: 0,
+            first_received: None,
+            last_pass: Instant::now(),
+        }
+    }
+
+    // Adds an event; true if a detection pass is due now
+    pub fn push(&mut self, event: MarketEvent, received: Instant) -> bool {
+        self.first_received.get_or_insert(received);
+        match event {
+            MarketEvent::Tickers(tickers) => {
+                self.updates += tickers.len();
+                for ticker in tickers {
+                    match self.ticker_index.get(&ticker.s) {
+                        Some(&index) => self.tickers[index] = ticker,
+                        None => {
+                            self.ticker_index.insert(ticker.s.clone(), self.tickers.len());
+                            self.tickers.push(ticker);
+                        }
+                    }
+                }
+            }
+            MarketEvent::Quotes(quotes) => {
+                self.updates += quotes.len();
+                for quote in quotes {
+                    let key = (quote.venue.clone(), quote.base.clone(), quote.quote.clone());
+                    match self.quote_index.get(&key) {
+                        Some(&index) => self.quotes[index] = quote,
+                        None => {
+                            self.quote_index.insert(key, self.quotes.len());
+                            self.quotes.push(quote);
+                        }
+                    }
+                }
+            }
+            MarketEvent::MarkPrices(marks) => {
+                self.updates += marks.len();
+                for mark in marks {
+                    match self.mark_index.get(&mark.symbol) {
+                        Some(&index) => self.marks[index] = mark,
+                        None => {
+                            self.mark_index.insert(mark.symbol.clone(), self.marks.len());
+                            self.marks.push(mark);
+                        }
+                    }
+                }
+            }
+            MarketEvent::Depth(depths) => {
+                self.updates += depths.len();
+                for depth in depths {
+                    match self.depth_index.get(&depth.symbol) {
+                        Some(&index) => self.depths[index] = depth,
+                        None => {
+                            self.depth_index.insert(depth.symbol.clone(), self.depths.len());
+                            self.depths.push(depth);
+                        }
+                    }
+                }
+            }
+            // The engine acts on these as they arrive; there is nothing to batch
+            MarketEvent::FeedState { .. } | MarketEvent::FeedStalled { .. } => {}
+            // Removals are rare and change the graph's shape; detect straight away
+            MarketEvent::SymbolRemoved(symbol) => {
+                self.tickers.retain(|ticker| ticker.s != symbol);
+                self.ticker_index = self.tickers.iter().enumerate().map(|(i, t)| (t.s.clone(), i)).collect();
+                self.depths.retain(|depth| depth.symbol != symbol);
+                self.depth_index = self.depths.iter().enumerate().map(|(i, d)| (d.symbol.clone(), i)).collect();
+                self.removed.push(symbol);
+                return true;
+            }
+        }
+        self.updates >= self.config.max_updates || received >= self.last_pass + self.config.interval
+    }
+
+    // When the pending updates must be detected on even if nothing else arrives
+    pub fn deadline(&self) -> Option<Instant> {
+        self.first_received.map(|_| self.last_pass + self.config.interval)
+    }
+
+    // Drains the batch into at most one event of each kind, plus when the oldest arrived
+    pub fn take(&mut self, now: Instant) -> Option<(Vec<MarketEvent>, Instant)> {
+        let first_received = self.first_received.take()?;
+        self.last_pass = now;
+        self.updates = 0;
+        self.ticker_index.clear();
+        self.quote_index.clear();
+        self.mark_index.clear();
+        self.depth_index.clear();
+        let mut events = Vec::new();
+        if !self.tickers.is_empty() {
+            events.push(MarketEvent::Tickers(std::mem::take(&mut self.tickers)));
+        }
+        if !self.quotes.is_empty() {
+            events.push(MarketEvent::Quotes(std::mem::take(&mut self.quotes)));
+        }
+        if !self.marks.is_empty() {
+            events.push(MarketEvent::MarkPrices(std::mem::take(&mut self.marks)));
+        }
+        if !self.depths.is_empty() {
+            events.push(MarketEvent::Depth(std::mem::take(&mut self.depths)));
+        }
+        events.extend(self.removed.drain(..).map(MarketEvent::SymbolRemoved));
+        Some((events, first_received))
+    }
+}
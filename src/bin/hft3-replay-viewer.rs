@@ -0,0 +1,240 @@
+// Interactive viewer for sessions recorded with `hft3 --record <path>`.
+// Steps through the session message by message, showing the graph and the
+// detection result at each point.
+//
+// Keys: space play/pause, right/l step, left/h step back, home/end jump to ends,
+// g jump to timestamp (type ms, enter), up/down scroll edges, q quit.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use hft3::graph::Graph;
+use hft3::recorder::{load_session, SessionStep};
+use hft3::ticker::apply_ticker_data;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+
+const PLAY_INTERVAL: Duration = Duration::from_millis(250);
+
+struct Viewer {
+    path: PathBuf,
+    steps: Vec<SessionStep>,
+    position: usize, // Number of steps applied to the graph
+    graph: Graph,
+    detection: Option<Vec<String>>,
+    history: Vec<(usize, u64, Vec<String>)>, // (step, event time, cycle) for every detection seen
+    playing: bool,
+    jump_input: Option<String>,
+    scroll: usize,
+}
+
+impl Viewer {
+    fn new(path: PathBuf, steps: Vec<SessionStep>) -> Self {
+        Viewer {
+            path,
+            steps,
+            position: 0,
+            graph: Graph::new(),
+            detection: None,
+            history: Vec::new(),
+            playing: false,
+            jump_input: None,
+            scroll: 0,
+        }
+    }
+
+    fn step_forward(&mut self) {
+        if self.position >= self.steps.len() {
+            self.playing = false;
+            return;
+        }
+        let step = &self.steps[self.position];
+        apply_ticker_data(&mut self.graph, &step.tickers);
+        self.position += 1;
+        self.detect();
+    }
+
+    // Rebuilds the graph from the start of the session up to `position`
+    fn seek(&mut self, position: usize) {
+        let position = position.min(self.steps.len());
+        if position < self.position {
+            self.graph = Graph::new();
+            self.position = 0;
+        }
+        while self.position < position {
+            let step = &self.steps[self.position];
+            apply_ticker_data(&mut self.graph, &step.tickers);
+            self.position += 1;
+        }
+        self.detect();
+    }
+
+    // Jumps to the first step at or after the given exchange event time
+    fn seek_time(&mut self, timestamp: u64) {
+        let index = self.steps.partition_point(|s| s.event_time < timestamp);
+        self.seek(index + 1);
+    }
+
+    fn detect(&mut self) {
+        self.detection = if self.position == 0 { None } else { self.graph.find_arbitrage() };
+        if let Some(cycle) = &self.detection {
+            let event_time = self.steps[self.position - 1].event_time;
+            if !self.history.iter().any(|(step, _, _)| *step == self.position) {
+                self.history.push((self.position, event_time, cycle.clone()));
+                self.history.sort_by_key(|(step, _, _)| *step);
+            }
+        }
+    }
+
+    // Returns false when the viewer should exit
+    fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(input) = self.jump_input.as_mut() {
+            match code {
+                KeyCode::Char(c) if c.is_ascii_digit() => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    if let Ok(timestamp) = input.parse() {
+                        self.seek_time(timestamp);
+                    }
+                    self.jump_input = None;
+                }
+                KeyCode::Esc => self.jump_input = None,
+                _ => {}
+            }
+            return true;
+        }
+
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Right | KeyCode::Char('l') => self.step_forward(),
+            KeyCode::Left | KeyCode::Char('h') => self.seek(self.position.saturating_sub(1)),
+            KeyCode::Home => self.seek(0),
+            KeyCode::End => self.seek(self.steps.len()),
+            KeyCode::Char('g') => {
+                self.playing = false;
+                self.jump_input = Some(String::new());
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.scroll = self.scroll.saturating_add(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll = self.scroll.saturating_sub(1),
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [edges_area, right] = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(body);
+        let [detection_area, history_area] = Layout::vertical([Constraint::Length(5), Constraint::Min(0)]).areas(right);
+
+        let event_time = match self.position {
+            0 => "-".to_string(),
+            p => self.steps[p - 1].event_time.to_string(),
+        };
+        let status = format!(
+            "{}  step {}/{}  event time {}  {}  vertices {}  edges {}",
+            self.path.display(),
+            self.position,
+            self.steps.len(),
+            event_time,
+            if self.playing { "PLAYING" } else { "PAUSED" },
+            self.graph.vertex_count(),
+            self.graph.edge_count(),
+        );
+        frame.render_widget(Paragraph::new(status).block(Block::default().borders(Borders::ALL).title("hft3 replay")), header);
+
+        let mut edges: Vec<_> = self.graph.edges().collect();
+        edges.sort_by(|a, b| (&a.start, &a.end).cmp(&(&b.start, &b.end)));
+        let rows = edges
+            .iter()
+            .skip(self.scroll)
+            .map(|e| Row::new(vec![e.start.clone(), e.end.clone(), format!("{:.8}", e.rate)]));
+        let table = Table::new(rows, [Constraint::Length(8), Constraint::Length(8), Constraint::Min(12)])
+            .header(Row::new(vec!["start", "end", "rate"]).style(Style::default().add_modifier(Modifier::BOLD)))
+            .block(Block::default().borders(Borders::ALL).title("Graph edges"));
+        frame.render_widget(table, edges_area);
+
+        let detection = match &self.detection {
+            Some(cycle) => format!("Arbitrage: {}", cycle.join(" -> ")),
+            None => "No arbitrage at this step".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(detection).block(Block::default().borders(Borders::ALL).title("Detection")),
+            detection_area,
+        );
+
+        let items: Vec<ListItem> = self
+            .history
+            .iter()
+            .map(|(step, time, cycle)| ListItem::new(format!("step {} @ {}: {}", step, time, cycle.join(" -> "))))
+            .collect();
+        frame.render_widget(
+            List::new(items).block(Block::default().borders(Borders::ALL).title("Detections seen")),
+            history_area,
+        );
+
+        let help = match &self.jump_input {
+            Some(input) => format!("Jump to timestamp (ms): {}_", input),
+            None => "space play/pause  ←/→ step  home/end  g jump  ↑/↓ scroll  q quit".to_string(),
+        };
+        frame.render_widget(Line::from(help), footer);
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, viewer: &mut Viewer) -> std::io::Result<()> {
+    let mut last_tick = Instant::now();
+    loop {
+        terminal.draw(|frame| viewer.draw(frame))?;
+
+        let timeout = PLAY_INTERVAL.saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && !viewer.handle_key(key.code) {
+                    return Ok(());
+                }
+            }
+        }
+        if last_tick.elapsed() >= PLAY_INTERVAL {
+            if viewer.playing {
+                viewer.step_forward();
+            }
+            last_tick = Instant::now();
+        }
+    }
+}
+
+fn main() {
+    let path = match std::env::args().nth(1) {
+        Some(path) => PathBuf::from(path),
+        None => {
+            eprintln!("Usage: hft3-replay-viewer <session file>");
+            std::process::exit(2);
+        }
+    };
+    // Only errors, so log lines don't scribble over the TUI
+    hft3::logging::init("error", false);
+    let steps = load_session(&path).expect("Failed to load session");
+    if steps.is_empty() {
+        eprintln!("Session {} contains no ticker data", path.display());
+        std::process::exit(1);
+    }
+
+    let mut viewer = Viewer::new(path, steps);
+    let mut terminal = ratatui::init();
+    let result = run(&mut terminal, &mut viewer);
+    ratatui::restore();
+    if let Err(e) = result {
+        eprintln!("Viewer error: {:?}", e);
+    }
+}
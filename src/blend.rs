@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+
+use crate::ticker::TickerData;
+
+#[derive(Default, Clone, Copy)]
+struct Sources {
+    trade: Option<f64>, // Last aggregated trade print
+    book: Option<f64>,  // Last mid of the book, or a ticker's last price when it has no book
+}
+
+// Prices each symbol as a weighted mix of its last trade print and its book: `weight` on
+// the trade, the rest on the book's mid. Whichever has been seen alone is used as is.
+pub struct TradeBlend {
+    weight: f64,
+    sources: HashMap<String, Sources>,
+}
+
+impl TradeBlend {
+    pub fn new(weight: f64) -> Self {
+        TradeBlend {
+            weight: weight.clamp(0.0, 1.0),
+            sources: HashMap::new(),
+        }
+    }
+
+    // Records the update and rewrites its price to the blend
+    pub fn apply(&mut self, ticker: &mut TickerData) {
+        let parse = |field: &Option<String>| field.as_deref().and_then(|v| v.parse::<f64>().ok());
+        let price = if ticker.trade {
+            ticker.c.parse::<f64>().ok()
+        } else {
+            match (parse(&ticker.b), parse(&ticker.a)) {
+                (Some(bid), Some(ask)) if bid > 0.0 && ask > 0.0 => Some((bid + ask) / 2.0),
+                _ => ticker.c.parse::<f64>().ok(),
+            }
+        };
+        let Some(price) = price.filter(|p| *p > 0.0) else {
+            return;
+        };
+        let sources = match self.sources.get_mut(&ticker.s) {
+            Some(sources) => sources,
+            None => self.sources.entry(ticker.s.clone()).or_default(),
+        };
+        if ticker.trade {
+            sources.trade = Some(price);
+        } else {
+            sources.book = Some(price);
+        }
+        let blended = match (sources.trade, sources.book) {
+            (Some(trade), Some(book)) => self.weight * trade + (1.0 - self.weight) * book,
+            (Some(only), None) | (None, Some(only)) => only,
+            (None, None) => return,
+        };
+        ticker.c = blended.to_string();
+    }
+}
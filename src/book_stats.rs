@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use rust_decimal::prelude::ToPrimitive;
+use serde_json::{json, Value};
+
+use crate::depth::BookDepth;
+use crate::order::{OrderRequest, Side};
+
+// Shape of one symbol's book, from its book ticker or, when it has one, its depth stream
+#[derive(Debug, Clone, Copy)]
+pub struct BookStats {
+    pub spread_bps: f64, // Best ask over best bid
+    pub bid_qty: f64,    // Base quantity bid, over every level known
+    pub ask_qty: f64,    // Base quantity offered, over every level known
+    pub imbalance: f64,  // (bid_qty - ask_qty) / (bid_qty + ask_qty): +1 only bids, -1 only asks
+    pub levels: usize,   // Levels per side the quantities cover; 1 for a book ticker
+    pub updated: Instant,
+}
+
+impl BookStats {
+    // None for a crossed or empty book
+    pub fn new(bid: f64, ask: f64, bid_qty: f64, ask_qty: f64, levels: usize, updated: Instant) -> Option<Self> {
+        if bid <= 0.0 || ask < bid {
+            return None;
+        }
+        let total = bid_qty + ask_qty;
+        Some(BookStats {
+            spread_bps: (ask - bid) / bid * 10_000.0,
+            bid_qty,
+            ask_qty,
+            imbalance: if total > 0.0 { (bid_qty - ask_qty) / total } else { 0.0 },
+            levels,
+            updated,
+        })
+    }
+
+    pub fn from_depth(depth: &BookDepth, updated: Instant) -> Option<Self> {
+        let (&(bid, _), &(ask, _)) = (depth.bids.first()?, depth.asks.first()?);
+        let sum = |levels: &[(f64, f64)]| levels.iter().map(|&(_, qty)| qty).sum();
+        let levels = depth.bids.len().max(depth.asks.len());
+        BookStats::new(bid, ask, sum(&depth.bids), sum(&depth.asks), levels, updated)
+    }
+
+    // How far the book leans against an order on `side`, 0..1: a buy lifts the asks, so
+    // it fares worst when nearly everything is bid
+    pub fn adverse_imbalance(&self, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.imbalance.max(0.0),
+            Side::Sell => (-self.imbalance).max(0.0),
+        }
+    }
+
+    // Quantity an order on `side` can take
+    pub fn available(&self, side: Side) -> f64 {
+        match side {
+            Side::Buy => self.ask_qty,
+            Side::Sell => self.bid_qty,
+        }
+    }
+
+    fn to_json(self, now: Instant) -> Value {
+        json!({
+            "spread_bps": self.spread_bps,
+            "imbalance": self.imbalance,
+            "bid_qty": self.bid_qty,
+            "ask_qty": self.ask_qty,
+            "levels": self.levels,
+            "age_ms": now.saturating_duration_since(self.updated).as_millis() as u64,
+        })
+    }
+}
+
+// Per-symbol book stats kept up to date by the engine and read by executors and /status.
+// A symbol with a depth stream is described by its levels; its book ticker would only
+// overwrite them with the top one.
+#[derive(Debug, Default)]
+pub struct BookMetrics {
+    books: RwLock<HashMap<String, BookStats>>,
+}
+
+impl BookMetrics {
+    pub fn record(&self, symbol: &str, stats: BookStats) {
+        let mut books = self.books.write().unwrap();
+        match books.get_mut(symbol) {
+            Some(known) if known.levels > stats.levels => {}
+            Some(known) => *known = stats,
+            None => {
+                books.insert(symbol.to_string(), stats);
+            }
+        }
+    }
+
+    pub fn remove(&self, symbol: &str) {
+        self.books.write().unwrap().remove(symbol);
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<BookStats> {
+        self.books.read().unwrap().get(symbol).copied()
+    }
+
+    // Every symbol's stats
+    pub fn to_json(&self, now: Instant) -> Value {
+        let books = self.books.read().unwrap();
+        let mut symbols: Vec<_> = books.iter().collect();
+        symbols.sort_by(|a, b| a.0.cmp(b.0));
+        Value::Object(symbols.into_iter().map(|(symbol, stats)| (symbol.clone(), stats.to_json(now))).collect())
+    }
+
+    // Symbol count and the `top` widest and most lopsided books
+    pub fn summary(&self, top: usize, now: Instant) -> Value {
+        let books = self.books.read().unwrap();
+        let ranked = |key: &dyn Fn(&BookStats) -> f64| {
+            let mut symbols: Vec<_> = books.iter().filter(|(_, stats)| key(stats).is_finite()).collect();
+            symbols.sort_by(|a, b| key(b.1).total_cmp(&key(a.1)));
+            symbols
+                .into_iter()
+                .take(top)
+                .map(|(symbol, stats)| {
+                    let mut entry = stats.to_json(now);
+                    entry["symbol"] = json!(symbol);
+                    entry
+                })
+                .collect::<Vec<_>>()
+        };
+        json!({
+            "symbols": books.len(),
+            "widest": ranked(&|stats| stats.spread_bps),
+            "most_imbalanced": ranked(&|stats| stats.imbalance.abs()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BookLimits {
+    pub max_spread_bps: f64, // Wider than this and the book is too thin to cross
+    pub max_imbalance: f64,  // How far the book may lean against the order, 0..1
+    pub min_cover: f64,      // Quantity on the side taken, as a multiple of the order's
+    pub max_age: Duration,   // Older stats don't count, for or against
+}
+
+impl Default for BookLimits {
+    fn default() -> Self {
+        BookLimits {
+            max_spread_bps: 50.0,
+            max_imbalance: 0.9,
+            min_cover: 1.0,
+            max_age: Duration::from_secs(5),
+        }
+    }
+}
+
+// Why a cycle was refused a book
+#[derive(Debug, Clone, PartialEq)]
+pub enum BookRejection {
+    Wide { symbol: String, spread_bps: f64 },
+    OneSided { symbol: String, imbalance: f64 },
+    Thin { symbol: String, cover: f64 },
+}
+
+impl fmt::Display for BookRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BookRejection::Wide { symbol, spread_bps } => write!(f, "{} spread {:.1} bps", symbol, spread_bps),
+            BookRejection::OneSided { symbol, imbalance } => write!(f, "{} book {:.0}% against the order", symbol, imbalance * 100.0),
+            BookRejection::Thin { symbol, cover } => write!(f, "{} book covers {:.2}x the order", symbol, cover),
+        }
+    }
+}
+
+// What a cycle planner checks its legs against before sending them: every order must
+// meet a book that is tight, not leaning against it, and deep enough to fill it. Legs
+// without recent stats pass, as nothing is known against them.
+#[derive(Debug, Clone)]
+pub struct BookGuard {
+    metrics: Arc<BookMetrics>,
+    limits: BookLimits,
+}
+
+impl BookGuard {
+    pub fn new(metrics: Arc<BookMetrics>, limits: BookLimits) -> Self {
+        BookGuard { metrics, limits }
+    }
+
+    pub fn check(&self, orders: &[OrderRequest], now: Instant) -> Result<(), BookRejection> {
+        for order in orders {
+            let Some(stats) = self.metrics.get(&order.symbol) else {
+                continue;
+            };
+            if now.saturating_duration_since(stats.updated) > self.limits.max_age {
+                continue;
+            }
+            let symbol = order.symbol.clone();
+            if stats.spread_bps > self.limits.max_spread_bps {
+                return Err(BookRejection::Wide { symbol, spread_bps: stats.spread_bps });
+            }
+            let imbalance = stats.adverse_imbalance(order.side);
+            if imbalance > self.limits.max_imbalance {
+                return Err(BookRejection::OneSided { symbol, imbalance });
+            }
+            let quantity = order.quantity.to_f64().unwrap_or_default();
+            if quantity > 0.0 {
+                let cover = stats.available(order.side) / quantity;
+                if cover < self.limits.min_cover {
+                    return Err(BookRejection::Thin { symbol, cover });
+                }
+            }
+        }
+        Ok(())
+    }
+}
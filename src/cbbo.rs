@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::time::Instant;
+
+// Top of book for one pair on one venue
+#[derive(Debug, Clone)]
+pub struct VenueQuote {
+    pub venue: String,
+    pub base: String,
+    pub quote: String,
+    pub bid: f64,
+    pub bid_qty: f64,
+    pub ask: f64,
+    pub ask_qty: f64,
+    pub updated: Instant,
+}
+
+// Best bid and best ask across venues, each attributed to the venue quoting it
+#[derive(Debug, Clone)]
+pub struct Cbbo {
+    pub bid: f64,
+    pub bid_qty: f64,
+    pub bid_venue: String,
+    pub ask: f64,
+    pub ask_qty: f64,
+    pub ask_venue: String,
+}
+
+impl Cbbo {
+    // A bid on one venue above the ask on another is itself an opportunity
+    pub fn is_crossed(&self) -> bool {
+        self.bid > self.ask
+    }
+}
+
+// Consolidated view of every venue's top of book, keyed by canonical (base, quote) pair.
+// Nested by asset rather than keyed by a (String, String) tuple so lookups can borrow.
+#[derive(Default)]
+pub struct ConsolidatedBook {
+    quotes: HashMap<String, HashMap<String, HashMap<String, VenueQuote>>>, // base -> quote -> venue
+}
+
+impl ConsolidatedBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, quote: VenueQuote) {
+        self.quotes
+            .entry(quote.base.clone())
+            .or_default()
+            .entry(quote.quote.clone())
+            .or_default()
+            .insert(quote.venue.clone(), quote);
+    }
+
+    // Overwrites a venue's (price, quantity) bid and ask in place, only allocating the first
+    // time the venue quotes the pair
+    pub fn update_top(&mut self, venue: &str, base: &str, quote: &str, bid: (f64, f64), ask: (f64, f64), updated: Instant) {
+        if let Some(known) = self.quotes.get_mut(base).and_then(|quotes| quotes.get_mut(quote)).and_then(|venues| venues.get_mut(venue)) {
+            (known.bid, known.bid_qty) = bid;
+            (known.ask, known.ask_qty) = ask;
+            known.updated = updated;
+            return;
+        }
+        self.update(VenueQuote {
+            venue: venue.to_string(),
+            base: base.to_string(),
+            quote: quote.to_string(),
+            bid: bid.0,
+            bid_qty: bid.1,
+            ask: ask.0,
+            ask_qty: ask.1,
+            updated,
+        });
+    }
+
+    fn venues(&self, base: &str, quote: &str) -> Option<&HashMap<String, VenueQuote>> {
+        self.quotes.get(base)?.get(quote)
+    }
+
+    pub fn remove(&mut self, venue: &str, base: &str, quote: &str) {
+        let Some(quotes) = self.quotes.get_mut(base) else {
+            return;
+        };
+        if let Some(venues) = quotes.get_mut(quote) {
+            venues.remove(venue);
+            if venues.is_empty() {
+                quotes.remove(quote);
+            }
+        }
+        if quotes.is_empty() {
+            self.quotes.remove(base);
+        }
+    }
+
+    // A single venue's own quote, for execution that must respect where liquidity actually is
+    pub fn venue_quote(&self, venue: &str, base: &str, quote: &str) -> Option<&VenueQuote> {
+        self.venues(base, quote)?.get(venue)
+    }
+
+    // Every venue's quote for the pair, in no particular order
+    pub fn quotes(&self, base: &str, quote: &str) -> impl Iterator<Item = &VenueQuote> {
+        self.venues(base, quote).into_iter().flat_map(|venues| venues.values())
+    }
+
+    // Best bid and best ask prices across venues, without naming the venues
+    pub fn top(&self, base: &str, quote: &str) -> Option<(f64, f64)> {
+        let venues = self.venues(base, quote)?;
+        let bid = venues.values().map(|q| q.bid).filter(|bid| *bid > 0.0).max_by(f64::total_cmp)?;
+        let ask = venues.values().map(|q| q.ask).filter(|ask| *ask > 0.0).min_by(f64::total_cmp)?;
+        Some((bid, ask))
+    }
+
+    pub fn best(&self, base: &str, quote: &str) -> Option<Cbbo> {
+        let venues = self.venues(base, quote)?;
+        let best_bid = venues.values().filter(|q| q.bid > 0.0).max_by(|a, b| a.bid.total_cmp(&b.bid))?;
+        let best_ask = venues.values().filter(|q| q.ask > 0.0).min_by(|a, b| a.ask.total_cmp(&b.ask))?;
+        Some(Cbbo {
+            bid: best_bid.bid,
+            bid_qty: best_bid.bid_qty,
+            bid_venue: best_bid.venue.clone(),
+            ask: best_ask.ask,
+            ask_qty: best_ask.ask_qty,
+            ask_venue: best_ask.venue.clone(),
+        })
+    }
+
+    // Rate for converting `from` into `to` at the consolidated top of book, and the venue
+    // providing it: selling base hits the best bid, buying base lifts the best ask
+    pub fn conversion(&self, from: &str, to: &str) -> Option<(f64, String)> {
+        if let Some(cbbo) = self.best(from, to) {
+            return Some((cbbo.bid, cbbo.bid_venue));
+        }
+        let cbbo = self.best(to, from)?;
+        Some((1.0 / cbbo.ask, cbbo.ask_venue))
+    }
+}
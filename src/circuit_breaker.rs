@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::ticker::TickerData;
+
+#[derive(Debug, Clone)]
+pub struct PriceGuardConfig {
+    pub max_move: f64,      // Largest accepted change from the last good price in one update, as a fraction
+    pub cooldown: Duration, // How long a tripped symbol stays quarantined
+}
+
+impl Default for PriceGuardConfig {
+    fn default() -> Self {
+        PriceGuardConfig {
+            max_move: 0.2,
+            cooldown: Duration::from_secs(60),
+        }
+    }
+}
+
+// What was wrong with the update that tripped a symbol's breaker
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceFault {
+    NonPositive { field: &'static str, value: String }, // Missing, unparsable, zero or negative
+    Jump { from: f64, to: f64 },                        // Moved more than the limit in one update
+}
+
+impl fmt::Display for PriceFault {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PriceFault::NonPositive { field, value } => write!(f, "invalid {} price {:?}", field, value),
+            PriceFault::Jump { from, to } => write!(f, "price jumped {:+.1}% from {} to {}", (to / from - 1.0) * 100.0, from, to),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PriceCheck {
+    Accepted,
+    Reset,               // Accepted, and the symbol's quarantine is over
+    Rejected,            // The symbol is quarantined
+    Tripped(PriceFault), // This update tripped the breaker
+}
+
+struct SymbolState {
+    last: f64,                // Last accepted price
+    tripped: Option<Instant>, // When the breaker tripped, while quarantined
+}
+
+// Per-symbol circuit breaker on bad data. An update with a price that isn't a positive
+// number, or whose last price moves more than `max_move` from the last accepted one, trips
+// the symbol's breaker: it and every later update are rejected until `cooldown` has passed,
+// after which the next valid price is taken as the new reference, since the market may
+// really have moved that far.
+pub struct PriceGuard {
+    config: PriceGuardConfig,
+    symbols: HashMap<String, SymbolState>,
+}
+
+impl PriceGuard {
+    pub fn new(config: PriceGuardConfig) -> Self {
+        PriceGuard {
+            config,
+            symbols: HashMap::new(),
+        }
+    }
+
+    pub fn tripped(&self) -> usize {
+        self.symbols.values().filter(|state| state.tripped.is_some()).count()
+    }
+
+    pub fn check(&mut self, ticker: &TickerData, now: Instant) -> PriceCheck {
+        let fault = match valid_price(ticker) {
+            Ok(price) => match self.symbols.get_mut(&ticker.s) {
+                Some(state) => match state.tripped {
+                    Some(at) if now.saturating_duration_since(at) < self.config.cooldown => return PriceCheck::Rejected,
+                    Some(_) => {
+                        state.last = price;
+                        state.tripped = None;
+                        return PriceCheck::Reset;
+                    }
+                    None if (price / state.last - 1.0).abs() > self.config.max_move => {
+                        PriceFault::Jump { from: state.last, to: price }
+                    }
+                    None => {
+                        state.last = price;
+                        return PriceCheck::Accepted;
+                    }
+                },
+                None => {
+                    self.symbols.insert(ticker.s.clone(), SymbolState { last: price, tripped: None });
+                    return PriceCheck::Accepted;
+                }
+            },
+            Err(fault) => fault,
+        };
+        match self.symbols.get_mut(&ticker.s) {
+            // Already quarantined: the cooldown runs from the first fault
+            Some(SymbolState { tripped: Some(_), .. }) => PriceCheck::Rejected,
+            Some(state) => {
+                state.tripped = Some(now);
+                PriceCheck::Tripped(fault)
+            }
+            // Nothing good seen yet, so nothing to fall back to; keep it out until it sends a valid price
+            None => PriceCheck::Rejected,
+        }
+    }
+}
+
+// The update's last price, once every price it carries checks out
+fn valid_price(ticker: &TickerData) -> Result<f64, PriceFault> {
+    let positive = |field: &'static str, value: &str| match value.parse::<f64>() {
+        Ok(price) if price > 0.0 && price.is_finite() => Ok(price),
+        _ => Err(PriceFault::NonPositive { field, value: value.to_string() }),
+    };
+    if let Some(bid) = &ticker.b {
+        positive("bid", bid)?;
+    }
+    if let Some(ask) = &ticker.a {
+        positive("ask", ask)?;
+    }
+    positive("last", &ticker.c)
+}
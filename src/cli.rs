@@ -0,0 +1,897 @@
+use std::cell::Cell;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use hft3::accounts::{AccountConfig, AccountPolicy};
+use hft3::batch::ThrottleConfig;
+use hft3::book_stats::BookLimits;
+use hft3::circuit_breaker::PriceGuardConfig;
+use hft3::clock::DEFAULT_SYNC_INTERVAL;
+use hft3::credentials::CredentialSource;
+use hft3::dedup::DedupConfig;
+use hft3::failover::FailoverConfig;
+use hft3::fallback::FallbackConfig;
+use hft3::fix::{CycleSizing, FixConfig};
+use hft3::health::DEFAULT_MAX_AGE;
+use hft3::hedger::HedgeConfig;
+use hft3::last_look::LastLookConfig;
+use hft3::latency_budget::LatencyBudgetConfig;
+use hft3::liquidity::LiquidityConfig;
+use hft3::depeg::DepegConfig;
+use hft3::depth::DepthConfig;
+use hft3::persistence::PersistenceConfig;
+use hft3::pipeline::{QueueConfig, QueuePolicy};
+use hft3::profile::{load_args, DEFAULT_CONFIG_PATH};
+use hft3::proxy::{Proxy, ProxyConfig};
+use hft3::revalidate::RevalidationConfig;
+use hft3::risk::RiskConfig;
+use hft3::routing::RoutingConfig;
+use hft3::sequence::SequenceConfig;
+use hft3::sim::SimConfig;
+use hft3::slippage::SlippageModel;
+use hft3::stable_edges::StableEdgeConfig;
+use hft3::stream_server::DEFAULT_HEARTBEAT;
+use hft3::trace::TraceConfig;
+use hft3::ttl::TtlConfig;
+use hft3::valuation::ValuationConfig;
+use hft3::uniswap::UniswapConfig;
+use hft3::watchdog::DEFAULT_MAX_SILENCE;
+use hft3::sinks::kafka::KafkaConfig;
+use hft3::sinks::nats::NatsConfig;
+use hft3::sinks::redis::RedisConfig;
+use hft3::sinks::webhook::{WebhookConfig, WebhookFormat};
+use hft3::{BasisConfig, LeadLagConfig, SpreadConfig};
+use rust_decimal::Decimal;
+
+// Executors size cycles in USDT, so risk limits are given in it too
+pub const RISK_REFERENCE: &str = "USDT";
+
+// How opportunities are written to stdout (or --output-file)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputMode {
+    Text,  // Human-readable log lines
+    Jsonl, // One JSON object per opportunity
+}
+
+// Command line options for the main binary
+pub struct Args {
+    pub record_path: Option<PathBuf>,           // --record <path>: save raw messages for replay
+    pub audit_path: Option<PathBuf>,            // --audit-log <path>: append every decision, order and fill
+    pub rest_fallback: Option<FallbackConfig>,  // --rest-fallback-ms <ms>: poll REST at this interval while the socket is down
+    pub failover: Option<FailoverConfig>,       // --ws-failover, --ws-endpoint <url> (repeatable): race stream hosts
+    pub max_silence: Option<Duration>,          // --ws-max-silence-ms <ms>: reconnect a stream quiet this long, 0 = never
+    pub rest_snapshot: bool,                    // --no-rest-snapshot: wait for the stream instead of loading prices over REST
+    pub time_sync: Option<Duration>,            // --time-sync-ms <ms>: how often to sync with the exchange clock, 0 = never
+    pub agg_trades: Vec<String>,                // --agg-trades <symbol> (repeatable): also price the pair from its trade prints
+    pub trade_blend: Option<f64>,               // --trade-blend <weight>: mix trade prints and book mids, 0..1 on the trade
+    pub liquidity: Option<LiquidityConfig>,     // --min-volume <usdt>: drop pairs under this 24h quote volume
+    pub price_guard: Option<PriceGuardConfig>,  // --max-tick-move-pct <pct> (0 = off), --breaker-cooldown-ms <ms>: quarantine bad prices
+    pub depth_streams: Vec<String>,             // --depth <symbol> (repeatable): stream the pair's top 20 book levels
+    pub depth: Option<DepthConfig>,             // --depth-notional <usdt>: price depth-streamed pairs for an order this size
+    pub replay_path: Option<PathBuf>,           // --replay <path>: run on a recorded session instead of the live feed
+    pub script_path: Option<PathBuf>,           // --simulate <script>: run on a scripted session instead of the live feed
+    pub walk_path: Option<PathBuf>,             // --random-walk <snapshot>: run on a seeded random walk from the snapshot's prices
+    pub sim: SimConfig,                         // --sim-seed <n>, --sim-steps <n>, --sim-move-bps <bps>: random walk parameters
+    pub strategy: String,                       // --strategy negative-cycle|two-phase
+    pub log_level: String,                      // --log-level <filter> (RUST_LOG overrides)
+    pub log_json: bool,                         // --log-json
+    pub cbbo: bool,                             // --cbbo: detect on the consolidated best bid/offer
+    pub routing: Option<RoutingConfig>,         // --smart-routing <notional>: pick each leg's venue for a trade this size
+    pub fees_path: Option<PathBuf>,             // --fees <path>: venue fee table for smart routing
+    pub telegram_min_bps: Option<f64>,          // --telegram-min-bps <bps>
+    pub webhook: Option<WebhookConfig>,         // --webhook-url/--webhook-format/--webhook-header
+    pub kafka: Option<KafkaConfig>,             // --kafka-brokers/--kafka-topic/--kafka-market-topic/--kafka-property
+    pub redis: Option<RedisConfig>,             // --redis-url/--redis-stream/--redis-channel/--redis-max-len
+    pub nats: Option<NatsConfig>,               // --nats-url/--nats-subject/--nats-heartbeat-subject/--nats-heartbeat-ms
+    pub calendar_path: Option<PathBuf>,         // --calendar <path>: quiet hours and blackouts
+    pub plugins: Vec<PathBuf>,                  // --plugin <executable> (repeatable)
+    pub output: OutputMode,                     // --output text|jsonl
+    pub output_file: Option<PathBuf>,           // --output-file <path>
+    pub sqlite_path: Option<PathBuf>,           // --sqlite <path>: store opportunities
+    pub dedup: Option<DedupConfig>,             // --dedup-cooldown-ms <ms> (0 disables), --dedup-close-ms <ms>; off in simulations unless given
+    pub ttl: Option<TtlConfig>,                 // --opportunity-ttl-max-ms <ms> (0 disables), --opportunity-ttl-min-ms <ms>; likewise
+    pub top_n: Option<usize>,                   // --top-n <n>: most profitable cycles reported per pass
+    pub slippage: SlippageModel,                // --slippage-bps/--slippage-notional/--slippage-beyond-top-bps
+    pub max_cycle_len: usize,                   // --max-cycle-len <legs>: longest cycle reported (default 3, at least 2)
+    pub home_assets: Vec<String>,               // --home-asset <asset> (repeatable): only cycles through it, entered there
+    pub depeg: Option<DepegConfig>,             // --depeg-alert-bps <bps> (0 disables)
+    pub stable_edges: Option<StableEdgeConfig>, // --stable-edges-bps <bps>: haircut on synthetic stablecoin conversions
+    pub spread: Option<SpreadConfig>,           // --spread-zscore <z>: alert on prices diverging across stablecoins
+    pub basis: Option<BasisConfig>,             // --basis-apr <percent>: stream USD-M perpetuals, flag funding carry
+    pub lead_lag: Option<LeadLagConfig>,        // --lead-lag-bps <bps>, --lead-lag-window-ms <ms>: flag venues leading others
+    pub futures_streams: Vec<String>,           // --futures-stream <stream> (repeatable): USD-M futures market data
+    pub uniswap: Option<UniswapConfig>,         // --uniswap-rpc/--uniswap-pool/--uniswap-poll-ms/--uniswap-notional-eth
+    pub fix: Option<FixConfig>,                 // --fix <host:port> with --fix-sender/--fix-target/--fix-account
+    pub fix_cycles: Option<CycleSizing>,        // --fix-notional <amount>: route detected cycles over FIX, sized in USDT
+    pub valuation: Option<ValuationConfig>,     // --profit-currency <asset>, --profit-notional <amount>: report profits in one asset
+    pub trace: Option<TraceConfig>,             // --trace-notional <usdt>, --trace-fee-bps <bps>: simulate each opportunity leg by leg
+    pub revalidate: Option<RevalidationConfig>, // --revalidate-bps <bps>, --revalidate-rest: re-check cycles before sending
+    pub book_stats: bool,                       // --book-stats: keep spread and imbalance per symbol, on /status and /books
+    pub book_limits: Option<BookLimits>,        // --book-max-spread-bps/--book-max-imbalance/--book-min-cover: skip poor legs
+    pub hedge: Option<HedgeConfig>,             // --hedge-pair <symbol>, --hedge-band <quote>, --hedge-interval-ms <ms>: offset net exposure
+    pub last_look: Option<LastLookConfig>,      // --last-look-bps <bps>: hold back a final leg whose rate moved against the cycle
+    pub last_look_log: Option<PathBuf>,         // --last-look-log <path>: append every last-look abort
+    pub latency: Option<LatencyBudgetConfig>,   // --latency-budget-ms/--leg-latency-budget-ms/--latency-breaches: slow cycles stop execution
+    pub persistence: Option<PersistenceConfig>, // --min-profit-ms <ms>, --min-profit-updates <n>
+    pub change_epsilon_bps: f64,                // --change-epsilon-bps <bps>: ignore smaller rate moves
+    pub throttle: Option<ThrottleConfig>,       // --detect-interval-ms <ms>, --detect-max-updates <n>
+    pub shards: Option<usize>,                  // --shards <n>: parse tickers on n worker tasks
+    pub max_edge_age: Option<Duration>,         // --max-edge-age-ms <ms>: drop edges that stopped updating
+    pub sequence: Option<SequenceConfig>,       // --max-event-gap-ms <ms>, --invalidate-on-gap: flag skipped or reordered updates
+    pub queue: QueueConfig,                     // --queue-capacity <n>, --queue-policy coalesce|block|drop: ingest to detection
+    pub parallel_detection: bool,               // --parallel-detection: search quote clusters concurrently
+    pub tui: bool,                              // --tui: live terminal dashboard instead of log output
+    pub web_addr: Option<SocketAddr>,           // --web <addr>: serve the web dashboard, e.g. 127.0.0.1:8080
+    pub grpc_addr: Option<SocketAddr>,          // --grpc <addr>: serve the gRPC API, e.g. 127.0.0.1:50051
+    pub stream_addr: Option<SocketAddr>,        // --stream-server <addr>: stream opportunities to clients holding a token
+    pub stream_heartbeat: Duration,             // --stream-heartbeat-ms <ms>: time between heartbeats to stream clients
+    pub health_addr: Option<SocketAddr>,        // --health <addr>: serve /healthz and /status
+    pub health_max_age: Duration,               // --health-max-age-ms <ms>: silence before /healthz fails
+    pub testnet: bool,                          // --testnet: market data, REST and keys from the spot testnet
+    pub user_stream: bool,                      // --user-stream: track balances and orders (needs API keys)
+    pub credentials: CredentialSource,          // --credentials env|file:<path>|keyring[:<account>]: where API keys come from
+    pub accounts: Vec<AccountConfig>,           // --account <name>=<source>: execute on this account too (repeatable)
+    pub account_policy: AccountPolicy,          // --account-policy round-robin|most-free: which account takes a cycle
+    pub account_notional: Option<Decimal>,      // --account-notional <usdt>: size of each cycle an account executes
+    pub proxy: ProxyConfig,                     // --proxy <url>, --venue-proxy <venue>=<url|direct>
+    pub risk: Option<RiskConfig>,               // --max-trade-notional/--max-position/--max-trades-per-minute/--max-daily-loss, in USDT
+    pub pnl_reference: String,                  // --pnl-reference <asset>: what PnL is valued in (default USDT)
+    pub profile: Option<String>,                // --profile <name> [--config <path>]: flags from a named profile in the config file
+}
+
+impl Args {
+    // Fails on unknown flags, bad values (in a profile too) and settings that can't run,
+    // before anything is started
+    pub fn parse() -> Result<Self, String> {
+        let command_line: Vec<String> = std::env::args().skip(1).collect();
+        // A profile's flags go first so anything given on the command line overrides them
+        let profile = flag_value(&command_line, "--profile");
+        let mut expanded = match &profile {
+            Some(name) => {
+                let path = flag_value(&command_line, "--config").map_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH), PathBuf::from);
+                load_args(&path, name).map_err(|e| format!("--profile {} from {}: {}", name, path.display(), e))?
+            }
+            None => Vec::new(),
+        };
+        let profile_len = expanded.len();
+        expanded.extend(command_line);
+        let consumed = Cell::new(0);
+        let mut args = expanded.into_iter().inspect(|_| consumed.set(consumed.get() + 1));
+        let mut parsed = Args {
+            record_path: None,
+            audit_path: None,
+            rest_fallback: None,
+            failover: None,
+            max_silence: Some(DEFAULT_MAX_SILENCE),
+            rest_snapshot: true,
+            time_sync: Some(DEFAULT_SYNC_INTERVAL),
+            agg_trades: Vec::new(),
+            trade_blend: None,
+            liquidity: None,
+            price_guard: Some(PriceGuardConfig::default()),
+            depth_streams: Vec::new(),
+            depth: None,
+            replay_path: None,
+            script_path: None,
+            walk_path: None,
+            sim: SimConfig::default(),
+            strategy: "negative-cycle".to_string(),
+            log_level: "info".to_string(),
+            log_json: false,
+            cbbo: false,
+            routing: None,
+            fees_path: None,
+            telegram_min_bps: None,
+            webhook: None,
+            kafka: None,
+            redis: None,
+            nats: None,
+            calendar_path: None,
+            plugins: Vec::new(),
+            output: OutputMode::Text,
+            output_file: None,
+            sqlite_path: None,
+            dedup: Some(DedupConfig::default()),
+            ttl: Some(TtlConfig::default()),
+            top_n: None,
+            slippage: SlippageModel::None,
+            max_cycle_len: 3,
+            home_assets: Vec::new(),
+            depeg: Some(DepegConfig::default()),
+            stable_edges: None,
+            spread: None,
+            basis: None,
+            lead_lag: None,
+            futures_streams: Vec::new(),
+            uniswap: None,
+            fix: None,
+            fix_cycles: None,
+            trace: None,
+            valuation: None,
+            revalidate: None,
+            book_stats: false,
+            book_limits: None,
+            hedge: None,
+            last_look: None,
+            last_look_log: None,
+            latency: None,
+            persistence: None,
+            change_epsilon_bps: 0.0,
+            throttle: None,
+            shards: None,
+            max_edge_age: None,
+            sequence: None,
+            queue: QueueConfig::default(),
+            parallel_detection: false,
+            tui: false,
+            web_addr: None,
+            grpc_addr: None,
+            stream_addr: None,
+            stream_heartbeat: DEFAULT_HEARTBEAT,
+            health_addr: None,
+            health_max_age: DEFAULT_MAX_AGE,
+            testnet: false,
+            user_stream: false,
+            credentials: CredentialSource::Env,
+            accounts: Vec::new(),
+            account_policy: AccountPolicy::default(),
+            account_notional: None,
+            risk: None,
+            proxy: ProxyConfig::default(),
+            pnl_reference: "USDT".to_string(),
+            profile,
+        };
+        let mut webhook_format = WebhookFormat::Json;
+        let mut webhook_headers = Vec::new();
+        let mut kafka_topic = None;
+        let mut kafka_market_topic = None;
+        let mut kafka_properties = Vec::new();
+        let mut uniswap_pools = Vec::new();
+        let mut uniswap_poll = None;
+        let mut uniswap_notional = None;
+        let mut fix_sender = None;
+        let mut fix_target = None;
+        let mut fix_account = None;
+        let mut redis_stream = None;
+        let mut redis_channel = None;
+        let mut redis_max_len = None;
+        let mut nats_subject = None;
+        let mut nats_heartbeat_subject = None;
+        let mut nats_heartbeat_ms = None;
+        let mut slippage_bps = None;
+        let mut slippage_notional = None;
+        let mut slippage_beyond_top_bps = 5.0;
+        let mut dedup_given = false;
+        let mut ttl_given = false;
+
+        let mut unknown: Vec<String> = Vec::new(); // Flags nothing handles
+        let mut invalid: Vec<String> = Vec::new(); // Flags whose value is missing or unusable
+        while let Some(arg) = args.next() {
+            // The profile's flags come first, so errors can say which came from it
+            let from_profile = consumed.get() <= profile_len;
+            let problems = (unknown.len(), invalid.len());
+            match arg.as_str() {
+                // Already applied
+                "--profile" | "--config" => {
+                    args.next();
+                }
+                "--record" => match args.next() {
+                    Some(path) => parsed.record_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--audit-log" => match args.next() {
+                    Some(path) => parsed.audit_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--no-rest-snapshot" => parsed.rest_snapshot = false,
+                "--agg-trades" => match args.next() {
+                    Some(symbol) => parsed.agg_trades.push(symbol.to_lowercase()),
+                    None => invalid.push(arg),
+                },
+                "--trade-blend" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(weight) if (0.0..=1.0).contains(&weight) => parsed.trade_blend = Some(weight),
+                    _ => invalid.push(arg),
+                },
+                "--depth" => match args.next() {
+                    Some(symbol) => parsed.depth_streams.push(symbol.to_lowercase()),
+                    None => invalid.push(arg),
+                },
+                "--depth-notional" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(notional) if notional > 0.0 => parsed.depth = Some(DepthConfig::new(notional)),
+                    _ => invalid.push(arg),
+                },
+                "--max-tick-move-pct" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(0.0) => parsed.price_guard = None,
+                    Some(pct) if pct > 0.0 => parsed.price_guard.get_or_insert_with(PriceGuardConfig::default).max_move = pct / 100.0,
+                    _ => invalid.push(arg),
+                },
+                "--breaker-cooldown-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) => {
+                        if let Some(guard) = parsed.price_guard.as_mut() {
+                            guard.cooldown = Duration::from_millis(ms);
+                        }
+                    }
+                    None => invalid.push(arg),
+                },
+                "--min-volume" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(volume) if volume >= 0.0 => parsed.liquidity = Some(LiquidityConfig::new(volume)),
+                    _ => invalid.push(arg),
+                },
+                "--time-sync-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) => parsed.time_sync = Some(Duration::from_millis(ms)).filter(|d| !d.is_zero()),
+                    None => invalid.push(arg),
+                },
+                "--ws-failover" => {
+                    parsed.failover.get_or_insert_with(FailoverConfig::default);
+                }
+                "--ws-endpoint" => match args.next() {
+                    Some(url) => parsed.failover.get_or_insert_with(FailoverConfig::default).endpoints.push(url),
+                    None => invalid.push(arg),
+                },
+                "--ws-max-silence-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) => parsed.max_silence = Some(Duration::from_millis(ms)).filter(|d| !d.is_zero()),
+                    None => invalid.push(arg),
+                },
+                "--rest-fallback-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) if ms > 0 => {
+                        parsed.rest_fallback = Some(FallbackConfig { poll_interval: Duration::from_millis(ms), ..Default::default() });
+                    }
+                    _ => invalid.push(arg),
+                },
+                "--replay" => match args.next() {
+                    Some(path) => parsed.replay_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--simulate" => match args.next() {
+                    Some(path) => parsed.script_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--random-walk" => match args.next() {
+                    Some(path) => parsed.walk_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--sim-seed" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(seed) => parsed.sim.seed = seed,
+                    None => invalid.push(arg),
+                },
+                "--sim-steps" => match args.next().and_then(|v| v.parse::<usize>().ok()) {
+                    Some(steps) if steps > 0 => parsed.sim.steps = steps,
+                    _ => invalid.push(arg),
+                },
+                "--sim-move-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) if bps >= 0.0 => parsed.sim.max_move_bps = bps,
+                    _ => invalid.push(arg),
+                },
+                "--strategy" => match args.next() {
+                    Some(value) => parsed.strategy = value,
+                    None => invalid.push(arg),
+                },
+                "--log-level" => match args.next() {
+                    Some(value) => parsed.log_level = value,
+                    None => invalid.push(arg),
+                },
+                "--log-json" => parsed.log_json = true,
+                "--calendar" => match args.next() {
+                    Some(path) => parsed.calendar_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--plugin" => match args.next() {
+                    Some(path) => parsed.plugins.push(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--cbbo" => parsed.cbbo = true,
+                "--smart-routing" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(notional) if notional > 0.0 => parsed.routing = Some(RoutingConfig::new(notional)),
+                    _ => invalid.push(arg),
+                },
+                "--fees" => match args.next() {
+                    Some(path) => parsed.fees_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--telegram-min-bps" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(value) => parsed.telegram_min_bps = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--webhook-url" => match args.next() {
+                    Some(url) => parsed.webhook = Some(WebhookConfig::new(&url)),
+                    None => invalid.push(arg),
+                },
+                "--webhook-format" => match args.next().and_then(|f| WebhookFormat::parse(&f)) {
+                    Some(format) => webhook_format = format,
+                    None => invalid.push(arg),
+                },
+                "--webhook-header" => match args.next().as_deref().and_then(|h| h.split_once(':')) {
+                    Some((name, value)) => webhook_headers.push((name.trim().to_string(), value.trim().to_string())),
+                    None => invalid.push(arg),
+                },
+                "--kafka-brokers" => match args.next() {
+                    Some(brokers) => parsed.kafka = Some(KafkaConfig::new(&brokers)),
+                    None => invalid.push(arg),
+                },
+                "--kafka-topic" => match args.next() {
+                    Some(value) => kafka_topic = Some(value),
+                    None => invalid.push(arg),
+                },
+                // An empty topic turns market data publishing off
+                "--kafka-market-topic" => match args.next() {
+                    Some(value) => kafka_market_topic = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--kafka-property" => match args.next().as_deref().and_then(|p| p.split_once('=')) {
+                    Some((key, value)) => kafka_properties.push((key.trim().to_string(), value.trim().to_string())),
+                    None => invalid.push(arg),
+                },
+                "--redis-url" => match args.next() {
+                    Some(url) => parsed.redis = Some(RedisConfig::new(&url)),
+                    None => invalid.push(arg),
+                },
+                "--redis-stream" => match args.next() {
+                    Some(value) => redis_stream = Some(value),
+                    None => invalid.push(arg),
+                },
+                // An empty channel turns notifications off
+                "--redis-channel" => match args.next() {
+                    Some(value) => redis_channel = Some(value),
+                    None => invalid.push(arg),
+                },
+                // 0 leaves the stream uncapped
+                "--redis-max-len" => match args.next().and_then(|v| v.parse::<usize>().ok()) {
+                    Some(len) => redis_max_len = Some(len),
+                    None => invalid.push(arg),
+                },
+                "--nats-url" => match args.next() {
+                    Some(url) => parsed.nats = Some(NatsConfig::new(&url)),
+                    None => invalid.push(arg),
+                },
+                "--nats-subject" => match args.next() {
+                    Some(value) => nats_subject = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--nats-heartbeat-subject" => match args.next() {
+                    Some(value) => nats_heartbeat_subject = Some(value),
+                    None => invalid.push(arg),
+                },
+                // 0 turns heartbeats off
+                "--nats-heartbeat-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) => nats_heartbeat_ms = Some(ms),
+                    None => invalid.push(arg),
+                },
+                "--output" => match args.next().as_deref() {
+                    Some("jsonl") => parsed.output = OutputMode::Jsonl,
+                    Some("text") => parsed.output = OutputMode::Text,
+                    _ => invalid.push(arg),
+                },
+                "--output-file" => match args.next() {
+                    Some(path) => parsed.output_file = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--sqlite" => match args.next() {
+                    Some(path) => parsed.sqlite_path = Some(PathBuf::from(path)),
+                    None => invalid.push(arg),
+                },
+                "--dedup-cooldown-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(0) => parsed.dedup = None,
+                    Some(ms) => {
+                        dedup_given = true;
+                        parsed.dedup.get_or_insert_with(DedupConfig::default).cooldown = Duration::from_millis(ms)
+                    }
+                    None => invalid.push(arg),
+                },
+                "--dedup-close-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => {
+                        dedup_given = true;
+                        if let Some(dedup) = parsed.dedup.as_mut() {
+                            dedup.close_after = Duration::from_millis(ms);
+                        }
+                    }
+                    None => invalid.push(arg),
+                },
+                "--opportunity-ttl-max-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(0) => parsed.ttl = None,
+                    Some(ms) => {
+                        ttl_given = true;
+                        parsed.ttl.get_or_insert_with(TtlConfig::default).max_ttl = Duration::from_millis(ms)
+                    }
+                    None => invalid.push(arg),
+                },
+                "--opportunity-ttl-min-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => {
+                        ttl_given = true;
+                        if let Some(ttl) = parsed.ttl.as_mut() {
+                            ttl.min_ttl = Duration::from_millis(ms);
+                        }
+                    }
+                    None => invalid.push(arg),
+                },
+                "--min-profit-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => {
+                        parsed.persistence.get_or_insert_with(PersistenceConfig::default).min_duration = Duration::from_millis(ms)
+                    }
+                    None => invalid.push(arg),
+                },
+                "--min-profit-updates" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(updates) => parsed.persistence.get_or_insert_with(PersistenceConfig::default).min_updates = updates,
+                    None => invalid.push(arg),
+                },
+                "--change-epsilon-bps" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(bps) => parsed.change_epsilon_bps = bps,
+                    None => invalid.push(arg),
+                },
+                "--detect-interval-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.throttle.get_or_insert_with(ThrottleConfig::default).interval = Duration::from_millis(ms),
+                    None => invalid.push(arg),
+                },
+                "--detect-max-updates" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(updates) => parsed.throttle.get_or_insert_with(ThrottleConfig::default).max_updates = updates,
+                    None => invalid.push(arg),
+                },
+                "--max-cycle-len" => match args.next().and_then(|v| v.parse().ok()) {
+                    // A cycle needs at least two legs to return to its start
+                    Some(len) if len >= 2 => parsed.max_cycle_len = len,
+                    _ => invalid.push(arg),
+                },
+                "--home-asset" => match args.next() {
+                    Some(asset) => parsed.home_assets.push(asset.to_uppercase()),
+                    None => invalid.push(arg),
+                },
+                "--max-edge-age-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.max_edge_age = Some(Duration::from_millis(ms)),
+                    None => invalid.push(arg),
+                },
+                "--max-event-gap-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.sequence.get_or_insert_with(SequenceConfig::default).max_gap = Duration::from_millis(ms),
+                    None => invalid.push(arg),
+                },
+                "--invalidate-on-gap" => parsed.sequence.get_or_insert_with(SequenceConfig::default).invalidate = true,
+                "--parallel-detection" => parsed.parallel_detection = true,
+                "--tui" => parsed.tui = true,
+                "--web" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(addr) => parsed.web_addr = Some(addr),
+                    None => invalid.push(arg),
+                },
+                "--grpc" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(addr) => parsed.grpc_addr = Some(addr),
+                    None => invalid.push(arg),
+                },
+                "--stream-server" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(addr) => parsed.stream_addr = Some(addr),
+                    None => invalid.push(arg),
+                },
+                "--stream-heartbeat-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) if ms > 0 => parsed.stream_heartbeat = Duration::from_millis(ms),
+                    _ => invalid.push(arg),
+                },
+                "--health" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(addr) => parsed.health_addr = Some(addr),
+                    None => invalid.push(arg),
+                },
+                "--health-max-age-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.health_max_age = Duration::from_millis(ms),
+                    None => invalid.push(arg),
+                },
+                "--testnet" => parsed.testnet = true,
+                "--user-stream" => parsed.user_stream = true,
+                "--proxy" => match args.next().map(|v| Proxy::parse(&v)) {
+                    Some(Ok(proxy)) => parsed.proxy.default = Some(proxy),
+                    _ => invalid.push(arg),
+                },
+                "--venue-proxy" => match args.next().map(|v| parsed.proxy.add_override(&v)) {
+                    Some(Ok(())) => {}
+                    _ => invalid.push(arg),
+                },
+                "--credentials" => match args.next().and_then(|v| CredentialSource::parse(&v)) {
+                    Some(source) => parsed.credentials = source,
+                    None => invalid.push(arg),
+                },
+                "--account" => match args.next().and_then(|v| AccountConfig::parse(&v)) {
+                    Some(account) => parsed.accounts.push(account),
+                    None => invalid.push(arg),
+                },
+                "--account-policy" => match args.next().and_then(|v| AccountPolicy::parse(&v)) {
+                    Some(policy) => parsed.account_policy = policy,
+                    None => invalid.push(arg),
+                },
+                "--max-trade-notional" => match args.next().and_then(|v| v.parse::<Decimal>().ok()) {
+                    Some(max) if max > Decimal::ZERO => parsed.risk.get_or_insert_with(|| RiskConfig::new(RISK_REFERENCE, Decimal::ZERO)).max_trade_notional = Some(max),
+                    _ => invalid.push(arg),
+                },
+                "--max-position" => match args.next().and_then(|v| v.parse::<Decimal>().ok()) {
+                    Some(max) if max > Decimal::ZERO => parsed.risk.get_or_insert_with(|| RiskConfig::new(RISK_REFERENCE, Decimal::ZERO)).max_asset_exposure = Some(max),
+                    _ => invalid.push(arg),
+                },
+                "--max-trades-per-minute" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(max) if max > 0 => parsed.risk.get_or_insert_with(|| RiskConfig::new(RISK_REFERENCE, Decimal::ZERO)).max_trades_per_minute = Some(max),
+                    _ => invalid.push(arg),
+                },
+                "--max-daily-loss" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(max) if max > 0.0 => parsed.risk.get_or_insert_with(|| RiskConfig::new(RISK_REFERENCE, Decimal::ZERO)).daily_loss_limit = Some(max),
+                    _ => invalid.push(arg),
+                },
+                "--account-notional" => match args.next().and_then(|v| v.parse::<Decimal>().ok()) {
+                    Some(notional) if notional > Decimal::ZERO => parsed.account_notional = Some(notional),
+                    _ => invalid.push(arg),
+                },
+                "--pnl-reference" => match args.next() {
+                    Some(asset) => parsed.pnl_reference = asset.to_uppercase(),
+                    None => invalid.push(arg),
+                },
+                "--queue-capacity" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(capacity) => parsed.queue.capacity = capacity,
+                    None => invalid.push(arg),
+                },
+                "--queue-policy" => match args.next().and_then(|v| QueuePolicy::parse(&v)) {
+                    Some(policy) => parsed.queue.policy = policy,
+                    None => invalid.push(arg),
+                },
+                "--shards" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(value) => parsed.shards = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--top-n" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(value) => parsed.top_n = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--slippage-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) => slippage_bps = Some(bps),
+                    None => invalid.push(arg),
+                },
+                // Order size in USDT, sized against the top of book
+                "--slippage-notional" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(notional) => slippage_notional = Some(notional),
+                    None => invalid.push(arg),
+                },
+                "--slippage-beyond-top-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) => slippage_beyond_top_bps = bps,
+                    None => invalid.push(arg),
+                },
+                "--stable-edges-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) if bps >= 0.0 => parsed.stable_edges.get_or_insert_with(StableEdgeConfig::default).haircut_bps = bps,
+                    _ => invalid.push(arg),
+                },
+                "--depeg-alert-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) if bps <= 0.0 => parsed.depeg = None,
+                    Some(bps) => parsed.depeg.get_or_insert_with(DepegConfig::default).alert_bps = bps,
+                    None => invalid.push(arg),
+                },
+                "--spread-zscore" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(z) if z > 0.0 => parsed.spread.get_or_insert_with(SpreadConfig::default).z_threshold = z,
+                    _ => invalid.push(arg),
+                },
+                "--futures-stream" => match args.next() {
+                    Some(stream) => parsed.futures_streams.push(stream),
+                    None => invalid.push(arg),
+                },
+                "--uniswap-rpc" => match args.next() {
+                    Some(url) => parsed.uniswap = Some(UniswapConfig::new(&url)),
+                    None => invalid.push(arg),
+                },
+                "--uniswap-pool" => match args.next() {
+                    Some(address) => uniswap_pools.push(address),
+                    None => invalid.push(arg),
+                },
+                "--uniswap-poll-ms" => match args.next().and_then(|v| v.parse::<u64>().ok()) {
+                    Some(ms) if ms > 0 => uniswap_poll = Some(Duration::from_millis(ms)),
+                    _ => invalid.push(arg),
+                },
+                "--uniswap-notional-eth" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(eth) if eth > 0.0 => uniswap_notional = Some(eth),
+                    _ => invalid.push(arg),
+                },
+                "--fix" => match args.next() {
+                    Some(addr) => parsed.fix = Some(FixConfig::new(&addr, "", "")),
+                    None => invalid.push(arg),
+                },
+                "--fix-sender" => match args.next() {
+                    Some(value) => fix_sender = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--fix-target" => match args.next() {
+                    Some(value) => fix_target = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--fix-account" => match args.next() {
+                    Some(value) => fix_account = Some(value),
+                    None => invalid.push(arg),
+                },
+                "--fix-notional" => match args.next().and_then(|v| v.parse::<Decimal>().ok()) {
+                    Some(notional) if notional > Decimal::ZERO => parsed.fix_cycles = Some(CycleSizing::new(notional)),
+                    _ => invalid.push(arg),
+                },
+                "--profit-currency" => match args.next() {
+                    Some(asset) => parsed.valuation.get_or_insert_with(ValuationConfig::default).reference = asset.to_uppercase(),
+                    None => invalid.push(arg),
+                },
+                "--profit-notional" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(notional) if notional > 0.0 => parsed.valuation.get_or_insert_with(ValuationConfig::default).notional = notional,
+                    _ => invalid.push(arg),
+                },
+                "--trace-notional" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(notional) if notional > 0.0 => parsed.trace.get_or_insert_with(TraceConfig::default).notional = notional,
+                    _ => invalid.push(arg),
+                },
+                "--trace-fee-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) if bps >= 0.0 => parsed.trace.get_or_insert_with(TraceConfig::default).fee_bps = bps,
+                    _ => invalid.push(arg),
+                },
+                "--revalidate-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) => parsed.revalidate.get_or_insert_with(RevalidationConfig::default).min_profit_bps = bps,
+                    None => invalid.push(arg),
+                },
+                "--book-stats" => parsed.book_stats = true,
+                "--book-max-spread-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) => parsed.book_limits.get_or_insert_with(BookLimits::default).max_spread_bps = bps,
+                    None => invalid.push(arg),
+                },
+                "--book-max-imbalance" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(imbalance) if (0.0..=1.0).contains(&imbalance) => {
+                        parsed.book_limits.get_or_insert_with(BookLimits::default).max_imbalance = imbalance
+                    }
+                    _ => invalid.push(arg),
+                },
+                "--book-min-cover" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(cover) => parsed.book_limits.get_or_insert_with(BookLimits::default).min_cover = cover,
+                    None => invalid.push(arg),
+                },
+                "--hedge-pair" => match args.next() {
+                    Some(pair) => parsed.hedge.get_or_insert_with(|| HedgeConfig::new("")).pair = pair.to_uppercase(),
+                    None => invalid.push(arg),
+                },
+                "--hedge-band" => match args.next().and_then(|v| v.parse::<Decimal>().ok()) {
+                    Some(band) if band >= Decimal::ZERO => parsed.hedge.get_or_insert_with(|| HedgeConfig::new("")).band = band,
+                    _ => invalid.push(arg),
+                },
+                "--hedge-interval-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.hedge.get_or_insert_with(|| HedgeConfig::new("")).min_interval = Duration::from_millis(ms),
+                    None => invalid.push(arg),
+                },
+                "--last-look-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) if bps >= 0.0 => parsed.last_look.get_or_insert_with(LastLookConfig::default).tolerance_bps = bps,
+                    _ => invalid.push(arg),
+                },
+                "--last-look-log" => match args.next() {
+                    Some(path) => {
+                        parsed.last_look.get_or_insert_with(LastLookConfig::default);
+                        parsed.last_look_log = Some(PathBuf::from(path));
+                    }
+                    None => invalid.push(arg),
+                },
+                "--latency-budget-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.latency.get_or_insert_with(LatencyBudgetConfig::default).max_first_order = Duration::from_millis(ms),
+                    None => invalid.push(arg),
+                },
+                "--leg-latency-budget-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) => parsed.latency.get_or_insert_with(LatencyBudgetConfig::default).max_leg_gap = Duration::from_millis(ms),
+                    None => invalid.push(arg),
+                },
+                "--latency-breaches" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(n) if n > 0 => parsed.latency.get_or_insert_with(LatencyBudgetConfig::default).breaches = n,
+                    _ => invalid.push(arg),
+                },
+                "--revalidate-rest" => parsed.revalidate.get_or_insert_with(RevalidationConfig::default).rest_quotes = true,
+                "--lead-lag-bps" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(bps) if bps > 0.0 => parsed.lead_lag.get_or_insert_with(LeadLagConfig::default).threshold_bps = bps,
+                    _ => invalid.push(arg),
+                },
+                "--lead-lag-window-ms" => match args.next().and_then(|v| v.parse().ok()) {
+                    Some(ms) if ms > 0 => parsed.lead_lag.get_or_insert_with(LeadLagConfig::default).window = Duration::from_millis(ms),
+                    _ => invalid.push(arg),
+                },
+                "--basis-apr" => match args.next().and_then(|v| v.parse::<f64>().ok()) {
+                    Some(pct) if pct > 0.0 => parsed.basis.get_or_insert_with(BasisConfig::default).min_apr_pct = pct,
+                    _ => invalid.push(arg),
+                },
+                _ => unknown.push(arg),
+            }
+            if from_profile {
+                for flag in unknown[problems.0..].iter_mut().chain(&mut invalid[problems.1..]) {
+                    flag.push_str(&format!(" (profile {})", parsed.profile.as_deref().unwrap_or_default()));
+                }
+            }
+        }
+        // What follows an unknown flag is likely its value, so only the first is named
+        if let Some(flag) = unknown.first() {
+            return Err(format!("unknown option {}", flag));
+        }
+        if !invalid.is_empty() {
+            return Err(format!("missing or invalid value for {}", invalid.join(", ")));
+        }
+
+        if let Some(webhook) = parsed.webhook.as_mut() {
+            webhook.format = webhook_format;
+            webhook.headers = webhook_headers;
+            webhook.proxy = parsed.proxy.default.clone();
+        }
+        if let Some(kafka) = parsed.kafka.as_mut() {
+            kafka.opportunity_topic = kafka_topic.unwrap_or(kafka.opportunity_topic.clone());
+            if let Some(topic) = kafka_market_topic {
+                kafka.market_topic = Some(topic).filter(|t| !t.is_empty());
+            }
+            kafka.properties = kafka_properties;
+        }
+        if let Some(uniswap) = parsed.uniswap.as_mut() {
+            uniswap.pools = uniswap_pools;
+            uniswap.poll_interval = uniswap_poll.unwrap_or(uniswap.poll_interval);
+            uniswap.notional_eth = uniswap_notional.unwrap_or(uniswap.notional_eth);
+        }
+        // Dedup and TTLs go by the wall clock, which a simulation runs well ahead of, so
+        // they stay off there unless asked for
+        if parsed.script_path.is_some() || parsed.walk_path.is_some() {
+            if !dedup_given {
+                parsed.dedup = None;
+            }
+            if !ttl_given {
+                parsed.ttl = None;
+            }
+        }
+        // A session needs both CompIDs
+        if let Some(mut fix) = parsed.fix.take() {
+            match (fix_sender, fix_target) {
+                (Some(sender), Some(target)) => {
+                    fix.sender_comp_id = sender;
+                    fix.target_comp_id = target;
+                    fix.account = fix_account;
+                    parsed.fix = Some(fix);
+                }
+                _ => return Err("--fix needs both --fix-sender and --fix-target".to_string()),
+            }
+        }
+        // The band and interval mean nothing without a pair to hedge in
+        if parsed.hedge.as_ref().is_some_and(|hedge| hedge.pair.is_empty()) {
+            return Err("--hedge-band and --hedge-interval-ms need --hedge-pair".to_string());
+        }
+        // Hedges are orders, so they need somewhere to go
+        if parsed.hedge.is_some() && parsed.fix.is_none() && parsed.accounts.is_empty() {
+            return Err("--hedge-pair needs --fix or --account to place hedges".to_string());
+        }
+        // Accounts can't execute without a cycle size
+        if !parsed.accounts.is_empty() && parsed.account_notional.is_none() {
+            return Err("--account needs --account-notional <usdt>, the size of each cycle it executes".to_string());
+        }
+        // Limits are checked against the largest cycle any executor sends, and a cap below
+        // it would refuse every cycle
+        if let Some(risk) = parsed.risk.as_mut() {
+            let fix_notional = parsed.fix_cycles.as_ref().map(|sizing| sizing.notional);
+            risk.trade_notional = parsed.account_notional.into_iter().chain(fix_notional).max().unwrap_or_default();
+            if let Some(max) = risk.max_trade_notional.filter(|&max| risk.trade_notional > max) {
+                return Err(format!(
+                    "--max-trade-notional {} is below the {} USDT cycle size, so no cycle could go out",
+                    max, risk.trade_notional
+                ));
+            }
+        }
+        if let Some(redis) = parsed.redis.as_mut() {
+            redis.stream = redis_stream.unwrap_or(redis.stream.clone());
+            if let Some(channel) = redis_channel {
+                redis.channel = Some(channel).filter(|c| !c.is_empty());
+            }
+            if let Some(len) = redis_max_len {
+                redis.max_len = Some(len).filter(|&len| len > 0);
+            }
+        }
+        if let Some(nats) = parsed.nats.as_mut() {
+            nats.opportunity_subject = nats_subject.unwrap_or(nats.opportunity_subject.clone());
+            nats.heartbeat_subject = nats_heartbeat_subject.unwrap_or(nats.heartbeat_subject.clone());
+            if let Some(ms) = nats_heartbeat_ms {
+                nats.heartbeat_interval = Some(Duration::from_millis(ms)).filter(|d| !d.is_zero());
+            }
+        }
+        parsed.slippage = match (slippage_notional, slippage_bps) {
+            (Some(notional), fallback) => SlippageModel::Depth {
+                notional,
+                reference: "USDT".to_string(),
+                beyond_top_bps: slippage_beyond_top_bps,
+                fallback_bps: fallback.unwrap_or(slippage_beyond_top_bps),
+            },
+            (None, Some(bps)) => SlippageModel::Fixed { bps },
+            (None, None) => SlippageModel::None,
+        };
+        Ok(parsed)
+    }
+}
+
+// Value following the last occurrence of `flag`
+fn flag_value(args: &[String], flag: &str) -> Option<String> {
+    let at = args.iter().rposition(|arg| arg == flag)?;
+    args.get(at + 1).cloned()
+}
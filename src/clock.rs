@@ -0,0 +1,86 @@
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::task::JoinHandle;
+
+use crate::rest::{EndpointCategory, RestClient, RestError};
+use crate::supervisor::Supervisor;
+
+// Default for how often the offset is refreshed
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+// Offsets beyond this get a warning: signed requests outside recvWindow are rejected
+const OFFSET_WARNING_MS: i64 = 1000;
+
+// Local wall clock in ms since the epoch
+pub fn local_ms() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64)
+}
+
+// Response of GET /api/v3/time
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ServerTime {
+    server_time: i64,
+}
+
+// The exchange's clock as seen from here: the local clock plus an offset measured against
+// /api/v3/time. Until the first sync the offset is zero, i.e. the local clock.
+#[derive(Debug, Default)]
+pub struct ServerClock {
+    offset_ms: AtomicI64,
+    synced: AtomicBool,
+}
+
+impl ServerClock {
+    // Exchange time now, in ms since the epoch
+    pub fn now_ms(&self) -> i64 {
+        local_ms() + self.offset_ms()
+    }
+
+    // How far the exchange's clock is ahead of ours
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.synced.load(Ordering::Relaxed)
+    }
+
+    // Measures the offset once (weight 1) and stores it. The server's timestamp is taken
+    // to fall halfway through the round trip.
+    pub async fn sync(&self, client: &RestClient) -> Result<i64, RestError> {
+        let sent = local_ms();
+        let body = client.get(EndpointCategory::Market, 1, "/api/v3/time", &[]).await?;
+        let received = local_ms();
+        let time: ServerTime = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+        let offset = time.server_time - (sent + received) / 2;
+        let previous = self.offset_ms.swap(offset, Ordering::Relaxed);
+        let first = !self.synced.swap(true, Ordering::Relaxed);
+        if offset.abs() > OFFSET_WARNING_MS && (first || (previous - offset).abs() > OFFSET_WARNING_MS / 2) {
+            tracing::warn!(offset_ms = offset, round_trip_ms = received - sent, "Local clock is off from the exchange's");
+        } else {
+            tracing::debug!(offset_ms = offset, round_trip_ms = received - sent, "Synced with the exchange clock");
+        }
+        Ok(offset)
+    }
+
+    // Re-syncs every `interval` on a background task, keeping the last offset when a sync
+    // fails. The first sync is `interval` from now; await `sync` for one up front.
+    pub fn spawn_sync(self: &Arc<Self>, client: Arc<RestClient>, interval: Duration, supervisor: &Supervisor) -> JoinHandle<()> {
+        let clock = self.clone();
+        supervisor.spawn_service("clock sync", move || {
+            let (clock, client) = (clock.clone(), client.clone());
+            async move {
+                let mut ticks = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+                loop {
+                    ticks.tick().await;
+                    if let Err(e) = clock.sync(&client).await {
+                        tracing::warn!(error = %e, "Failed to sync with the exchange clock");
+                    }
+                }
+            }
+        })
+    }
+}
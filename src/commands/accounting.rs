@@ -0,0 +1,36 @@
+// `hft3 accounting <fills.jsonl> [--format beancount|ledger] [--output <path>]`
+// Converts the fill ledger into plain-text accounting entries.
+
+use std::path::PathBuf;
+
+use hft3::accounting::{export, AccountingFormat};
+use hft3::ledger::load_fills;
+
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut format = AccountingFormat::Beancount;
+    let mut output = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = args.next().unwrap_or_default();
+                format = AccountingFormat::parse(&value).ok_or(format!("unknown format {}, expected beancount or ledger", value))?;
+            }
+            "--output" => output = args.next().map(PathBuf::from),
+            _ => positional.push(arg),
+        }
+    }
+    let [path] = positional.as_slice() else {
+        return Err("usage: hft3 accounting <fills.jsonl> [--format beancount|ledger] [--output <path>]".into());
+    };
+    let fills = load_fills(path.as_ref()).map_err(|e| format!("failed to load {}: {}", path, e))?;
+    let text = export(&fills, format);
+    match output {
+        Some(output) => std::fs::write(&output, text).map_err(|e| format!("failed to write {}: {}", output.display(), e)),
+        None => {
+            print!("{}", text);
+            Ok(())
+        }
+    }
+}
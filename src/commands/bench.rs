@@ -0,0 +1,109 @@
+// `hft3 bench [<snapshot>] [--updates <n>] [--batch <n>] [--seed <n>]`
+// Loads a captured ticker snapshot (tick_data.txt by default), replays a synthetic random
+// walk over its prices and reports graph-update and find_arbitrage latencies, so a
+// regression in either hot path shows up without external tooling.
+
+use std::time::{Duration, Instant};
+
+use hft3::graph::Graph;
+use hft3::sim::{snapshot, Rng};
+use hft3::ticker::apply_ticker_data;
+
+const DEFAULT_SNAPSHOT: &str = "tick_data.txt";
+const DEFAULT_UPDATES: usize = 2_000;
+const DEFAULT_BATCH: usize = 50;
+const STEP_BPS: f64 = 5.0; // Largest price move per update
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted[((sorted.len() - 1) as f64 * p) as usize]
+}
+
+fn report(name: &str, mut samples: Vec<Duration>) {
+    samples.sort_unstable();
+    println!(
+        "{:<14} p50 {:>9.1?}  p99 {:>9.1?}  max {:>9.1?}",
+        name,
+        percentile(&samples, 0.5),
+        percentile(&samples, 0.99),
+        samples[samples.len() - 1]
+    );
+}
+
+fn parse_count(flag: &str, value: Option<String>) -> Result<usize, String> {
+    let value = value.unwrap_or_default();
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("{} expects a positive integer, got {:?}", flag, value)),
+    }
+}
+
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut updates = DEFAULT_UPDATES;
+    let mut batch = DEFAULT_BATCH;
+    let mut seed = 1;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--updates" => updates = parse_count("--updates", args.next())?,
+            "--batch" => batch = parse_count("--batch", args.next())?,
+            "--seed" => seed = parse_count("--seed", args.next())? as u64,
+            _ => positional.push(arg),
+        }
+    }
+    let path = match positional.as_slice() {
+        [] => DEFAULT_SNAPSHOT,
+        [path] => path.as_str(),
+        _ => return Err("usage: hft3 bench [<snapshot>] [--updates <n>] [--batch <n>] [--seed <n>]".into()),
+    };
+
+    // Latest ticker per symbol across the whole capture is the starting book
+    let tickers = snapshot(path.as_ref()).map_err(|e| format!("failed to load {}: {}", path, e))?;
+    if tickers.is_empty() {
+        return Err(format!("{} has no usable tickers", path));
+    }
+    let mut prices: Vec<f64> = tickers.iter().map(|t| t.c.parse().unwrap_or_default()).collect();
+
+    let mut graph = Graph::new();
+    apply_ticker_data(&mut graph, &tickers);
+    println!(
+        "{} symbols, {} assets, {} edges; {} updates of {} tickers, seed {}",
+        tickers.len(),
+        graph.vertex_count(),
+        graph.edge_count(),
+        updates,
+        batch,
+        seed
+    );
+
+    let mut rng = Rng::new(seed);
+    let mut update_samples = Vec::with_capacity(updates);
+    let mut detect_samples = Vec::with_capacity(updates);
+    let mut found = 0;
+    let mut pending = Vec::with_capacity(batch);
+    for _ in 0..updates {
+        // Build the batch first so only the graph work is timed
+        pending.clear();
+        for _ in 0..batch {
+            let i = rng.below(tickers.len());
+            prices[i] *= 1.0 + rng.signed_unit() * STEP_BPS / 10_000.0;
+            let mut ticker = tickers[i].clone();
+            ticker.c = prices[i].to_string();
+            pending.push(ticker);
+        }
+
+        let start = Instant::now();
+        apply_ticker_data(&mut graph, &pending);
+        update_samples.push(start.elapsed());
+
+        let start = Instant::now();
+        let cycle = graph.find_arbitrage();
+        detect_samples.push(start.elapsed());
+        found += cycle.is_some() as usize;
+    }
+
+    report("graph update", update_samples);
+    report("find_arbitrage", detect_samples);
+    println!("{} of {} passes found a cycle", found, updates);
+    Ok(())
+}
@@ -0,0 +1,91 @@
+// `hft3 credentials encrypt <path> | keyring [--account <name>] | check [--credentials <source>]`, each with [--testnet]
+// Stores API keys in an encrypted file or the OS keyring, and checks that a source loads.
+// Keys come from the environment's variables when set, otherwise from hidden prompts.
+
+use std::path::PathBuf;
+
+use hft3::credentials::{encrypt_file, read_passphrase, store_in_keyring, CredentialSource, Secret, PASSPHRASE_VAR};
+use hft3::rest::{ApiCredentials, BinanceEndpoints};
+
+const USAGE: &str =
+    "usage: hft3 credentials encrypt <path> | keyring [--account <name>] | check [--credentials <source>] [--testnet]";
+
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut account = None;
+    let mut source = CredentialSource::Env;
+    let mut testnet = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--account" => account = args.next(),
+            "--credentials" => {
+                let value = args.next().unwrap_or_default();
+                source = CredentialSource::parse(&value)
+                    .ok_or(format!("unknown source {}, expected env, file:<path> or keyring[:<account>]", value))?;
+            }
+            "--testnet" => testnet = true,
+            _ => positional.push(arg),
+        }
+    }
+    let endpoints = if testnet { BinanceEndpoints::testnet() } else { BinanceEndpoints::live() };
+    match positional.as_slice() {
+        [action, path] if action == "encrypt" => {
+            let credentials = read_credentials(&endpoints)?;
+            let passphrase = new_passphrase()?;
+            let path = PathBuf::from(path);
+            encrypt_file(&path, &credentials, &passphrase).map_err(|e| e.to_string())?;
+            println!("Wrote {} (use --credentials file:{})", path.display(), path.display());
+            Ok(())
+        }
+        [action] if action == "keyring" => {
+            let account = account.unwrap_or_else(|| endpoints.keyring_account.to_string());
+            let credentials = read_credentials(&endpoints)?;
+            store_in_keyring(&account, &credentials).map_err(|e| e.to_string())?;
+            println!("Stored in the keyring as hft3/{} (use --credentials keyring:{})", account, account);
+            Ok(())
+        }
+        [action] if action == "check" => {
+            let credentials = source.load(&endpoints).map_err(|e| e.to_string())?;
+            println!("{}: API key {}", source, mask(credentials.api_key.expose()));
+            Ok(())
+        }
+        _ => Err(USAGE.into()),
+    }
+}
+
+// The environment's key pair if both variables are set, otherwise typed without echo
+fn read_credentials(endpoints: &BinanceEndpoints) -> Result<ApiCredentials, String> {
+    if let Some(credentials) = endpoints.credentials() {
+        return Ok(credentials);
+    }
+    let prompt = |label: &str| rpassword::prompt_password(label).map_err(|e| format!("failed to read {}: {}", label.trim_end_matches(": "), e));
+    let credentials = ApiCredentials::new(prompt("API key: ")?, prompt("API secret: ")?);
+    if credentials.api_key.is_empty() || credentials.secret.is_empty() {
+        return Err("API key and secret must not be empty".into());
+    }
+    Ok(credentials)
+}
+
+// A passphrase for a new file, typed twice unless it comes from the environment
+fn new_passphrase() -> Result<Secret, String> {
+    let passphrase = read_passphrase("New passphrase: ").map_err(|e| e.to_string())?;
+    if std::env::var(PASSPHRASE_VAR).is_err() && read_passphrase("Repeat passphrase: ").map_err(|e| e.to_string())? != passphrase {
+        return Err("passphrases don't match".into());
+    }
+    if passphrase.is_empty() {
+        return Err("passphrase must not be empty".into());
+    }
+    Ok(passphrase)
+}
+
+// First and last four characters, enough to tell keys apart
+fn mask(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    if chars.len() <= 8 {
+        return "*".repeat(chars.len());
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
@@ -0,0 +1,74 @@
+// `hft3 download <symbol>... --from <date> --to <date> [--interval <interval>] [--agg-trades] [--output <path>]
+//                [--proxy <url>] [--testnet]`
+// Fetches historical candles (or aggregated trades) from the REST API and writes them as a
+// recorded session for --replay, e.g. `hft3 download BTCUSDT ETHBTC ETHUSDT --from 2024-03-01
+// --to 2024-03-02 --output march1.jsonl`. Dates are UTC days or RFC 3339 timestamps; `--to`
+// is exclusive. Requests wait on the usual REST weight budget.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use hft3::history::{write_session, HistorySource};
+use hft3::proxy::Proxy;
+use hft3::rest::{BinanceEndpoints, RateLimits, RestClient};
+
+const USAGE: &str = "usage: hft3 download <symbol>... --from <date> --to <date> [--interval <interval>] [--agg-trades] \
+                     [--output <path>] [--proxy <url>] [--testnet]";
+const DEFAULT_INTERVAL: &str = "1m";
+
+pub async fn run(args: Vec<String>) -> Result<(), String> {
+    let mut symbols = Vec::new();
+    let (mut from, mut to) = (None, None);
+    let mut interval = DEFAULT_INTERVAL.to_string();
+    let mut agg_trades = false;
+    let mut output = PathBuf::from("history.jsonl");
+    let mut proxy = None;
+    let mut testnet = false;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--from" => from = Some(parse_time(&args.next().unwrap_or_default())?),
+            "--to" => to = Some(parse_time(&args.next().unwrap_or_default())?),
+            "--interval" => interval = args.next().ok_or(USAGE)?,
+            "--agg-trades" => agg_trades = true,
+            "--output" => output = args.next().map(PathBuf::from).ok_or(USAGE)?,
+            "--proxy" => proxy = Some(Proxy::parse(&args.next().unwrap_or_default())?),
+            "--testnet" => testnet = true,
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}\n{}", flag, USAGE)),
+            _ => symbols.extend(arg.split(',').filter(|s| !s.is_empty()).map(str::to_uppercase)),
+        }
+    }
+    let (Some(from), Some(to)) = (from, to) else {
+        return Err(USAGE.into());
+    };
+    if symbols.is_empty() {
+        return Err(USAGE.into());
+    }
+    if from >= to {
+        return Err("--from must be before --to".into());
+    }
+    let source = if agg_trades { HistorySource::AggTrades } else { HistorySource::Klines { interval } };
+
+    let endpoints = if testnet { BinanceEndpoints::testnet() } else { BinanceEndpoints::live() };
+    let client = RestClient::new(&endpoints.rest, RateLimits::default()).with_proxy(proxy.as_ref());
+    let mut ticks = Vec::new();
+    for symbol in &symbols {
+        let fetched = source.fetch(&client, symbol, from, to).await.map_err(|e| format!("{}: {}", symbol, e))?;
+        eprintln!("{}: {} ticks", symbol, fetched.len());
+        ticks.extend(fetched);
+    }
+    let messages = write_session(&output, ticks).map_err(|e| format!("failed to write {}: {}", output.display(), e))?;
+    println!("Wrote {} messages to {} (replay with --replay {})", messages, output.display(), output.display());
+    Ok(())
+}
+
+// Epoch ms of a UTC date (midnight) or an RFC 3339 timestamp
+fn parse_time(value: &str) -> Result<u64, String> {
+    let time = match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        Ok(date) => date.and_hms_opt(0, 0, 0).expect("midnight exists").and_utc(),
+        Err(_) => DateTime::parse_from_rfc3339(value)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|_| format!("invalid time {}, expected YYYY-MM-DD or RFC 3339", value))?,
+    };
+    u64::try_from(time.timestamp_millis()).map_err(|_| format!("time {} is before 1970", value))
+}
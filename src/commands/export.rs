@@ -0,0 +1,61 @@
+// `hft3 export <audit.jsonl | fills.jsonl> [--columns <name[=header],...>] [--reference <asset>]
+//              [--delimiter <char>] [--output <path>]`
+// Writes every executed trade as CSV for tax and accounting tools, from the `fill` records
+// of an --audit-log or from a fill ledger, e.g. `hft3 export audit.jsonl --columns
+// time=Date,pair=Market,side=Type,quantity=Amount,price,fee,fee_asset=Currency --output
+// trades.csv`. Realized PnL is at average cost in the reference asset.
+
+use std::path::PathBuf;
+
+use hft3::trade_export::{export_csv, load_trades, TradeColumn};
+
+const USAGE: &str = "usage: hft3 export <audit.jsonl | fills.jsonl> [--columns <name[=header],...>] [--reference <asset>] \
+                     [--delimiter <char>] [--output <path>]";
+
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut columns: Vec<_> = TradeColumn::DEFAULT.iter().map(|column| (*column, column.name().to_string())).collect();
+    let mut reference = "USDT".to_string();
+    let mut delimiter = ',';
+    let mut output = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--columns" => {
+                columns = TradeColumn::parse_list(&args.next().unwrap_or_default())?;
+                if columns.is_empty() {
+                    return Err(USAGE.into());
+                }
+            }
+            "--reference" => reference = args.next().ok_or(USAGE)?.to_uppercase(),
+            "--delimiter" => {
+                let value = args.next().unwrap_or_default();
+                delimiter = match value.as_str() {
+                    "tab" | "\\t" => '\t',
+                    _ => {
+                        let mut chars = value.chars();
+                        match (chars.next(), chars.next()) {
+                            (Some(c), None) => c,
+                            _ => return Err(format!("delimiter must be one character, got {:?}", value)),
+                        }
+                    }
+                };
+            }
+            "--output" => output = args.next().map(PathBuf::from),
+            flag if flag.starts_with("--") => return Err(format!("unknown option {}\n{}", flag, USAGE)),
+            _ => positional.push(arg),
+        }
+    }
+    let [path] = positional.as_slice() else {
+        return Err(USAGE.into());
+    };
+    let fills = load_trades(path.as_ref()).map_err(|e| format!("failed to load {}: {}", path, e))?;
+    let csv = export_csv(&fills, &columns, &reference, delimiter);
+    match output {
+        Some(output) => std::fs::write(&output, csv).map_err(|e| format!("failed to write {}: {}", output.display(), e)),
+        None => {
+            print!("{}", csv);
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,7 @@
+pub mod accounting;
+pub mod bench;
+pub mod credentials;
+pub mod download;
+pub mod export;
+pub mod replay_diff;
+pub mod route;
@@ -0,0 +1,149 @@
+// `hft3 replay-diff <session> [--left <args>] [--right <args>] [--left-bin <path>] [--right-bin <path>]
+//                   [--tolerance-bps <bps>]`
+// Replays one recorded session through two builds or configurations and prints the detections
+// and execution decisions that differ, e.g.
+// `hft3 replay-diff session.jsonl --right-bin ./hft3-new --right "--strategy two-phase"`.
+// Exits non-zero when anything differs so it can gate a deploy.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+const DEFAULT_TOLERANCE_BPS: f64 = 0.01;
+
+// One reported opportunity, reduced to what should be identical between two runs
+#[derive(Debug, Clone)]
+struct Detection {
+    strategy: String,
+    cycle: String,
+    profit_bps: Option<f64>,
+    executed: bool,
+}
+
+// Detections grouped by the exchange event time that triggered them, then by
+// (strategy, cycle, occurrence) so repeats within one update still line up
+type Run = BTreeMap<u64, BTreeMap<(String, String, usize), Detection>>;
+
+// Runs `binary` on the session with jsonl output. Dedup keys on wall-clock time, which a
+// replay compresses, so it is disabled unless the extra arguments turn it back on.
+fn replay(binary: &Path, session: &str, extra: &str) -> Result<Run, String> {
+    let output = Command::new(binary)
+        .args(["--replay", session, "--output", "jsonl", "--log-level", "warn", "--dedup-cooldown-ms", "0"])
+        .args(extra.split_whitespace())
+        .env_remove("TELEGRAM_BOT_TOKEN")
+        .stdin(Stdio::null())
+        .stderr(Stdio::inherit())
+        .output()
+        .map_err(|e| format!("failed to run {}: {}", binary.display(), e))?;
+    if !output.status.success() {
+        return Err(format!("{} {} exited with {}", binary.display(), extra, output.status));
+    }
+
+    let mut run = Run::new();
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        let Ok(record) = serde_json::from_str::<Value>(line) else { continue };
+        if record["type"] != "opportunity" {
+            continue;
+        }
+        let detection = Detection {
+            strategy: record["strategy"].as_str().unwrap_or_default().to_string(),
+            cycle: record["cycle"].as_str().unwrap_or_default().to_string(),
+            profit_bps: record["profit_bps"].as_f64(),
+            executed: record["executed"].as_bool().unwrap_or(false),
+        };
+        let group = run.entry(record["event_time"].as_u64().unwrap_or(0)).or_default();
+        let occurrence = (0..)
+            .find(|&i| !group.contains_key(&(detection.strategy.clone(), detection.cycle.clone(), i)))
+            .unwrap_or(0);
+        group.insert((detection.strategy.clone(), detection.cycle.clone(), occurrence), detection);
+    }
+    Ok(run)
+}
+
+fn describe(detection: &Detection) -> String {
+    let profit = detection.profit_bps.map(|p| format!(" {:.2} bps", p)).unwrap_or_default();
+    let executed = if detection.executed { " executed" } else { "" };
+    format!("{} {}{}{}", detection.strategy, detection.cycle, profit, executed)
+}
+
+// Prints every difference at one event time and returns how many there were
+fn diff_group(
+    time: u64,
+    left: &BTreeMap<(String, String, usize), Detection>,
+    right: &BTreeMap<(String, String, usize), Detection>,
+    tolerance_bps: f64,
+) -> usize {
+    let mut changes = 0;
+    for (key, before) in left {
+        match right.get(key) {
+            None => {
+                println!("- {} {}", time, describe(before));
+                changes += 1;
+            }
+            Some(after) if before.executed != after.executed => {
+                println!("! {} {} {} executed {} -> {}", time, key.0, key.1, before.executed, after.executed);
+                changes += 1;
+            }
+            Some(after) => {
+                let moved = match (before.profit_bps, after.profit_bps) {
+                    (Some(a), Some(b)) => (a - b).abs() > tolerance_bps,
+                    (a, b) => a.is_some() != b.is_some(),
+                };
+                if moved {
+                    println!("~ {} {} -> {}", time, describe(before), describe(after));
+                    changes += 1;
+                }
+            }
+        }
+    }
+    for (key, after) in right {
+        if !left.contains_key(key) {
+            println!("+ {} {}", time, describe(after));
+            changes += 1;
+        }
+    }
+    changes
+}
+
+pub fn run(args: Vec<String>) -> Result<(), String> {
+    let current = std::env::current_exe().map_err(|e| format!("cannot locate this binary: {}", e))?;
+    let mut positional = Vec::new();
+    let (mut left_bin, mut right_bin) = (current.clone(), current);
+    let (mut left_args, mut right_args) = (String::new(), String::new());
+    let mut tolerance_bps = DEFAULT_TOLERANCE_BPS;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--left" => left_args = args.next().unwrap_or_default(),
+            "--right" => right_args = args.next().unwrap_or_default(),
+            "--left-bin" => left_bin = args.next().map(PathBuf::from).unwrap_or(left_bin),
+            "--right-bin" => right_bin = args.next().map(PathBuf::from).unwrap_or(right_bin),
+            "--tolerance-bps" => tolerance_bps = args.next().and_then(|v| v.parse().ok()).unwrap_or(tolerance_bps),
+            _ => positional.push(arg),
+        }
+    }
+    let [session] = positional.as_slice() else {
+        return Err("usage: hft3 replay-diff <session> [--left <args>] [--right <args>] [--left-bin <path>] \
+                    [--right-bin <path>] [--tolerance-bps <bps>]"
+            .into());
+    };
+
+    let left = replay(&left_bin, session, &left_args)?;
+    let right = replay(&right_bin, session, &right_args)?;
+
+    let empty = BTreeMap::new();
+    let mut changes = 0;
+    let times: BTreeSet<u64> = left.keys().chain(right.keys()).copied().collect();
+    for time in times {
+        changes += diff_group(time, left.get(&time).unwrap_or(&empty), right.get(&time).unwrap_or(&empty), tolerance_bps);
+    }
+
+    let count = |run: &Run| run.values().map(BTreeMap::len).sum::<usize>();
+    println!("{} detections left, {} right, {} changed", count(&left), count(&right), changes);
+    if changes > 0 {
+        return Err(format!("{} changed detections", changes));
+    }
+    Ok(())
+}
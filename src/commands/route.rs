@@ -0,0 +1,81 @@
+// `hft3 route <from> <to> <amount> [--fees <path>] [--max-steps <n>] [--proxy <url>]`
+// Prints the cheapest trade-and-transfer route, e.g. `hft3 route USDT@binance ETH@binance 1000`.
+
+use std::path::PathBuf;
+
+use hft3::proxy::Proxy;
+use hft3::rest::{RateLimits, RestClient, BINANCE_REST_URL};
+use hft3::route::{FeeTable, Location, RoutePlanner, RouteStep};
+use hft3::snapshot::fetch_book_tickers;
+use rust_decimal::Decimal;
+
+const DEFAULT_MAX_STEPS: usize = 4;
+
+pub async fn run(args: Vec<String>) -> Result<(), String> {
+    let mut positional = Vec::new();
+    let mut fees_path = None;
+    let mut max_steps = DEFAULT_MAX_STEPS;
+    let mut proxy = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--fees" => fees_path = args.next().map(PathBuf::from),
+            "--max-steps" => max_steps = args.next().and_then(|v| v.parse().ok()).unwrap_or(max_steps),
+            "--proxy" => proxy = Some(Proxy::parse(&args.next().unwrap_or_default())?),
+            _ => positional.push(arg),
+        }
+    }
+    let [from, to, amount] = positional.as_slice() else {
+        return Err("usage: hft3 route <asset@venue> <asset@venue> <amount> [--fees <path>] [--max-steps <n>] [--proxy <url>]".into());
+    };
+    let from = Location::parse(from).ok_or(format!("invalid location {}, expected ASSET@venue", from))?;
+    let to = Location::parse(to).ok_or(format!("invalid location {}, expected ASSET@venue", to))?;
+    let amount: Decimal = amount.parse().map_err(|_| format!("invalid amount {}", amount))?;
+    let fees = match fees_path {
+        Some(path) => FeeTable::load(&path).map_err(|e| format!("failed to load {}: {}", path.display(), e))?,
+        None => FeeTable::default(),
+    };
+
+    // Binance is the only venue with a market data connector; other venues are reachable
+    // through transfers in the fee table
+    let client = RestClient::new(BINANCE_REST_URL, RateLimits::default()).with_proxy(proxy.as_ref());
+    let tickers = fetch_book_tickers(&client).await.map_err(|e| e.to_string())?;
+    let quotes: Vec<_> = tickers.iter().filter_map(|t| t.to_venue_quote("binance")).collect();
+
+    let planner = RoutePlanner::new(&quotes, &fees);
+    let route = planner
+        .plan(&from, &to, amount, max_steps)
+        .ok_or(format!("no route from {} to {} within {} steps", from, to, max_steps))?;
+
+    println!("{} {} -> {}", amount, from, to);
+    for (i, step) in route.steps.iter().enumerate() {
+        let (before, after) = (route.amounts[i], route.amounts[i + 1]);
+        match step {
+            RouteStep::Trade { venue, symbol, side, price, fee_bps } => println!(
+                "  {}. {} {} on {} @ {} (fee {} bps): {:.8} -> {:.8}",
+                i + 1,
+                side.as_str(),
+                symbol,
+                venue,
+                price,
+                fee_bps,
+                before,
+                after
+            ),
+            RouteStep::Transfer { asset, from, to, fee, minutes } => println!(
+                "  {}. TRANSFER {} {} -> {} (fee {} {}, ~{} min): {:.8} -> {:.8}",
+                i + 1,
+                asset,
+                from,
+                to,
+                fee,
+                asset,
+                minutes,
+                before,
+                after
+            ),
+        }
+    }
+    println!("Receive {:.8} {}", route.final_amount(), to);
+    Ok(())
+}
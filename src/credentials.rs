@@ -0,0 +1,250 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use argon2::Argon2;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::rest::{ApiCredentials, BinanceEndpoints};
+
+// Where the passphrase for encrypted credential files comes from when not typed in
+pub const PASSPHRASE_VAR: &str = "HFT3_CREDENTIALS_PASSPHRASE";
+pub const KEYRING_SERVICE: &str = "hft3";
+
+const FILE_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+
+// A string that never shows up in logs or panic messages: Debug prints a placeholder,
+// there is no Display, and the memory is wiped when it drops
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Secret(value.into())
+    }
+
+    // The only way to read the value; keep the result out of anything that gets printed
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Secret(<redacted>)")
+    }
+}
+
+impl Drop for Secret {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[derive(Debug)]
+pub enum CredentialsError {
+    MissingEnv(String), // Variable name
+    Io(PathBuf, io::Error),
+    Format(String),
+    Decrypt, // Wrong passphrase or a damaged file; the two can't be told apart
+    Keyring(keyring::Error),
+}
+
+impl fmt::Display for CredentialsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CredentialsError::MissingEnv(var) => write!(f, "{} is not set", var),
+            CredentialsError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            CredentialsError::Format(e) => write!(f, "Malformed credentials: {}", e),
+            CredentialsError::Decrypt => write!(f, "Could not decrypt credentials: wrong passphrase or damaged file"),
+            CredentialsError::Keyring(e) => write!(f, "Keyring: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for CredentialsError {}
+
+// Plaintext inside an encrypted file or keyring entry
+#[derive(serde::Serialize, serde::Deserialize)]
+struct StoredCredentials {
+    api_key: String,
+    secret: String,
+}
+
+impl Drop for StoredCredentials {
+    fn drop(&mut self) {
+        self.api_key.zeroize();
+        self.secret.zeroize();
+    }
+}
+
+impl StoredCredentials {
+    fn from_credentials(credentials: &ApiCredentials) -> Self {
+        StoredCredentials {
+            api_key: credentials.api_key.expose().to_string(),
+            secret: credentials.secret.expose().to_string(),
+        }
+    }
+
+    fn to_credentials(&self) -> ApiCredentials {
+        ApiCredentials::new(self.api_key.as_str(), self.secret.as_str())
+    }
+}
+
+// On-disk format of an encrypted credentials file; binary fields are hex
+#[derive(serde::Serialize, serde::Deserialize)]
+struct EncryptedFile {
+    version: u32,
+    kdf: String, // argon2id with the crate's default parameters
+    salt: String,
+    nonce: String,
+    ciphertext: String, // XChaCha20-Poly1305 over the JSON of StoredCredentials
+}
+
+fn derive_key(passphrase: &Secret, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>, CredentialsError> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    Argon2::default()
+        .hash_password_into(passphrase.expose().as_bytes(), salt, key.as_mut())
+        .map_err(|e| CredentialsError::Format(e.to_string()))?;
+    Ok(key)
+}
+
+// Writes `credentials` to `path`, encrypted with a key derived from `passphrase`.
+// The file is readable by its owner only.
+pub fn encrypt_file(path: &Path, credentials: &ApiCredentials, passphrase: &Secret) -> Result<(), CredentialsError> {
+    let mut salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut salt);
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let plaintext = Zeroizing::new(
+        serde_json::to_vec(&StoredCredentials::from_credentials(credentials)).map_err(|e| CredentialsError::Format(e.to_string()))?,
+    );
+    let ciphertext = cipher.encrypt(&nonce, plaintext.as_slice()).map_err(|_| CredentialsError::Decrypt)?;
+    let file = EncryptedFile {
+        version: FILE_VERSION,
+        kdf: "argon2id".to_string(),
+        salt: hex::encode(salt),
+        nonce: hex::encode(nonce),
+        ciphertext: hex::encode(ciphertext),
+    };
+    let json = serde_json::to_string_pretty(&file).map_err(|e| CredentialsError::Format(e.to_string()))?;
+    write_private(path, json.as_bytes()).map_err(|e| CredentialsError::Io(path.to_path_buf(), e))
+}
+
+#[cfg(unix)]
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+    file.write_all(contents)
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    fs::write(path, contents)
+}
+
+pub fn decrypt_file(path: &Path, passphrase: &Secret) -> Result<ApiCredentials, CredentialsError> {
+    let text = fs::read_to_string(path).map_err(|e| CredentialsError::Io(path.to_path_buf(), e))?;
+    let file: EncryptedFile = serde_json::from_str(&text).map_err(|e| CredentialsError::Format(e.to_string()))?;
+    if file.version != FILE_VERSION || file.kdf != "argon2id" {
+        return Err(CredentialsError::Format(format!("unsupported version {} ({})", file.version, file.kdf)));
+    }
+    let decode = |field: &str| hex::decode(field).map_err(|e| CredentialsError::Format(e.to_string()));
+    let (salt, nonce, ciphertext) = (decode(&file.salt)?, decode(&file.nonce)?, decode(&file.ciphertext)?);
+    if nonce.len() != 24 {
+        return Err(CredentialsError::Format("bad nonce length".to_string()));
+    }
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = XChaCha20Poly1305::new(key.as_ref().into());
+    let plaintext = Zeroizing::new(
+        cipher
+            .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+            .map_err(|_| CredentialsError::Decrypt)?,
+    );
+    // Serde errors can quote the input, so don't pass them on
+    let stored: StoredCredentials = serde_json::from_slice(&plaintext).map_err(|_| CredentialsError::Format("bad payload".to_string()))?;
+    Ok(stored.to_credentials())
+}
+
+// The passphrase from HFT3_CREDENTIALS_PASSPHRASE, or typed at the terminal without echo
+pub fn read_passphrase(prompt: &str) -> Result<Secret, CredentialsError> {
+    if let Ok(passphrase) = std::env::var(PASSPHRASE_VAR) {
+        return Ok(Secret::new(passphrase));
+    }
+    rpassword::prompt_password(prompt)
+        .map(Secret::new)
+        .map_err(|e| CredentialsError::Io(PathBuf::from("<terminal>"), e))
+}
+
+// Keyring entries hold both halves as one JSON password under service "hft3"
+pub fn store_in_keyring(account: &str, credentials: &ApiCredentials) -> Result<(), CredentialsError> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, account).map_err(CredentialsError::Keyring)?;
+    let payload = Zeroizing::new(
+        serde_json::to_string(&StoredCredentials::from_credentials(credentials)).map_err(|e| CredentialsError::Format(e.to_string()))?,
+    );
+    entry.set_password(&payload).map_err(CredentialsError::Keyring)
+}
+
+pub fn load_from_keyring(account: &str) -> Result<ApiCredentials, CredentialsError> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, account).map_err(CredentialsError::Keyring)?;
+    let payload = Zeroizing::new(entry.get_password().map_err(CredentialsError::Keyring)?);
+    let stored: StoredCredentials = serde_json::from_str(&payload).map_err(|_| CredentialsError::Format("bad keyring entry".to_string()))?;
+    Ok(stored.to_credentials())
+}
+
+// Where to load API keys from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialSource {
+    Env,             // The environment's key and secret variables
+    File(PathBuf),   // Encrypted file, unlocked with the passphrase
+    Keyring(String), // OS keyring entry under this account name
+}
+
+impl CredentialSource {
+    // "env", "file:<path>", "keyring" or "keyring:<account>"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.split_once(':') {
+            None if value == "env" => Some(CredentialSource::Env),
+            None if value == "keyring" => Some(CredentialSource::Keyring(String::new())),
+            Some(("file", path)) if !path.is_empty() => Some(CredentialSource::File(PathBuf::from(path))),
+            Some(("keyring", account)) if !account.is_empty() => Some(CredentialSource::Keyring(account.to_string())),
+            _ => None,
+        }
+    }
+
+    // Loads the keys for `endpoints`; a keyring source without an account uses the
+    // environment's default one
+    pub fn load(&self, endpoints: &BinanceEndpoints) -> Result<ApiCredentials, CredentialsError> {
+        match self {
+            CredentialSource::Env => {
+                let var = |name: &str| std::env::var(name).map_err(|_| CredentialsError::MissingEnv(name.to_string()));
+                Ok(ApiCredentials::new(var(endpoints.key_var)?, var(endpoints.secret_var)?))
+            }
+            CredentialSource::File(path) => decrypt_file(path, &read_passphrase("Credentials passphrase: ")?),
+            CredentialSource::Keyring(account) if account.is_empty() => load_from_keyring(endpoints.keyring_account),
+            CredentialSource::Keyring(account) => load_from_keyring(account),
+        }
+    }
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CredentialSource::Env => f.write_str("env"),
+            CredentialSource::File(path) => write!(f, "file:{}", path.display()),
+            CredentialSource::Keyring(account) if account.is_empty() => f.write_str("keyring"),
+            CredentialSource::Keyring(account) => write!(f, "keyring:{}", account),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+// Live terminal dashboard for the bot (`--tui`): feed status and throughput, the best
+// cycles currently open, recent opportunities and detection latency.
+//
+// Keys: q quit.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hft3::pipeline::PipelineMetrics;
+use hft3::shared_graph::SharedGraph;
+use hft3::{EngineEvent, Opportunity};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::broadcast::{self, error::TryRecvError};
+use tokio::task::JoinHandle;
+
+const REFRESH_INTERVAL: Duration = Duration::from_millis(250);
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+const QUIET_AFTER: Duration = Duration::from_secs(5); // No graph updates for this long marks the feed quiet
+const OPEN_TIMEOUT: Duration = Duration::from_secs(5); // Cycles not re-detected for this long drop off the top list
+const RECENT: usize = 100;
+const LATENCY_SAMPLES: usize = 1000;
+
+// A cycle detected recently enough to still be considered open
+struct OpenCycle {
+    path: Vec<String>,
+    profit_bps: Option<f64>,
+    opened: Instant,
+    last_seen: Instant,
+}
+
+struct Recent {
+    seen: Instant,
+    strategy: String,
+    path: Vec<String>,
+    profit_bps: Option<f64>,
+    executed: bool,
+}
+
+struct Dashboard {
+    events: broadcast::Receiver<EngineEvent>,
+    graph: Arc<SharedGraph>,
+    pipeline: Option<Arc<PipelineMetrics>>,
+    started: Instant,
+    feed_closed: bool,
+    last_update: Option<Instant>,              // Last time the graph snapshot advanced
+    sampled: (Instant, u64, u64),              // (when, ingest events, graph epoch) at the last rate sample
+    rates: (Option<f64>, f64),                 // (ingest events/s when known, detection passes/s)
+    open: HashMap<String, OpenCycle>,          // By cycle key
+    recent: VecDeque<Recent>,                  // Newest first
+    latencies: VecDeque<(Duration, Duration)>, // (receive to graph, graph to detect)
+    halted: Option<String>,
+    kill_switch: Option<String>,
+    depegs: HashMap<String, f64>,
+    skipped: u64, // Events lost because the dashboard fell behind
+}
+
+fn profit_bps(opportunity: &Opportunity) -> Option<f64> {
+    let gross = (!opportunity.rates.is_empty()).then(|| opportunity.rates.iter().product::<f64>() - 1.0);
+    opportunity.profit.or(gross).map(|p| p * 10_000.0)
+}
+
+fn format_bps(bps: Option<f64>) -> String {
+    bps.map(|bps| format!("{:.2}", bps)).unwrap_or_else(|| "-".to_string())
+}
+
+fn format_age(age: Duration) -> String {
+    match age.as_secs() {
+        0 => format!("{}ms", age.as_millis()),
+        s if s < 120 => format!("{}s", s),
+        s => format!("{}m", s / 60),
+    }
+}
+
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    sorted.get(((sorted.len().saturating_sub(1)) as f64 * p) as usize).copied().unwrap_or_default()
+}
+
+impl Dashboard {
+    fn new(events: broadcast::Receiver<EngineEvent>, graph: Arc<SharedGraph>, pipeline: Option<Arc<PipelineMetrics>>) -> Self {
+        let now = Instant::now();
+        let ingested = pipeline.as_ref().map_or(0, |m| m.snapshot().events);
+        let epoch = graph.epoch();
+        Dashboard {
+            events,
+            graph,
+            pipeline,
+            started: now,
+            feed_closed: false,
+            last_update: None,
+            sampled: (now, ingested, epoch),
+            rates: (None, 0.0),
+            open: HashMap::new(),
+            recent: VecDeque::with_capacity(RECENT),
+            latencies: VecDeque::with_capacity(LATENCY_SAMPLES),
+            halted: None,
+            kill_switch: None,
+            depegs: HashMap::new(),
+            skipped: 0,
+        }
+    }
+
+    // Applies every event broadcast since the last refresh
+    fn drain(&mut self) {
+        loop {
+            match self.events.try_recv() {
+                Ok(event) => self.apply(event),
+                Err(TryRecvError::Lagged(skipped)) => self.skipped += skipped,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Closed) => {
+                    self.feed_closed = true;
+                    break;
+                }
+            }
+        }
+    }
+
+    fn apply(&mut self, event: EngineEvent) {
+        let now = Instant::now();
+        match event {
+            EngineEvent::Opportunity(opportunity) => {
+                let bps = profit_bps(&opportunity);
+                let cycle = self.open.entry(opportunity.cycle_key()).or_insert_with(|| OpenCycle {
+                    path: opportunity.path.clone(),
+                    profit_bps: bps,
+                    opened: now,
+                    last_seen: now,
+                });
+                cycle.profit_bps = bps;
+                cycle.last_seen = now;
+
+                if let Some(latency) = opportunity.latency {
+                    if self.latencies.len() == LATENCY_SAMPLES {
+                        self.latencies.pop_back();
+                    }
+                    self.latencies.push_front((latency.receive_to_graph, latency.graph_to_detect));
+                }
+                if self.recent.len() == RECENT {
+                    self.recent.pop_back();
+                }
+                self.recent.push_front(Recent {
+                    seen: now,
+                    strategy: opportunity.strategy,
+                    path: opportunity.path,
+                    profit_bps: bps,
+                    executed: opportunity.executed,
+                });
+            }
+            EngineEvent::OpportunityClosed(summary) => {
+                self.open.remove(&summary.cycle);
+            }
+            EngineEvent::TradingHalted { reason } => self.halted = Some(reason),
+            EngineEvent::TradingResumed => self.halted = None,
+            EngineEvent::KillSwitchTripped { reason } => self.kill_switch = Some(reason),
+            EngineEvent::KillSwitchReset => self.kill_switch = None,
+            EngineEvent::StablecoinDepeg { asset, deviation_bps } => {
+                self.depegs.insert(asset, deviation_bps);
+            }
+            EngineEvent::StablecoinRepegged { asset } => {
+                self.depegs.remove(&asset);
+            }
+            EngineEvent::FeedClosed => self.feed_closed = true,
+            _ => {}
+        }
+    }
+
+    // Recomputes throughput once per window and drops cycles that stopped being detected
+    fn tick(&mut self) {
+        let now = Instant::now();
+        self.open.retain(|_, cycle| now.duration_since(cycle.last_seen) < OPEN_TIMEOUT);
+
+        let (since, ingested, epoch) = self.sampled;
+        let elapsed = now.duration_since(since);
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+        let current_ingested = self.pipeline.as_ref().map(|m| m.snapshot().events);
+        let current_epoch = self.graph.epoch();
+        if current_epoch != epoch {
+            self.last_update = Some(now);
+        }
+        let seconds = elapsed.as_secs_f64();
+        self.rates = (
+            current_ingested.map(|n| (n - ingested) as f64 / seconds),
+            (current_epoch - epoch) as f64 / seconds,
+        );
+        self.sampled = (now, current_ingested.unwrap_or(0), current_epoch);
+    }
+
+    fn status(&self) -> (&'static str, Color) {
+        match self.last_update {
+            _ if self.feed_closed => ("CLOSED", Color::Red),
+            None => ("WAITING", Color::Yellow),
+            Some(at) if at.elapsed() > QUIET_AFTER => ("QUIET", Color::Yellow),
+            Some(_) => ("LIVE", Color::Green),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [header, body, latency_area, footer] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Min(0),
+            Constraint::Length(4),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [top_area, recent_area] = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(body);
+        let bold = Style::default().add_modifier(Modifier::BOLD);
+        let now = Instant::now();
+
+        let (status, color) = self.status();
+        let snapshot = self.graph.load();
+        let ingest = match self.rates.0 {
+            Some(rate) => format!("{:.0} msg/s", rate),
+            None => "msg/s n/a".to_string(),
+        };
+        let mut alerts = Vec::new();
+        if let Some(reason) = &self.halted {
+            alerts.push(format!("trading halted: {}", reason));
+        }
+        if let Some(reason) = &self.kill_switch {
+            alerts.push(format!("kill switch: {}", reason));
+        }
+        let mut depegs: Vec<_> = self.depegs.iter().collect();
+        depegs.sort_by(|a, b| a.0.cmp(b.0));
+        alerts.extend(depegs.into_iter().map(|(asset, bps)| format!("{} depeg {:.1} bps", asset, bps)));
+        if self.skipped > 0 {
+            alerts.push(format!("{} events dropped", self.skipped));
+        }
+        let lines = vec![
+            Line::from(vec![
+                Span::styled(status, bold.fg(color)),
+                Span::raw(format!(
+                    "  up {}  {}  {:.1} passes/s  assets {}  edges {}",
+                    format_age(self.started.elapsed()),
+                    ingest,
+                    self.rates.1,
+                    snapshot.vertex_count(),
+                    snapshot.edge_count()
+                )),
+            ]),
+            Line::from(alerts.join("  |  ")).style(Style::default().fg(Color::Red)),
+        ];
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("hft3")), header);
+
+        let mut top: Vec<&OpenCycle> = self.open.values().collect();
+        top.sort_by(|a, b| b.profit_bps.unwrap_or(f64::MIN).total_cmp(&a.profit_bps.unwrap_or(f64::MIN)));
+        let rows = top.iter().map(|cycle| {
+            Row::new(vec![
+                format_bps(cycle.profit_bps),
+                format_age(now.duration_since(cycle.opened)),
+                cycle.path.join(">"),
+            ])
+        });
+        let table = Table::new(rows, [Constraint::Length(9), Constraint::Length(7), Constraint::Min(10)])
+            .header(Row::new(vec!["bps", "open", "cycle"]).style(bold))
+            .block(Block::default().borders(Borders::ALL).title(format!("Top spreads ({} open)", top.len())));
+        frame.render_widget(table, top_area);
+
+        let rows = self.recent.iter().map(|recent| {
+            let row = Row::new(vec![
+                format_age(now.duration_since(recent.seen)),
+                format_bps(recent.profit_bps),
+                recent.strategy.clone(),
+                recent.path.join(">"),
+            ]);
+            if recent.executed {
+                row.style(Style::default().fg(Color::Green))
+            } else {
+                row
+            }
+        });
+        let table = Table::new(
+            rows,
+            [Constraint::Length(7), Constraint::Length(9), Constraint::Length(20), Constraint::Min(10)],
+        )
+        .header(Row::new(vec!["age", "bps", "strategy", "path"]).style(bold))
+        .block(Block::default().borders(Borders::ALL).title("Recent opportunities"));
+        frame.render_widget(table, recent_area);
+
+        let mut graph: Vec<Duration> = self.latencies.iter().map(|l| l.0).collect();
+        let mut detect: Vec<Duration> = self.latencies.iter().map(|l| l.1).collect();
+        graph.sort_unstable();
+        detect.sort_unstable();
+        let describe = |name: &str, sorted: &[Duration]| {
+            format!(
+                "{:<16} p50 {:>9.1?}  p99 {:>9.1?}  max {:>9.1?}",
+                name,
+                percentile(sorted, 0.5),
+                percentile(sorted, 0.99),
+                sorted.last().copied().unwrap_or_default()
+            )
+        };
+        let lines = vec![
+            Line::from(describe("receive to graph", &graph)),
+            Line::from(describe("graph to detect", &detect)),
+        ];
+        let title = format!("Detection latency (last {})", self.latencies.len());
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), latency_area);
+
+        frame.render_widget(Line::from("q quit"), footer);
+    }
+}
+
+fn run(terminal: &mut DefaultTerminal, dashboard: &mut Dashboard) -> std::io::Result<()> {
+    loop {
+        dashboard.drain();
+        dashboard.tick();
+        terminal.draw(|frame| dashboard.draw(frame))?;
+
+        if event::poll(REFRESH_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+// Takes over the terminal on a blocking thread until the operator quits. `pipeline` is
+// the ingest stage's counters when the feed runs behind one; without it msg/s isn't shown.
+pub fn spawn(
+    events: broadcast::Receiver<EngineEvent>,
+    graph: Arc<SharedGraph>,
+    pipeline: Option<Arc<PipelineMetrics>>,
+) -> JoinHandle<()> {
+    let mut dashboard = Dashboard::new(events, graph, pipeline);
+    tokio::task::spawn_blocking(move || {
+        let mut terminal = ratatui::init();
+        let result = run(&mut terminal, &mut dashboard);
+        ratatui::restore();
+        if let Err(e) = result {
+            eprintln!("Dashboard error: {:?}", e);
+        }
+    })
+}
+
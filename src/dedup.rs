@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::events::{Opportunity, OpportunitySummary};
+
+#[derive(Debug, Clone)]
+pub struct DedupConfig {
+    pub cooldown: Duration,    // A still-open cycle is re-reported at most this often
+    pub close_after: Duration, // A cycle not re-detected for this long is considered closed
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            cooldown: Duration::from_secs(5),
+            close_after: Duration::from_secs(1),
+        }
+    }
+}
+
+struct OpenCycle {
+    path: Vec<String>,
+    opened_at: SystemTime,
+    first_seen: Instant,
+    last_seen: Instant,
+    last_reported: Instant,
+    detections: u64,
+    best_profit: Option<f64>,
+}
+
+// Suppresses repeat reports of a cycle while it stays open, keyed by the rotation-independent
+// cycle key, and summarises each cycle's lifetime once it closes
+pub struct Deduplicator {
+    config: DedupConfig,
+    open: HashMap<String, OpenCycle>,
+}
+
+impl Deduplicator {
+    pub fn new(config: DedupConfig) -> Self {
+        Deduplicator {
+            config,
+            open: HashMap::new(),
+        }
+    }
+
+    // Records a detection; returns true if it should be reported
+    pub fn observe(&mut self, opportunity: &Opportunity, now: Instant) -> bool {
+        let key = opportunity.cycle_key();
+        match self.open.get_mut(&key) {
+            Some(cycle) => {
+                cycle.last_seen = now;
+                cycle.detections += 1;
+                if let Some(profit) = opportunity.profit {
+                    cycle.best_profit = Some(cycle.best_profit.map_or(profit, |best| best.max(profit)));
+                }
+                if now.duration_since(cycle.last_reported) >= self.config.cooldown {
+                    cycle.last_reported = now;
+                    return true;
+                }
+                false
+            }
+            None => {
+                self.open.insert(
+                    key,
+                    OpenCycle {
+                        path: opportunity.path.clone(),
+                        opened_at: opportunity.detected_at,
+                        first_seen: now,
+                        last_seen: now,
+                        last_reported: now,
+                        detections: 1,
+                        best_profit: opportunity.profit,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    // Removes cycles that haven't been seen for `close_after` and summarises them
+    pub fn close_expired(&mut self, now: Instant) -> Vec<OpportunitySummary> {
+        let close_after = self.config.close_after;
+        let expired: Vec<String> = self
+            .open
+            .iter()
+            .filter(|(_, cycle)| now.duration_since(cycle.last_seen) >= close_after)
+            .map(|(key, _)| key.clone())
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|key| {
+                let cycle = self.open.remove(&key)?;
+                Some(OpportunitySummary {
+                    cycle: key,
+                    path: cycle.path,
+                    opened_at: cycle.opened_at,
+                    duration: cycle.last_seen.duration_since(cycle.first_seen),
+                    detections: cycle.detections,
+                    best_profit: cycle.best_profit,
+                })
+            })
+            .collect()
+    }
+}
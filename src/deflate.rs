@@ -0,0 +1,264 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use flate2::{Decompress, FlushDecompress, Status};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+// Sec-WebSocket-Extensions value offered on every handshake. We only ever inflate, so
+// nothing about our own window needs negotiating.
+pub const OFFER: &str = "permessage-deflate";
+
+// Largest message inflated, compressed or not; tungstenite's own default limit
+const MAX_MESSAGE: usize = 64 << 20;
+
+// Longest handshake response scanned for the negotiated extension
+const MAX_RESPONSE_HEADER: usize = 64 * 1024;
+
+// What every compressed message has had stripped from its end (RFC 7692 7.2.2)
+const TAIL: [u8; 4] = [0x00, 0x00, 0xff, 0xff];
+
+const READ_CHUNK: usize = 16 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    Handshake, // Waiting for the end of the server's response header
+    Frames,
+    Passthrough, // Not a response we understand; tungstenite will say why
+}
+
+// permessage-deflate (RFC 7692) underneath tungstenite, which has no support for it and
+// rejects frames with RSV1 set. Sits on the plain byte stream (inside TLS), reads the
+// server's handshake response for the negotiated parameters, and rewrites every
+// compressed message into the uncompressed frame tungstenite expects. Frames also get
+// inflated if a server compresses without negotiating it. Writes pass through untouched:
+// compression is per message and optional for the sender, so we send ours as they are.
+pub struct DeflateStream<S> {
+    inner: S,
+    mode: Mode,
+    negotiated: bool,
+    raw: Vec<u8>,                   // Read from the server, not yet a whole frame
+    ready: Vec<u8>,                 // Rewritten bytes waiting to be read
+    ready_at: usize,                // How much of `ready` has been read
+    message: Option<(u8, Vec<u8>)>, // Opcode and payload so far of a fragmented compressed message
+    inflater: Inflater,
+    eof: bool,
+}
+
+struct Inflater {
+    decompress: Decompress,
+    reset_context: bool, // server_no_context_takeover: every message starts a fresh window
+    inflated: Vec<u8>,
+}
+
+impl<S> DeflateStream<S> {
+    pub fn new(inner: S) -> Self {
+        DeflateStream {
+            inner,
+            mode: Mode::Handshake,
+            negotiated: false,
+            raw: Vec::new(),
+            ready: Vec::new(),
+            ready_at: 0,
+            message: None,
+            inflater: Inflater {
+                decompress: Decompress::new(false),
+                reset_context: false,
+                inflated: Vec::new(),
+            },
+            eof: false,
+        }
+    }
+
+    // Whether the server agreed to compress; known once the handshake is through
+    pub fn negotiated(&self) -> bool {
+        self.negotiated
+    }
+
+    // Moves everything complete in `raw` to `ready`
+    fn process(&mut self) -> io::Result<()> {
+        if self.mode == Mode::Handshake {
+            match self.raw.windows(4).position(|w| w == b"\r\n\r\n") {
+                Some(end) => {
+                    let header = &self.raw[..end + 4];
+                    if let Some(reset_context) = negotiated(&String::from_utf8_lossy(header)) {
+                        self.negotiated = true;
+                        self.inflater.reset_context = reset_context;
+                    }
+                    self.ready.extend_from_slice(header);
+                    self.raw.drain(..end + 4);
+                    self.mode = Mode::Frames;
+                }
+                None if self.eof || self.raw.len() > MAX_RESPONSE_HEADER => self.mode = Mode::Passthrough,
+                None => return Ok(()),
+            }
+        }
+        if self.mode == Mode::Passthrough {
+            self.ready.append(&mut self.raw);
+            return Ok(());
+        }
+        let mut at = 0;
+        while let Some((header_len, payload_len)) = frame_len(&self.raw[at..]) {
+            if payload_len > MAX_MESSAGE {
+                // Not worth buffering; tungstenite turns it down as it comes
+                self.mode = Mode::Passthrough;
+                break;
+            }
+            let end = at + header_len + payload_len;
+            if self.raw.len() < end {
+                break;
+            }
+            let first = self.raw[at];
+            let (fin, rsv1, opcode) = (first & 0x80 != 0, first & 0x40 != 0, first & 0x0f);
+            let masked = self.raw[at + 1] & 0x80 != 0;
+            let payload = &self.raw[at + header_len..end];
+            match (opcode, &mut self.message) {
+                // Servers never mask, and control frames are never compressed: as they are
+                _ if masked || opcode >= 8 => self.ready.extend_from_slice(&self.raw[at..end]),
+                (1 | 2, None) if rsv1 => {
+                    if fin {
+                        self.inflater.inflate(opcode, payload, &mut self.ready)?;
+                    } else {
+                        self.message = Some((opcode, payload.to_vec()));
+                    }
+                }
+                (0, Some((_, compressed))) => {
+                    if compressed.len() + payload.len() > MAX_MESSAGE {
+                        return Err(io::Error::new(io::ErrorKind::InvalidData, "compressed WebSocket message too large"));
+                    }
+                    compressed.extend_from_slice(payload);
+                    if fin {
+                        let (opcode, compressed) = self.message.take().expect("message in progress");
+                        self.inflater.inflate(opcode, &compressed, &mut self.ready)?;
+                    }
+                }
+                _ => self.ready.extend_from_slice(&self.raw[at..end]),
+            }
+            at = end;
+        }
+        self.raw.drain(..at);
+        if self.eof || self.mode == Mode::Passthrough {
+            // A torn last frame or one too big to buffer, left for tungstenite to report
+            self.ready.append(&mut self.raw);
+        }
+        Ok(())
+    }
+}
+
+impl Inflater {
+    // Inflates one whole message and queues it as a single unfragmented frame
+    fn inflate(&mut self, opcode: u8, compressed: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+        self.inflated.clear();
+        let mut ended = false;
+        for mut input in [compressed, &TAIL[..]] {
+            // Until the input is used up and the output had room to spare, so nothing is left inside
+            while !ended && (!input.is_empty() || self.inflated.len() == self.inflated.capacity()) {
+                if self.inflated.len() == self.inflated.capacity() {
+                    self.inflated.reserve(input.len().max(READ_CHUNK));
+                }
+                let before = self.decompress.total_in();
+                let status = self
+                    .decompress
+                    .decompress_vec(input, &mut self.inflated, FlushDecompress::Sync)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("inflating WebSocket message: {}", e)))?;
+                input = &input[(self.decompress.total_in() - before) as usize..];
+                if self.inflated.len() > MAX_MESSAGE {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, "inflated WebSocket message too large"));
+                }
+                ended = status == Status::StreamEnd;
+            }
+        }
+        // A final block ends the stream, so the next message has to start a new one
+        if self.reset_context || ended {
+            self.decompress.reset(false);
+        }
+        let len = self.inflated.len();
+        out.push(0x80 | opcode);
+        if len < 126 {
+            out.push(len as u8);
+        } else if len <= u16::MAX as usize {
+            out.push(126);
+            out.extend_from_slice(&(len as u16).to_be_bytes());
+        } else {
+            out.push(127);
+            out.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        out.extend_from_slice(&self.inflated);
+        Ok(())
+    }
+}
+
+// Whether the response header accepts permessage-deflate, and if so whether the server
+// starts every message with a fresh window
+fn negotiated(header: &str) -> Option<bool> {
+    for (name, value) in header.lines().filter_map(|line| line.split_once(':')) {
+        if !name.trim().eq_ignore_ascii_case("sec-websocket-extensions") {
+            continue;
+        }
+        for extension in value.split(',') {
+            let mut params = extension.split(';').map(str::trim);
+            if params.next() == Some("permessage-deflate") {
+                return Some(params.any(|param| param == "server_no_context_takeover"));
+            }
+        }
+    }
+    None
+}
+
+// Header and payload length of the frame at the start of `bytes`, once the header is there
+fn frame_len(bytes: &[u8]) -> Option<(usize, usize)> {
+    let second = *bytes.get(1)?;
+    let mask = if second & 0x80 != 0 { 4 } else { 0 };
+    let (header, len) = match second & 0x7f {
+        126 => (4, u16::from_be_bytes(bytes.get(2..4)?.try_into().ok()?) as usize),
+        127 => (10, u64::from_be_bytes(bytes.get(2..10)?.try_into().ok()?) as usize),
+        len => (2, len as usize),
+    };
+    Some((header + mask, len))
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for DeflateStream<S> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.ready_at < this.ready.len() {
+                let n = buf.remaining().min(this.ready.len() - this.ready_at);
+                buf.put_slice(&this.ready[this.ready_at..this.ready_at + n]);
+                this.ready_at += n;
+                if this.ready_at == this.ready.len() {
+                    this.ready.clear();
+                    this.ready_at = 0;
+                }
+                return Poll::Ready(Ok(()));
+            }
+            if this.eof {
+                return Poll::Ready(Ok(()));
+            }
+            let mut chunk = [0u8; READ_CHUNK];
+            let mut read = ReadBuf::new(&mut chunk);
+            match Pin::new(&mut this.inner).poll_read(cx, &mut read) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Ready(Ok(())) => {
+                    this.eof = read.filled().is_empty();
+                    this.raw.extend_from_slice(read.filled());
+                }
+            }
+            this.process()?;
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for DeflateStream<S> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
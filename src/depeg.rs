@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use crate::graph::Graph;
+
+#[derive(Debug, Clone)]
+pub struct DepegConfig {
+    pub stablecoins: Vec<String>,
+    pub alert_bps: f64,          // Deviation at which a depeg is announced
+    pub reprice_bps: f64,        // Deviations below this are treated as noise and left unpriced
+    pub premium_multiplier: f64, // Haircut on edges into the coin, as a multiple of its deviation
+}
+
+impl Default for DepegConfig {
+    fn default() -> Self {
+        DepegConfig {
+            stablecoins: ["USDT", "USDC", "FDUSD", "DAI"].iter().map(|s| s.to_string()).collect(),
+            alert_bps: 50.0,
+            reprice_bps: 10.0,
+            premium_multiplier: 1.0,
+        }
+    }
+}
+
+// A stablecoin crossing the alert threshold in either direction
+#[derive(Debug, Clone)]
+pub struct DepegAlert {
+    pub asset: String,
+    pub deviation_bps: f64, // Signed: negative when the coin trades below its peers
+    pub depegged: bool,     // False when it has recovered
+}
+
+// Watches stablecoins against each other and prices depeg risk into the graph.
+// There is no fiat reference on the feed, so each coin is valued as the median of its
+// rates to every quoted stablecoin including itself (at 1.0). With three or more coins
+// quoted, one coin drifting away doesn't drag its peers' estimates with it.
+pub struct DepegMonitor {
+    config: DepegConfig,
+    depegged: HashSet<String>,
+    synthetic: HashSet<(String, String)>, // Pairs priced by assumption rather than a market
+}
+
+impl DepegMonitor {
+    pub fn new(config: DepegConfig) -> Self {
+        DepegMonitor {
+            config,
+            depegged: HashSet::new(),
+            synthetic: HashSet::new(),
+        }
+    }
+
+    // Leaves these pairs out of the estimates: a rate assumed near 1.0 says nothing about
+    // where a coin actually trades
+    pub fn set_synthetic(&mut self, pairs: &HashSet<(String, String)>) {
+        if self.synthetic != *pairs {
+            self.synthetic = pairs.clone();
+        }
+    }
+
+    // Signed deviation from peg in basis points, or None if no peer is quoted
+    pub fn deviation_bps(&self, graph: &Graph, asset: &str) -> Option<f64> {
+        let mut deviations: Vec<f64> = self
+            .config
+            .stablecoins
+            .iter()
+            .filter(|peer| peer.as_str() != asset)
+            .filter(|peer| !self.synthetic.contains(&(asset.to_string(), peer.to_string())))
+            .filter_map(|peer| graph.rate(asset, peer))
+            .filter(|rate| rate.is_finite() && *rate > 0.0)
+            .map(|rate| (rate - 1.0) * 10_000.0)
+            .collect();
+        if deviations.is_empty() {
+            return None;
+        }
+        deviations.push(0.0);
+        deviations.sort_by(|a, b| a.total_cmp(b));
+        let mid = deviations.len() / 2;
+        Some(if deviations.len().is_multiple_of(2) {
+            (deviations[mid - 1] + deviations[mid]) / 2.0
+        } else {
+            deviations[mid]
+        })
+    }
+
+    // Re-estimates every stablecoin, updates the graph's risk premiums and returns
+    // the coins that crossed the alert threshold since the last check
+    pub fn check(&mut self, graph: &mut Graph) -> Vec<DepegAlert> {
+        let mut alerts = Vec::new();
+        for asset in self.config.stablecoins.clone() {
+            let Some(deviation_bps) = self.deviation_bps(graph, &asset) else {
+                continue;
+            };
+            let magnitude = deviation_bps.abs();
+            let premium = if magnitude >= self.config.reprice_bps {
+                (magnitude * self.config.premium_multiplier / 10_000.0).min(1.0)
+            } else {
+                0.0
+            };
+            graph.set_premium(&asset, premium);
+
+            let depegged = magnitude >= self.config.alert_bps;
+            let was_depegged = self.depegged.contains(&asset);
+            if depegged && !was_depegged {
+                tracing::warn!(%asset, deviation_bps, "Stablecoin depeg detected");
+                self.depegged.insert(asset.clone());
+                alerts.push(DepegAlert { asset, deviation_bps, depegged });
+            } else if !depegged && was_depegged {
+                tracing::info!(%asset, deviation_bps, "Stablecoin back on peg");
+                self.depegged.remove(&asset);
+                alerts.push(DepegAlert { asset, deviation_bps, depegged });
+            }
+        }
+        alerts
+    }
+}
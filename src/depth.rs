@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+
+use crate::graph::{split_pair, Graph};
+use crate::slippage::value_in;
+
+/// Top levels of one symbol's order book, from a `<symbol>@depth<n>` partial book stream.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct BookDepth {
+    pub symbol: String,
+    pub update_id: u64,
+    pub bids: Vec<(f64, f64)>, // (price, quantity), best first
+    pub asks: Vec<(f64, f64)>, // (price, quantity), best first
+}
+
+// Payload of a partial book depth event. It doesn't name the symbol; that comes from the stream.
+#[derive(serde::Deserialize)]
+struct RawDepth<'a> {
+    #[serde(rename = "lastUpdateId", default)]
+    update_id: u64,
+    #[serde(borrow, default)]
+    bids: Vec<(&'a str, &'a str)>,
+    #[serde(borrow, default)]
+    asks: Vec<(&'a str, &'a str)>,
+}
+
+impl BookDepth {
+    // Parses the payload of `stream`, e.g. "btcusdt@depth20@100ms"
+    pub fn parse(stream: &str, json: &str) -> serde_json::Result<Self> {
+        let raw: RawDepth = serde_json::from_str(json)?;
+        let levels = |levels: Vec<(&str, &str)>| {
+            levels
+                .into_iter()
+                .filter_map(|(price, qty)| Some((price.parse::<f64>().ok()?, qty.parse::<f64>().ok()?)))
+                .filter(|&(price, qty)| price > 0.0 && qty > 0.0)
+                .collect()
+        };
+        Ok(BookDepth {
+            symbol: depth_stream_symbol(stream).unwrap_or_default().to_uppercase(),
+            update_id: raw.update_id,
+            bids: levels(raw.bids),
+            asks: levels(raw.asks),
+        })
+    }
+}
+
+// Symbol part of a partial book depth stream name, None for any other stream
+pub fn depth_stream_symbol(stream: &str) -> Option<&str> {
+    let (symbol, kind) = stream.split_once('@')?;
+    let levels = kind.strip_prefix("depth")?;
+    let levels = levels.split('@').next().unwrap_or_default();
+    matches!(levels, "5" | "10" | "20").then_some(symbol)
+}
+
+// Average price of filling `size` base against `levels`, and the share of `size` they
+// could fill. A size of zero fills at the best level.
+pub fn fill_price(levels: &[(f64, f64)], size: f64) -> Option<(f64, f64)> {
+    let &(best, _) = levels.first()?;
+    if size <= 0.0 || !size.is_finite() {
+        return Some((best, 1.0));
+    }
+    let (mut filled, mut cost) = (0.0, 0.0);
+    for &(price, qty) in levels {
+        let take = qty.min(size - filled);
+        filled += take;
+        cost += take * price;
+        if filled >= size {
+            break;
+        }
+    }
+    Some((cost / filled, filled / size))
+}
+
+#[derive(Debug, Clone)]
+pub struct DepthConfig {
+    pub notional: f64,     // Order size the rates are computed for, in `reference`
+    pub reference: String, // Asset `notional` is given in
+}
+
+impl DepthConfig {
+    pub fn new(notional: f64) -> Self {
+        DepthConfig {
+            notional,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for DepthConfig {
+    fn default() -> Self {
+        DepthConfig {
+            notional: 0.0,
+            reference: "USDT".to_string(),
+        }
+    }
+}
+
+// Prices pairs with a depth stream at what an order of the configured notional would pay
+// rather than the top of book: selling base averages down the bids, buying it averages up
+// the asks. A book too thin for the whole order scales the rate by the share it fills, so
+// the unfilled part counts as lost. Orders that can't be valued yet are priced at the top.
+pub struct DepthPricer {
+    config: DepthConfig,
+    books: HashMap<String, BookDepth>,
+}
+
+impl DepthPricer {
+    pub fn new(config: DepthConfig) -> Self {
+        DepthPricer {
+            config,
+            books: HashMap::new(),
+        }
+    }
+
+    // Whether the symbol is priced from its book rather than its ticker
+    pub fn covers(&self, symbol: &str) -> bool {
+        self.books.contains_key(symbol)
+    }
+
+    pub fn remove(&mut self, symbol: &str) -> bool {
+        self.books.remove(symbol).is_some()
+    }
+
+    // Stores the book and returns its effective base→quote and quote→base rates
+    pub fn update(&mut self, depth: &BookDepth, graph: &Graph) -> Option<(f64, f64)> {
+        match self.books.get_mut(&depth.symbol) {
+            Some(known) if known.update_id > depth.update_id => return None,
+            // Copied into the stored book's level buffers rather than fresh ones
+            Some(known) => {
+                known.update_id = depth.update_id;
+                known.bids.clone_from(&depth.bids);
+                known.asks.clone_from(&depth.asks);
+            }
+            None => {
+                self.books.insert(depth.symbol.clone(), depth.clone());
+            }
+        }
+        let (base, _) = split_pair(&depth.symbol);
+        let size = value_in(graph, base, &self.config.reference).map_or(0.0, |value| self.config.notional / value);
+        let (bid, sold) = fill_price(&depth.bids, size)?;
+        let (ask, bought) = fill_price(&depth.asks, size)?;
+        Some((bid * sold, bought / ask))
+    }
+}
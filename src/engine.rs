@@ -0,0 +1,1109 @@
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::json;
+use tokio::sync::{broadcast, watch};
+
+use crate::audit::{self, AuditLog};
+use crate::batch::{ThrottleConfig, UpdateBatch};
+use crate::blend::TradeBlend;
+use crate::book_stats::{BookMetrics, BookStats};
+use crate::cbbo::{ConsolidatedBook, VenueQuote};
+use crate::circuit_breaker::{PriceCheck, PriceGuard, PriceGuardConfig};
+use crate::clock::{self, ServerClock};
+use crate::dedup::{DedupConfig, Deduplicator};
+use crate::depeg::{DepegConfig, DepegMonitor};
+use crate::depth::{DepthConfig, DepthPricer};
+use crate::events::{EngineEvent, LatencyBreakdown, MarketEvent, Opportunity};
+use crate::executor::Executor;
+use crate::feed::Feed;
+use crate::graph::{split_pair, Graph};
+use crate::health::HealthMetrics;
+use crate::liquidity::{Liquidity, LiquidityConfig, LiquidityFilter};
+use crate::persistence::{PersistenceConfig, PersistenceFilter, PersistenceMetrics};
+use crate::risk::RiskManager;
+use crate::routing::{RoutingConfig, SmartRouter};
+use crate::schedule::Schedule;
+use crate::sequence::{SequenceConfig, SequenceIssue, SequenceMetrics, SequenceTracker};
+use crate::shard::ShardPool;
+use crate::sinks::jsonl::event_json;
+use crate::shared_graph::SharedGraph;
+use crate::stable_edges::{StableEdgeConfig, StableEdges};
+use crate::strategy::Strategy;
+use crate::supervisor::{panic_message, Backoff, RestartPolicy};
+use crate::ticker::{apply_ticker_data, book_top, set_pair_edges};
+use crate::trace::CycleTracer;
+use crate::ttl::{TtlConfig, TtlEstimator};
+use crate::valuation::{ProfitValuation, ValuationConfig};
+
+const EVENT_CAPACITY: usize = 1024;
+
+/// Pauses and resumes detection on a running [`Engine`] from another task. While paused
+/// the graph keeps tracking the market but no strategy runs.
+#[derive(Debug, Clone, Default)]
+pub struct DetectionControl {
+    paused: Arc<AtomicBool>,
+}
+
+impl DetectionControl {
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+}
+
+/// Asks a running [`Engine`] to stop, from another task or a signal handler. Clones
+/// share state; the first reason given is kept.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    reason: Arc<watch::Sender<Option<String>>>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Shutdown {
+            reason: Arc::new(watch::channel(None).0),
+        }
+    }
+}
+
+impl Shutdown {
+    pub fn trigger(&self, reason: &str) {
+        self.reason.send_if_modified(|current| {
+            if current.is_some() {
+                return false;
+            }
+            *current = Some(reason.to_string());
+            true
+        });
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.reason.borrow().is_some()
+    }
+
+    pub fn reason(&self) -> Option<String> {
+        self.reason.borrow().clone()
+    }
+
+    /// Completes once shutdown has been requested.
+    pub async fn wait(&self) {
+        let mut receiver = self.reason.subscribe();
+        let _ = receiver.wait_for(Option::is_some).await;
+    }
+}
+
+/// Counts from an [`Engine`]'s run so far.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunStats {
+    pub events: u64,        // Market events handled
+    pub passes: u64,        // Detection passes
+    pub opportunities: u64, // Opportunities reported to subscribers
+    pub executed: u64,      // Opportunities handed to the executor
+    pub signals: u64,       // Strategy signals reported to subscribers
+}
+
+// A strategy that panicked sits out detection until `resume_at`
+struct StrategyRestart {
+    backoff: Backoff,
+    resume_at: Option<Instant>,
+}
+
+/// Drives a [`Feed`] into the market graph and runs strategies on every update.
+pub struct Engine<F: Feed> {
+    feed: F,
+    graph: Graph,
+    strategies: Vec<Box<dyn Strategy>>,
+    strategy_restarts: Vec<StrategyRestart>, // One per strategy
+    executor: Option<Box<dyn Executor>>,
+    events: broadcast::Sender<EngineEvent>,
+    market: broadcast::Sender<Arc<MarketEvent>>, // Raw updates, only sent while someone listens
+    book: ConsolidatedBook,
+    cbbo_detection: bool,
+    router: Option<SmartRouter>,
+    tracer: Option<CycleTracer>,
+    valuation: Option<ProfitValuation>,
+    audit: Option<AuditLog>,
+    degraded: HashSet<String>, // Venues whose feed is polling instead of streaming
+    schedule: Option<Schedule>,
+    halted: Option<String>, // Reason execution is currently suspended
+    dedup: Option<Deduplicator>,
+    ttl: Option<TtlEstimator>,
+    books: Option<Arc<BookMetrics>>,
+    depeg: Option<DepegMonitor>,
+    stable_edges: Option<StableEdges>,
+    persistence: Option<PersistenceFilter>,
+    batch: Option<UpdateBatch>,
+    max_cycle_len: Option<usize>,
+    home_assets: Vec<String>,
+    shards: Option<ShardPool>,
+    max_edge_age: Option<Duration>,
+    sequence: Option<SequenceTracker>,
+    trade_blend: Option<TradeBlend>,
+    liquidity: Option<LiquidityFilter>,
+    price_guard: Option<PriceGuard>,
+    depth: Option<DepthPricer>,
+    exchange_lag_ms: Option<i64>, // Worst exchange-to-receipt lag since the last detection pass
+    clock: Option<Arc<ServerClock>>, // Exchange time for that lag; the local clock when None
+    shared: Option<Arc<SharedGraph>>,
+    control: DetectionControl,
+    health: Option<Arc<HealthMetrics>>,
+    risk: Option<RiskManager>,
+    kill_switch: Option<String>, // Kill switch reason last announced
+    shutdown: Shutdown,
+    stats: RunStats,
+}
+
+impl<F: Feed> Engine<F> {
+    pub fn new(feed: F) -> Self {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let (market, _) = broadcast::channel(EVENT_CAPACITY);
+        Engine {
+            feed,
+            graph: Graph::new(),
+            strategies: Vec::new(),
+            strategy_restarts: Vec::new(),
+            executor: None,
+            events,
+            market,
+            book: ConsolidatedBook::new(),
+            cbbo_detection: false,
+            router: None,
+            tracer: None,
+            valuation: None,
+            audit: None,
+            degraded: HashSet::new(),
+            schedule: None,
+            halted: None,
+            dedup: None,
+            ttl: None,
+            books: None,
+            depeg: None,
+            stable_edges: None,
+            persistence: None,
+            batch: None,
+            max_cycle_len: None,
+            home_assets: Vec::new(),
+            shards: None,
+            max_edge_age: None,
+            sequence: None,
+            trade_blend: None,
+            liquidity: None,
+            price_guard: None,
+            depth: None,
+            exchange_lag_ms: None,
+            clock: None,
+            shared: None,
+            control: DetectionControl::default(),
+            health: None,
+            risk: None,
+            kill_switch: None,
+            shutdown: Shutdown::default(),
+            stats: RunStats::default(),
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: impl Strategy + 'static) -> Self {
+        self.strategies.push(Box::new(strategy));
+        self.strategy_restarts.push(StrategyRestart {
+            backoff: Backoff::new(RestartPolicy::default(), Instant::now()),
+            resume_at: None,
+        });
+        self
+    }
+
+    pub fn with_executor(mut self, executor: impl Executor + 'static) -> Self {
+        self.executor = Some(Box::new(executor));
+        self
+    }
+
+    /// Builds graph edges from the consolidated best bid/offer across venues instead of
+    /// last prices: base→quote at the best bid, quote→base at the inverse best ask.
+    /// Opportunities then carry the venue behind each leg.
+    pub fn with_cbbo_detection(mut self, enabled: bool) -> Self {
+        self.cbbo_detection = enabled;
+        self
+    }
+
+    /// Chooses the venue for each leg of a cycle by price net of fees and top-of-book
+    /// depth for the configured notional, reported as [`Opportunity::routing`]. Only
+    /// takes effect with consolidated-book detection.
+    pub fn with_smart_routing(mut self, config: RoutingConfig) -> Self {
+        self.router = Some(SmartRouter::new(config));
+        self
+    }
+
+    /// Attaches a simulated execution to every opportunity, reported as
+    /// [`Opportunity::trace`]: the starting amount, each leg's price, fee, rounded
+    /// quantity and proceeds, and the final amount.
+    pub fn with_cycle_trace(mut self, tracer: CycleTracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Expresses every opportunity's expected profit as an amount of `config.reference`
+    /// for a pass of `config.notional`, at current graph rates, reported as
+    /// [`Opportunity::reference_profit`].
+    pub fn with_profit_valuation(mut self, config: ValuationConfig) -> Self {
+        self.valuation = Some(ProfitValuation::new(config));
+        self
+    }
+
+    /// Writes the engine's decisions to `log`: every update applied, detection pass,
+    /// risk check and opportunity, reported or suppressed, and every event it broadcasts.
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    /// Suspends execution during the calendar's quiet hours and blackouts.
+    /// Detection, events and recording carry on as normal.
+    pub fn with_schedule(mut self, schedule: Schedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Stamps every opportunity with an estimated expiry, from how long cycles have stayed
+    /// open and how often their legs' quotes update. Expired opportunities aren't executed.
+    pub fn with_opportunity_ttl(mut self, config: TtlConfig) -> Self {
+        self.ttl = Some(TtlEstimator::new(config));
+        self
+    }
+
+    /// Keeps spread and bid/ask imbalance per symbol in `metrics`, from book tickers and
+    /// partial depth streams.
+    pub fn with_book_metrics(mut self, metrics: Arc<BookMetrics>) -> Self {
+        self.books = Some(metrics);
+        self
+    }
+
+    /// Reports each cycle once while it stays open (re-reporting after `cooldown`) and
+    /// broadcasts an [`EngineEvent::OpportunityClosed`] summary when it goes away.
+    pub fn with_dedup(mut self, config: DedupConfig) -> Self {
+        self.dedup = Some(Deduplicator::new(config));
+        self
+    }
+
+    /// Watches stablecoins against each other, broadcasting depeg and recovery events and
+    /// pricing the deviation into edges so a depegging coin doesn't look like free profit.
+    pub fn with_depeg_monitor(mut self, config: DepegConfig) -> Self {
+        self.depeg = Some(DepegMonitor::new(config));
+        self
+    }
+
+    /// Adds edges near 1.0 between stablecoins the feed lists no pair for, less a haircut,
+    /// so cycles can convert between them. A listed pair always uses its market rate.
+    pub fn with_stable_edges(mut self, config: StableEdgeConfig) -> Self {
+        self.stable_edges = Some(StableEdges::new(config));
+        self
+    }
+
+    /// Rate moves smaller than this many bps don't mark edges dirty, so incremental
+    /// strategies skip detection when nothing moved meaningfully.
+    pub fn with_change_epsilon(mut self, bps: f64) -> Self {
+        self.graph.set_change_epsilon(bps / 10_000.0);
+        self
+    }
+
+    /// Runs strategies at most once per `interval` (or as soon as `max_updates` symbol
+    /// updates are pending) on the coalesced updates, instead of on every message.
+    /// The graph itself is still updated as each message arrives.
+    pub fn with_throttle(mut self, config: ThrottleConfig) -> Self {
+        self.batch = Some(UpdateBatch::new(config));
+        self
+    }
+
+    /// Parses tickers on a pool of `workers` tasks, each owning a consistent-hash shard
+    /// of symbols, and merges their changes into the graph before every detection pass.
+    /// Pair with [`with_throttle`](Engine::with_throttle): without it a pass may run
+    /// before the workers have caught up with the message that triggered it.
+    /// Must be called inside a Tokio runtime.
+    pub fn with_shards(mut self, workers: usize) -> Self {
+        self.shards = Some(ShardPool::spawn(workers, self.feed.venue()));
+        self
+    }
+
+    /// Drops opportunities with more than `legs` legs from every strategy, plugins included.
+    /// At least 2, like [`NegativeCycleStrategy::with_max_cycle_len`](crate::NegativeCycleStrategy::with_max_cycle_len).
+    pub fn with_max_cycle_len(mut self, legs: usize) -> Self {
+        self.max_cycle_len = Some(legs.max(2));
+        self
+    }
+
+    /// Only reports cycles passing through one of `assets`, rotated to start and end at
+    /// it (the first listed, when a cycle passes through several), so that profit,
+    /// valuation and execution are all from the asset actually held.
+    pub fn with_home_assets(mut self, assets: Vec<String>) -> Self {
+        self.home_assets = assets;
+        self
+    }
+
+    /// Only executes a cycle once it has stayed profitable for a minimum time across
+    /// consecutive detection passes. Reporting is unaffected.
+    pub fn with_min_time_in_profit(mut self, config: PersistenceConfig) -> Self {
+        self.persistence = Some(PersistenceFilter::new(config));
+        self
+    }
+
+    /// Removes edges that haven't been updated for `max_age` before each detection pass,
+    /// so a halted or unsubscribed pair can't produce phantom arbitrage from its last
+    /// price. Strategies see the removal as [`MarketEvent::SymbolRemoved`].
+    pub fn with_max_edge_age(mut self, max_age: Duration) -> Self {
+        self.max_edge_age = Some(max_age);
+        self
+    }
+
+    /// Tracks exchange event times and book update ids per symbol, dropping updates that
+    /// arrive out of order and counting them and gaps longer than `max_gap`. With
+    /// `invalidate`, either takes the symbol's edge out until its next in-order update.
+    pub fn with_sequence_check(mut self, config: SequenceConfig) -> Self {
+        self.sequence = Some(SequenceTracker::new(config));
+        self
+    }
+
+    /// Drops ticker updates with a missing, zero or negative price, or whose last price
+    /// moved more than `config.max_move` from the previous one, and quarantines the symbol:
+    /// its edge is removed and its updates ignored for `config.cooldown`. Each trip and
+    /// reset is broadcast as [`EngineEvent::PriceBreakerTripped`] and
+    /// [`EngineEvent::PriceBreakerReset`].
+    pub fn with_price_guard(mut self, config: PriceGuardConfig) -> Self {
+        self.price_guard = Some(PriceGuard::new(config));
+        self
+    }
+
+    /// Keeps pairs whose 24h quote volume, valued in the reference asset, is under the
+    /// floor out of the graph: their updates are dropped and their edges removed, seen by
+    /// strategies as [`MarketEvent::SymbolRemoved`]. A pair returns once it clears the floor.
+    pub fn with_min_volume(mut self, config: LiquidityConfig) -> Self {
+        self.liquidity = Some(LiquidityFilter::new(config));
+        self
+    }
+
+    /// Prices pairs that have a partial depth stream at the average an order of
+    /// `config.notional` would fill at across the book's levels, in both directions,
+    /// instead of the ticker's last price. Only affects last-price edges; the consolidated
+    /// book and sharded parsing keep to the top of book.
+    pub fn with_depth_pricing(mut self, config: DepthConfig) -> Self {
+        self.depth = Some(DepthPricer::new(config));
+        self
+    }
+
+    /// Prices each symbol as `weight` times its last `@aggTrade` print plus the rest times
+    /// its book mid, instead of whichever update came last. Only affects last-price edges;
+    /// consolidated-book detection needs a bid and ask, which trades don't carry.
+    pub fn with_trade_blend(mut self, weight: f64) -> Self {
+        self.trade_blend = Some(TradeBlend::new(weight));
+        self
+    }
+
+    /// Measures exchange-to-receipt lag against the exchange's clock rather than the local
+    /// one, so clock drift doesn't show up as feed latency.
+    pub fn with_clock(mut self, clock: Arc<ServerClock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    /// Consults `risk` before every execution and stops executing while its kill switch
+    /// is tripped, announcing the trip and reset as events. Detection is unaffected.
+    pub fn with_risk(mut self, risk: RiskManager) -> Self {
+        self.risk = Some(risk);
+        self
+    }
+
+    /// Pass/reject counts of the time-in-profit filter, if enabled.
+    pub fn persistence_metrics(&self) -> Option<Arc<PersistenceMetrics>> {
+        self.persistence.as_ref().map(PersistenceFilter::metrics)
+    }
+
+    /// Out-of-order and gap counts of the sequence check, if enabled.
+    pub fn sequence_metrics(&self) -> Option<Arc<SequenceMetrics>> {
+        self.sequence.as_ref().map(SequenceTracker::metrics)
+    }
+
+    /// Receives every [`EngineEvent`] emitted after this call.
+    pub fn subscribe(&self) -> broadcast::Receiver<EngineEvent> {
+        self.events.subscribe()
+    }
+
+    /// Receives every [`MarketEvent`] the feed delivers after this call, before it is
+    /// applied. Copies are only made while at least one receiver is alive.
+    pub fn subscribe_market(&self) -> broadcast::Receiver<Arc<MarketEvent>> {
+        self.market.subscribe()
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+
+    /// Lock-free handle on the graph for readers on other tasks or threads. The engine
+    /// publishes a snapshot of the graph as each detection pass sees it; until the first
+    /// call nothing is published, so the copy costs nothing when unused.
+    pub fn shared_graph(&mut self) -> Arc<SharedGraph> {
+        self.shared.get_or_insert_with(|| Arc::new(SharedGraph::new(self.graph.clone()))).clone()
+    }
+
+    /// Handle for pausing detection while the engine runs.
+    pub fn detection_control(&self) -> DetectionControl {
+        self.control.clone()
+    }
+
+    /// Handle for stopping the engine. Once triggered no new detection pass starts,
+    /// [`run`](Engine::run) lets the executor unwind, closes the feed and returns.
+    pub fn shutdown_handle(&self) -> Shutdown {
+        self.shutdown.clone()
+    }
+
+    pub fn stats(&self) -> RunStats {
+        self.stats
+    }
+
+    /// Last message per venue and last detection pass, for health probes. Nothing is
+    /// recorded until the first call.
+    pub fn health(&mut self) -> Arc<HealthMetrics> {
+        self.health.get_or_insert_with(Default::default).clone()
+    }
+
+    /// Records liveness into `metrics`, shared with whatever else reports there.
+    pub fn with_health(mut self, metrics: Arc<HealthMetrics>) -> Self {
+        self.health = Some(metrics);
+        self
+    }
+
+    /// Consolidated top of book across every venue seen so far.
+    pub fn book(&self) -> &ConsolidatedBook {
+        &self.book
+    }
+
+    pub fn feed_mut(&mut self) -> &mut F {
+        &mut self.feed
+    }
+
+    /// Runs until the feed ends or shutdown is requested through
+    /// [`shutdown_handle`](Engine::shutdown_handle).
+    pub async fn run(&mut self) {
+        let shutdown = self.shutdown.clone();
+        loop {
+            let deadline = self.batch.as_ref().and_then(UpdateBatch::deadline);
+            let event = match deadline {
+                Some(deadline) => tokio::select! {
+                    event = self.feed.next_event() => event,
+                    _ = tokio::time::sleep_until(deadline.into()) => {
+                        self.flush();
+                        continue;
+                    }
+                    _ = shutdown.wait() => None,
+                },
+                None => tokio::select! {
+                    event = self.feed.next_event() => event,
+                    _ = shutdown.wait() => None,
+                },
+            };
+            let Some(event) = event else {
+                break;
+            };
+            self.handle(event);
+        }
+        match shutdown.reason() {
+            Some(reason) => tracing::info!(%reason, "Shutting down"),
+            // Whatever the throttle held back still gets its pass
+            None => self.flush(),
+        }
+        if let Some(executor) = self.executor.as_mut() {
+            executor.shutdown().await;
+        }
+        self.feed.close().await;
+        if let Some(health) = &self.health {
+            health.record_feed_closed();
+        }
+        announce(&self.events, self.audit.as_ref(), EngineEvent::FeedClosed);
+    }
+
+    fn handle(&mut self, mut event: MarketEvent) {
+        let received = Instant::now();
+        self.stats.events += 1;
+        if let Some(health) = &self.health {
+            match &event {
+                MarketEvent::Quotes(quotes) => quotes.iter().for_each(|q| health.record_message(&q.venue, received)),
+                MarketEvent::MarkPrices(_) => health.record_message(crate::perp::VENUE, received),
+                // Says the feed heard nothing, so it can't count as hearing something
+                MarketEvent::FeedStalled { venue, .. } => health.record_feed_stalled(venue, received),
+                _ => health.record_message(self.feed.venue(), received),
+            }
+        }
+        self.record_exchange_lag(&event);
+        if let Some(ttl) = self.ttl.as_mut() {
+            match &event {
+                MarketEvent::Tickers(tickers) => tickers.iter().for_each(|t| ttl.observe_quote(&t.s, received)),
+                MarketEvent::Depth(depths) => depths.iter().for_each(|d| ttl.observe_quote(&d.symbol, received)),
+                _ => {}
+            }
+        }
+        if let Some(books) = &self.books {
+            match &event {
+                MarketEvent::Tickers(tickers) => {
+                    for ticker in tickers {
+                        let stats = book_top(ticker).and_then(|((bid, bid_qty), (ask, ask_qty))| {
+                            BookStats::new(bid, ask, bid_qty, ask_qty, 1, received)
+                        });
+                        if let Some(stats) = stats {
+                            books.record(&ticker.s, stats);
+                        }
+                    }
+                }
+                MarketEvent::Depth(depths) => {
+                    for depth in depths {
+                        if let Some(stats) = BookStats::from_depth(depth, received) {
+                            books.record(&depth.symbol, stats);
+                        }
+                    }
+                }
+                MarketEvent::SymbolRemoved(symbol) => books.remove(symbol),
+                _ => {}
+            }
+        }
+        if self.market.receiver_count() > 0 {
+            let _ = self.market.send(Arc::new(event.clone()));
+        }
+        for removed in self.check_sequence(&mut event) {
+            self.process(removed, received);
+        }
+        for removed in self.check_prices(&mut event, received) {
+            self.process(removed, received);
+        }
+        for removed in self.check_liquidity(&mut event) {
+            self.process(removed, received);
+        }
+        if let (Some(blend), MarketEvent::Tickers(tickers)) = (self.trade_blend.as_mut(), &mut event) {
+            tickers.iter_mut().for_each(|ticker| blend.apply(ticker));
+        }
+        self.process(event, received);
+    }
+
+    // Applies one event and runs detection on it, or leaves it to the throttle
+    fn process(&mut self, event: MarketEvent, received: Instant) {
+        if let Some(edges) = self.stable_edges.as_mut() {
+            edges.observe(&mut self.graph, &event);
+        }
+        self.apply(&event);
+        if let Some(log) = &self.audit {
+            log.record("update", audit::market_summary(&event));
+        }
+        match self.batch.as_mut() {
+            Some(batch) => {
+                if batch.push(event, received) {
+                    self.flush();
+                }
+            }
+            None => self.detect(&[event], received),
+        }
+    }
+
+    // Drops tickers that arrive out of order. With invalidation on, those and the first
+    // update after a gap come back as removals of their edges instead.
+    fn check_sequence(&mut self, event: &mut MarketEvent) -> Vec<MarketEvent> {
+        let (Some(tracker), MarketEvent::Tickers(tickers)) = (self.sequence.as_mut(), event) else {
+            return Vec::new();
+        };
+        let invalidate = tracker.config().invalidate;
+        let mut removed = Vec::new();
+        tickers.retain(|ticker| match tracker.observe(ticker) {
+            None => true,
+            Some(issue) => {
+                if invalidate {
+                    removed.push(MarketEvent::SymbolRemoved(ticker.s.clone()));
+                }
+                issue == SequenceIssue::Gap && !invalidate
+            }
+        });
+        removed
+    }
+
+    // Drops tickers with bad prices, removing each symbol's edge when its breaker trips
+    fn check_prices(&mut self, event: &mut MarketEvent, received: Instant) -> Vec<MarketEvent> {
+        let (Some(guard), MarketEvent::Tickers(tickers)) = (self.price_guard.as_mut(), event) else {
+            return Vec::new();
+        };
+        let mut removed = Vec::new();
+        tickers.retain(|ticker| match guard.check(ticker, received) {
+            PriceCheck::Accepted => true,
+            PriceCheck::Reset => {
+                announce(&self.events, self.audit.as_ref(), EngineEvent::PriceBreakerReset { symbol: ticker.s.clone() });
+                true
+            }
+            PriceCheck::Rejected => false,
+            PriceCheck::Tripped(fault) => {
+                let reason = fault.to_string();
+                announce(&self.events, self.audit.as_ref(), EngineEvent::PriceBreakerTripped { symbol: ticker.s.clone(), reason });
+                removed.push(MarketEvent::SymbolRemoved(ticker.s.clone()));
+                false
+            }
+        });
+        removed
+    }
+
+    // Drops tickers of pairs under the volume floor, removing each pair's edge once
+    fn check_liquidity(&mut self, event: &mut MarketEvent) -> Vec<MarketEvent> {
+        let (Some(filter), MarketEvent::Tickers(tickers)) = (self.liquidity.as_mut(), event) else {
+            return Vec::new();
+        };
+        let mut removed = Vec::new();
+        tickers.retain(|ticker| match filter.check(ticker, &self.graph) {
+            Liquidity::Liquid => true,
+            Liquidity::Dropped { newly } => {
+                if newly {
+                    removed.push(MarketEvent::SymbolRemoved(ticker.s.clone()));
+                }
+                false
+            }
+        });
+        removed
+    }
+
+    fn record_exchange_lag(&mut self, event: &MarketEvent) {
+        let MarketEvent::Tickers(tickers) = event else {
+            return;
+        };
+        let Some(oldest) = tickers.iter().map(|t| t.event_time).filter(|&t| t > 0).min() else {
+            return;
+        };
+        let now_ms = self.clock.as_ref().map_or_else(clock::local_ms, |clock| clock.now_ms());
+        let lag = now_ms - oldest as i64;
+        self.exchange_lag_ms = Some(self.exchange_lag_ms.map_or(lag, |worst| worst.max(lag)));
+    }
+
+    // Runs detection on whatever the throttle has batched up
+    fn flush(&mut self) {
+        let Some((events, first_received)) = self.batch.as_mut().and_then(|batch| batch.take(Instant::now())) else {
+            return;
+        };
+        self.detect(&events, first_received);
+    }
+
+    fn apply(&mut self, event: &MarketEvent) {
+        let _span = tracing::debug_span!("handle_event").entered();
+        match event {
+            MarketEvent::Tickers(tickers) if self.shards.is_some() => {
+                if let Some(shards) = &self.shards {
+                    shards.dispatch(tickers);
+                }
+            }
+            MarketEvent::Tickers(tickers) => {
+                // Steady state allocates nothing here: quotes are overwritten in place and
+                // edges looked up by borrowed asset names
+                let venue = self.feed.venue();
+                let now = Instant::now();
+                for ticker in tickers {
+                    let (base, quote) = split_pair(&ticker.s);
+                    if let Some((bid, ask)) = book_top(ticker) {
+                        self.book.update_top(venue, base, quote, bid, ask, now);
+                        if self.cbbo_detection {
+                            refresh_cbbo_edges(&self.book, &mut self.graph, base, quote);
+                        }
+                    }
+                    // Pairs with a book are priced from it; their tickers would undo that
+                    let covered = self.depth.as_ref().is_some_and(|pricer| pricer.covers(&ticker.s));
+                    if !self.cbbo_detection && !covered {
+                        apply_ticker_data(&mut self.graph, std::slice::from_ref(ticker));
+                    }
+                }
+            }
+            MarketEvent::Quotes(quotes) => self.apply_quotes(quotes.clone()),
+            // Futures prices stay out of the spot graph; strategies get the event as is
+            MarketEvent::MarkPrices(_) => {}
+            MarketEvent::Depth(depths) => {
+                let Some(pricer) = self.depth.as_mut().filter(|_| !self.cbbo_detection && self.shards.is_none()) else {
+                    return;
+                };
+                for depth in depths {
+                    if let Some((sell, buy)) = pricer.update(depth, &self.graph) {
+                        let (base, quote) = split_pair(&depth.symbol);
+                        self.graph.set_edge(base, quote, sell);
+                        self.graph.set_edge(quote, base, buy);
+                    }
+                }
+            }
+            MarketEvent::FeedState { venue, degraded } => {
+                if *degraded {
+                    tracing::warn!(%venue, "Feed degraded, opportunities are low confidence and won't execute");
+                    self.degraded.insert(venue.clone());
+                } else if self.degraded.remove(venue) {
+                    tracing::info!(%venue, "Feed streaming again");
+                }
+            }
+            MarketEvent::FeedStalled { venue, silent_ms } => {
+                let event = EngineEvent::FeedStalled { venue: venue.clone(), silent_ms: *silent_ms };
+                announce(&self.events, self.audit.as_ref(), event);
+            }
+            MarketEvent::SymbolRemoved(symbol) => {
+                let (start, end) = split_pair(symbol);
+                self.book.remove(self.feed.venue(), start, end);
+                if self.cbbo_detection {
+                    // Other venues may still quote the pair
+                    refresh_cbbo_edges(&self.book, &mut self.graph, start, end);
+                } else {
+                    if let Some(pricer) = self.depth.as_mut() {
+                        pricer.remove(symbol);
+                    }
+                    self.graph.remove_edge(start, end);
+                    self.graph.remove_edge(end, start);
+                }
+            }
+        }
+    }
+
+    // One detection pass over `events`; `received` is when the oldest of them arrived
+    fn detect(&mut self, events: &[MarketEvent], received: Instant) {
+        let event_time = events
+            .iter()
+            .filter_map(|event| match event {
+                MarketEvent::Tickers(tickers) => tickers.iter().map(|t| t.event_time).max(),
+                MarketEvent::MarkPrices(marks) => marks.iter().map(|m| m.event_time).max(),
+                _ => None,
+            })
+            .max()
+            .filter(|&t| t > 0);
+
+        self.merge_shards();
+        let expired = self.expire_stale_edges();
+        self.check_schedule();
+        self.check_kill_switch();
+        if let Some(edges) = self.stable_edges.as_mut() {
+            edges.refresh(&mut self.graph);
+        }
+        self.check_depeg();
+        if let Some(shared) = &self.shared {
+            shared.publish(&self.graph);
+        }
+        let graph_ready = Instant::now();
+        let exchange_lag_ms = self.exchange_lag_ms.take();
+        if let Some(health) = &self.health {
+            health.record_pass(graph_ready);
+        }
+        if self.control.is_paused() || self.shutdown.is_triggered() {
+            return;
+        }
+        self.stats.passes += 1;
+        let (mut found, mut reported) = (0, 0);
+        for (strategy, restart) in self.strategies.iter_mut().zip(&mut self.strategy_restarts) {
+            match restart.resume_at {
+                Some(at) if graph_ready < at => continue,
+                Some(_) => {
+                    restart.resume_at = None;
+                    restart.backoff.started(graph_ready);
+                    if let Some(health) = &self.health {
+                        health.record_task_started(&format!("{} strategy", strategy.name()));
+                    }
+                }
+                None => {}
+            }
+            let mut opportunities: Vec<Opportunity> = Vec::new();
+            let mut failure = None;
+            {
+                let _span = tracing::debug_span!("detect", strategy = strategy.name()).entered();
+                for event in events.iter().chain(&expired) {
+                    // A panic costs the strategy this pass and a backoff, not the engine
+                    let found = match panic::catch_unwind(AssertUnwindSafe(|| strategy.on_event(&self.graph, event))) {
+                        Ok(found) => found,
+                        Err(panic) => {
+                            failure = Some(panic_message(panic));
+                            break;
+                        }
+                    };
+                    for mut opportunity in found {
+                        if self.max_cycle_len.is_some_and(|max| opportunity.path.len().saturating_sub(1) > max) {
+                            continue;
+                        }
+                        if !self.home_assets.is_empty() && !self.home_assets.iter().any(|home| opportunity.rotate_to(home)) {
+                            continue;
+                        }
+                        // A batch can hold several events; report each cycle once per pass
+                        if !opportunities.iter().any(|o| o.cycle_key() == opportunity.cycle_key()) {
+                            opportunities.push(opportunity);
+                        }
+                    }
+                }
+            }
+            if let Some(failure) = failure {
+                // What it found before panicking may come from half-updated state
+                let now = Instant::now();
+                let delay = restart.backoff.failed(now);
+                restart.resume_at = Some(now + delay);
+                tracing::error!(strategy = strategy.name(), %failure, restart_in_ms = delay.as_millis() as u64, "Strategy failed, restarting");
+                if let Some(health) = &self.health {
+                    health.record_task_failed(&format!("{} strategy", strategy.name()), &failure, now);
+                }
+                continue;
+            }
+            let detected = Instant::now();
+            let detection_latency = detected.duration_since(received);
+            let latency = LatencyBreakdown {
+                exchange_to_receive_ms: exchange_lag_ms,
+                receive_to_graph: graph_ready.duration_since(received),
+                graph_to_detect: detected.duration_since(graph_ready),
+            };
+            if !opportunities.is_empty() {
+                tracing::debug!(count = opportunities.len(), "Strategy reported opportunities");
+            }
+            found += opportunities.len();
+            for mut opportunity in opportunities {
+                opportunity.strategy = strategy.name().to_string();
+                opportunity.detection_latency = Some(detection_latency);
+                opportunity.latency = Some(latency);
+                opportunity.event_time = opportunity.event_time.or(event_time);
+                if self.cbbo_detection {
+                    opportunity.venues = opportunity
+                        .path
+                        .windows(2)
+                        .map(|leg| self.book.conversion(&leg[0], &leg[1]).map(|(_, venue)| venue).unwrap_or_default())
+                        .collect();
+                    if let Some(routing) = self.router.as_ref().and_then(|router| router.route(&self.book, &self.graph, &opportunity.path)) {
+                        opportunity.venues = routing.iter().map(|leg| leg.venue.clone()).collect();
+                        opportunity.routing = routing;
+                    }
+                }
+                if opportunity.rates.is_empty() {
+                    opportunity.rates = opportunity
+                        .path
+                        .windows(2)
+                        .map(|leg| self.graph.rate(&leg[0], &leg[1]).unwrap_or(f64::NAN))
+                        .collect();
+                }
+                if let Some(tracer) = &self.tracer {
+                    opportunity.trace = tracer.trace(&opportunity, &self.graph);
+                }
+                if let Some(valuation) = &self.valuation {
+                    opportunity.reference_profit = valuation.value(&opportunity, &self.graph);
+                }
+                opportunity.low_confidence = !self.degraded.is_empty();
+                if let Some(ttl) = self.ttl.as_mut() {
+                    ttl.observe(&opportunity, received);
+                    // Counted from when the prices it was found on arrived
+                    let priced_at = opportunity.detected_at.checked_sub(detection_latency).unwrap_or(opportunity.detected_at);
+                    opportunity.expires_at = Some(priced_at + ttl.estimate(&opportunity));
+                }
+                let report = self.dedup.as_mut().is_none_or(|dedup| dedup.observe(&opportunity, received));
+                // Without the filter only reported opportunities execute; with it, execution
+                // happens once per streak when the cycle qualifies, even if dedup holds the report
+                let execute = match self.persistence.as_mut() {
+                    Some(filter) => filter.observe(&opportunity.cycle_key(), received),
+                    None => report,
+                };
+                let expired = opportunity.is_expired(SystemTime::now());
+                if execute && expired && self.executor.is_some() {
+                    tracing::info!(cycle = %opportunity.cycle_key(), "Opportunity expired before it could execute");
+                }
+                // Polled prices are too stale and sparse to trade on
+                if let (true, false, false, Some(executor), None) =
+                    (execute, opportunity.low_confidence, expired, self.executor.as_mut(), &self.halted)
+                {
+                    let approved = match &self.risk {
+                        Some(risk) => risk.check(&opportunity, &self.graph, Instant::now()).map_err(|rejection| {
+                            tracing::info!(cycle = %opportunity.cycle_key(), %rejection, "Execution refused by risk limits");
+                            rejection.to_string()
+                        }),
+                        None => Ok(()),
+                    };
+                    if let (Some(log), Some(_)) = (&self.audit, &self.risk) {
+                        let rejection = approved.as_ref().err();
+                        log.record(
+                            "risk_check",
+                            json!({ "cycle": opportunity.cycle_key(), "approved": rejection.is_none(), "rejection": rejection }),
+                        );
+                    }
+                    if approved.is_ok() {
+                        opportunity.executed = true;
+                        executor.execute(&opportunity);
+                        self.stats.executed += 1;
+                    }
+                }
+                if !report && !opportunity.executed {
+                    if let Some(log) = &self.audit {
+                        log.record(
+                            "opportunity_suppressed",
+                            json!({ "cycle": opportunity.cycle_key(), "profit_bps": opportunity.profit.map(|p| p * 10_000.0) }),
+                        );
+                    }
+                    continue;
+                }
+                reported += 1;
+                self.stats.opportunities += 1;
+                announce(&self.events, self.audit.as_ref(), EngineEvent::Opportunity(opportunity));
+            }
+
+            for mut signal in strategy.take_signals() {
+                signal.strategy = strategy.name().to_string();
+                signal.event_time = signal.event_time.or(event_time);
+                if let (false, Some(executor), None) = (signal.orders.is_empty(), self.executor.as_mut(), &self.halted) {
+                    let approved = match &self.risk {
+                        Some(risk) => risk.check_orders(&signal.orders, &self.graph, Instant::now()).map_err(|rejection| {
+                            tracing::info!(kind = %signal.kind, %rejection, "Signal orders refused by risk limits");
+                            rejection.to_string()
+                        }),
+                        None => Ok(()),
+                    };
+                    if let (Some(log), Some(_)) = (&self.audit, &self.risk) {
+                        let rejection = approved.as_ref().err();
+                        log.record(
+                            "risk_check",
+                            json!({ "signal": signal.kind, "approved": rejection.is_none(), "rejection": rejection }),
+                        );
+                    }
+                    if approved.is_ok() {
+                        signal.executed = true;
+                        executor.submit(&signal);
+                    }
+                }
+                self.stats.signals += 1;
+                announce(&self.events, self.audit.as_ref(), EngineEvent::Signal(signal));
+            }
+        }
+
+        if let Some(log) = &self.audit {
+            log.record("detection", json!({ "events": events.len() + expired.len(), "found": found, "reported": reported }));
+        }
+        if let Some(filter) = self.persistence.as_mut() {
+            filter.end_pass();
+        }
+        if let Some(ttl) = self.ttl.as_mut() {
+            ttl.end_pass(received);
+        }
+        if let Some(dedup) = self.dedup.as_mut() {
+            for summary in dedup.close_expired(received) {
+                announce(&self.events, self.audit.as_ref(), EngineEvent::OpportunityClosed(summary));
+            }
+        }
+    }
+
+    // Folds what the shard workers parsed since the last pass into the graph and book
+    fn merge_shards(&mut self) {
+        let Some(shards) = &self.shards else {
+            return;
+        };
+        let entries = shards.merge();
+        let quotes: Vec<VenueQuote> = entries.iter().filter_map(|entry| entry.top_of_book.clone()).collect();
+        if self.cbbo_detection {
+            self.apply_quotes(quotes);
+        } else {
+            for entry in &entries {
+                let (bid, ask) = entry.top_of_book.as_ref().map_or((entry.last, entry.last), |top| (top.bid, top.ask));
+                set_pair_edges(&mut self.graph, &entry.base, &entry.quote, bid, ask);
+            }
+            quotes.into_iter().for_each(|q| self.book.update(q));
+        }
+    }
+
+    // Drops edges older than the max age, returning removal events for strategies that
+    // keep their own prices
+    fn expire_stale_edges(&mut self) -> Vec<MarketEvent> {
+        let Some(max_age) = self.max_edge_age else {
+            return Vec::new();
+        };
+        self.graph
+            .expire_stale(max_age, Instant::now())
+            .into_iter()
+            .map(|(start, end)| {
+                tracing::info!(%start, %end, "Removed stale edge");
+                MarketEvent::SymbolRemoved(format!("{}{}", start, end))
+            })
+            .collect()
+    }
+
+    fn check_depeg(&mut self) {
+        let Some(monitor) = self.depeg.as_mut() else {
+            return;
+        };
+        if let Some(edges) = &self.stable_edges {
+            monitor.set_synthetic(edges.pairs());
+        }
+        for alert in monitor.check(&mut self.graph) {
+            let event = if alert.depegged {
+                EngineEvent::StablecoinDepeg { asset: alert.asset, deviation_bps: alert.deviation_bps }
+            } else {
+                EngineEvent::StablecoinRepegged { asset: alert.asset }
+            };
+            announce(&self.events, self.audit.as_ref(), event);
+        }
+    }
+
+    // Tracks calendar transitions and announces them once
+    fn check_schedule(&mut self) {
+        let Some(schedule) = self.schedule.as_mut() else {
+            return;
+        };
+        let blocked = schedule.blocked_at(chrono::Utc::now());
+        match (&self.halted, blocked) {
+            (None, Some(reason)) => {
+                tracing::warn!(%reason, "Execution halted by trading calendar");
+                announce(&self.events, self.audit.as_ref(), EngineEvent::TradingHalted { reason: reason.clone() });
+                self.halted = Some(reason);
+            }
+            (Some(_), None) => {
+                tracing::info!("Execution resumed by trading calendar");
+                announce(&self.events, self.audit.as_ref(), EngineEvent::TradingResumed);
+                self.halted = None;
+            }
+            _ => {}
+        }
+    }
+
+    // Announces kill switch trips and resets, whoever caused them
+    fn check_kill_switch(&mut self) {
+        let Some(risk) = &self.risk else {
+            return;
+        };
+        match (&self.kill_switch, risk.kill_reason()) {
+            (None, Some(reason)) => {
+                announce(&self.events, self.audit.as_ref(), EngineEvent::KillSwitchTripped { reason: reason.clone() });
+                self.kill_switch = Some(reason);
+            }
+            (Some(_), None) => {
+                announce(&self.events, self.audit.as_ref(), EngineEvent::KillSwitchReset);
+                self.kill_switch = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_quotes(&mut self, quotes: Vec<VenueQuote>) {
+        for quote in quotes {
+            let (base, quote_asset) = (quote.base.clone(), quote.quote.clone());
+            self.book.update(quote);
+            if self.cbbo_detection {
+                refresh_cbbo_edges(&self.book, &mut self.graph, &base, &quote_asset);
+            }
+        }
+    }
+}
+
+// Re-derives both directions of a pair's edges from the current CBBO
+fn refresh_cbbo_edges(book: &ConsolidatedBook, graph: &mut Graph, base: &str, quote: &str) {
+    match book.top(base, quote) {
+        Some((bid, ask)) => {
+            graph.set_edge(base, quote, bid);
+            graph.set_edge(quote, base, 1.0 / ask);
+        }
+        None => {
+            graph.remove_edge(base, quote);
+            graph.remove_edge(quote, base);
+        }
+    }
+}
+
+// Sends an engine event to subscribers, writing it to the audit log first
+fn announce(events: &broadcast::Sender<EngineEvent>, audit: Option<&AuditLog>, event: EngineEvent) {
+    if let Some(log) = audit {
+        log.record("event", event_json(&event));
+    }
+    // No subscribers is fine
+    let _ = events.send(event);
+}
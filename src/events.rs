@@ -0,0 +1,470 @@
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde_json::{json, Value};
+
+use crate::cbbo::VenueQuote;
+use crate::depth::BookDepth;
+use crate::order::{OrderRequest, Side};
+use crate::perp::MarkPrice;
+use crate::ticker::TickerData;
+
+/// A normalized market update produced by a [`Feed`](crate::Feed).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum MarketEvent {
+    /// Latest prices for one or more symbols.
+    Tickers(Vec<TickerData>),
+    /// Venue-attributed top of book, for feeds that aren't Binance tickers.
+    /// These only reach the graph when the engine runs detection on the CBBO.
+    Quotes(Vec<VenueQuote>),
+    /// A symbol is no longer streamed; its edge should leave the graph.
+    SymbolRemoved(String),
+    /// Mark prices and funding rates of USD-M perpetuals. They don't enter the spot
+    /// graph; strategies that trade the basis read them from here.
+    MarkPrices(Vec<MarkPrice>),
+    /// A feed lost its stream and fell back to polling (`degraded`), or got it back.
+    /// Prices it delivers while degraded are older and sparser than streamed ones.
+    FeedState { venue: String, degraded: bool },
+    /// A feed's connection delivered nothing for `silent_ms` and was dropped as dead; the
+    /// feed reconnects on its own.
+    FeedStalled { venue: String, silent_ms: u64 },
+    /// Top levels of the order book for symbols subscribed to a partial depth stream.
+    Depth(Vec<BookDepth>),
+}
+
+/// An arbitrage cycle reported by a [`Strategy`](crate::Strategy).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Opportunity {
+    /// Assets visited by the cycle, starting and ending on the same asset.
+    pub path: Vec<String>,
+    /// When the opportunity was detected.
+    pub detected_at: SystemTime,
+    /// Exchange event time (ms) of the update that triggered detection, filled in by the
+    /// engine when the feed provides one. Unlike `detected_at` it is stable across replays.
+    pub event_time: Option<u64>,
+    /// Expected return of one pass around the cycle after fees (0.001 = 10 bps), if known.
+    pub profit: Option<f64>,
+    /// Conversion rate used for each leg, filled in by the engine if the strategy doesn't.
+    pub rates: Vec<f64>,
+    /// Name of the strategy that reported it, filled in by the engine.
+    pub strategy: String,
+    /// Time from the engine receiving the triggering update to the strategy reporting.
+    pub detection_latency: Option<Duration>,
+    /// Where that time went, filled in by the engine.
+    pub latency: Option<LatencyBreakdown>,
+    /// Whether the opportunity was handed to the executor.
+    pub executed: bool,
+    /// Venue providing the price for each leg when detection runs on the consolidated
+    /// book; empty otherwise. Execution should route leg `i` to `venues[i]`.
+    pub venues: Vec<String>,
+    /// Per-leg venue choice of the smart order router, when enabled; `venues` then
+    /// follows it.
+    pub routing: Vec<LegRoute>,
+    /// Detected while a feed was polling instead of streaming, so on older prices than
+    /// usual. Such opportunities are reported but not executed.
+    pub low_confidence: bool,
+    /// Simulated execution of the cycle leg by leg, when the engine is configured to
+    /// trace opportunities.
+    pub trace: Option<CycleTrace>,
+    /// Expected profit as an amount of the engine's reference asset, when the engine is
+    /// configured to value opportunities.
+    pub reference_profit: Option<ReferenceProfit>,
+    /// When the opportunity is expected to be gone, estimated by the engine from how long
+    /// cycles stay open and how often their quotes update. Executors and alerting sinks
+    /// don't act on it afterwards.
+    pub expires_at: Option<SystemTime>,
+}
+
+impl Opportunity {
+    pub fn new(path: Vec<String>) -> Self {
+        Opportunity {
+            path,
+            detected_at: SystemTime::now(),
+            event_time: None,
+            profit: None,
+            rates: Vec::new(),
+            strategy: String::new(),
+            detection_latency: None,
+            latency: None,
+            executed: false,
+            venues: Vec::new(),
+            routing: Vec::new(),
+            low_confidence: false,
+            trace: None,
+            reference_profit: None,
+            expires_at: None,
+        }
+    }
+
+    /// Whether `now` is past [`expires_at`](Opportunity::expires_at); never without one.
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    /// Rotates the cycle to start and end at `asset`, along with its per-leg rates, venues
+    /// and routes. Returns false, leaving it as it was, if the cycle doesn't pass through
+    /// `asset`; a path that isn't closed can only start where it does.
+    pub fn rotate_to(&mut self, asset: &str) -> bool {
+        let closed = self.path.len() > 1 && self.path.first() == self.path.last();
+        let Some(start) = self.path.iter().position(|a| a == asset) else {
+            return false;
+        };
+        if start == 0 || !closed {
+            return start == 0;
+        }
+        let legs = self.path.len() - 1;
+        self.path.pop();
+        self.path.rotate_left(start);
+        self.path.push(asset.to_string());
+        if self.rates.len() == legs {
+            self.rates.rotate_left(start);
+        }
+        if self.venues.len() == legs {
+            self.venues.rotate_left(start);
+        }
+        if self.routing.len() == legs {
+            self.routing.rotate_left(start);
+        }
+        true
+    }
+
+    /// Identifies the cycle independent of where it starts: the path rotated to begin
+    /// at its smallest asset, e.g. `BTC>ETH>USDT` for both `USDT>BTC>ETH>USDT` and
+    /// `ETH>USDT>BTC>ETH`.
+    pub fn cycle_key(&self) -> String {
+        let assets = match self.path.split_last() {
+            Some((last, rest)) if Some(last) == self.path.first() => rest,
+            _ => &self.path[..],
+        };
+        let Some(start) = (0..assets.len()).min_by_key(|&i| &assets[i]) else {
+            return String::new();
+        };
+        let rotated: Vec<&str> = assets[start..].iter().chain(&assets[..start]).map(String::as_str).collect();
+        rotated.join(">")
+    }
+
+    /// Stable JSON representation shared by all machine-readable outputs.
+    pub fn to_json(&self) -> Value {
+        let detected_at: DateTime<Utc> = self.detected_at.into();
+        json!({
+            "path": self.path,
+            "cycle": self.cycle_key(),
+            "rates": self.rates,
+            "gross_return": (!self.rates.is_empty()).then(|| self.rates.iter().product::<f64>() - 1.0),
+            "profit_bps": self.profit.map(|p| p * 10_000.0),
+            "venues": self.venues,
+            "routing": self.routing.iter().map(LegRoute::to_json).collect::<Vec<_>>(),
+            "strategy": self.strategy,
+            "detection_latency_us": self.detection_latency.map(|d| d.as_micros() as u64),
+            "latency": self.latency.map(|latency| latency.to_json(self.detected_at)),
+            "executed": self.executed,
+            "low_confidence": self.low_confidence,
+            "detected_at": detected_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "event_time": self.event_time,
+            "trace": self.trace.as_ref().map(CycleTrace::to_json),
+            "reference_profit": self.reference_profit.as_ref().map(ReferenceProfit::to_json),
+            "expires_at": self.expires_at.map(|at| DateTime::<Utc>::from(at).to_rfc3339_opts(SecondsFormat::Millis, true)),
+        })
+    }
+
+    pub fn with_profit(mut self, profit: f64) -> Self {
+        self.profit = Some(profit);
+        self
+    }
+}
+
+/// Venue chosen for one leg of a cycle by the smart order router.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegRoute {
+    /// Venue the leg executes on.
+    pub venue: String,
+    /// Conversion rate on that venue after its taker fee.
+    pub rate: f64,
+    /// Taker fee the venue charges, in bps.
+    pub fee_bps: f64,
+    /// Share of the leg's amount the venue's top of book can fill, from 0 to 1.
+    pub fill: f64,
+}
+
+impl LegRoute {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "venue": self.venue,
+            "rate": self.rate,
+            "fee_bps": self.fee_bps,
+            "fill": self.fill,
+        })
+    }
+}
+
+/// Expected profit of an opportunity in a common reference asset, for comparing cycles
+/// that start in different assets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReferenceProfit {
+    /// Asset the profit is expressed in, e.g. USDT.
+    pub asset: String,
+    /// Size of the pass the profit is for, in `asset`.
+    pub notional: f64,
+    /// Expected profit of that pass, in `asset`.
+    pub amount: f64,
+}
+
+impl ReferenceProfit {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "asset": self.asset,
+            "notional": self.notional,
+            "amount": self.amount,
+        })
+    }
+}
+
+/// Step-by-step simulation of walking a cycle with a starting amount: what each leg
+/// spends, the price and fee it pays, how its order quantity rounds and what it returns.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleTrace {
+    /// Amount of the cycle's first asset the simulation starts with.
+    pub start_amount: f64,
+    /// Amount of the first asset held after the last leg.
+    pub final_amount: f64,
+    /// One step per leg, in order.
+    pub legs: Vec<LegTrace>,
+}
+
+impl CycleTrace {
+    /// Return of the simulated pass, `final_amount / start_amount - 1`.
+    pub fn profit(&self) -> f64 {
+        self.final_amount / self.start_amount - 1.0
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "start_amount": self.start_amount,
+            "final_amount": self.final_amount,
+            "profit_bps": self.profit() * 10_000.0,
+            "legs": self.legs.iter().map(LegTrace::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// One leg of a [`CycleTrace`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegTrace {
+    /// Asset spent.
+    pub from: String,
+    /// Asset received.
+    pub to: String,
+    /// Exchange symbol and side of the order, when the exchange's filters are known.
+    pub order: Option<(String, Side)>,
+    /// Conversion rate the opportunity was detected at, `to` per `from`.
+    pub rate: f64,
+    /// Order price in quote per base after rounding onto the tick grid, when the filters
+    /// are known.
+    pub price: Option<f64>,
+    /// Order quantity in base units after rounding down to the lot step, when the filters
+    /// are known.
+    pub quantity: Option<f64>,
+    /// Amount of `from` available to the leg.
+    pub amount_in: f64,
+    /// Part of `amount_in` left unspent by quantity rounding.
+    pub residual: f64,
+    /// Fee charged on what the leg returns, in bps.
+    pub fee_bps: f64,
+    /// Amount of `to` received after the fee.
+    pub amount_out: f64,
+}
+
+impl LegTrace {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "from": self.from,
+            "to": self.to,
+            "symbol": self.order.as_ref().map(|(symbol, _)| symbol),
+            "side": self.order.as_ref().map(|(_, side)| side.as_str()),
+            "rate": self.rate,
+            "price": self.price,
+            "quantity": self.quantity,
+            "amount_in": self.amount_in,
+            "residual": self.residual,
+            "fee_bps": self.fee_bps,
+            "amount_out": self.amount_out,
+        })
+    }
+}
+
+/// Timing of one opportunity from the exchange to the output that reports it.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct LatencyBreakdown {
+    /// Exchange event time to local receipt (ms) for the stalest update in the detection
+    /// pass, when the feed provides event times. Includes clock skew, so it can be negative.
+    pub exchange_to_receive_ms: Option<i64>,
+    /// Receipt of the oldest update in the pass to the graph being ready for detection,
+    /// including any throttle wait and shard merge.
+    pub receive_to_graph: Duration,
+    /// Graph ready to the strategy reporting the opportunity.
+    pub graph_to_detect: Duration,
+}
+
+impl LatencyBreakdown {
+    /// The breakdown plus `detect_to_output_us`, the age of the signal at the moment it is
+    /// serialized, measured from `detected_at`.
+    pub fn to_json(&self, detected_at: SystemTime) -> Value {
+        let detect_to_output = SystemTime::now().duration_since(detected_at).unwrap_or_default();
+        json!({
+            "exchange_to_receive_ms": self.exchange_to_receive_ms,
+            "receive_to_graph_us": self.receive_to_graph.as_micros() as u64,
+            "graph_to_detect_us": self.graph_to_detect.as_micros() as u64,
+            "detect_to_output_us": detect_to_output.as_micros() as u64,
+        })
+    }
+}
+
+/// Lifetime of a cycle that was open across several detections and has now closed.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct OpportunitySummary {
+    /// Rotation-independent cycle key, see [`Opportunity::cycle_key`].
+    pub cycle: String,
+    /// Path as first reported.
+    pub path: Vec<String>,
+    /// When the cycle was first detected.
+    pub opened_at: SystemTime,
+    /// Time between the first and last detection.
+    pub duration: Duration,
+    /// Number of detections while open, including suppressed repeats.
+    pub detections: u64,
+    /// Highest net profit seen while open, if known.
+    pub best_profit: Option<f64>,
+}
+
+impl OpportunitySummary {
+    pub fn to_json(&self) -> Value {
+        let opened_at: DateTime<Utc> = self.opened_at.into();
+        json!({
+            "cycle": self.cycle,
+            "path": self.path,
+            "opened_at": opened_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "duration_ms": self.duration.as_millis() as u64,
+            "detections": self.detections,
+            "best_profit_bps": self.best_profit.map(|p| p * 10_000.0),
+        })
+    }
+}
+
+/// Output of a [`Strategy`](crate::Strategy) other than an arbitrage cycle: a view on one
+/// or more instruments, optionally with the orders that act on it.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Signal {
+    /// What is being signalled, named by the strategy (e.g. "spread_zscore").
+    pub kind: String,
+    /// Symbols or assets the signal is about.
+    pub instruments: Vec<String>,
+    /// Strength in the strategy's own units; the sign gives the direction.
+    pub value: f64,
+    /// Orders the strategy wants placed, in sequence. Empty for informational signals.
+    pub orders: Vec<OrderRequest>,
+    /// Strategy-specific figures behind the signal, passed through to JSON outputs.
+    pub details: serde_json::Map<String, Value>,
+    /// Name of the strategy that emitted it, filled in by the engine.
+    pub strategy: String,
+    /// When the signal was emitted.
+    pub emitted_at: SystemTime,
+    /// Exchange event time (ms) of the update that triggered it, filled in by the engine.
+    pub event_time: Option<u64>,
+    /// Whether the orders were handed to the executor.
+    pub executed: bool,
+}
+
+impl Signal {
+    pub fn new(kind: impl Into<String>, instruments: Vec<String>, value: f64) -> Self {
+        Signal {
+            kind: kind.into(),
+            instruments,
+            value,
+            orders: Vec::new(),
+            details: serde_json::Map::new(),
+            strategy: String::new(),
+            emitted_at: SystemTime::now(),
+            event_time: None,
+            executed: false,
+        }
+    }
+
+    pub fn with_orders(mut self, orders: Vec<OrderRequest>) -> Self {
+        self.orders = orders;
+        self
+    }
+
+    pub fn with_detail(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.details.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Stable JSON representation shared by all machine-readable outputs.
+    pub fn to_json(&self) -> Value {
+        let emitted_at: DateTime<Utc> = self.emitted_at.into();
+        let orders: Vec<Value> = self
+            .orders
+            .iter()
+            .map(|order| {
+                json!({
+                    "symbol": order.symbol,
+                    "side": order.side,
+                    "quantity": order.quantity,
+                    "price": order.price,
+                })
+            })
+            .collect();
+        json!({
+            "kind": self.kind,
+            "instruments": self.instruments,
+            "value": self.value,
+            "orders": orders,
+            "details": self.details,
+            "strategy": self.strategy,
+            "executed": self.executed,
+            "emitted_at": emitted_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+            "event_time": self.event_time,
+        })
+    }
+}
+
+/// Events the [`Engine`](crate::Engine) broadcasts to its subscribers.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EngineEvent {
+    /// A strategy found an opportunity.
+    Opportunity(Opportunity),
+    /// A strategy emitted a signal.
+    Signal(Signal),
+    /// A deduplicated cycle closed; repeats while it was open were suppressed.
+    OpportunityClosed(OpportunitySummary),
+    /// Execution is suspended by the trading calendar; detection continues.
+    TradingHalted { reason: String },
+    /// The calendar allows execution again.
+    TradingResumed,
+    /// A stablecoin moved at least the alert threshold away from its peers. Edges into
+    /// it carry a risk premium while it stays off peg.
+    StablecoinDepeg { asset: String, deviation_bps: f64 },
+    /// A previously depegged stablecoin is back within the alert threshold.
+    StablecoinRepegged { asset: String },
+    /// The risk manager's kill switch tripped; nothing executes until it is reset.
+    KillSwitchTripped { reason: String },
+    /// The kill switch was reset and execution may resume.
+    KillSwitchReset,
+    /// An update for `symbol` carried an invalid price or an implausible move. The update
+    /// was dropped and the symbol's edge removed; its updates are ignored until the
+    /// breaker resets.
+    PriceBreakerTripped { symbol: String, reason: String },
+    /// A quarantined symbol's cooldown passed and its prices are accepted again.
+    PriceBreakerReset { symbol: String },
+    /// A feed's connection went quiet for `silent_ms` and was reconnected, so its prices
+    /// may have been stale for that long.
+    FeedStalled { venue: String, silent_ms: u64 },
+    /// The feed ended; the engine is about to stop.
+    FeedClosed,
+}
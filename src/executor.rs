@@ -0,0 +1,24 @@
+use futures_util::future::BoxFuture;
+
+use crate::events::{Opportunity, Signal};
+
+/// Acts on opportunities reported by strategies (paper trading, live orders, ...).
+///
+/// `execute` is called inline on the engine loop, so implementations that talk to
+/// an exchange should hand the work to their own task and return quickly.
+pub trait Executor: Send {
+    fn execute(&mut self, opportunity: &Opportunity);
+
+    /// Called for strategy signals that carry orders, under the same trading calendar and
+    /// kill switch as cycles. The default ignores them.
+    fn submit(&mut self, signal: &Signal) {
+        let _ = signal;
+    }
+
+    /// Called once when the engine stops, after the last `execute`: cancel or unwind
+    /// whatever is still in flight. The engine waits for the returned future before
+    /// closing the feed. The default does nothing.
+    fn shutdown(&mut self) -> BoxFuture<'static, ()> {
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,88 @@
+use std::io;
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use tokio_tungstenite::tungstenite::error::UrlError;
+use tokio_tungstenite::tungstenite::protocol::Message;
+use tokio_tungstenite::tungstenite::Error as WsError;
+
+use crate::proxy::{connect_websocket, Proxy, WsStream};
+
+// Hosts serving Binance's spot combined streams. data-stream.binance.vision carries
+// market data only, which is all the combined streams are.
+pub const BINANCE_WS_MIRRORS: &[&str] = &[
+    "wss://stream.binance.com:9443/stream",
+    "wss://stream.binance.com:443/stream",
+    "wss://data-stream.binance.vision/stream",
+];
+
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    pub endpoints: Vec<String>,  // Combined stream endpoints to race; the environment's mirrors when empty
+    pub probe_timeout: Duration, // How long an endpoint gets to connect and deliver its first message
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        FailoverConfig {
+            endpoints: Vec::new(),
+            probe_timeout: Duration::from_secs(5),
+        }
+    }
+}
+
+// The endpoint that won a race
+pub struct Connected {
+    pub index: usize, // Into the raced endpoints
+    pub ws: WsStream,
+    pub first: Option<Message>, // Already read, so it still needs handling
+    pub latency: Duration,      // From the start of the race to the handshake, or to the first message when waited for
+}
+
+// Opens `streams` on every endpoint at once and keeps whichever is ready first: handshake
+// done and, with streams to wait for, data flowing, so a socket that connects and then
+// stalls gets no credit. The rest are dropped mid-flight. Fails with the last error when
+// none is ready within `timeout`.
+pub async fn race(endpoints: &[String], streams: &[String], proxy: Option<&Proxy>, timeout: Duration) -> Result<Connected, WsError> {
+    let started = Instant::now();
+    let mut attempts: FuturesUnordered<_> = endpoints
+        .iter()
+        .enumerate()
+        .map(|(index, endpoint)| async move {
+            let attempt = async {
+                let (mut ws, _) = connect_websocket(&stream_url(endpoint, streams), proxy).await?;
+                if streams.is_empty() {
+                    return Ok((ws, None));
+                }
+                match ws.next().await {
+                    Some(Ok(message)) => Ok((ws, Some(message))),
+                    Some(Err(e)) => Err(e),
+                    None => Err(WsError::ConnectionClosed),
+                }
+            };
+            match tokio::time::timeout(timeout, attempt).await {
+                Ok(result) => (index, result),
+                Err(_) => (index, Err(WsError::Io(io::Error::new(io::ErrorKind::TimedOut, "not ready in time")))),
+            }
+        })
+        .collect();
+    let mut last_error = None;
+    while let Some((index, result)) = attempts.next().await {
+        match result {
+            Ok((ws, first)) => {
+                let latency = started.elapsed();
+                return Ok(Connected { index, ws, first, latency });
+            }
+            Err(e) => {
+                tracing::warn!(endpoint = %endpoints[index], error = %e, "Endpoint failed to connect");
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(last_error.unwrap_or(WsError::Url(UrlError::NoHostName)))
+}
+
+// A combined stream endpoint with `streams` subscribed from the start
+pub fn stream_url(endpoint: &str, streams: &[String]) -> String {
+    format!("{}?streams={}", endpoint, streams.join("/"))
+}
@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+
+use crate::rest::{EndpointCategory, RestClient, RestError};
+use crate::ticker::TickerData;
+
+#[derive(Debug, Clone)]
+pub struct FallbackConfig {
+    pub poll_interval: Duration,   // Between bookTicker polls while the socket is down
+    pub reconnect_delay: Duration, // Between attempts to reopen the socket
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        FallbackConfig {
+            poll_interval: Duration::from_secs(1),
+            reconnect_delay: Duration::from_secs(5),
+        }
+    }
+}
+
+// Entry of GET /api/v3/ticker/bookTicker
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BookTicker {
+    symbol: String,
+    bid_price: String,
+    bid_qty: String,
+    ask_price: String,
+    ask_qty: String,
+}
+
+// Top of book for every symbol, or only `symbols` when given, in the stream's ticker
+// format: bid and ask with the mid as the last price. No event time, as REST gives none.
+// All symbols in one request (weight 4).
+pub async fn book_tickers(client: &RestClient, symbols: Option<&HashSet<String>>) -> Result<Vec<TickerData>, RestError> {
+    let body = client.get(EndpointCategory::Market, 4, "/api/v3/ticker/bookTicker", &[]).await?;
+    let tickers: Vec<BookTicker> = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+    Ok(tickers
+        .into_iter()
+        .filter(|t| symbols.is_none_or(|s| s.contains(&t.symbol)))
+        .filter_map(to_ticker)
+        .collect())
+}
+
+// Like `book_tickers`, asking for just these symbols (weight 4, 2 for one symbol)
+pub async fn quote_symbols(client: &RestClient, symbols: &[String]) -> Result<Vec<TickerData>, RestError> {
+    let list = serde_json::to_string(symbols).map_err(|e| RestError::Decode(e.to_string()))?;
+    let weight = if symbols.len() == 1 { 2 } else { 4 };
+    let body = client.get(EndpointCategory::Market, weight, "/api/v3/ticker/bookTicker", &[("symbols", list)]).await?;
+    let tickers: Vec<BookTicker> = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+    Ok(tickers.into_iter().filter_map(to_ticker).collect())
+}
+
+fn to_ticker(t: BookTicker) -> Option<TickerData> {
+    let bid: Decimal = t.bid_price.parse().ok()?;
+    let ask: Decimal = t.ask_price.parse().ok()?;
+    // Symbols that aren't trading come back with an empty book
+    if bid <= Decimal::ZERO || ask <= Decimal::ZERO {
+        return None;
+    }
+    Some(TickerData {
+        s: t.symbol,
+        c: ((bid + ask) / Decimal::TWO).normalize().to_string(),
+        event_time: 0,
+        b: Some(t.bid_price),
+        bid_qty: Some(t.bid_qty),
+        a: Some(t.ask_price),
+        ask_qty: Some(t.ask_qty),
+        update_id: None,
+        quote_volume: None,
+        trade: false,
+    })
+}
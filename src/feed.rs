@@ -0,0 +1,431 @@
+use std::collections::{HashSet, VecDeque};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::sink::SinkExt;
+use futures_util::stream::{SplitSink, SplitStream, StreamExt};
+use tokio::sync::mpsc;
+use tokio::time::{Interval, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::protocol::Message;
+use url::Url;
+
+use crate::events::MarketEvent;
+use crate::failover::{self, FailoverConfig};
+use crate::fallback::{self, FallbackConfig};
+use crate::proxy::{connect_websocket, Proxy, WsStream};
+use crate::recorder::Recorder;
+use crate::rest::{RestClient, RestError};
+use crate::subscription::{self, SubscriptionCommand, SubscriptionManager};
+use crate::ticker::StreamMessage;
+use crate::watchdog::{self, Watchdog};
+
+pub const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/stream";
+
+/// A source of [`MarketEvent`]s. `None` means the feed has ended.
+pub trait Feed: Send {
+    /// Venue name attached to quotes from this feed.
+    fn venue(&self) -> &str {
+        "unknown"
+    }
+
+    /// Waits for the next event. With a throttle the engine may drop this future to run a
+    /// pending detection pass, so it should not lose data when cancelled.
+    fn next_event(&mut self) -> impl Future<Output = Option<MarketEvent>> + Send;
+
+    /// Closes the connection cleanly and flushes anything buffered. Called once when the
+    /// engine stops, whether or not the feed has ended; the default does nothing.
+    fn close(&mut self) -> impl Future<Output = ()> + Send {
+        async {}
+    }
+}
+
+const VENUE: &str = "binance";
+
+struct Link {
+    write: SplitSink<WsStream, Message>,
+    read: SplitStream<WsStream>,
+}
+
+impl Link {
+    fn new(ws: WsStream) -> Self {
+        let (write, read) = ws.split();
+        Link { write, read }
+    }
+}
+
+// Racing between stream hosts
+struct Failover {
+    config: FailoverConfig,
+    active: usize, // Index of the endpoint in use
+}
+
+// Polling state while the socket is down
+struct Fallback {
+    client: Arc<RestClient>,
+    poll: Interval,
+    reconnect: Interval,
+}
+
+/// Binance combined stream feed with runtime SUBSCRIBE/UNSUBSCRIBE support.
+pub struct BinanceFeed {
+    endpoint: String,
+    proxy: Option<Proxy>,
+    link: Option<Link>, // None while degraded to REST polling
+    held: Option<Message>, // Read while racing endpoints, not handled yet
+    failover: Option<Failover>,
+    fallback: Option<Fallback>,
+    watchdog: Option<Watchdog>,
+    subscriptions: SubscriptionManager,
+    commands_tx: mpsc::UnboundedSender<SubscriptionCommand>,
+    commands: mpsc::UnboundedReceiver<SubscriptionCommand>,
+    recorder: Option<Recorder>,
+    pending: VecDeque<MarketEvent>,
+}
+
+impl BinanceFeed {
+    /// Connects to the combined stream endpoint with the given initial streams.
+    pub async fn connect(streams: &[String]) -> Result<Self, tokio_tungstenite::tungstenite::Error> {
+        Self::connect_to(BINANCE_WS_URL, streams).await
+    }
+
+    /// Like [`connect`](BinanceFeed::connect) on another combined stream endpoint, such
+    /// as the spot testnet's.
+    pub async fn connect_to(endpoint: &str, streams: &[String]) -> Result<Self, tokio_tungstenite::tungstenite::Error> {
+        Self::connect_via(endpoint, streams, None).await
+    }
+
+    /// Like [`connect_to`](BinanceFeed::connect_to), tunnelled through `proxy` when given.
+    pub async fn connect_via(
+        endpoint: &str,
+        streams: &[String],
+        proxy: Option<&Proxy>,
+    ) -> Result<Self, tokio_tungstenite::tungstenite::Error> {
+        let link = open(endpoint, streams, proxy).await?;
+        Ok(Self::from_link(endpoint, streams, proxy, link))
+    }
+
+    /// Races every endpoint in `config` and streams from whichever delivers data first.
+    /// When the active endpoint errors, closes or trips the [watchdog](BinanceFeed::with_watchdog),
+    /// the feed moves to the fastest of the others before falling back to REST polling.
+    pub async fn connect_fastest(
+        streams: &[String],
+        proxy: Option<&Proxy>,
+        config: FailoverConfig,
+    ) -> Result<Self, tokio_tungstenite::tungstenite::Error> {
+        let connected = failover::race(&config.endpoints, streams, proxy, config.probe_timeout).await?;
+        let endpoint = &config.endpoints[connected.index];
+        tracing::info!(%endpoint, latency_ms = connected.latency.as_millis() as u64, "Connected to the fastest endpoint");
+        let mut feed = Self::from_link(endpoint, streams, proxy, Link::new(connected.ws));
+        feed.held = connected.first;
+        feed.failover = Some(Failover { active: connected.index, config });
+        Ok(feed)
+    }
+
+    fn from_link(endpoint: &str, streams: &[String], proxy: Option<&Proxy>, link: Link) -> Self {
+        let (commands_tx, commands) = mpsc::unbounded_channel();
+        BinanceFeed {
+            endpoint: endpoint.to_string(),
+            proxy: proxy.cloned(),
+            link: Some(link),
+            held: None,
+            failover: None,
+            fallback: None,
+            watchdog: None,
+            subscriptions: SubscriptionManager::new(streams),
+            commands_tx,
+            commands,
+            recorder: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Saves every raw message to `recorder` for later replay.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Keeps the feed alive when the socket drops: until it reconnects, top of book for the
+    /// subscribed symbols is polled from REST. The engine hears about the switch through
+    /// [`MarketEvent::FeedState`] and treats the polled prices as lower confidence. Without
+    /// a fallback the feed ends when the socket does.
+    pub fn with_rest_fallback(mut self, client: Arc<RestClient>, config: FallbackConfig) -> Self {
+        let mut poll = tokio::time::interval(config.poll_interval);
+        let mut reconnect = tokio::time::interval(config.reconnect_delay);
+        poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        reconnect.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        self.fallback = Some(Fallback { client, poll, reconnect });
+        self
+    }
+
+    /// Reconnects when the socket delivers nothing, not even a ping, for `max_silence`, as
+    /// a half-open connection never errors. The engine hears about it through
+    /// [`MarketEvent::FeedStalled`]. The reconnect goes to another endpoint with failover;
+    /// if it fails the feed polls REST with a fallback, or keeps waiting on the old socket.
+    pub fn with_watchdog(mut self, max_silence: Duration) -> Self {
+        self.watchdog = Some(Watchdog::new(max_silence));
+        self
+    }
+
+    /// Queues a REST top-of-book snapshot of the subscribed symbols as the feed's first
+    /// event, so the graph is complete before every symbol has ticked on the stream.
+    /// Returns the number of symbols in it.
+    pub async fn bootstrap(&mut self, client: &RestClient) -> Result<usize, RestError> {
+        let tickers = fallback::book_tickers(client, self.polled_symbols().as_ref()).await?;
+        let count = tickers.len();
+        if count > 0 {
+            self.pending.push_front(MarketEvent::Tickers(tickers));
+        }
+        Ok(count)
+    }
+
+    /// Handle for changing subscriptions while the feed is running.
+    pub fn commands(&self) -> mpsc::UnboundedSender<SubscriptionCommand> {
+        self.commands_tx.clone()
+    }
+
+    fn handle_message(&mut self, message: Message) {
+        if message.is_text() || message.is_binary() {
+            match message.to_text() {
+                Ok(text) => self.handle_text(text),
+                Err(e) => tracing::warn!(error = %e, "Error decoding message"),
+            }
+        }
+    }
+
+    fn handle_text(&mut self, text: &str) {
+        let _span = tracing::debug_span!("message", bytes = text.len()).entered();
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.record(text) {
+                tracing::error!(error = %e, "Error recording message");
+            }
+        }
+        match StreamMessage::parse(text) {
+            Ok(StreamMessage::Event { stream, data }) => {
+                // Late events for a stream we just unsubscribed from
+                if self.subscriptions.is_active(&stream) {
+                    self.pending.push_back(MarketEvent::Tickers(data.into_vec()));
+                }
+            }
+            Ok(StreamMessage::Depth { stream, depth }) => {
+                if self.subscriptions.is_active(&stream) {
+                    self.pending.push_back(MarketEvent::Depth(vec![depth]));
+                }
+            }
+            Ok(StreamMessage::Response(response)) => match self.subscriptions.handle_response(response) {
+                Some(SubscriptionCommand::Subscribe(streams)) => {
+                    tracing::info!(?streams, "Subscribed");
+                }
+                Some(SubscriptionCommand::Unsubscribe(streams)) => {
+                    for symbol in streams.iter().filter_map(|s| subscription::stream_symbol(s)) {
+                        self.pending.push_back(MarketEvent::SymbolRemoved(symbol));
+                    }
+                    tracing::info!(?streams, "Unsubscribed");
+                }
+                None => {}
+            },
+            Err(e) => {
+                tracing::warn!(error = %e, "Error parsing ticker data");
+            }
+        }
+    }
+
+    // Symbols the subscribed streams cover, or None when a market-wide stream covers them all
+    fn polled_symbols(&self) -> Option<HashSet<String>> {
+        let streams = self.subscriptions.active();
+        streams.iter().map(|s| subscription::stream_symbol(s)).collect()
+    }
+
+    // After the socket failed: the fastest other endpoint if one answers, otherwise REST
+    // polling; false when neither is available
+    async fn recover(&mut self) -> bool {
+        self.flush_recording();
+        self.fail_over(true).await || self.degrade()
+    }
+
+    // Moves the streams to the fastest endpoint that answers, leaving out the one in use
+    // when `skip_active` and there are others; false without failover or when none answers
+    async fn fail_over(&mut self, skip_active: bool) -> bool {
+        let Some(failover) = self.failover.as_ref() else {
+            return false;
+        };
+        let endpoints = &failover.config.endpoints;
+        let candidates: Vec<usize> = (0..endpoints.len())
+            .filter(|&i| !skip_active || i != failover.active || endpoints.len() == 1)
+            .collect();
+        let raced: Vec<String> = candidates.iter().map(|&i| endpoints[i].clone()).collect();
+        let streams = self.subscriptions.active();
+        match failover::race(&raced, &streams, self.proxy.as_ref(), failover.config.probe_timeout).await {
+            Ok(connected) => {
+                let endpoint = raced[connected.index].clone();
+                tracing::info!(%endpoint, latency_ms = connected.latency.as_millis() as u64, "Switched to the fastest endpoint");
+                self.link = Some(Link::new(connected.ws));
+                self.held = connected.first;
+                self.endpoint = endpoint;
+                if let Some(failover) = self.failover.as_mut() {
+                    failover.active = candidates[connected.index];
+                }
+                self.alive();
+                true
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "No endpoint to fail over to");
+                false
+            }
+        }
+    }
+
+    // Reopens the socket, on the fastest endpoint with failover
+    async fn reopen(&mut self) -> bool {
+        if self.failover.is_some() {
+            return self.fail_over(false).await;
+        }
+        let streams = self.subscriptions.active();
+        match open(&self.endpoint, &streams, self.proxy.as_ref()).await {
+            Ok(link) => {
+                self.link = Some(link);
+                self.alive();
+                true
+            }
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to reconnect to the Binance WebSocket");
+                false
+            }
+        }
+    }
+
+    // After the socket went quiet: a fresh connection, on another endpoint with failover,
+    // otherwise REST polling. With neither the quiet socket stays, as it may yet recover.
+    async fn stalled(&mut self) {
+        let silent = self.watchdog.as_ref().map_or(Duration::ZERO, |w| w.silent_for(Instant::now()));
+        tracing::warn!(endpoint = %self.endpoint, silent_ms = silent.as_millis() as u64, "No messages from the Binance WebSocket, reconnecting");
+        self.pending.push_back(MarketEvent::FeedStalled { venue: VENUE.to_string(), silent_ms: silent.as_millis() as u64 });
+        self.flush_recording();
+        let reconnected = if self.failover.is_some() { self.fail_over(true).await } else { self.reopen().await };
+        if !reconnected && self.fallback.is_some() {
+            self.degrade();
+        }
+        self.alive();
+    }
+
+    // Something arrived or a connection was just opened, so silence counts from now
+    fn alive(&mut self) {
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            watchdog.reset();
+        }
+    }
+
+    // Drops the socket and switches to polling; false when there is no fallback to switch to
+    fn degrade(&mut self) -> bool {
+        self.link = None;
+        let Some(fallback) = self.fallback.as_mut() else {
+            return false;
+        };
+        fallback.poll.reset_immediately();
+        fallback.reconnect.reset();
+        tracing::warn!("Binance WebSocket lost, polling REST until it reconnects");
+        self.pending.push_back(MarketEvent::FeedState { venue: VENUE.to_string(), degraded: true });
+        true
+    }
+
+    // One poll or reconnect attempt while degraded
+    async fn next_degraded(&mut self) {
+        let symbols = self.polled_symbols();
+        let Some(fallback) = self.fallback.as_mut() else {
+            return;
+        };
+        tokio::select! {
+            _ = fallback.poll.tick() => match fallback::book_tickers(&fallback.client, symbols.as_ref()).await {
+                Ok(tickers) if !tickers.is_empty() => self.pending.push_back(MarketEvent::Tickers(tickers)),
+                Ok(_) => {}
+                Err(e) => tracing::warn!(error = %e, "REST fallback poll failed"),
+            },
+            _ = fallback.reconnect.tick() => {
+                if self.reopen().await {
+                    tracing::info!("Reconnected to the Binance WebSocket, REST polling stopped");
+                    self.pending.push_back(MarketEvent::FeedState { venue: VENUE.to_string(), degraded: false });
+                }
+            }
+        }
+    }
+
+    fn flush_recording(&mut self) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            if let Err(e) = recorder.flush() {
+                tracing::error!(error = %e, "Error flushing recording");
+            }
+        }
+    }
+}
+
+async fn open(endpoint: &str, streams: &[String], proxy: Option<&Proxy>) -> Result<Link, tokio_tungstenite::tungstenite::Error> {
+    let url = Url::parse(&failover::stream_url(endpoint, streams)).expect("Failed to parse URL");
+    let (ws_stream, _) = connect_websocket(url.as_str(), proxy).await?;
+    Ok(Link::new(ws_stream))
+}
+
+impl Feed for BinanceFeed {
+    fn venue(&self) -> &str {
+        VENUE
+    }
+
+    async fn close(&mut self) {
+        // A close frame lets the server end the session instead of seeing a dropped socket
+        if let Some(link) = self.link.as_mut() {
+            if let Err(e) = link.write.send(Message::Close(None)).await {
+                tracing::debug!(error = %e, "Error closing the WebSocket");
+            }
+        }
+        self.flush_recording();
+    }
+
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            if let Some(message) = self.held.take() {
+                self.handle_message(message);
+                continue;
+            }
+            // Subscription changes wait for the socket to come back
+            let Some(link) = self.link.as_mut() else {
+                self.next_degraded().await;
+                continue;
+            };
+            tokio::select! {
+                message = link.read.next() => {
+                    match message {
+                        Some(Ok(msg)) => {
+                            self.alive();
+                            self.handle_message(msg);
+                        }
+                        Some(Err(e)) => {
+                            tracing::error!(error = %e, "Error receiving message");
+                            if !self.recover().await {
+                                return None;
+                            }
+                        }
+                        None => {
+                            if !self.recover().await {
+                                return None;
+                            }
+                        }
+                    }
+                }
+                Some(command) = self.commands.recv() => {
+                    if let Some(request) = self.subscriptions.request(command) {
+                        if let Err(e) = link.write.send(Message::Text(request)).await {
+                            tracing::error!(error = %e, "Error sending subscription request");
+                            if !self.recover().await {
+                                return None;
+                            }
+                        }
+                    }
+                }
+                _ = watchdog::expired(self.watchdog.as_ref()) => self.stalled().await,
+            }
+        }
+    }
+}
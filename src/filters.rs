@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+
+use crate::graph::Graph;
+use crate::order::{OrderRequest, Side};
+use crate::rest::{EndpointCategory, RestClient, RestError};
+
+// exchangeInfo response, reduced to what order construction needs
+#[derive(serde::Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolInfo {
+    symbol: String,
+    status: String,
+    base_asset: String,
+    quote_asset: String,
+    filters: Vec<RawFilter>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(tag = "filterType", rename_all = "SCREAMING_SNAKE_CASE")]
+enum RawFilter {
+    #[serde(rename_all = "camelCase")]
+    PriceFilter { min_price: Decimal, max_price: Decimal, tick_size: Decimal },
+    #[serde(rename_all = "camelCase")]
+    LotSize { min_qty: Decimal, max_qty: Decimal, step_size: Decimal },
+    #[serde(rename_all = "camelCase")]
+    MarketLotSize { min_qty: Decimal, max_qty: Decimal, step_size: Decimal },
+    #[serde(rename_all = "camelCase")]
+    MinNotional {
+        min_notional: Decimal,
+        #[serde(default = "yes")]
+        apply_to_market: bool,
+    },
+    #[serde(rename_all = "camelCase")]
+    Notional {
+        min_notional: Decimal,
+        #[serde(default = "yes")]
+        apply_min_to_market: bool,
+    },
+    #[serde(other)]
+    Other,
+}
+
+fn yes() -> bool {
+    true
+}
+
+// Allowed quantities: between min and max, in multiples of step (zero means unchecked)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LotSize {
+    pub min_qty: Decimal,
+    pub max_qty: Decimal,
+    pub step_size: Decimal,
+}
+
+// Trading rules for one symbol, from exchangeInfo
+#[derive(Debug, Clone, Default)]
+pub struct SymbolFilters {
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub trading: bool,
+    pub min_price: Decimal, // PRICE_FILTER; zeros mean unchecked
+    pub max_price: Decimal,
+    pub tick_size: Decimal,
+    pub lot: LotSize,                // LOT_SIZE
+    pub market_lot: Option<LotSize>, // MARKET_LOT_SIZE, when it differs for market orders
+    pub min_notional: Decimal,       // MIN_NOTIONAL or NOTIONAL
+    pub min_notional_market: bool,   // Whether the minimum also applies to market orders
+}
+
+// Multiple of `step` at or below `value`; unchanged when there is no step
+fn floor_to(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    (value / step).round_dp_with_strategy(0, RoundingStrategy::ToZero) * step
+}
+
+fn ceil_to(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO {
+        return value;
+    }
+    (value / step).round_dp_with_strategy(0, RoundingStrategy::AwayFromZero) * step
+}
+
+impl SymbolFilters {
+    fn lot_for(&self, market: bool) -> LotSize {
+        match self.market_lot {
+            Some(lot) if market && lot.step_size > Decimal::ZERO => lot,
+            _ => self.lot,
+        }
+    }
+
+    // Rounds down to the lot step, so the order never spends more than it has
+    pub fn round_quantity(&self, quantity: Decimal, market: bool) -> Decimal {
+        floor_to(quantity, self.lot_for(market).step_size).normalize()
+    }
+
+    // Rounds a limit price onto the tick grid on the safe side: buys down, sells up
+    pub fn round_price(&self, price: Decimal, side: Side) -> Decimal {
+        let rounded = match side {
+            Side::Buy => floor_to(price, self.tick_size),
+            Side::Sell => ceil_to(price, self.tick_size),
+        };
+        rounded.normalize()
+    }
+
+    // Checks an order against every filter; `price` is the limit price, or an estimate
+    // for market orders
+    pub fn check(&self, quantity: Decimal, price: Decimal, market: bool) -> Result<(), FilterViolation> {
+        let violation = |kind| Err(FilterViolation { symbol: self.symbol.clone(), kind });
+        if !self.trading {
+            return violation(ViolationKind::NotTrading);
+        }
+        if !market {
+            if price < self.min_price || (self.max_price > Decimal::ZERO && price > self.max_price) {
+                return violation(ViolationKind::Price { price });
+            }
+            if self.tick_size > Decimal::ZERO && !(price % self.tick_size).is_zero() {
+                return violation(ViolationKind::Price { price });
+            }
+        }
+        let lot = self.lot_for(market);
+        if quantity < lot.min_qty || quantity <= Decimal::ZERO {
+            return violation(ViolationKind::MinQuantity { quantity, min: lot.min_qty });
+        }
+        if lot.max_qty > Decimal::ZERO && quantity > lot.max_qty {
+            return violation(ViolationKind::MaxQuantity { quantity, max: lot.max_qty });
+        }
+        if lot.step_size > Decimal::ZERO && !(quantity % lot.step_size).is_zero() {
+            return violation(ViolationKind::Step { quantity, step: lot.step_size });
+        }
+        let notional = quantity * price;
+        if (!market || self.min_notional_market) && notional < self.min_notional {
+            return violation(ViolationKind::MinNotional { notional, min: self.min_notional });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ViolationKind {
+    UnknownPair { from: String, to: String },
+    NoPrice,
+    NotTrading,
+    Price { price: Decimal },
+    MinQuantity { quantity: Decimal, min: Decimal },
+    MaxQuantity { quantity: Decimal, max: Decimal },
+    Step { quantity: Decimal, step: Decimal },
+    MinNotional { notional: Decimal, min: Decimal },
+}
+
+// An order the exchange would reject
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterViolation {
+    pub symbol: String,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for FilterViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.kind {
+            ViolationKind::UnknownPair { from, to } => write!(f, "no symbol trades {} for {}", from, to),
+            ViolationKind::NoPrice => write!(f, "{}: no price", self.symbol),
+            ViolationKind::NotTrading => write!(f, "{}: not trading", self.symbol),
+            ViolationKind::Price { price } => write!(f, "{}: price {} outside PRICE_FILTER", self.symbol, price),
+            ViolationKind::MinQuantity { quantity, min } => {
+                write!(f, "{}: quantity {} below LOT_SIZE minimum {}", self.symbol, quantity, min)
+            }
+            ViolationKind::MaxQuantity { quantity, max } => {
+                write!(f, "{}: quantity {} above LOT_SIZE maximum {}", self.symbol, quantity, max)
+            }
+            ViolationKind::Step { quantity, step } => write!(f, "{}: quantity {} not a multiple of {}", self.symbol, quantity, step),
+            ViolationKind::MinNotional { notional, min } => {
+                write!(f, "{}: notional {} below minimum {}", self.symbol, notional, min)
+            }
+        }
+    }
+}
+
+// Trading rules for every symbol on the exchange, looked up by symbol or by pair
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeFilters {
+    symbols: HashMap<String, SymbolFilters>,
+    pairs: HashMap<(String, String), String>, // (base, quote) -> symbol
+}
+
+impl ExchangeFilters {
+    // All symbols in one request (weight 20)
+    pub async fn fetch(client: &RestClient) -> Result<Self, RestError> {
+        let body = client.get(EndpointCategory::Market, 20, "/api/v3/exchangeInfo", &[]).await?;
+        Self::parse(&body).map_err(|e| RestError::Decode(e.to_string()))
+    }
+
+    pub fn parse(body: &str) -> Result<Self, serde_json::Error> {
+        let info: ExchangeInfo = serde_json::from_str(body)?;
+        let mut filters = ExchangeFilters::default();
+        for symbol in info.symbols {
+            let mut entry = SymbolFilters {
+                symbol: symbol.symbol.clone(),
+                base: symbol.base_asset.clone(),
+                quote: symbol.quote_asset.clone(),
+                trading: symbol.status == "TRADING",
+                ..Default::default()
+            };
+            for filter in symbol.filters {
+                match filter {
+                    RawFilter::PriceFilter { min_price, max_price, tick_size } => {
+                        (entry.min_price, entry.max_price, entry.tick_size) = (min_price, max_price, tick_size);
+                    }
+                    RawFilter::LotSize { min_qty, max_qty, step_size } => entry.lot = LotSize { min_qty, max_qty, step_size },
+                    RawFilter::MarketLotSize { min_qty, max_qty, step_size } => {
+                        entry.market_lot = Some(LotSize { min_qty, max_qty, step_size });
+                    }
+                    // Symbols carry one or the other; NOTIONAL replaced MIN_NOTIONAL
+                    RawFilter::MinNotional { min_notional, apply_to_market } => {
+                        (entry.min_notional, entry.min_notional_market) = (min_notional, apply_to_market);
+                    }
+                    RawFilter::Notional { min_notional, apply_min_to_market } => {
+                        (entry.min_notional, entry.min_notional_market) = (min_notional, apply_min_to_market);
+                    }
+                    RawFilter::Other => {}
+                }
+            }
+            filters.pairs.insert((symbol.base_asset, symbol.quote_asset), symbol.symbol.clone());
+            filters.symbols.insert(symbol.symbol, entry);
+        }
+        Ok(filters)
+    }
+
+    pub fn get(&self, symbol: &str) -> Option<&SymbolFilters> {
+        self.symbols.get(symbol)
+    }
+
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    // The symbol converting `from` into `to` and the side that does it
+    pub fn leg(&self, from: &str, to: &str) -> Option<(&SymbolFilters, Side)> {
+        if let Some(symbol) = self.pairs.get(&(from.to_string(), to.to_string())) {
+            return Some((&self.symbols[symbol], Side::Sell));
+        }
+        let symbol = self.pairs.get(&(to.to_string(), from.to_string()))?;
+        Some((&self.symbols[symbol], Side::Buy))
+    }
+
+    // Limit IOC orders for walking `path` starting with `amount` of its first asset, at
+    // the graph's rates. Each leg spends what the previous one is expected to return
+    // after `fee_bps`; quantities round down to the lot step and prices onto the tick
+    // grid. Fails on the first leg the exchange would reject, which includes a starting
+    // amount too small to clear every leg's minimums.
+    pub fn cycle_orders(&self, path: &[String], amount: Decimal, graph: &Graph, fee_bps: Decimal) -> Result<Vec<OrderRequest>, FilterViolation> {
+        let keep = Decimal::ONE - fee_bps / Decimal::from(10_000);
+        let mut amount = amount;
+        let mut orders = Vec::with_capacity(path.len().saturating_sub(1));
+        for leg in path.windows(2) {
+            let (from, to) = (&leg[0], &leg[1]);
+            let Some((filters, side)) = self.leg(from, to) else {
+                return Err(FilterViolation {
+                    symbol: String::new(),
+                    kind: ViolationKind::UnknownPair { from: from.clone(), to: to.clone() },
+                });
+            };
+            // Quote per base unit, from whichever direction of the pair the graph has
+            let rate = match side {
+                Side::Sell => graph.rate(from, to),
+                Side::Buy => graph.rate(from, to).filter(|&r| r > 0.0).map(|r| 1.0 / r),
+            };
+            let Some(price) = rate.and_then(|r| Decimal::try_from(r).ok()).filter(|p| *p > Decimal::ZERO) else {
+                return Err(FilterViolation { symbol: filters.symbol.clone(), kind: ViolationKind::NoPrice });
+            };
+            let price = filters.round_price(price, side);
+            let quantity = match side {
+                Side::Sell => filters.round_quantity(amount, false),
+                Side::Buy => filters.round_quantity(amount / price, false),
+            };
+            filters.check(quantity, price, false)?;
+            amount = match side {
+                Side::Sell => quantity * price,
+                Side::Buy => quantity,
+            } * keep;
+            orders.push(OrderRequest {
+                symbol: filters.symbol.clone(),
+                side,
+                quantity,
+                price: Some(price),
+            });
+        }
+        Ok(orders)
+    }
+}
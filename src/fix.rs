@@ -0,0 +1,770 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rust_decimal::Decimal;
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+
+use crate::audit::AuditLog;
+use crate::book_stats::BookGuard;
+use crate::events::{Opportunity, Signal};
+use crate::executor::Executor;
+use crate::filters::ExchangeFilters;
+use crate::graph::extract_currency_pair;
+use crate::last_look::{LastLook, LastLookGuard};
+use crate::latency_budget::{LatencyBudget, LegTimer};
+use crate::ledger::Fill;
+use crate::order::{OrderRequest, Side};
+use crate::order_tracker::{ExecutionMonitor, OrderStatus, OrderUpdate};
+use crate::revalidate::Revalidator;
+use crate::shared_graph::SharedGraph;
+use crate::user_stream::AccountEvent;
+
+const SOH: u8 = 0x01;
+const EVENT_CAPACITY: usize = 1024;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const LOGOUT_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Header fields encode() writes itself; decode() keeps them so callers can read them
+const TAG_BEGIN_STRING: u32 = 8;
+const TAG_BODY_LENGTH: u32 = 9;
+const TAG_MSG_TYPE: u32 = 35;
+const TAG_CHECKSUM: u32 = 10;
+
+#[derive(Debug, Clone)]
+pub struct FixConfig {
+    pub addr: String, // host:port of the acceptor; TLS, where required, goes through a tunnel
+    pub sender_comp_id: String,
+    pub target_comp_id: String,
+    pub account: Option<String>, // Account (1) on every order
+    pub heartbeat: Duration,     // HeartBtInt proposed at logon
+    pub venue: String,           // Venue name on drop-copy fills
+    pub begin_string: String,
+}
+
+impl FixConfig {
+    pub fn new(addr: &str, sender_comp_id: &str, target_comp_id: &str) -> Self {
+        FixConfig {
+            addr: addr.to_string(),
+            sender_comp_id: sender_comp_id.to_string(),
+            target_comp_id: target_comp_id.to_string(),
+            account: None,
+            heartbeat: Duration::from_secs(30),
+            venue: "fix".to_string(),
+            begin_string: "FIX.4.4".to_string(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum FixError {
+    Io(std::io::Error),
+    Malformed(String),
+    Checksum { expected: u8, actual: u8 },
+}
+
+impl fmt::Display for FixError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FixError::Io(e) => write!(f, "I/O error: {}", e),
+            FixError::Malformed(e) => write!(f, "malformed message: {}", e),
+            FixError::Checksum { expected, actual } => write!(f, "checksum {:03} instead of {:03}", actual, expected),
+        }
+    }
+}
+
+impl std::error::Error for FixError {}
+
+impl From<std::io::Error> for FixError {
+    fn from(e: std::io::Error) -> Self {
+        FixError::Io(e)
+    }
+}
+
+// A FIX message as tag=value pairs in wire order, without BeginString, BodyLength,
+// MsgType and CheckSum, which belong to the framing
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixMessage {
+    pub msg_type: String,
+    pub fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    pub fn new(msg_type: &str) -> Self {
+        FixMessage {
+            msg_type: msg_type.to_string(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn with(mut self, tag: u32, value: impl ToString) -> Self {
+        self.fields.push((tag, value.to_string()));
+        self
+    }
+
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| v.as_str())
+    }
+
+    fn decimal(&self, tag: u32) -> Decimal {
+        self.get(tag).and_then(|v| v.parse().ok()).unwrap_or_default()
+    }
+
+    // Frames the message: BeginString, BodyLength and MsgType first, CheckSum last
+    pub fn encode(&self, begin_string: &str) -> Vec<u8> {
+        let mut body = format!("{}={}\x01", TAG_MSG_TYPE, self.msg_type);
+        for (tag, value) in &self.fields {
+            body.push_str(&format!("{}={}\x01", tag, value));
+        }
+        let mut raw = format!("{}={}\x01{}={}\x01{}", TAG_BEGIN_STRING, begin_string, TAG_BODY_LENGTH, body.len(), body).into_bytes();
+        let checksum = checksum(&raw);
+        raw.extend_from_slice(format!("{}={:03}\x01", TAG_CHECKSUM, checksum).as_bytes());
+        raw
+    }
+
+    // Parses one complete frame, checking BodyLength and CheckSum
+    pub fn decode(raw: &[u8]) -> Result<Self, FixError> {
+        let text = std::str::from_utf8(raw).map_err(|e| FixError::Malformed(e.to_string()))?;
+        let mut pairs = Vec::new();
+        for field in text.split('\x01').filter(|f| !f.is_empty()) {
+            let (tag, value) = field.split_once('=').ok_or_else(|| FixError::Malformed(format!("field without '=': {}", field)))?;
+            let tag: u32 = tag.parse().map_err(|_| FixError::Malformed(format!("bad tag {}", tag)))?;
+            pairs.push((tag, value.to_string()));
+        }
+        let [(TAG_BEGIN_STRING, _), (TAG_BODY_LENGTH, _), (TAG_MSG_TYPE, msg_type), ..] = pairs.as_slice() else {
+            return Err(FixError::Malformed("header out of order".to_string()));
+        };
+        let Some((TAG_CHECKSUM, expected)) = pairs.last() else {
+            return Err(FixError::Malformed("no checksum".to_string()));
+        };
+        let expected: u8 = expected.parse().map_err(|_| FixError::Malformed(format!("bad checksum {}", expected)))?;
+        let trailer = raw.len() - 7; // "10=nnn" and its SOH
+        let actual = checksum(&raw[..trailer]);
+        if actual != expected {
+            return Err(FixError::Checksum { expected, actual });
+        }
+        let msg_type = msg_type.clone();
+        let fields = pairs[3..pairs.len() - 1].to_vec();
+        Ok(FixMessage { msg_type, fields })
+    }
+}
+
+fn checksum(raw: &[u8]) -> u8 {
+    raw.iter().fold(0u8, |sum, b| sum.wrapping_add(*b))
+}
+
+// Length of the first complete frame in `buf`, or None until more bytes arrive
+fn frame_len(buf: &[u8]) -> Result<Option<usize>, FixError> {
+    let mut fields = buf.split(|b| *b == SOH);
+    let (Some(begin), Some(length)) = (fields.next(), fields.next()) else {
+        return Ok(None);
+    };
+    if !begin.starts_with(b"8=") {
+        return Err(FixError::Malformed("frame doesn't start with BeginString".to_string()));
+    }
+    // The BodyLength field is only usable once its SOH has arrived
+    let header = begin.len() + 1 + length.len() + 1;
+    if buf.len() < header {
+        return Ok(None);
+    }
+    let body_len: usize = std::str::from_utf8(length)
+        .ok()
+        .and_then(|f| f.strip_prefix("9="))
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| FixError::Malformed("bad BodyLength".to_string()))?;
+    let total = header + body_len + 7;
+    Ok((buf.len() >= total).then_some(total))
+}
+
+// UTCTimestamp with milliseconds, e.g. 20240301-14:05:09.123
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    ["%Y%m%d-%H:%M:%S%.f", "%Y%m%d-%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|time| time.and_utc())
+}
+
+fn ord_status(value: &str) -> Option<OrderStatus> {
+    Some(match value {
+        "0" => OrderStatus::New,
+        "1" => OrderStatus::PartiallyFilled,
+        "2" => OrderStatus::Filled,
+        "4" => OrderStatus::Canceled,
+        "6" => OrderStatus::PendingCancel,
+        "8" => OrderStatus::Rejected,
+        "A" => OrderStatus::PendingNew,
+        "C" => OrderStatus::Expired,
+        _ => return None,
+    })
+}
+
+// NewOrderSingle (D): IOC limit at the order's price, or market without one
+pub fn new_order_single(order: &OrderRequest, client_order_id: &str, account: Option<&str>) -> FixMessage {
+    let mut message = FixMessage::new("D").with(11, client_order_id);
+    if let Some(account) = account {
+        message = message.with(1, account);
+    }
+    message = message
+        .with(55, &order.symbol)
+        .with(54, if order.side == Side::Buy { "1" } else { "2" })
+        .with(60, timestamp(Utc::now()))
+        .with(38, order.quantity.normalize());
+    match order.price {
+        Some(price) => message.with(40, "2").with(44, price.normalize()).with(59, "3"),
+        None => message.with(40, "1"),
+    }
+}
+
+// ExecutionReport (8) as account events: the order's new state, and a fill when it
+// reports an execution
+pub fn execution_events(report: &FixMessage, venue: &str) -> Vec<AccountEvent> {
+    let mut events = Vec::new();
+    let (Some(client_order_id), Some(status)) = (report.get(11), report.get(39).and_then(ord_status)) else {
+        return events;
+    };
+    let cum_qty = report.decimal(14);
+    events.push(AccountEvent::Order(OrderUpdate {
+        client_order_id: client_order_id.to_string(),
+        status,
+        executed_qty: cum_qty,
+        quote_qty: cum_qty * report.decimal(6), // CumQty at AvgPx
+    }));
+    let last_qty = report.decimal(32);
+    let side = match report.get(54) {
+        Some("1") => Some(Side::Buy),
+        Some("2") => Some(Side::Sell),
+        _ => None,
+    };
+    if let (true, Some(symbol), Some(side)) = (last_qty > Decimal::ZERO, report.get(55), side) {
+        let (base, quote) = extract_currency_pair(symbol);
+        events.push(AccountEvent::Fill(Fill {
+            time: report.get(60).and_then(parse_timestamp).unwrap_or_else(Utc::now),
+            venue: venue.to_string(),
+            symbol: symbol.to_string(),
+            base,
+            quote,
+            side,
+            quantity: last_qty,
+            price: report.decimal(31),
+            fee: report.decimal(12),
+            fee_asset: report.get(479).unwrap_or_default().to_string(),
+            order_id: report.get(37).map(str::to_string),
+        }));
+    }
+    events
+}
+
+enum Command {
+    Order(String, OrderRequest),
+    Logout,
+}
+
+// How a connection ended
+enum Exit {
+    LoggedOut,    // We asked to stop
+    Disconnected, // Anything else; reconnect
+}
+
+// A FIX 4.4 initiator session on its own task: logs on (resetting sequence numbers),
+// keeps the session alive with heartbeats and test requests, answers resend requests
+// with a gap fill, sends orders and publishes execution reports as account events, the
+// same as the user data stream's. Reconnects after a dropped connection; orders sent
+// while it is down are dropped rather than sent late.
+pub struct FixSession {
+    commands: mpsc::UnboundedSender<Command>,
+    events: broadcast::Sender<AccountEvent>,
+    next_id: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+    audit: Option<AuditLog>,
+}
+
+impl FixSession {
+    pub fn spawn(config: FixConfig) -> Self {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let task = tokio::spawn(run(config, receiver, events.clone()));
+        let start = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+        FixSession {
+            commands,
+            events,
+            next_id: Arc::new(AtomicU64::new(start)),
+            task,
+            audit: None,
+        }
+    }
+
+    // Records every order sent through the session in `log`
+    pub fn with_audit(mut self, log: AuditLog) -> Self {
+        self.audit = Some(log);
+        self
+    }
+
+    // Execution reports, including drop-copies of orders placed elsewhere
+    pub fn subscribe(&self) -> broadcast::Receiver<AccountEvent> {
+        self.events.subscribe()
+    }
+
+    fn sender(&self) -> OrderSender {
+        OrderSender {
+            commands: self.commands.clone(),
+            next_id: self.next_id.clone(),
+            audit: self.audit.clone(),
+        }
+    }
+
+    // Queues `order` and returns the ClOrdID it goes out with
+    pub fn send_order(&self, order: &OrderRequest) -> String {
+        self.sender().send(order)
+    }
+
+    // Logs out and waits for the session task to end
+    pub async fn logout(self) {
+        let _ = self.commands.send(Command::Logout);
+        let _ = self.task.await;
+    }
+}
+
+#[derive(Clone)]
+struct OrderSender {
+    commands: mpsc::UnboundedSender<Command>,
+    next_id: Arc<AtomicU64>,
+    audit: Option<AuditLog>,
+}
+
+impl OrderSender {
+    fn next_id(&self) -> String {
+        format!("hft3-{}", self.next_id.fetch_add(1, Ordering::Relaxed))
+    }
+
+    fn send_as(&self, client_order_id: String, order: &OrderRequest) {
+        if let Some(log) = &self.audit {
+            log.record(
+                "order_sent",
+                json!({
+                    "client_order_id": client_order_id,
+                    "symbol": order.symbol,
+                    "side": order.side.as_str(),
+                    "quantity": order.quantity,
+                    "price": order.price,
+                }),
+            );
+        }
+        let _ = self.commands.send(Command::Order(client_order_id, order.clone()));
+    }
+
+    fn send(&self, order: &OrderRequest) -> String {
+        let client_order_id = self.next_id();
+        self.send_as(client_order_id.clone(), order);
+        client_order_id
+    }
+}
+
+async fn run(config: FixConfig, mut commands: mpsc::UnboundedReceiver<Command>, events: broadcast::Sender<AccountEvent>) {
+    loop {
+        let exit = match TcpStream::connect(&config.addr).await {
+            Ok(stream) => Connection::new(stream, &config).serve(&mut commands, &events).await,
+            Err(e) => Err(FixError::Io(e)),
+        };
+        match exit {
+            Ok(Exit::LoggedOut) => return,
+            Ok(Exit::Disconnected) => tracing::warn!(addr = %config.addr, "FIX session disconnected, reconnecting"),
+            Err(e) => tracing::warn!(addr = %config.addr, error = %e, "FIX session failed, reconnecting"),
+        }
+        let reconnect = tokio::time::sleep(RECONNECT_DELAY);
+        tokio::pin!(reconnect);
+        loop {
+            tokio::select! {
+                _ = &mut reconnect => break,
+                command = commands.recv() => match command {
+                    Some(Command::Order(id, order)) => {
+                        tracing::warn!(client_order_id = %id, symbol = %order.symbol, "FIX session down, order dropped");
+                    }
+                    Some(Command::Logout) | None => return,
+                },
+            }
+        }
+    }
+}
+
+struct Connection<'a> {
+    stream: TcpStream,
+    config: &'a FixConfig,
+    buf: Vec<u8>,
+    next_out: u64,
+    next_in: u64,
+    last_sent: Instant,
+    last_received: Instant,
+    test_request_sent: bool,
+    logged_on: bool,
+}
+
+impl<'a> Connection<'a> {
+    fn new(stream: TcpStream, config: &'a FixConfig) -> Self {
+        Connection {
+            stream,
+            config,
+            buf: Vec::new(),
+            next_out: 1,
+            next_in: 1,
+            last_sent: Instant::now(),
+            last_received: Instant::now(),
+            test_request_sent: false,
+            logged_on: false,
+        }
+    }
+
+    async fn send(&mut self, message: FixMessage) -> Result<(), FixError> {
+        let mut framed = FixMessage::new(&message.msg_type)
+            .with(49, &self.config.sender_comp_id)
+            .with(56, &self.config.target_comp_id)
+            .with(34, self.next_out)
+            .with(52, timestamp(Utc::now()));
+        framed.fields.extend(message.fields);
+        self.stream.write_all(&framed.encode(&self.config.begin_string)).await?;
+        self.next_out += 1;
+        self.last_sent = Instant::now();
+        Ok(())
+    }
+
+    async fn serve(
+        mut self,
+        commands: &mut mpsc::UnboundedReceiver<Command>,
+        events: &broadcast::Sender<AccountEvent>,
+    ) -> Result<Exit, FixError> {
+        let heartbeat = self.config.heartbeat;
+        let logon = FixMessage::new("A").with(98, 0).with(108, heartbeat.as_secs()).with(141, "Y");
+        self.send(logon).await?;
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        let mut chunk = [0u8; 4096];
+        loop {
+            tokio::select! {
+                read = self.stream.read(&mut chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        return Ok(Exit::Disconnected);
+                    }
+                    self.buf.extend_from_slice(&chunk[..n]);
+                    while let Some(len) = frame_len(&self.buf)? {
+                        let raw: Vec<u8> = self.buf.drain(..len).collect();
+                        let message = FixMessage::decode(&raw)?;
+                        if let Some(exit) = self.handle(message, events).await? {
+                            return Ok(exit);
+                        }
+                    }
+                }
+                command = commands.recv() => match command {
+                    Some(Command::Order(id, order)) if self.logged_on => {
+                        let message = new_order_single(&order, &id, self.config.account.as_deref());
+                        self.send(message).await?;
+                        tracing::info!(client_order_id = %id, symbol = %order.symbol, side = order.side.as_str(), quantity = %order.quantity, "FIX order sent");
+                    }
+                    Some(Command::Order(id, order)) => {
+                        tracing::warn!(client_order_id = %id, symbol = %order.symbol, "FIX session not logged on, order dropped");
+                    }
+                    Some(Command::Logout) | None => return self.logout(events).await,
+                },
+                _ = ticker.tick() => {
+                    let silent = self.last_received.elapsed();
+                    if silent > heartbeat * 2 + heartbeat / 5 {
+                        tracing::warn!(silent_ms = silent.as_millis() as u64, "FIX counterparty stopped responding");
+                        return Ok(Exit::Disconnected);
+                    }
+                    if silent > heartbeat + heartbeat / 5 && !self.test_request_sent {
+                        self.send(FixMessage::new("1").with(112, "hft3")).await?;
+                        self.test_request_sent = true;
+                    } else if self.last_sent.elapsed() >= heartbeat {
+                        self.send(FixMessage::new("0")).await?;
+                    }
+                }
+            }
+        }
+    }
+
+    // Session-level handling of one incoming message; Some ends the connection
+    async fn handle(&mut self, message: FixMessage, events: &broadcast::Sender<AccountEvent>) -> Result<Option<Exit>, FixError> {
+        self.last_received = Instant::now();
+        self.test_request_sent = false;
+        let seq: u64 = message.get(34).and_then(|s| s.parse().ok()).unwrap_or(0);
+        let reset = message.msg_type == "4" && message.get(123) != Some("Y");
+        if seq > self.next_in && !reset {
+            tracing::warn!(expected = self.next_in, received = seq, "FIX sequence gap, requesting resend");
+            let resend = FixMessage::new("2").with(7, self.next_in).with(16, 0);
+            self.send(resend).await?;
+        }
+        self.next_in = self.next_in.max(seq + 1);
+        match message.msg_type.as_str() {
+            "A" => {
+                self.logged_on = true;
+                tracing::info!(addr = %self.config.addr, target = %self.config.target_comp_id, "FIX session logged on");
+            }
+            "0" => {}
+            "1" => {
+                let id = message.get(112).unwrap_or_default().to_string();
+                self.send(FixMessage::new("0").with(112, id)).await?;
+            }
+            // Nothing we sent is worth replaying late, so a gap fill covers the whole range
+            "2" => {
+                let begin = message.get(7).and_then(|s| s.parse().ok()).unwrap_or(1);
+                let next = self.next_out;
+                self.next_out = begin;
+                let gap_fill = FixMessage::new("4").with(43, "Y").with(122, timestamp(Utc::now())).with(123, "Y").with(36, next);
+                self.send(gap_fill).await?;
+                self.next_out = next;
+            }
+            "4" => {
+                if let Some(next) = message.get(36).and_then(|s| s.parse().ok()) {
+                    self.next_in = next;
+                }
+            }
+            "5" => {
+                tracing::warn!(text = message.get(58).unwrap_or_default(), "FIX counterparty logged out");
+                self.send(FixMessage::new("5")).await?;
+                return Ok(Some(Exit::Disconnected));
+            }
+            "3" | "j" => {
+                tracing::warn!(msg_type = %message.msg_type, text = message.get(58).unwrap_or_default(), "FIX reject");
+            }
+            "8" => {
+                for event in execution_events(&message, &self.config.venue) {
+                    match &event {
+                        AccountEvent::Order(update) => {
+                            tracing::debug!(client_order_id = %update.client_order_id, status = ?update.status, "FIX execution report")
+                        }
+                        AccountEvent::Fill(fill) => {
+                            tracing::info!(symbol = %fill.symbol, side = fill.side.as_str(), quantity = %fill.quantity, price = %fill.price, "FIX fill")
+                        }
+                    }
+                    let _ = events.send(event);
+                }
+            }
+            other => tracing::debug!(msg_type = other, "Ignoring FIX message"),
+        }
+        Ok(None)
+    }
+
+    // Sends Logout and waits briefly for the counterparty's, still publishing any
+    // execution reports that arrive first
+    async fn logout(mut self, events: &broadcast::Sender<AccountEvent>) -> Result<Exit, FixError> {
+        self.send(FixMessage::new("5")).await?;
+        let deadline = tokio::time::sleep(LOGOUT_TIMEOUT);
+        tokio::pin!(deadline);
+        let mut chunk = [0u8; 4096];
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                read = self.stream.read(&mut chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        break;
+                    }
+                    self.buf.extend_from_slice(&chunk[..n]);
+                    while let Some(len) = frame_len(&self.buf)? {
+                        let raw: Vec<u8> = self.buf.drain(..len).collect();
+                        let message = FixMessage::decode(&raw)?;
+                        if message.msg_type == "5" {
+                            tracing::info!("FIX session logged out");
+                            return Ok(Exit::LoggedOut);
+                        }
+                        if message.msg_type == "8" {
+                            execution_events(&message, &self.config.venue).into_iter().for_each(|e| {
+                                let _ = events.send(e);
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        Ok(Exit::LoggedOut)
+    }
+}
+
+// Sizing for routing whole cycles: `notional` of `reference` walked through the legs
+#[derive(Debug, Clone)]
+pub struct CycleSizing {
+    pub notional: Decimal,
+    pub reference: String,
+    pub fee_bps: Decimal,
+}
+
+impl CycleSizing {
+    pub fn new(notional: Decimal) -> Self {
+        CycleSizing {
+            notional,
+            reference: "USDT".to_string(),
+            fee_bps: Decimal::from(10),
+        }
+    }
+}
+
+// Routes orders to an OMS over a FIX session: every signal's orders, and, given exchange
+// filters and sizing, each opportunity's legs as IOC limits at the detected rates. With an
+// execution monitor on the session's events, every order is tracked to completion and
+// each cycle reported once its legs are done. A revalidator gets the last word on each
+// cycle; when it quotes over REST the cycle waits for the answer on its own task. A book
+// guard first turns away cycles with a leg on a wide, one-sided or thin book. A latency
+// budget times each cycle's orders as they go out, and a last-look guard holds back the
+// final leg when its rate has moved against the cycle since detection.
+pub struct FixExecutor {
+    orders: OrderSender,
+    cycles: Option<(CycleSizing, ExchangeFilters, Arc<SharedGraph>)>,
+    monitor: Option<ExecutionMonitor>,
+    revalidator: Option<Arc<Revalidator>>,
+    books: Option<BookGuard>,
+    latency: Option<LatencyBudget>,
+    last_look: Option<LastLookGuard>,
+}
+
+impl FixExecutor {
+    pub fn new(session: &FixSession) -> Self {
+        FixExecutor {
+            orders: session.sender(),
+            cycles: None,
+            monitor: None,
+            revalidator: None,
+            books: None,
+            latency: None,
+            last_look: None,
+        }
+    }
+
+    pub fn with_cycles(mut self, sizing: CycleSizing, filters: ExchangeFilters, graph: Arc<SharedGraph>) -> Self {
+        self.cycles = Some((sizing, filters, graph));
+        self
+    }
+
+    pub fn with_monitor(mut self, monitor: ExecutionMonitor) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    pub fn with_revalidation(mut self, revalidator: Revalidator) -> Self {
+        self.revalidator = Some(Arc::new(revalidator));
+        self
+    }
+
+    pub fn with_book_guard(mut self, books: BookGuard) -> Self {
+        self.books = Some(books);
+        self
+    }
+
+    pub fn with_latency_budget(mut self, latency: LatencyBudget) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    pub fn with_last_look(mut self, guard: LastLookGuard) -> Self {
+        self.last_look = Some(guard);
+        self
+    }
+}
+
+fn send_tracked(sender: &OrderSender, monitor: Option<&ExecutionMonitor>, order: &OrderRequest, cycle: Option<&str>) {
+    let client_order_id = sender.next_id();
+    if let Some(monitor) = monitor {
+        monitor.track(&order.symbol, &client_order_id, order.side, order.quantity, cycle);
+    }
+    sender.send_as(client_order_id, order);
+}
+
+fn send_cycle(
+    sender: &OrderSender,
+    monitor: Option<&ExecutionMonitor>,
+    latency: Option<&LatencyBudget>,
+    last_look: Option<(&LastLookGuard, &SharedGraph)>,
+    opportunity: &Opportunity,
+    orders: &[OrderRequest],
+) {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let cycle_id = format!("{}@{}", opportunity.cycle_key(), millis);
+    if let Some(monitor) = monitor {
+        monitor.begin_cycle(&cycle_id, &opportunity.path);
+    }
+    let mut timer = LegTimer::start(opportunity);
+    for (leg, order) in orders.iter().enumerate() {
+        if let Some((guard, graph)) = last_look.filter(|_| leg > 0 && leg + 1 == orders.len()) {
+            if let LastLook::Abort(_) = guard.check_graph(opportunity, &graph.load()) {
+                tracing::warn!(cycle = %cycle_id, "Final leg held back, earlier legs left to the monitor");
+                break;
+            }
+        }
+        send_tracked(sender, monitor, order, Some(&cycle_id));
+        timer.leg_sent();
+    }
+    if let Some(monitor) = monitor {
+        monitor.end_cycle(&cycle_id);
+    }
+    if let Some(latency) = latency {
+        latency.record(&cycle_id, &timer);
+    }
+}
+
+impl Executor for FixExecutor {
+    fn execute(&mut self, opportunity: &Opportunity) {
+        let Some((sizing, filters, shared)) = &self.cycles else {
+            return;
+        };
+        let Some(start) = opportunity.path.first() else {
+            return;
+        };
+        let graph = shared.load();
+        let rate = if *start == sizing.reference { Some(1.0) } else { graph.rate(&sizing.reference, start) };
+        let Some(amount) = rate.and_then(|r| Decimal::try_from(r).ok()).map(|r| sizing.notional * r) else {
+            tracing::warn!(%start, "No price to size the cycle, not routed");
+            return;
+        };
+        let orders = match filters.cycle_orders(&opportunity.path, amount, &graph, sizing.fee_bps) {
+            Ok(orders) => orders,
+            Err(e) => {
+                tracing::warn!(path = ?opportunity.path, error = %e, "Cycle not routed");
+                return;
+            }
+        };
+        if let Some(Err(rejection)) = self.books.as_ref().map(|books| books.check(&orders, Instant::now())) {
+            tracing::info!(cycle = %opportunity.cycle_key(), %rejection, "Book too poor to cross, cycle not routed");
+            return;
+        }
+        let last_look = self.last_look.as_ref().map(|guard| (guard, shared.as_ref()));
+        match &self.revalidator {
+            Some(revalidator) if revalidator.uses_rest() => {
+                let (revalidator, sender, monitor, latency) =
+                    (revalidator.clone(), self.orders.clone(), self.monitor.clone(), self.latency.clone());
+                let last_look = self.last_look.clone().map(|guard| (guard, shared.clone()));
+                let opportunity = opportunity.clone();
+                tokio::spawn(async move {
+                    match revalidator.revalidate(&opportunity, &graph, &orders).await {
+                        Ok(_) if opportunity.is_expired(SystemTime::now()) => {
+                            tracing::info!(cycle = %opportunity.cycle_key(), "Cycle expired during revalidation");
+                        }
+                        Ok(_) => {
+                            let last_look = last_look.as_ref().map(|(guard, shared)| (guard, shared.as_ref()));
+                            send_cycle(&sender, monitor.as_ref(), latency.as_ref(), last_look, &opportunity, &orders)
+                        }
+                        Err(e) => tracing::info!(cycle = %opportunity.cycle_key(), error = %e, "Cycle dropped on revalidation"),
+                    }
+                });
+            }
+            Some(revalidator) => match revalidator.check(opportunity, &graph) {
+                Ok(_) => send_cycle(&self.orders, self.monitor.as_ref(), self.latency.as_ref(), last_look, opportunity, &orders),
+                Err(e) => tracing::info!(cycle = %opportunity.cycle_key(), error = %e, "Cycle dropped on revalidation"),
+            },
+            None => send_cycle(&self.orders, self.monitor.as_ref(), self.latency.as_ref(), last_look, opportunity, &orders),
+        }
+    }
+
+    fn submit(&mut self, signal: &Signal) {
+        for order in &signal.orders {
+            send_tracked(&self.orders, self.monitor.as_ref(), order, None);
+        }
+        tracing::debug!(kind = %signal.kind, orders = signal.orders.len(), "Signal routed over FIX");
+    }
+}
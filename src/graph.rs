@@ -0,0 +1,540 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+use smallvec::SmallVec;
+
+// Quote assets Binance lists pairs against, longest first so "FDUSD" wins over "USD"-like
+// suffixes. Anything else falls back to a 3-letter base.
+const QUOTE_ASSETS: &[&str] = &[
+    "FDUSD", "USDT", "USDC", "TUSD", "BUSD", "DAI", "BTC", "ETH", "BNB", "TRY", "EUR", "BRL", "GBP", "AUD",
+    "JPY", "RUB", "UAH", "ZAR", "PLN", "RON", "ARS", "BIDR", "IDRT", "NGN", "XRP", "TRX", "DOGE", "DOT",
+];
+
+// Helper function to extract currency pair from a symbol like "BTCUSDT"
+pub fn extract_currency_pair(symbol: &str) -> (String, String) {
+    let (base, quote) = split_pair(symbol);
+    (base.to_string(), quote.to_string())
+}
+
+// `extract_currency_pair` borrowing from the symbol, for the update path
+pub fn split_pair(symbol: &str) -> (&str, &str) {
+    for quote in QUOTE_ASSETS {
+        if symbol.len() > quote.len() && symbol.ends_with(quote) {
+            return symbol.split_at(symbol.len() - quote.len());
+        }
+    }
+    symbol.split_at(symbol.len().min(3))
+}
+
+// Rank of `asset` as a quote currency, lower first; None for assets that are only ever base
+pub fn quote_priority(asset: &str) -> Option<usize> {
+    QUOTE_ASSETS.iter().position(|quote| *quote == asset)
+}
+
+// Owned copy of an edge, for display and export
+pub struct Edge {
+    pub start: String,
+    pub end: String,
+    pub rate: f64,
+}
+
+// Sentinel for "no predecessor" in the flat Bellman-Ford arrays
+const NO_VERTEX: u32 = u32::MAX;
+
+// Vertex IDs of a cycle in trading order, the first repeated last. Up to seven legs stay
+// inline, so tracing and deduplicating cycles doesn't touch the heap.
+pub type CycleIds = SmallVec<[u32; 8]>;
+
+// Working buffers of a Bellman-Ford search. Kept per thread (rayon workers included) and
+// cleared rather than dropped between passes, so steady-state detection doesn't allocate.
+#[derive(Default)]
+struct Scratch {
+    weighted: Vec<(u32, u32, f64)>,
+    distances: Vec<f64>,
+    predecessors: Vec<u32>,
+    member: Vec<bool>,
+    sources: Vec<u32>,
+    seen: HashSet<CycleIds>,
+}
+
+thread_local! {
+    static SCRATCH: RefCell<Scratch> = RefCell::default();
+}
+
+fn with_scratch<T>(f: impl FnOnce(&mut Scratch) -> T) -> T {
+    SCRATCH.with(|scratch| match scratch.try_borrow_mut() {
+        Ok(mut scratch) => f(&mut scratch),
+        // Re-entered, e.g. a rayon worker picking up another cluster while it waits
+        Err(_) => f(&mut Scratch::default()),
+    })
+}
+
+// Assets are interned to dense u32 IDs on first sight and never released, so IDs stay
+// stable for the life of the graph. Edges live in per-vertex adjacency lists and
+// detection works on flat Vecs indexed by ID; strings only appear at the boundaries.
+#[derive(Default, Clone)]
+pub struct Graph {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+    adjacency: Vec<Vec<(u32, f64, Instant)>>, // Outgoing (to, rate, last update) per vertex
+    degree: Vec<u32>,                         // In plus out edges, to count live vertices
+    premiums: Vec<f64>,                       // Risk haircut on edges into a vertex, as a fraction
+    edge_count: usize,
+    generation: u64,                          // Bumped on every change that matters to detection
+    topology: u64,                            // Bumped whenever an edge is added or removed
+    touched: Vec<u64>,                        // Generation at which each vertex last changed
+    change_epsilon: f64,                      // Relative rate moves at or below this don't count as changes
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // ID for `asset`, assigning the next one if it's new
+    pub fn intern(&mut self, asset: &str) -> u32 {
+        if let Some(&id) = self.ids.get(asset) {
+            return id;
+        }
+        let id = self.names.len() as u32;
+        self.ids.insert(asset.to_string(), id);
+        self.names.push(asset.to_string());
+        self.adjacency.push(Vec::new());
+        self.degree.push(0);
+        self.premiums.push(0.0);
+        self.touched.push(0);
+        id
+    }
+
+    // Marks both ends of a changed edge so incremental detection revisits them
+    fn touch(&mut self, from: u32, to: u32) {
+        self.generation += 1;
+        self.touched[from as usize] = self.generation;
+        self.touched[to as usize] = self.generation;
+    }
+
+    // Rate updates that move less than this fraction leave the edge clean, so tiny
+    // ticks don't trigger detection. Zero (the default) counts every change.
+    pub fn set_change_epsilon(&mut self, epsilon: f64) {
+        self.change_epsilon = epsilon.max(0.0);
+    }
+
+    // Increases whenever an edge is added, removed or moves beyond the epsilon
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    // Increases whenever an edge is added or removed, so anything derived from the graph's
+    // shape (like `quote_clusters`) only needs rebuilding when it moves
+    pub fn topology(&self) -> u64 {
+        self.topology
+    }
+
+    // Replaces `dirty` with the vertices touched after `generation`
+    pub fn dirty_since(&self, generation: u64, dirty: &mut Vec<u32>) {
+        dirty.clear();
+        dirty.extend((0..self.touched.len() as u32).filter(|id| self.touched[*id as usize] > generation));
+    }
+
+    pub fn id(&self, asset: &str) -> Option<u32> {
+        self.ids.get(asset).copied()
+    }
+
+    pub fn name(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn add_edge(&mut self, start: &str, end: &str, rate: f64) {
+        let (from, to) = (self.intern(start), self.intern(end));
+        self.adjacency[from as usize].push((to, rate, Instant::now()));
+        self.degree[from as usize] += 1;
+        self.degree[to as usize] += 1;
+        self.edge_count += 1;
+        self.topology += 1;
+        self.touch(from, to);
+    }
+
+    // Returns false if the edge doesn't exist yet
+    pub fn update_edge(&mut self, start: &str, end: &str, rate: f64) -> bool {
+        let (Some(from), Some(to)) = (self.id(start), self.id(end)) else {
+            return false;
+        };
+        let Some(edge) = self.adjacency[from as usize].iter_mut().find(|(target, ..)| *target == to) else {
+            return false;
+        };
+        let moved = edge.1 == 0.0 || (rate / edge.1 - 1.0).abs() > self.change_epsilon;
+        edge.1 = rate;
+        // Even an unchanged rate proves the pair is still ticking
+        edge.2 = Instant::now();
+        if moved {
+            self.touch(from, to);
+        }
+        true
+    }
+
+    // Updates the edge, adding it first if needed
+    pub fn set_edge(&mut self, start: &str, end: &str, rate: f64) {
+        if !self.update_edge(start, end, rate) {
+            self.add_edge(start, end, rate);
+        }
+    }
+
+    pub fn remove_edge(&mut self, start: &str, end: &str) {
+        let (Some(from), Some(to)) = (self.id(start), self.id(end)) else {
+            return;
+        };
+        let edges = &mut self.adjacency[from as usize];
+        if let Some(index) = edges.iter().position(|(target, ..)| *target == to) {
+            edges.swap_remove(index);
+            self.degree[from as usize] -= 1;
+            self.degree[to as usize] -= 1;
+            self.edge_count -= 1;
+            self.topology += 1;
+            self.touch(from, to);
+        }
+    }
+
+    // Drops every edge not updated within `max_age` of `now`, so a pair that stopped ticking
+    // can't keep feeding its last price into detection. Returns the removed (start, end) pairs.
+    pub fn expire_stale(&mut self, max_age: Duration, now: Instant) -> Vec<(String, String)> {
+        let mut stale = Vec::new();
+        for (from, edges) in self.adjacency.iter().enumerate() {
+            for &(to, _, updated) in edges {
+                if now.saturating_duration_since(updated) > max_age {
+                    stale.push((self.names[from].clone(), self.names[to as usize].clone()));
+                }
+            }
+        }
+        for (start, end) in &stale {
+            self.remove_edge(start, end);
+        }
+        stale
+    }
+
+    fn direct_rate(&self, from: u32, to: u32) -> Option<f64> {
+        self.adjacency[from as usize].iter().find(|(target, ..)| *target == to).map(|(_, rate, _)| *rate)
+    }
+
+    // Conversion rate from one asset to another using the direct edge or the inverse of the reverse edge
+    pub fn rate(&self, from: &str, to: &str) -> Option<f64> {
+        let (from, to) = (self.id(from)?, self.id(to)?);
+        if let Some(rate) = self.direct_rate(from, to) {
+            return Some(rate);
+        }
+        self.direct_rate(to, from).filter(|rate| *rate != 0.0).map(|rate| 1.0 / rate)
+    }
+
+    // Haircuts every conversion into `asset` during detection; zero clears it.
+    // Edge rates and `rate` stay at market so reports show what was actually quoted.
+    pub fn set_premium(&mut self, asset: &str, premium: f64) {
+        let id = self.intern(asset);
+        let premium = premium.max(0.0);
+        if self.premiums[id as usize] != premium {
+            self.premiums[id as usize] = premium;
+            self.touch(id, id);
+        }
+    }
+
+    pub fn premium(&self, asset: &str) -> f64 {
+        self.id(asset).map_or(0.0, |id| self.premiums[id as usize])
+    }
+
+    // Rate used for detection: the market rate less any premium on the destination
+    fn effective_rate(&self, to: u32, rate: f64) -> f64 {
+        rate * (1.0 - self.premiums[to as usize])
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Edge> + '_ {
+        self.adjacency.iter().enumerate().flat_map(move |(from, edges)| {
+            edges.iter().map(move |(to, rate, _)| Edge {
+                start: self.names[from].clone(),
+                end: self.names[*to as usize].clone(),
+                rate: *rate,
+            })
+        })
+    }
+
+    // Every edge with when its rate last changed
+    pub fn edges_with_updates(&self) -> impl Iterator<Item = (Edge, Instant)> + '_ {
+        self.adjacency.iter().enumerate().flat_map(move |(from, edges)| {
+            edges.iter().map(move |(to, rate, updated)| {
+                let edge = Edge {
+                    start: self.names[from].clone(),
+                    end: self.names[*to as usize].clone(),
+                    rate: *rate,
+                };
+                (edge, *updated)
+            })
+        })
+    }
+
+    // Assets with at least one edge, with their premium
+    pub fn vertices(&self) -> impl Iterator<Item = (&str, f64)> + '_ {
+        self.names
+            .iter()
+            .zip(&self.degree)
+            .zip(&self.premiums)
+            .filter(|((_, degree), _)| **degree > 0)
+            .map(|((name, _), premium)| (name.as_str(), *premium))
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    // Assets with at least one edge
+    pub fn vertex_count(&self) -> usize {
+        self.degree.iter().filter(|degree| **degree > 0).count()
+    }
+
+    pub fn find_arbitrage(&self) -> Option<Vec<String>> {
+        self.find_cycles(1, usize::MAX).into_iter().next().map(|cycle| cycle.path(self))
+    }
+
+    // Every distinct negative cycle Bellman-Ford exposes, ranked by gross return
+    // (product of detection rates minus one), best first; at most `limit` are returned,
+    // none with more than `max_len` legs. Searches from a virtual source joined to every
+    // vertex by a zero-weight edge, so cycles in every component are reachable and the
+    // result doesn't depend on which vertex happens to come first.
+    pub fn find_cycles(&self, limit: usize, max_len: usize) -> Vec<Cycle> {
+        self.find_cycles_from(&self.live_vertices(), limit, max_len)
+    }
+
+    // IDs of the assets with at least one edge
+    pub fn live_vertices(&self) -> Vec<u32> {
+        (0..self.names.len() as u32).filter(|id| self.degree[*id as usize] > 0).collect()
+    }
+
+    // Like `find_cycles` but starting from every vertex in `sources` at once. Any negative
+    // cycle through one of them is reachable, so passing the dirty vertices finds every
+    // cycle a change could have created without searching from the whole graph.
+    pub fn find_cycles_from(&self, sources: &[u32], limit: usize, max_len: usize) -> Vec<Cycle> {
+        with_scratch(|scratch| {
+            self.weighted_edges(&mut scratch.weighted, |_, _| true);
+            self.search(scratch, self.vertex_count(), sources, limit, max_len)
+        })
+    }
+
+    // Splits the market into quote clusters: for every pair of quote assets with a market
+    // between them, those two plus every asset listed against both. Each pair of a
+    // triangle is quoted in one of its assets, so every triangle has two quote assets and
+    // lies inside the cluster for them; longer cycles are only covered within a cluster.
+    // Only changes with `topology`, so callers searching every pass should keep it.
+    pub fn quote_clusters(&self) -> Vec<Vec<u32>> {
+        let mut neighbours: Vec<HashSet<u32>> = vec![HashSet::new(); self.names.len()];
+        for (from, edges) in self.adjacency.iter().enumerate() {
+            for &(to, ..) in edges {
+                neighbours[from].insert(to);
+                neighbours[to as usize].insert(from as u32);
+            }
+        }
+        let hubs: Vec<u32> = QUOTE_ASSETS
+            .iter()
+            .filter_map(|asset| self.id(asset))
+            .filter(|id| self.degree[*id as usize] > 0)
+            .collect();
+        let mut clusters = Vec::new();
+        for (i, &a) in hubs.iter().enumerate() {
+            for &b in &hubs[i + 1..] {
+                if !neighbours[a as usize].contains(&b) {
+                    continue;
+                }
+                let mut cluster = vec![a, b];
+                cluster.extend(neighbours[a as usize].intersection(&neighbours[b as usize]).copied());
+                clusters.push(cluster);
+            }
+        }
+        clusters
+    }
+
+    // `find_cycles_from` run on every one of `clusters` (from `quote_clusters`) in parallel,
+    // each cluster searched only from the sources inside it. Much smaller relaxation passes
+    // than the whole graph at the cost of missing cycles longer than a triangle that span
+    // clusters.
+    pub fn find_cycles_parallel(&self, clusters: &[Vec<u32>], sources: &[u32], limit: usize, max_len: usize) -> Vec<Cycle> {
+        let found: Vec<Cycle> = clusters
+            .par_iter()
+            .flat_map_iter(|cluster| {
+                with_scratch(|scratch| {
+                    let member = &mut scratch.member;
+                    member.clear();
+                    member.resize(self.names.len(), false);
+                    cluster.iter().for_each(|id| member[*id as usize] = true);
+                    let mut cluster_sources = std::mem::take(&mut scratch.sources);
+                    cluster_sources.clear();
+                    cluster_sources.extend(sources.iter().copied().filter(|id| member[*id as usize]));
+                    self.weighted_edges(&mut scratch.weighted, |from, to| member[from as usize] && member[to as usize]);
+                    let cycles = self.search(scratch, cluster.len(), &cluster_sources, limit, max_len);
+                    scratch.sources = cluster_sources;
+                    cycles
+                })
+            })
+            .collect();
+
+        // Clusters overlap, so the same cycle can come back from several of them
+        let mut cycles = with_scratch(|scratch| {
+            scratch.seen.clear();
+            found.into_iter().filter(|cycle| scratch.seen.insert(rotation_key(&cycle.ids))).collect::<Vec<_>>()
+        });
+        cycles.sort_by(|a, b| b.gross_return.total_cmp(&a.gross_return));
+        cycles.truncate(limit);
+        cycles
+    }
+
+    // Refills `weighted` with the edges `keep` accepts as (from, to, -ln rate) so the
+    // relaxation loop touches no maps
+    fn weighted_edges(&self, weighted: &mut Vec<(u32, u32, f64)>, keep: impl Fn(u32, u32) -> bool) {
+        weighted.clear();
+        for (from, edges) in self.adjacency.iter().enumerate() {
+            for &(to, rate, _) in edges {
+                if keep(from as u32, to) {
+                    weighted.push((from as u32, to, -self.effective_rate(to, rate).ln()));
+                }
+            }
+        }
+    }
+
+    // Bellman-Ford over the scratch's weighted edges from a virtual source joined to
+    // `sources`, with `vertex_count` bounding the number of relaxation passes
+    fn search(&self, scratch: &mut Scratch, vertex_count: usize, sources: &[u32], limit: usize, max_len: usize) -> Vec<Cycle> {
+        let Scratch { weighted, distances, predecessors, seen, .. } = scratch;
+        if weighted.is_empty() || sources.is_empty() {
+            return Vec::new();
+        }
+
+        let passes = vertex_count;
+        let vertex_count = self.names.len();
+        distances.clear();
+        distances.resize(vertex_count, f64::INFINITY);
+        predecessors.clear();
+        predecessors.resize(vertex_count, NO_VERTEX);
+        // Starting every source at zero is the first relaxation from a virtual source
+        for source in sources {
+            distances[*source as usize] = 0.0;
+        }
+
+        // Relax edges repeatedly, stopping early once nothing changes
+        for _ in 1..passes {
+            let mut changed = false;
+            for &(from, to, weight) in weighted.iter() {
+                let new_dist = distances[from as usize] + weight;
+                // Check for overflow/underflow or any other arithmetic issues
+                if new_dist.is_finite() && new_dist < distances[to as usize] {
+                    distances[to as usize] = new_dist;
+                    predecessors[to as usize] = from;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        // Every edge that still relaxes in the final pass marks a negative cycle. Two cycles
+        // can hang off each violated edge u→v: the one its predecessor walk from v falls
+        // into, and the one closed by the edge itself if v is an ancestor of u. Collecting
+        // both from every violated edge surfaces simultaneous cycles, not just the first.
+        seen.clear();
+        let mut cycles = Vec::new();
+        for &(from, to, weight) in weighted.iter() {
+            let new_dist = distances[from as usize] + weight;
+            if !(new_dist.is_finite() && new_dist < distances[to as usize]) {
+                continue;
+            }
+            let candidates = [trace_cycle(predecessors, to), close_cycle(predecessors, from, to)];
+            for ids in candidates.into_iter().flatten() {
+                if ids.len() - 1 > max_len || !seen.insert(rotation_key(&ids)) {
+                    continue;
+                }
+                let gross_return = ids
+                    .windows(2)
+                    .map(|leg| self.direct_rate(leg[0], leg[1]).map_or(0.0, |rate| self.effective_rate(leg[1], rate)))
+                    .product::<f64>()
+                    - 1.0;
+                // A closed cycle isn't necessarily negative on its own
+                if gross_return <= 0.0 {
+                    continue;
+                }
+                cycles.push(Cycle { ids, gross_return });
+            }
+        }
+
+        cycles.sort_by(|a, b| b.gross_return.total_cmp(&a.gross_return));
+        cycles.truncate(limit);
+        cycles
+    }
+}
+
+// A detected arbitrage cycle, by vertex ID of the graph it was found in; names are only
+// looked up when it's reported
+#[derive(Debug, Clone)]
+pub struct Cycle {
+    pub ids: CycleIds,
+    pub gross_return: f64,
+}
+
+impl Cycle {
+    // Asset names in trading order, starting and ending on the same asset
+    pub fn path(&self, graph: &Graph) -> Vec<String> {
+        self.ids.iter().map(|id| graph.name(*id).to_string()).collect()
+    }
+
+    // Same for every rotation of the cycle
+    pub fn assets(&self) -> CycleIds {
+        let mut assets: CycleIds = self.ids.iter().skip(1).copied().collect();
+        assets.sort_unstable();
+        assets
+    }
+
+    pub fn touches(&self, ids: &[u32]) -> bool {
+        self.ids.iter().any(|id| ids.contains(id))
+    }
+}
+
+// Walks predecessors from `from` far enough to be inside the cycle, then collects it
+// in trading order. None if the walk runs off the predecessor tree.
+fn trace_cycle(predecessors: &[u32], from: u32) -> Option<CycleIds> {
+    let step = |vertex: u32| Some(predecessors[vertex as usize]).filter(|pred| *pred != NO_VERTEX);
+    let mut vertex = from;
+    for _ in 0..predecessors.len() {
+        vertex = step(vertex)?;
+    }
+    let mut cycle = CycleIds::new();
+    cycle.push(vertex);
+    let mut current = step(vertex)?;
+    while current != vertex {
+        cycle.push(current);
+        current = step(current)?;
+    }
+    cycle.push(vertex);
+    // Predecessors point backwards
+    cycle.reverse();
+    Some(cycle)
+}
+
+// The cycle formed by edge from→to when `to` is an ancestor of `from`, in trading order
+fn close_cycle(predecessors: &[u32], from: u32, to: u32) -> Option<CycleIds> {
+    let mut cycle = CycleIds::new();
+    cycle.push(from);
+    let mut vertex = from;
+    while vertex != to {
+        vertex = Some(predecessors[vertex as usize]).filter(|pred| *pred != NO_VERTEX)?;
+        // The walk fell into a loop that doesn't contain `to`
+        if cycle.contains(&vertex) {
+            return None;
+        }
+        cycle.push(vertex);
+    }
+    cycle.reverse();
+    cycle.push(to);
+    Some(cycle)
+}
+
+// Identifies a cycle regardless of where it starts: its vertices rotated to the smallest ID
+fn rotation_key(cycle: &[u32]) -> CycleIds {
+    let vertices = &cycle[..cycle.len() - 1];
+    let start = (0..vertices.len()).min_by_key(|&i| vertices[i]).unwrap_or(0);
+    vertices[start..].iter().chain(&vertices[..start]).copied().collect()
+}
@@ -0,0 +1,113 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::graph::Graph;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormat {
+    Dot,
+    GraphMl,
+}
+
+impl GraphFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "dot" | "gv" => Some(GraphFormat::Dot),
+            "graphml" => Some(GraphFormat::GraphMl),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "text/vnd.graphviz",
+            GraphFormat::GraphMl => "application/graphml+xml",
+        }
+    }
+}
+
+// Writes every asset and edge of `graph` for Graphviz, Gephi, yEd and the like. Edges
+// carry their rate and how long ago it last changed, as of `now`; with `stale_after`,
+// edges older than that are also flagged (and drawn dashed in DOT). Vertices carry their
+// depeg premium.
+pub fn export(graph: &Graph, format: GraphFormat, now: Instant, stale_after: Option<Duration>) -> String {
+    let mut edges: Vec<_> = graph
+        .edges_with_updates()
+        .map(|(edge, updated)| {
+            let age = now.saturating_duration_since(updated);
+            let stale = stale_after.is_some_and(|max| age > max);
+            (edge, age.as_millis() as u64, stale)
+        })
+        .collect();
+    edges.sort_by(|(a, ..), (b, ..)| (&a.start, &a.end).cmp(&(&b.start, &b.end)));
+    let mut vertices: Vec<_> = graph.vertices().collect();
+    vertices.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut out = String::new();
+    match format {
+        GraphFormat::Dot => {
+            out.push_str("digraph market {\n  rankdir=LR;\n  node [shape=ellipse];\n");
+            for (asset, premium) in &vertices {
+                let _ = writeln!(out, "  \"{}\" [premium={}];", dot_escape(asset), premium);
+            }
+            for (edge, age_ms, stale) in &edges {
+                let style = if *stale { ", style=dashed, color=gray" } else { "" };
+                let _ = writeln!(
+                    out,
+                    "  \"{}\" -> \"{}\" [label=\"{}\", rate={}, age_ms={}, stale={}{}];",
+                    dot_escape(&edge.start),
+                    dot_escape(&edge.end),
+                    edge.rate,
+                    edge.rate,
+                    age_ms,
+                    stale,
+                    style
+                );
+            }
+            out.push_str("}\n");
+        }
+        GraphFormat::GraphMl => {
+            out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+            out.push_str("  <key id=\"premium\" for=\"node\" attr.name=\"premium\" attr.type=\"double\"/>\n");
+            out.push_str("  <key id=\"rate\" for=\"edge\" attr.name=\"rate\" attr.type=\"double\"/>\n");
+            out.push_str("  <key id=\"age_ms\" for=\"edge\" attr.name=\"age_ms\" attr.type=\"long\"/>\n");
+            out.push_str("  <key id=\"stale\" for=\"edge\" attr.name=\"stale\" attr.type=\"boolean\"/>\n");
+            out.push_str("  <graph id=\"market\" edgedefault=\"directed\">\n");
+            for (asset, premium) in &vertices {
+                let _ = writeln!(
+                    out,
+                    "    <node id=\"{}\"><data key=\"premium\">{}</data></node>",
+                    xml_escape(asset),
+                    premium
+                );
+            }
+            for (edge, age_ms, stale) in &edges {
+                let _ = writeln!(
+                    out,
+                    "    <edge source=\"{}\" target=\"{}\"><data key=\"rate\">{}</data><data key=\"age_ms\">{}</data><data key=\"stale\">{}</data></edge>",
+                    xml_escape(&edge.start),
+                    xml_escape(&edge.end),
+                    edge.rate,
+                    age_ms,
+                    stale
+                );
+            }
+            out.push_str("  </graph>\n</graphml>\n");
+        }
+    }
+    out
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
@@ -0,0 +1,142 @@
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, TcpListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::engine::DetectionControl;
+use crate::events::{EngineEvent, Opportunity};
+use crate::shared_graph::SharedGraph;
+
+// Types and service traits generated from proto/hft3.proto
+pub mod proto {
+    tonic::include_proto!("hft3.v1");
+}
+
+use proto::hft3_server::{Hft3, Hft3Server};
+
+impl From<&Opportunity> for proto::Opportunity {
+    fn from(opportunity: &Opportunity) -> Self {
+        proto::Opportunity {
+            path: opportunity.path.clone(),
+            cycle: opportunity.cycle_key(),
+            rates: opportunity.rates.clone(),
+            profit_bps: opportunity.profit.map(|p| p * 10_000.0),
+            strategy: opportunity.strategy.clone(),
+            executed: opportunity.executed,
+            detected_at_ms: opportunity.detected_at.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as i64),
+            event_time: opportunity.event_time,
+            detection_latency_us: opportunity.detection_latency.map(|d| d.as_micros() as u64),
+            venues: opportunity.venues.clone(),
+        }
+    }
+}
+
+// gRPC API over a running engine: opportunity streaming, graph queries and a detection
+// pause switch. Holds handles only, so it never touches the engine task.
+pub struct GrpcService {
+    events: broadcast::Receiver<EngineEvent>, // Never read; each watch call resubscribes from it
+    graph: Arc<SharedGraph>,
+    control: DetectionControl,
+}
+
+impl GrpcService {
+    pub fn new(events: broadcast::Receiver<EngineEvent>, graph: Arc<SharedGraph>, control: DetectionControl) -> Self {
+        GrpcService { events, graph, control }
+    }
+
+    // Binds `addr` and serves on a background task
+    pub async fn spawn(self, addr: SocketAddr) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        tokio::spawn(async move {
+            let result = tonic::transport::Server::builder()
+                .add_service(Hft3Server::new(self))
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await;
+            if let Err(e) = result {
+                tracing::error!(error = %e, "gRPC server stopped");
+            }
+        });
+        Ok(())
+    }
+
+    fn detection_state(&self) -> proto::DetectionState {
+        proto::DetectionState { paused: self.control.is_paused() }
+    }
+}
+
+type OpportunityStream = Pin<Box<dyn Stream<Item = Result<proto::Opportunity, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Hft3 for GrpcService {
+    type WatchOpportunitiesStream = OpportunityStream;
+
+    async fn watch_opportunities(
+        &self,
+        request: Request<proto::WatchOpportunitiesRequest>,
+    ) -> Result<Response<OpportunityStream>, Status> {
+        let filter = request.into_inner();
+        let stream = BroadcastStream::new(self.events.resubscribe()).filter_map(move |event| match event {
+            Ok(EngineEvent::Opportunity(opportunity)) => {
+                let below = opportunity.profit.is_some_and(|p| p * 10_000.0 < filter.min_profit_bps);
+                let other_strategy = !filter.strategy.is_empty() && filter.strategy != opportunity.strategy;
+                let expired = opportunity.is_expired(SystemTime::now());
+                (!below && !other_strategy && !expired).then(|| Ok(proto::Opportunity::from(&opportunity)))
+            }
+            Ok(_) => None,
+            // A slow client misses opportunities rather than holding up the others
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                tracing::warn!(skipped, "gRPC watcher fell behind");
+                None
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+
+    async fn get_graph(&self, request: Request<proto::GetGraphRequest>) -> Result<Response<proto::GraphState>, Status> {
+        let graph = self.graph.load();
+        let edges = match request.into_inner().include_edges {
+            true => graph
+                .edges()
+                .map(|edge| proto::Edge { start: edge.start, end: edge.end, rate: edge.rate })
+                .collect(),
+            false => Vec::new(),
+        };
+        Ok(Response::new(proto::GraphState {
+            assets: graph.vertex_count() as u32,
+            edge_count: graph.edge_count() as u32,
+            passes: self.graph.epoch(),
+            detection_paused: self.control.is_paused(),
+            edges,
+        }))
+    }
+
+    async fn get_rate(&self, request: Request<proto::GetRateRequest>) -> Result<Response<proto::GetRateResponse>, Status> {
+        let request = request.into_inner();
+        let rate = self.graph.load().rate(&request.from, &request.to);
+        Ok(Response::new(proto::GetRateResponse { rate }))
+    }
+
+    async fn pause_detection(
+        &self,
+        _: Request<proto::PauseDetectionRequest>,
+    ) -> Result<Response<proto::DetectionState>, Status> {
+        self.control.pause();
+        tracing::info!("Detection paused over gRPC");
+        Ok(Response::new(self.detection_state()))
+    }
+
+    async fn resume_detection(
+        &self,
+        _: Request<proto::ResumeDetectionRequest>,
+    ) -> Result<Response<proto::DetectionState>, Status> {
+        self.control.resume();
+        tracing::info!("Detection resumed over gRPC");
+        Ok(Response::new(self.detection_state()))
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Query, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Json, Response};
+use axum::routing::get;
+use axum::Router;
+use serde_json::{json, Value};
+
+use crate::book_stats::BookMetrics;
+use crate::engine::DetectionControl;
+use crate::graph_export::{export, GraphFormat};
+use crate::opportunity_stats::OpportunityStats;
+use crate::pnl::PnlTracker;
+use crate::shared_graph::SharedGraph;
+
+// Default for how long the feed or the detector may go quiet before /healthz fails
+pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(30);
+
+// Cycles and assets listed in the opportunity statistics on /status
+const STATUS_TOP_CYCLES: usize = 10;
+
+// Widest and most imbalanced books listed on /status
+const STATUS_TOP_BOOKS: usize = 10;
+
+// Liveness marks recorded by the engine loop, readable from other tasks
+#[derive(Debug)]
+pub struct HealthMetrics {
+    started: Instant,
+    last_message: Mutex<HashMap<String, Instant>>, // By venue
+    stalls: Mutex<HashMap<String, (u64, Instant)>>, // Watchdog reconnects and the latest one, by venue
+    last_pass: Mutex<Option<Instant>>,
+    passes: AtomicU64,
+    feed_closed: AtomicBool,
+    tasks: Mutex<BTreeMap<String, TaskStatus>>, // Supervised tasks, by name
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TaskState {
+    Running,
+    Restarting, // Failed, waiting out its backoff
+    Finished,
+}
+
+#[derive(Debug)]
+struct TaskStatus {
+    state: TaskState,
+    restarts: u64,
+    last_failure: Option<(String, Instant)>,
+}
+
+impl Default for HealthMetrics {
+    fn default() -> Self {
+        HealthMetrics {
+            started: Instant::now(),
+            last_message: Mutex::new(HashMap::new()),
+            stalls: Mutex::new(HashMap::new()),
+            last_pass: Mutex::new(None),
+            passes: AtomicU64::new(0),
+            feed_closed: AtomicBool::new(false),
+            tasks: Mutex::new(BTreeMap::new()),
+        }
+    }
+}
+
+impl HealthMetrics {
+    pub fn record_message(&self, venue: &str, at: Instant) {
+        let mut last = self.last_message.lock().unwrap();
+        match last.get_mut(venue) {
+            Some(seen) => *seen = at,
+            None => {
+                last.insert(venue.to_string(), at);
+            }
+        }
+    }
+
+    pub fn record_feed_stalled(&self, venue: &str, at: Instant) {
+        let mut stalls = self.stalls.lock().unwrap();
+        let (count, last) = stalls.entry(venue.to_string()).or_insert((0, at));
+        *count += 1;
+        *last = at;
+    }
+
+    pub fn record_pass(&self, at: Instant) {
+        *self.last_pass.lock().unwrap() = Some(at);
+        self.passes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_feed_closed(&self) {
+        self.feed_closed.store(true, Ordering::Relaxed);
+    }
+
+    pub fn record_task_started(&self, name: &str) {
+        let mut tasks = self.tasks.lock().unwrap();
+        match tasks.get_mut(name) {
+            Some(task) => {
+                task.state = TaskState::Running;
+                task.restarts += 1;
+            }
+            None => {
+                tasks.insert(name.to_string(), TaskStatus { state: TaskState::Running, restarts: 0, last_failure: None });
+            }
+        }
+    }
+
+    pub fn record_task_failed(&self, name: &str, failure: &str, at: Instant) {
+        let mut tasks = self.tasks.lock().unwrap();
+        let task = tasks
+            .entry(name.to_string())
+            .or_insert_with(|| TaskStatus { state: TaskState::Running, restarts: 0, last_failure: None });
+        task.state = TaskState::Restarting;
+        task.last_failure = Some((failure.to_string(), at));
+    }
+
+    pub fn record_task_finished(&self, name: &str) {
+        if let Some(task) = self.tasks.lock().unwrap().get_mut(name) {
+            task.state = TaskState::Finished;
+        }
+    }
+
+    // Reasons the bot is unhealthy; empty when it is fine. Until something arrives the
+    // startup itself counts as the last sign of life, so a fresh process gets `max_age`
+    // to connect before probes fail.
+    pub fn problems(&self, max_age: Duration, now: Instant) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.feed_closed.load(Ordering::Relaxed) {
+            problems.push("feed closed".to_string());
+        }
+        let last_message = self.last_message.lock().unwrap();
+        let newest = last_message.values().max().copied().unwrap_or(self.started);
+        if now.duration_since(newest) > max_age {
+            problems.push(format!("no market data for {} ms", now.duration_since(newest).as_millis()));
+        }
+        let last_pass = self.last_pass.lock().unwrap().unwrap_or(self.started);
+        if now.duration_since(last_pass) > max_age {
+            problems.push(format!("no detection pass for {} ms", now.duration_since(last_pass).as_millis()));
+        }
+        for (name, task) in self.tasks.lock().unwrap().iter() {
+            if let (TaskState::Restarting, Some((failure, _))) = (task.state, &task.last_failure) {
+                problems.push(format!("{} restarting after: {}", name, failure));
+            }
+        }
+        problems
+    }
+}
+
+#[derive(Clone)]
+struct HealthState {
+    metrics: Arc<HealthMetrics>,
+    graph: Arc<SharedGraph>,
+    control: DetectionControl,
+    max_age: Duration,
+    sources: Arc<StatusSources>,
+}
+
+// Optional sections of /status, present when the run keeps them
+#[derive(Default)]
+pub struct StatusSources {
+    pub pnl: Option<Arc<Mutex<PnlTracker>>>,
+    pub opportunities: Option<Arc<Mutex<OpportunityStats>>>,
+    pub books: Option<Arc<BookMetrics>>, // Also served in full on /books
+}
+
+fn age_ms(at: Instant, now: Instant) -> u64 {
+    now.duration_since(at).as_millis() as u64
+}
+
+// Liveness/readiness probe: 200 "ok", or 503 listing what is wrong
+async fn healthz(State(state): State<HealthState>) -> impl IntoResponse {
+    let problems = state.metrics.problems(state.max_age, Instant::now());
+    if problems.is_empty() {
+        (StatusCode::OK, "ok\n".to_string())
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, format!("{}\n", problems.join("\n")))
+    }
+}
+
+async fn status(State(state): State<HealthState>) -> Json<Value> {
+    let now = Instant::now();
+    let metrics = &state.metrics;
+    let problems = metrics.problems(state.max_age, now);
+    let mut venues: serde_json::Map<String, Value> = metrics
+        .last_message
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(venue, at)| (venue.clone(), json!({ "last_message_age_ms": age_ms(*at, now), "stalls": 0 })))
+        .collect();
+    for (venue, (count, at)) in metrics.stalls.lock().unwrap().iter() {
+        let entry = venues.entry(venue.clone()).or_insert_with(|| json!({ "last_message_age_ms": null }));
+        entry["stalls"] = json!(count);
+        entry["last_stall_age_ms"] = json!(age_ms(*at, now));
+    }
+    let last_pass = *metrics.last_pass.lock().unwrap();
+    let tasks: serde_json::Map<String, Value> = metrics
+        .tasks
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|(name, task)| {
+            let state = match task.state {
+                TaskState::Running => "running",
+                TaskState::Restarting => "restarting",
+                TaskState::Finished => "finished",
+            };
+            let status = json!({
+                "state": state,
+                "restarts": task.restarts,
+                "last_failure": task.last_failure.as_ref().map(|(failure, _)| failure),
+                "last_failure_age_ms": task.last_failure.as_ref().map(|(_, at)| age_ms(*at, now)),
+            });
+            (name.clone(), status)
+        })
+        .collect();
+    let graph = state.graph.load();
+    Json(json!({
+        "healthy": problems.is_empty(),
+        "problems": problems,
+        "uptime_s": metrics.started.elapsed().as_secs(),
+        "feed": {
+            "closed": metrics.feed_closed.load(Ordering::Relaxed),
+            "venues": venues,
+        },
+        "graph": {
+            "assets": graph.vertex_count(),
+            "edges": graph.edge_count(),
+        },
+        "detector": {
+            "passes": metrics.passes.load(Ordering::Relaxed),
+            "last_pass_age_ms": last_pass.map(|at| age_ms(at, now)),
+            "paused": state.control.is_paused(),
+        },
+        "tasks": tasks,
+        "pnl": state.sources.pnl.as_ref().map(|pnl| pnl.lock().unwrap().summary(&graph).to_json()),
+        "opportunities": state.sources.opportunities.as_ref().map(|stats| stats.lock().unwrap().summary(STATUS_TOP_CYCLES)),
+        "books": state.sources.books.as_ref().map(|books| books.summary(STATUS_TOP_BOOKS, now)),
+    }))
+}
+
+// Spread and imbalance of every symbol's book; 404 when they aren't kept
+async fn books(State(state): State<HealthState>) -> Response {
+    match &state.sources.books {
+        Some(books) => Json(books.to_json(Instant::now())).into_response(),
+        None => (StatusCode::NOT_FOUND, "book stats are off\n").into_response(),
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct GraphQuery {
+    format: Option<String>, // dot (default) or graphml
+    stale_ms: Option<u64>,  // Flag edges whose rate hasn't changed for this long
+}
+
+// The graph as of the last detection pass, for visualizing in Graphviz or Gephi
+async fn graph_dump(State(state): State<HealthState>, Query(query): Query<GraphQuery>) -> impl IntoResponse {
+    let format = match query.format.as_deref() {
+        None => GraphFormat::Dot,
+        Some(name) => match GraphFormat::parse(name) {
+            Some(format) => format,
+            None => return (StatusCode::BAD_REQUEST, [(header::CONTENT_TYPE, "text/plain")], format!("unknown format {}\n", name)),
+        },
+    };
+    let stale_after = query.stale_ms.map(Duration::from_millis);
+    let body = export(&state.graph.load(), format, Instant::now(), stale_after);
+    (StatusCode::OK, [(header::CONTENT_TYPE, format.content_type())], body)
+}
+
+// Binds `addr` and serves /healthz, /status, /graph and /books on a background task
+pub async fn spawn(
+    addr: SocketAddr,
+    metrics: Arc<HealthMetrics>,
+    graph: Arc<SharedGraph>,
+    control: DetectionControl,
+    max_age: Duration,
+    sources: StatusSources,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let state = HealthState { metrics, graph, control, max_age, sources: Arc::new(sources) };
+    let app = Router::new()
+        .route("/healthz", get(healthz))
+        .route("/status", get(status))
+        .route("/graph", get(graph_dump))
+        .route("/books", get(books))
+        .with_state(state);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "Health endpoint stopped");
+        }
+    });
+    Ok(())
+}
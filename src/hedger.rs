@@ -0,0 +1,144 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::events::{MarketEvent, Opportunity, Signal};
+use crate::filters::SymbolFilters;
+use crate::graph::{extract_currency_pair, Graph};
+use crate::inventory::Inventory;
+use crate::order::{OrderRequest, Side};
+use crate::strategy::Strategy;
+
+#[derive(Debug, Clone)]
+pub struct HedgeConfig {
+    pub pair: String,           // Liquid pair used for hedging, e.g. "BTCUSDT"
+    pub band: Decimal,          // Allowed net exposure in the pair's quote asset before hedging
+    pub min_interval: Duration, // Minimum time between hedges so in-flight orders can land
+}
+
+impl HedgeConfig {
+    pub fn new(pair: &str) -> Self {
+        HedgeConfig {
+            pair: pair.to_string(),
+            band: Decimal::from(100),
+            min_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+// Keeps the arbitrage book market-neutral by offsetting net exposure in one liquid pair
+pub struct Hedger {
+    config: HedgeConfig,
+    base: String,
+    quote: String,
+    filters: Option<SymbolFilters>,
+    last_hedge: Option<Instant>,
+}
+
+impl Hedger {
+    pub fn new(config: HedgeConfig) -> Self {
+        let (base, quote) = extract_currency_pair(&config.pair);
+        Hedger {
+            config,
+            base,
+            quote,
+            filters: None,
+            last_hedge: None,
+        }
+    }
+
+    // Rounds hedges to the pair's lot size and drops those the exchange would refuse
+    pub fn with_filters(mut self, filters: SymbolFilters) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    pub fn pair(&self) -> &str {
+        &self.config.pair
+    }
+
+    // Returns an offsetting order, with the net exposure (valued in the hedge pair's quote
+    // asset) it offsets, when that exposure is outside the band. Long exposure is hedged by
+    // selling the base, short by buying it.
+    pub fn check(&mut self, inventory: &Inventory, graph: &Graph) -> Option<(OrderRequest, Decimal)> {
+        if let Some(last) = self.last_hedge {
+            if last.elapsed() < self.config.min_interval {
+                return None;
+            }
+        }
+
+        let (exposure, unpriced) = inventory.net_exposure(graph, &self.quote);
+        if !unpriced.is_empty() {
+            tracing::warn!(?unpriced, "Hedger has no price for some assets, exposure is understated");
+        }
+        if exposure.abs() <= self.config.band {
+            return None;
+        }
+
+        let price = Decimal::try_from(graph.rate(&self.base, &self.quote)?).ok()?;
+        if price <= Decimal::ZERO {
+            return None;
+        }
+        let side = if exposure > Decimal::ZERO { Side::Sell } else { Side::Buy };
+        let mut quantity = exposure.abs().checked_div(price)?;
+        if let Some(filters) = &self.filters {
+            quantity = filters.round_quantity(quantity, true);
+            if let Err(e) = filters.check(quantity, price, true) {
+                tracing::debug!(pair = %self.config.pair, %exposure, error = %e, "Hedge too small to place");
+                return None;
+            }
+        }
+        self.last_hedge = Some(Instant::now());
+        let order = OrderRequest {
+            symbol: self.config.pair.clone(),
+            side,
+            quantity,
+            price: None,
+        };
+        Some((order, exposure))
+    }
+}
+
+// Runs the hedger after every market update, on an inventory the account streams' fills
+// are applied to. A hedge goes out as a `hedge` signal carrying the market order, so the
+// executor places it under the same risk limits and calendar as cycles; its fill flows
+// back into the inventory like any other. The signal's value is the net exposure the
+// hedge offsets, in the pair's quote asset (positive when long).
+pub struct HedgeStrategy {
+    hedger: Hedger,
+    inventory: Arc<Mutex<Inventory>>,
+    signals: Vec<Signal>,
+}
+
+impl HedgeStrategy {
+    pub fn new(hedger: Hedger, inventory: Arc<Mutex<Inventory>>) -> Self {
+        HedgeStrategy { hedger, inventory, signals: Vec::new() }
+    }
+}
+
+impl Strategy for HedgeStrategy {
+    fn name(&self) -> &str {
+        "hedge"
+    }
+
+    fn on_update(&mut self, _graph: &Graph) -> Vec<Opportunity> {
+        Vec::new()
+    }
+
+    fn on_event(&mut self, graph: &Graph, _event: &MarketEvent) -> Vec<Opportunity> {
+        let inventory = self.inventory.lock().unwrap();
+        if let Some((order, exposure)) = self.hedger.check(&inventory, graph) {
+            tracing::info!(pair = %order.symbol, side = order.side.as_str(), quantity = %order.quantity, %exposure, "Hedging net exposure");
+            let signal = Signal::new("hedge", vec![self.hedger.pair().to_string()], exposure.to_f64().unwrap_or_default())
+                .with_orders(vec![order]);
+            self.signals.push(signal);
+        }
+        Vec::new()
+    }
+
+    fn take_signals(&mut self) -> Vec<Signal> {
+        std::mem::take(&mut self.signals)
+    }
+}
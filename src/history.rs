@@ -0,0 +1,144 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::recorder::Recorder;
+use crate::rest::{EndpointCategory, RestClient, RestError};
+use crate::ticker::TickerData;
+
+// Most rows Binance returns per klines or aggTrades request
+const PAGE_LIMIT: u32 = 1000;
+// aggTrades only accepts startTime/endTime spanning at most an hour
+const AGG_TRADES_WINDOW_MS: u64 = 60 * 60 * 1000;
+
+// What to turn into ticks: candle closes or individual (aggregated) trades
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HistorySource {
+    Klines { interval: String }, // One tick per candle, at its close time and price
+    AggTrades,                   // One tick per aggregated trade
+}
+
+impl HistorySource {
+    // Ticks of `symbol` between `start` (inclusive) and `end` (exclusive), epoch ms
+    pub async fn fetch(&self, client: &RestClient, symbol: &str, start: u64, end: u64) -> Result<Vec<TickerData>, RestError> {
+        match self {
+            HistorySource::Klines { interval } => fetch_klines(client, symbol, interval, start, end).await,
+            HistorySource::AggTrades => fetch_agg_trades(client, symbol, start, end).await,
+        }
+    }
+}
+
+// Candle closes of `symbol` in [start, end) ms, paging 1000 at a time (weight 2 each).
+// Candles still open at `end` are left out.
+pub async fn fetch_klines(client: &RestClient, symbol: &str, interval: &str, start: u64, end: u64) -> Result<Vec<TickerData>, RestError> {
+    let mut ticks = Vec::new();
+    let mut from = start;
+    while from < end {
+        let query = [
+            ("symbol", symbol.to_string()),
+            ("interval", interval.to_string()),
+            ("startTime", from.to_string()),
+            ("endTime", (end - 1).to_string()),
+            ("limit", PAGE_LIMIT.to_string()),
+        ];
+        let body = client.get(EndpointCategory::Market, 2, "/api/v3/klines", &query).await?;
+        let rows: Vec<Vec<Value>> = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+        let Some(last) = rows.last() else {
+            break;
+        };
+        // [open time, open, high, low, close, volume, close time, ...]
+        let next = last.first().and_then(Value::as_u64).ok_or_else(|| RestError::Decode("kline without open time".into()))?;
+        for row in &rows {
+            let (Some(close), Some(close_time)) = (row.get(4).and_then(Value::as_str), row.get(6).and_then(Value::as_u64)) else {
+                return Err(RestError::Decode(format!("malformed kline {:?}", row)));
+            };
+            if close_time < end {
+                ticks.push(tick(symbol, close, close_time));
+            }
+        }
+        if rows.len() < PAGE_LIMIT as usize {
+            break;
+        }
+        from = next + 1;
+    }
+    Ok(ticks)
+}
+
+// Entry of /api/v3/aggTrades
+#[derive(serde::Deserialize)]
+struct AggTrade {
+    a: u64,    // Aggregate trade id
+    p: String, // Price
+    #[serde(rename = "T")]
+    time: u64,
+}
+
+// Trades of `symbol` in [start, end) ms (weight 4 per page). The first trade is found by
+// time, an hour at a time; the rest are paged by id, which never splits a millisecond.
+pub async fn fetch_agg_trades(client: &RestClient, symbol: &str, start: u64, end: u64) -> Result<Vec<TickerData>, RestError> {
+    let mut ticks = Vec::new();
+    let mut window = start;
+    let mut from_id: Option<u64> = None;
+    loop {
+        let mut query = vec![("symbol", symbol.to_string()), ("limit", PAGE_LIMIT.to_string())];
+        match from_id {
+            Some(id) => query.push(("fromId", id.to_string())),
+            None if window >= end => break,
+            None => {
+                query.push(("startTime", window.to_string()));
+                query.push(("endTime", ((window + AGG_TRADES_WINDOW_MS).min(end) - 1).to_string()));
+            }
+        }
+        let body = client.get(EndpointCategory::Market, 4, "/api/v3/aggTrades", &query).await?;
+        let trades: Vec<AggTrade> = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+        let Some(last) = trades.last() else {
+            if from_id.is_some() {
+                break;
+            }
+            window += AGG_TRADES_WINDOW_MS;
+            continue;
+        };
+        let next = last.a + 1;
+        let done = last.time >= end;
+        ticks.extend(trades.iter().filter(|t| t.time < end).map(|t| tick(symbol, &t.p, t.time)));
+        if done || (from_id.is_some() && trades.len() < PAGE_LIMIT as usize) {
+            break;
+        }
+        from_id = Some(next);
+    }
+    Ok(ticks)
+}
+
+fn tick(symbol: &str, price: &str, time: u64) -> TickerData {
+    TickerData {
+        s: symbol.to_string(),
+        c: price.to_string(),
+        event_time: time,
+        b: None,
+        bid_qty: None,
+        a: None,
+        ask_qty: None,
+        update_id: None,
+        quote_volume: None,
+        trade: false,
+    }
+}
+
+// Writes ticks of any number of symbols as a recorded session: one `!ticker@arr` message
+// per millisecond with ticks, in time order, so ReplayFeed plays it like a capture.
+// Returns the number of messages written.
+pub fn write_session(path: &Path, ticks: Vec<TickerData>) -> io::Result<usize> {
+    let mut steps: BTreeMap<u64, Vec<TickerData>> = BTreeMap::new();
+    for tick in ticks {
+        steps.entry(tick.event_time).or_default().push(tick);
+    }
+    let mut recorder = Recorder::create(path)?;
+    for data in steps.values() {
+        let message = serde_json::json!({ "stream": "!ticker@arr", "data": data });
+        recorder.record(&message.to_string())?;
+    }
+    recorder.flush()?;
+    Ok(steps.len())
+}
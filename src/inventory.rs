@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use rust_decimal::Decimal;
+
+use crate::graph::Graph;
+use crate::ledger::Fill;
+use crate::order::Side;
+
+// Per-asset balances, with an optional target the book should return to
+#[derive(Default)]
+pub struct Inventory {
+    balances: HashMap<String, Decimal>,
+    targets: HashMap<String, Decimal>,
+}
+
+impl Inventory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_balance(&mut self, asset: &str, amount: Decimal) {
+        self.balances.insert(asset.to_string(), amount);
+    }
+
+    // Target holdings are what we consider "flat"; anything above or below is exposure
+    pub fn set_target(&mut self, asset: &str, amount: Decimal) {
+        self.targets.insert(asset.to_string(), amount);
+    }
+
+    pub fn balance(&self, asset: &str) -> Decimal {
+        self.balances.get(asset).copied().unwrap_or_default()
+    }
+
+    pub fn balances(&self) -> &HashMap<String, Decimal> {
+        &self.balances
+    }
+
+    // Applies a fill on base/quote; fee is charged in the quote asset
+    pub fn apply_fill(&mut self, base: &str, quote: &str, side: Side, quantity: Decimal, price: Decimal, fee: Decimal) {
+        let notional = quantity * price;
+        let (base_delta, quote_delta) = match side {
+            Side::Buy => (quantity, -notional),
+            Side::Sell => (-quantity, notional),
+        };
+        *self.balances.entry(base.to_string()).or_default() += base_delta;
+        *self.balances.entry(quote.to_string()).or_default() += quote_delta - fee;
+    }
+
+    // Applies a recorded fill, charging the fee in whichever asset it was paid in
+    pub fn apply(&mut self, fill: &Fill) {
+        let fee_asset = fill.fee_asset_or_quote();
+        let fee_in_quote = if fee_asset == fill.quote { fill.fee } else { Decimal::ZERO };
+        self.apply_fill(&fill.base, &fill.quote, fill.side, fill.quantity, fill.price, fee_in_quote);
+        if fee_asset != fill.quote && !fill.fee.is_zero() {
+            *self.balances.entry(fee_asset.to_string()).or_default() -= fill.fee;
+        }
+    }
+
+    // Net exposure valued in `reference`: sum over every other asset of (balance - target) * price.
+    // Assets with no price path to the reference are skipped and returned separately. Graph
+    // rates are f64, so the valuation is only as exact as the rate it uses.
+    pub fn net_exposure(&self, graph: &Graph, reference: &str) -> (Decimal, Vec<String>) {
+        let mut exposure = Decimal::ZERO;
+        let mut unpriced = Vec::new();
+        for (asset, balance) in &self.balances {
+            if asset == reference {
+                continue;
+            }
+            let deviation = balance - self.targets.get(asset).copied().unwrap_or_default();
+            if deviation.is_zero() {
+                continue;
+            }
+            match graph.rate(asset, reference).and_then(|rate| Decimal::try_from(rate).ok()) {
+                Some(price) => exposure += deviation * price,
+                None => unpriced.push(asset.clone()),
+            }
+        }
+        (exposure, unpriced)
+    }
+}
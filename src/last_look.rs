@@ -0,0 +1,145 @@
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+
+use crate::events::Opportunity;
+use crate::graph::Graph;
+
+#[derive(Debug, Clone)]
+pub struct LastLookConfig {
+    pub tolerance_bps: f64, // Largest adverse move on the final leg that still completes the cycle
+}
+
+impl Default for LastLookConfig {
+    fn default() -> Self {
+        LastLookConfig { tolerance_bps: 2.0 }
+    }
+}
+
+// Counters readable while the executor runs
+#[derive(Debug, Default)]
+pub struct LastLookMetrics {
+    pub passed: AtomicU64,  // Final legs sent after the check
+    pub aborted: AtomicU64, // Cycles abandoned before the final leg
+}
+
+impl LastLookMetrics {
+    pub fn snapshot(&self) -> (u64, u64) {
+        (self.passed.load(Ordering::Relaxed), self.aborted.load(Ordering::Relaxed))
+    }
+}
+
+// One abandoned cycle. `completed_bps` is what finishing at the current rate would have
+// returned, so summing it over aborts shows whether the guard saves or costs money.
+#[derive(serde::Serialize, Debug, Clone)]
+pub struct LastLookAbort {
+    pub time: DateTime<Utc>,
+    pub cycle: String,
+    pub from: String,
+    pub to: String,
+    pub decided_rate: f64,
+    pub current_rate: Option<f64>, // None when the pair has no price any more
+    pub move_bps: Option<f64>,     // Adverse move since the decision
+    pub expected_bps: f64,         // Return expected when the cycle was executed
+    pub completed_bps: Option<f64>,
+}
+
+pub enum LastLook {
+    Proceed,
+    Abort(LastLookAbort),
+}
+
+// Re-checks the final leg of a cycle against the current book before it is sent, since
+// the earlier legs take long enough for the price the decision relied on to move away.
+// Aborts are counted apart from passes and optionally appended to a JSON lines file.
+// Clones share the counters and the file, so one guard can serve every executor.
+#[derive(Clone)]
+pub struct LastLookGuard {
+    config: Arc<LastLookConfig>,
+    metrics: Arc<LastLookMetrics>,
+    log: Option<Arc<Mutex<BufWriter<File>>>>,
+}
+
+impl fmt::Debug for LastLookGuard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LastLookGuard").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl LastLookGuard {
+    pub fn new(config: LastLookConfig) -> Self {
+        LastLookGuard {
+            config: Arc::new(config),
+            metrics: Arc::new(LastLookMetrics::default()),
+            log: None,
+        }
+    }
+
+    // Appends every abort to `path`, one JSON object per line
+    pub fn with_abort_log(mut self, path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.log = Some(Arc::new(Mutex::new(BufWriter::new(file))));
+        Ok(self)
+    }
+
+    pub fn metrics(&self) -> Arc<LastLookMetrics> {
+        self.metrics.clone()
+    }
+
+    // Compares the final leg's rate now with the one the opportunity was decided on. A
+    // lower rate is a move against us; a missing one means the book can't be verified.
+    pub fn check(&self, opportunity: &Opportunity, current_rate: Option<f64>) -> LastLook {
+        let (Some(&decided_rate), [.., from, to]) = (opportunity.rates.last(), opportunity.path.as_slice()) else {
+            // Nothing to compare against; the guard only ever removes legs it can judge
+            self.metrics.passed.fetch_add(1, Ordering::Relaxed);
+            return LastLook::Proceed;
+        };
+        let move_bps = current_rate.map(|rate| (decided_rate - rate) / decided_rate * 10_000.0);
+        if move_bps.is_some_and(|bps| bps <= self.config.tolerance_bps) {
+            self.metrics.passed.fetch_add(1, Ordering::Relaxed);
+            return LastLook::Proceed;
+        }
+
+        let expected = opportunity
+            .profit
+            .unwrap_or_else(|| opportunity.rates.iter().product::<f64>() - 1.0);
+        let abort = LastLookAbort {
+            time: Utc::now(),
+            cycle: opportunity.cycle_key(),
+            from: from.clone(),
+            to: to.clone(),
+            decided_rate,
+            current_rate,
+            move_bps,
+            expected_bps: expected * 10_000.0,
+            completed_bps: current_rate.map(|rate| ((1.0 + expected) * rate / decided_rate - 1.0) * 10_000.0),
+        };
+        self.metrics.aborted.fetch_add(1, Ordering::Relaxed);
+        tracing::info!(cycle = %abort.cycle, move_bps = ?abort.move_bps, "Last look aborted the final leg");
+        if let Some(log) = &self.log {
+            let mut log = log.lock().unwrap();
+            let result = serde_json::to_writer(&mut *log, &abort)
+                .map_err(io::Error::from)
+                .and_then(|_| log.write_all(b"\n"))
+                .and_then(|_| log.flush());
+            if let Err(e) = result {
+                tracing::error!(error = %e, "Failed to record last-look abort");
+            }
+        }
+        LastLook::Abort(abort)
+    }
+
+    // `check` against the final leg's rate in `graph`
+    pub fn check_graph(&self, opportunity: &Opportunity, graph: &Graph) -> LastLook {
+        let current_rate = match opportunity.path.as_slice() {
+            [.., from, to] => graph.rate(from, to),
+            _ => None,
+        };
+        self.check(opportunity, current_rate)
+    }
+}
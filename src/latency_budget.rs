@@ -0,0 +1,153 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::events::Opportunity;
+use crate::risk::RiskManager;
+
+// How slow an executed cycle may be before it counts against the budget; a zero bound
+// isn't checked
+#[derive(Debug, Clone)]
+pub struct LatencyBudgetConfig {
+    pub max_first_order: Duration, // Detection to the first leg's order going out
+    pub max_leg_gap: Duration,     // One leg's order going out to the next one's
+    pub breaches: u32,             // Cycles over budget in a row that trip the kill switch
+}
+
+impl Default for LatencyBudgetConfig {
+    fn default() -> Self {
+        LatencyBudgetConfig {
+            max_first_order: Duration::from_millis(50),
+            max_leg_gap: Duration::from_millis(200),
+            breaches: 3,
+        }
+    }
+}
+
+// When one cycle's orders went out, measured as the executor sends them
+#[derive(Debug, Clone)]
+pub struct LegTimer {
+    detected_at: SystemTime,
+    first_order: Option<Duration>,
+    last_sent: Option<Instant>,
+    leg_gaps: Vec<Duration>,
+}
+
+impl LegTimer {
+    pub fn start(opportunity: &Opportunity) -> Self {
+        LegTimer {
+            detected_at: opportunity.detected_at,
+            first_order: None,
+            last_sent: None,
+            leg_gaps: Vec::new(),
+        }
+    }
+
+    // Call as each leg's order goes out
+    pub fn leg_sent(&mut self) {
+        let now = Instant::now();
+        match self.last_sent {
+            Some(last) => self.leg_gaps.push(now.duration_since(last)),
+            None => self.first_order = Some(SystemTime::now().duration_since(self.detected_at).unwrap_or_default()),
+        }
+        self.last_sent = Some(now);
+    }
+
+    pub fn first_order(&self) -> Option<Duration> {
+        self.first_order
+    }
+
+    // Slowest hand-off between consecutive legs
+    pub fn worst_leg_gap(&self) -> Duration {
+        self.leg_gaps.iter().copied().max().unwrap_or_default()
+    }
+}
+
+// Latencies of the cycles executed so far
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LatencySnapshot {
+    pub cycles: u64,
+    pub over_budget: u64,
+    pub mean_first_order: Duration,
+    pub worst_first_order: Duration,
+    pub worst_leg_gap: Duration,
+}
+
+#[derive(Default)]
+struct BudgetState {
+    consecutive: u32, // Cycles over budget since the last one within it or the last trip
+    total_first_order: Duration,
+    snapshot: LatencySnapshot,
+}
+
+// Latency budget shared by every executor: each cycle they send is timed from detection
+// to its first order and between its legs, and once `breaches` cycles in a row go over,
+// the kill switch trips. The engine then stops executing and keeps reporting, as a slow
+// path turns the edge it detects into slippage. Clones share state.
+#[derive(Clone)]
+pub struct LatencyBudget {
+    config: Arc<LatencyBudgetConfig>,
+    kill_switch: RiskManager,
+    state: Arc<Mutex<BudgetState>>,
+}
+
+impl fmt::Debug for LatencyBudget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LatencyBudget").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl LatencyBudget {
+    pub fn new(config: LatencyBudgetConfig, kill_switch: RiskManager) -> Self {
+        LatencyBudget {
+            config: Arc::new(config),
+            kill_switch,
+            state: Arc::default(),
+        }
+    }
+
+    // Checks a cycle whose orders have all gone out, or as many as did before it stopped
+    pub fn record(&self, cycle: &str, timer: &LegTimer) {
+        let Some(first_order) = timer.first_order() else {
+            return;
+        };
+        let leg_gap = timer.worst_leg_gap();
+        let over = |latency: Duration, max: Duration| !max.is_zero() && latency > max;
+        let breached = over(first_order, self.config.max_first_order) || over(leg_gap, self.config.max_leg_gap);
+
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        state.total_first_order += first_order;
+        let snapshot = &mut state.snapshot;
+        snapshot.cycles += 1;
+        snapshot.worst_first_order = snapshot.worst_first_order.max(first_order);
+        snapshot.worst_leg_gap = snapshot.worst_leg_gap.max(leg_gap);
+        if !breached {
+            state.consecutive = 0;
+            return;
+        }
+        snapshot.over_budget += 1;
+        state.consecutive += 1;
+        let (first_ms, gap_ms) = (first_order.as_secs_f64() * 1_000.0, leg_gap.as_secs_f64() * 1_000.0);
+        tracing::warn!(%cycle, first_order_ms = first_ms, leg_gap_ms = gap_ms, in_a_row = state.consecutive, "Cycle over its latency budget");
+        if state.consecutive >= self.config.breaches.max(1) {
+            // Starts over, so a reset switch needs a fresh run of slow cycles to trip again
+            state.consecutive = 0;
+            self.kill_switch.trip(&format!(
+                "{} cycles in a row over the latency budget, the last {:.1} ms to its first order and {:.1} ms between legs",
+                self.config.breaches.max(1),
+                first_ms,
+                gap_ms
+            ));
+        }
+    }
+
+    pub fn snapshot(&self) -> LatencySnapshot {
+        let state = self.state.lock().unwrap();
+        let mut snapshot = state.snapshot;
+        if snapshot.cycles > 0 {
+            snapshot.mean_first_order = state.total_first_order.div_f64(snapshot.cycles as f64);
+        }
+        snapshot
+    }
+}
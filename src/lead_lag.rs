@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::events::{MarketEvent, Opportunity, Signal};
+use crate::graph::Graph;
+use crate::perp;
+use crate::strategy::Strategy;
+use crate::ticker::book_top;
+
+// Weight of the newest lag in a leader's moving average
+const LAG_ALPHA: f64 = 0.1;
+
+/// Settings for [`LeadLagStrategy`].
+#[derive(Debug, Clone)]
+pub struct LeadLagConfig {
+    /// A venue's price moving this many basis points from where it last moved counts as a
+    /// move.
+    pub threshold_bps: f64,
+    /// How soon after a move the same move elsewhere counts as following it.
+    pub window: Duration,
+    /// Followed moves a venue pair needs before either side can be called the leader.
+    pub min_samples: u64,
+    /// Share of a venue pair's followed moves the leader must have made first, 0.5..1.
+    pub min_lead_ratio: f64,
+    /// Venue name for [`MarketEvent::Tickers`], which don't carry one.
+    pub ticker_venue: String,
+}
+
+impl Default for LeadLagConfig {
+    fn default() -> Self {
+        LeadLagConfig {
+            threshold_bps: 5.0,
+            window: Duration::from_secs(2),
+            min_samples: 20,
+            min_lead_ratio: 0.7,
+            ticker_venue: "binance".to_string(),
+        }
+    }
+}
+
+// One venue's price of one pair
+#[derive(Debug)]
+struct VenuePrice {
+    price: f64,
+    anchor: f64,                            // Price at its last move
+    last_move: Option<(f64, Instant, bool)>, // Direction, when, and whether another venue has followed it
+}
+
+// Who moved first between two venues quoting the same pair, in the order they were met
+#[derive(Debug, Default)]
+struct PairStats {
+    leads: [u64; 2],
+    lag_ms: [f64; 2],      // Moving average of how far behind the other venue followed
+    leader: Option<usize>, // Established once both `min_samples` and `min_lead_ratio` are met
+}
+
+impl PairStats {
+    fn samples(&self) -> u64 {
+        self.leads[0] + self.leads[1]
+    }
+
+    fn ratio(&self, side: usize) -> f64 {
+        self.leads[side] as f64 / self.samples().max(1) as f64
+    }
+}
+
+/// Latency arbitrage detector across venues quoting the same pair: Binance spot tickers,
+/// other venues' [`MarketEvent::Quotes`] and USD-M mark prices alike.
+///
+/// A venue's price moving `threshold_bps` away from where it last moved is a move; the same
+/// move on another venue within `window` follows it. Each venue pair keeps count of who
+/// moved first and how long the other took. Once one side has led `min_lead_ratio` of at
+/// least `min_samples` followed moves it is the leader, announced by a `lead_lag_stats`
+/// [`Signal`] valued at its mean lead in milliseconds (and again if leadership changes).
+/// From then on, every move the leader makes first emits a `lead_lag` signal valued at
+/// the move in basis points, naming the pair on the leader then the follower as
+/// `<symbol>@<venue>`: the follower is expected to make the same move, so positive means
+/// it should rise. No cycles are reported.
+pub struct LeadLagStrategy {
+    config: LeadLagConfig,
+    prices: HashMap<String, HashMap<String, VenuePrice>>, // Symbol -> venue -> price
+    pairs: HashMap<(String, String, String), PairStats>,  // (symbol, venue, venue), venues in order
+    signals: Vec<Signal>,
+}
+
+impl LeadLagStrategy {
+    pub fn new(config: LeadLagConfig) -> Self {
+        LeadLagStrategy {
+            config,
+            prices: HashMap::new(),
+            pairs: HashMap::new(),
+            signals: Vec::new(),
+        }
+    }
+
+    fn update(&mut self, symbol: String, venue: &str, price: f64, now: Instant) {
+        if !price.is_finite() || price <= 0.0 {
+            return;
+        }
+        let venues = self.prices.entry(symbol.clone()).or_default();
+        let quote = venues.entry(venue.to_string()).or_insert(VenuePrice { price, anchor: price, last_move: None });
+        quote.price = price;
+        let moved_bps = (price / quote.anchor - 1.0) * 10_000.0;
+        if moved_bps.abs() < self.config.threshold_bps {
+            return;
+        }
+        quote.anchor = price;
+        let direction = moved_bps.signum();
+        // Whoever made the same move lately led this one
+        let mut followed = Vec::new();
+        for (other, quote) in venues.iter_mut().filter(|(other, _)| *other != venue) {
+            if let Some((other_direction, at, matched @ false)) = quote.last_move.as_mut() {
+                let lag = now.saturating_duration_since(*at);
+                if *other_direction == direction && lag <= self.config.window {
+                    *matched = true;
+                    followed.push((other.clone(), lag));
+                }
+            }
+        }
+        let led = followed.is_empty();
+        if let Some(quote) = venues.get_mut(venue) {
+            quote.last_move = Some((direction, now, !led));
+        }
+        for (leader, lag) in followed {
+            self.record_lead(&symbol, &leader, venue, lag);
+        }
+        if led {
+            self.signal_followers(&symbol, venue, moved_bps);
+        }
+    }
+
+    fn record_lead(&mut self, symbol: &str, leader: &str, follower: &str, lag: Duration) {
+        let (key, side) = pair_key(symbol, leader, follower);
+        let venues = [key.1.clone(), key.2.clone()];
+        let stats = self.pairs.entry(key).or_default();
+        let lag_ms = lag.as_secs_f64() * 1_000.0;
+        stats.lag_ms[side] = if stats.leads[side] == 0 { lag_ms } else { stats.lag_ms[side] + LAG_ALPHA * (lag_ms - stats.lag_ms[side]) };
+        stats.leads[side] += 1;
+        let established = (0..2).find(|&side| {
+            stats.samples() >= self.config.min_samples && stats.ratio(side) >= self.config.min_lead_ratio.max(0.5)
+        });
+        let Some(side) = established.filter(|&side| stats.leader != Some(side)) else {
+            return;
+        };
+        stats.leader = Some(side);
+        let instruments = vec![format!("{}@{}", symbol, venues[side]), format!("{}@{}", symbol, venues[1 - side])];
+        let signal = Signal::new("lead_lag_stats", instruments, stats.lag_ms[side])
+            .with_detail("lead_ratio", stats.ratio(side))
+            .with_detail("samples", stats.samples());
+        self.signals.push(signal);
+    }
+
+    // The leader just moved first, so the venues that follow it have yet to. Prices are
+    // compared move for move rather than level for level, as venues can differ by a
+    // steady premium (a perpetual's basis, a DEX's fee).
+    fn signal_followers(&mut self, symbol: &str, leader: &str, moved_bps: f64) {
+        let Some(venues) = self.prices.get(symbol) else {
+            return;
+        };
+        for (follower, quote) in venues.iter().filter(|(follower, _)| *follower != leader) {
+            let (key, side) = pair_key(symbol, leader, follower);
+            let Some(stats) = self.pairs.get(&key).filter(|stats| stats.leader == Some(side)) else {
+                continue;
+            };
+            let signal = Signal::new("lead_lag", vec![format!("{}@{}", symbol, leader), format!("{}@{}", symbol, follower)], moved_bps)
+                .with_detail("follower_price", quote.price)
+                .with_detail("lead_ratio", stats.ratio(side))
+                .with_detail("lag_ms", stats.lag_ms[side])
+                .with_detail("samples", stats.samples());
+            self.signals.push(signal);
+        }
+    }
+}
+
+// Key of a venue pair, and which side of it `leader` is
+fn pair_key(symbol: &str, leader: &str, follower: &str) -> ((String, String, String), usize) {
+    if leader < follower {
+        ((symbol.to_string(), leader.to_string(), follower.to_string()), 0)
+    } else {
+        ((symbol.to_string(), follower.to_string(), leader.to_string()), 1)
+    }
+}
+
+impl Strategy for LeadLagStrategy {
+    fn name(&self) -> &str {
+        "lead_lag"
+    }
+
+    fn on_update(&mut self, _graph: &Graph) -> Vec<Opportunity> {
+        Vec::new()
+    }
+
+    fn on_event(&mut self, _graph: &Graph, event: &MarketEvent) -> Vec<Opportunity> {
+        let now = Instant::now();
+        match event {
+            MarketEvent::Tickers(tickers) => {
+                let venue = self.config.ticker_venue.clone();
+                for ticker in tickers.iter().filter(|t| !t.trade) {
+                    let price = match book_top(ticker) {
+                        Some(((bid, _), (ask, _))) => (bid + ask) / 2.0,
+                        None => ticker.c.parse().unwrap_or(f64::NAN),
+                    };
+                    self.update(ticker.s.clone(), &venue, price, now);
+                }
+            }
+            MarketEvent::Quotes(quotes) => {
+                for quote in quotes {
+                    self.update(format!("{}{}", quote.base, quote.quote), &quote.venue, (quote.bid + quote.ask) / 2.0, now);
+                }
+            }
+            MarketEvent::MarkPrices(marks) => {
+                for mark in marks {
+                    self.update(mark.symbol.clone(), perp::VENUE, mark.mark_price.parse().unwrap_or(f64::NAN), now);
+                }
+            }
+            MarketEvent::SymbolRemoved(symbol) => {
+                if let Some(venues) = self.prices.get_mut(symbol) {
+                    venues.remove(&self.config.ticker_venue);
+                }
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    fn take_signals(&mut self) -> Vec<Signal> {
+        std::mem::take(&mut self.signals)
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+use crate::order::Side;
+
+// An executed trade as reported by a venue; quantity is in base units, price in quote.
+// Amounts are written as decimal strings so they round-trip exactly; plain numbers are accepted.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+pub struct Fill {
+    pub time: DateTime<Utc>,
+    pub venue: String,
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    pub side: Side,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    #[serde(default)]
+    pub fee: Decimal,
+    #[serde(default)]
+    pub fee_asset: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_id: Option<String>,
+}
+
+impl Fill {
+    // Fees without a recorded asset were charged in quote
+    pub fn fee_asset_or_quote(&self) -> &str {
+        if self.fee_asset.is_empty() { &self.quote } else { &self.fee_asset }
+    }
+}
+
+// Append-only fill ledger, one JSON object per line
+pub struct FillLog {
+    writer: BufWriter<File>,
+}
+
+impl FillLog {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(FillLog {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    // Flushed per fill; losing fills on a crash would leave the books wrong
+    pub fn append(&mut self, fill: &Fill) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, fill)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+// Loads every fill in the ledger in file order
+pub fn load_fills(path: &Path) -> io::Result<Vec<Fill>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut fills = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fill = serde_json::from_str(&line)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, e)))?;
+        fills.push(fill);
+    }
+    Ok(fills)
+}
@@ -0,0 +1,181 @@
+//! Real-time cross-rate arbitrage detection for Binance spot markets.
+//!
+//! The stable API is what is re-exported from the crate root and [`prelude`]:
+//! an [`Engine`] pulls [`MarketEvent`]s from a [`Feed`], keeps the market [`Graph`]
+//! up to date, runs every [`Strategy`] after each update, hands opportunities and
+//! [`Signal`]s to an optional [`Executor`] and broadcasts [`EngineEvent`]s to subscribers.
+//!
+//! Modules marked hidden in the docs are implementation details used by the bundled
+//! binaries and may change in any release.
+
+mod basis;
+mod engine;
+mod events;
+mod executor;
+mod feed;
+mod lead_lag;
+mod spread;
+mod strategy;
+mod triangle;
+
+#[doc(hidden)]
+pub mod accounting;
+#[doc(hidden)]
+pub mod accounts;
+#[doc(hidden)]
+pub mod audit;
+#[doc(hidden)]
+pub mod batch;
+#[doc(hidden)]
+pub mod blend;
+#[doc(hidden)]
+pub mod book_stats;
+#[doc(hidden)]
+pub mod cbbo;
+#[doc(hidden)]
+pub mod circuit_breaker;
+#[doc(hidden)]
+pub mod clock;
+#[doc(hidden)]
+pub mod credentials;
+#[doc(hidden)]
+pub mod dedup;
+#[doc(hidden)]
+pub mod deflate;
+#[doc(hidden)]
+pub mod depeg;
+#[doc(hidden)]
+pub mod depth;
+#[doc(hidden)]
+pub mod failover;
+#[doc(hidden)]
+pub mod fallback;
+#[doc(hidden)]
+pub mod filters;
+#[doc(hidden)]
+pub mod fix;
+#[doc(hidden)]
+pub mod graph;
+#[doc(hidden)]
+pub mod graph_export;
+#[doc(hidden)]
+pub mod grpc;
+#[doc(hidden)]
+pub mod health;
+#[doc(hidden)]
+pub mod history;
+#[doc(hidden)]
+pub mod hedger;
+#[doc(hidden)]
+pub mod inventory;
+#[doc(hidden)]
+pub mod last_look;
+#[doc(hidden)]
+pub mod latency_budget;
+#[doc(hidden)]
+pub mod ledger;
+#[doc(hidden)]
+pub mod liquidity;
+#[doc(hidden)]
+pub mod logging;
+#[doc(hidden)]
+pub mod margin;
+#[doc(hidden)]
+pub mod merge;
+#[doc(hidden)]
+pub mod opportunity_stats;
+#[doc(hidden)]
+pub mod order;
+#[doc(hidden)]
+pub mod order_tracker;
+#[doc(hidden)]
+pub mod persistence;
+#[doc(hidden)]
+pub mod perp;
+#[doc(hidden)]
+pub mod pipeline;
+#[doc(hidden)]
+pub mod plugin;
+#[doc(hidden)]
+pub mod pnl;
+#[doc(hidden)]
+pub mod profile;
+#[doc(hidden)]
+pub mod proxy;
+#[doc(hidden)]
+pub mod recorder;
+#[doc(hidden)]
+pub mod rest;
+#[doc(hidden)]
+pub mod revalidate;
+#[doc(hidden)]
+pub mod risk;
+#[doc(hidden)]
+pub mod route;
+#[doc(hidden)]
+pub mod routing;
+#[doc(hidden)]
+pub mod schedule;
+#[doc(hidden)]
+pub mod sequence;
+#[doc(hidden)]
+pub mod shard;
+#[doc(hidden)]
+pub mod shared_graph;
+#[doc(hidden)]
+pub mod sim;
+#[doc(hidden)]
+pub mod sinks;
+#[doc(hidden)]
+pub mod slippage;
+#[doc(hidden)]
+pub mod snapshot;
+#[doc(hidden)]
+pub mod stable_edges;
+#[doc(hidden)]
+pub mod stream_server;
+#[doc(hidden)]
+pub mod subscription;
+#[doc(hidden)]
+pub mod supervisor;
+#[doc(hidden)]
+pub mod ticker;
+#[doc(hidden)]
+pub mod trace;
+#[doc(hidden)]
+pub mod trade_export;
+#[doc(hidden)]
+pub mod ttl;
+#[doc(hidden)]
+pub mod uniswap;
+#[doc(hidden)]
+pub mod unwind;
+#[doc(hidden)]
+pub mod user_stream;
+#[doc(hidden)]
+pub mod valuation;
+#[doc(hidden)]
+pub mod watchdog;
+
+pub use basis::{BasisConfig, BasisStrategy};
+pub use engine::{DetectionControl, Engine, RunStats, Shutdown};
+pub use events::{CycleTrace, EngineEvent, LatencyBreakdown, LegRoute, LegTrace, MarketEvent, Opportunity, OpportunitySummary, ReferenceProfit, Signal};
+pub use executor::Executor;
+pub use feed::{BinanceFeed, Feed};
+pub use graph::{Edge, Graph};
+pub use lead_lag::{LeadLagConfig, LeadLagStrategy};
+pub use order::{OrderRequest, Side};
+pub use perp::MarkPrice;
+pub use strategy::{NegativeCycleStrategy, Strategy};
+pub use spread::{SpreadConfig, SpreadStrategy};
+pub use subscription::SubscriptionCommand;
+pub use ticker::TickerData;
+pub use triangle::{TwoPhaseConfig, TwoPhaseStrategy};
+
+/// Glob-importable set of the types most embedders need.
+pub mod prelude {
+    pub use crate::{
+        BinanceFeed, Engine, EngineEvent, Executor, Feed, Graph, MarketEvent, NegativeCycleStrategy, Opportunity,
+        Signal, Strategy, TwoPhaseStrategy,
+    };
+}
@@ -0,0 +1,94 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::graph::{extract_currency_pair, Graph};
+use crate::ticker::TickerData;
+
+#[derive(Debug, Clone)]
+pub struct LiquidityConfig {
+    pub min_volume: f64,   // 24h volume a pair needs to stay in the graph, in `reference`
+    pub reference: String, // Asset volumes are compared in
+}
+
+impl LiquidityConfig {
+    pub fn new(min_volume: f64) -> Self {
+        LiquidityConfig {
+            min_volume,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for LiquidityConfig {
+    fn default() -> Self {
+        LiquidityConfig {
+            min_volume: 0.0,
+            reference: "USDT".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Liquidity {
+    Liquid,
+    Dropped { newly: bool }, // Below the floor; `newly` the first time since it was last liquid
+}
+
+// Keeps pairs trading less than a 24h volume floor out of the graph. Volumes come from the
+// `q` field of 24h tickers and are valued in the reference asset at the graph's rates;
+// updates without one (book tickers, trades, REST polls) go by the pair's last known
+// volume. A pair whose volume is unknown, or can't be valued yet, is let through.
+pub struct LiquidityFilter {
+    config: LiquidityConfig,
+    volumes: HashMap<String, f64>, // Last 24h volume by symbol, in its quote asset
+    dropped: HashSet<String>,
+}
+
+impl LiquidityFilter {
+    pub fn new(config: LiquidityConfig) -> Self {
+        LiquidityFilter {
+            config,
+            volumes: HashMap::new(),
+            dropped: HashSet::new(),
+        }
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped.len()
+    }
+
+    pub fn check(&mut self, ticker: &TickerData, graph: &Graph) -> Liquidity {
+        if let Some(volume) = ticker.quote_volume.as_deref().and_then(|v| v.parse::<f64>().ok()) {
+            match self.volumes.get_mut(&ticker.s) {
+                Some(known) => *known = volume,
+                None => {
+                    self.volumes.insert(ticker.s.clone(), volume);
+                }
+            }
+        }
+        let Some(&volume) = self.volumes.get(&ticker.s) else {
+            return Liquidity::Liquid;
+        };
+        let (_, quote) = extract_currency_pair(&ticker.s);
+        let value = if quote == self.config.reference {
+            Some(volume)
+        } else {
+            graph.rate(&quote, &self.config.reference).map(|rate| volume * rate)
+        };
+        match value {
+            Some(value) if value < self.config.min_volume => {
+                let newly = !self.dropped.contains(&ticker.s);
+                if newly {
+                    self.dropped.insert(ticker.s.clone());
+                    tracing::debug!(symbol = %ticker.s, volume = value, "Pair below the volume floor, dropped");
+                }
+                Liquidity::Dropped { newly }
+            }
+            _ => {
+                if self.dropped.remove(&ticker.s) {
+                    tracing::debug!(symbol = %ticker.s, "Pair back above the volume floor");
+                }
+                Liquidity::Liquid
+            }
+        }
+    }
+}
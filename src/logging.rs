@@ -0,0 +1,16 @@
+use tracing_subscriber::EnvFilter;
+
+// Installs the global subscriber. RUST_LOG takes precedence over `level` so individual
+// modules can be tuned (e.g. RUST_LOG=info,hft3::feed=debug). Logs go to stderr.
+pub fn init(level: &str, json: bool) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter).with_writer(std::io::stderr);
+    let result = if json {
+        builder.json().with_current_span(true).try_init()
+    } else {
+        builder.try_init()
+    };
+    if let Err(e) = result {
+        eprintln!("Failed to initialise logging: {}", e);
+    }
+}
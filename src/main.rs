@@ -1,174 +1,1020 @@
-use std::collections::{HashMap, HashSet};
-use std::f64::consts::E;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message, WebSocketStream};
-use tokio::net::TcpStream;
-use url::Url;
-use futures_util::stream::StreamExt;
-
-// Helper function to extract currency pair from a symbol like "BTCUSDT"
-fn extract_currency_pair(symbol: &str) -> (String, String) {
-    let base = &symbol[0..3];
-    let quote = &symbol[3..];
-    (base.to_string(), quote.to_string())
+use std::sync::{Arc, Mutex, RwLock};
+
+use rust_decimal::Decimal;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+
+use hft3::prelude::*;
+use hft3::accounts::MultiAccountExecutor;
+use hft3::audit::AuditLog;
+use hft3::book_stats::{BookGuard, BookMetrics};
+use hft3::clock::ServerClock;
+use hft3::dedup::DedupConfig;
+use hft3::filters::{ExchangeFilters, SymbolFilters};
+use hft3::fix::{FixExecutor, FixSession};
+use hft3::grpc::GrpcService;
+use hft3::health::{self, HealthMetrics, StatusSources};
+use hft3::hedger::{HedgeStrategy, Hedger};
+use hft3::inventory::Inventory;
+use hft3::last_look::LastLookGuard;
+use hft3::latency_budget::LatencyBudget;
+use hft3::logging;
+use hft3::margin::{MarginConfig, MarginExecutor};
+use hft3::merge::MergedFeed;
+use hft3::opportunity_stats::OpportunityStats;
+use hft3::order_tracker::{ExecutionMonitor, TrackerConfig};
+use hft3::perp::{self, FuturesFeed};
+use hft3::pipeline::{PipelineFeed, PipelineMetrics};
+use hft3::plugin::SubprocessStrategy;
+use hft3::pnl::PnlTracker;
+use hft3::recorder::{Recorder, ReplayFeed};
+use hft3::rest::{ApiCredentials, BinanceEndpoints, RateLimits, RestClient};
+use hft3::revalidate::Revalidator;
+use hft3::risk::{RiskConfig, RiskManager};
+use hft3::route::FeeTable;
+use hft3::schedule::Schedule;
+use hft3::sim::{self, SimFeed};
+use hft3::sinks::jsonl::JsonlSink;
+use hft3::sinks::kafka::KafkaSink;
+use hft3::sinks::log::LogSink;
+use hft3::sinks::nats::NatsSink;
+use hft3::sinks::redis::RedisSink;
+use hft3::sinks::sqlite::SqliteSink;
+use hft3::sinks::telegram::{TelegramConfig, TelegramSink};
+use hft3::sinks::webhook::WebhookSink;
+use hft3::sinks;
+use hft3::stream_server::{self, StreamServer, StreamServerConfig};
+use hft3::subscription;
+use hft3::supervisor::Supervisor;
+use hft3::trace::CycleTracer;
+use hft3::uniswap::{self, UniswapFeed};
+use hft3::user_stream::{AccountEvent, AccountState, UserStream};
+use hft3::{BasisStrategy, LeadLagStrategy, Shutdown, SpreadStrategy, SubscriptionCommand, TwoPhaseConfig};
+
+mod cli;
+mod commands;
+mod dashboard;
+mod web;
+
+use cli::{Args, OutputMode, RISK_REFERENCE};
+
+// How long sinks get to write out queued events once the engine has stopped
+const OUTPUT_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+// Cycles and assets named in the periodic opportunity statistics log
+const LOGGED_TOP_CYCLES: usize = 5;
+
+// Reads operator commands ("subscribe <stream>...", "unsubscribe <stream>...") from stdin
+async fn read_subscription_commands(commands: mpsc::UnboundedSender<SubscriptionCommand>) {
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        match subscription::parse_command(&line) {
+            Some(command) => {
+                if commands.send(command).is_err() {
+                    break;
+                }
+            }
+            None => tracing::warn!(command = line.trim(), "Unknown command"),
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // Subcommands run once and exit; anything else starts the live bot
+    let subcommand = std::env::args().nth(1);
+    if let Some(name @ ("route" | "accounting" | "bench" | "replay-diff" | "credentials" | "download" | "export")) = subcommand.as_deref() {
+        logging::init("warn", false);
+        let raw_args: Vec<String> = std::env::args().skip(2).collect();
+        let result = match name {
+            "route" => commands::route::run(raw_args).await,
+            "bench" => commands::bench::run(raw_args),
+            "replay-diff" => commands::replay_diff::run(raw_args),
+            "credentials" => commands::credentials::run(raw_args),
+            "download" => commands::download::run(raw_args).await,
+            "export" => commands::export::run(raw_args),
+            _ => commands::accounting::run(raw_args),
+        };
+        if let Err(e) = result {
+            eprintln!("{}: {}", name, e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let args = match Args::parse() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("hft3: {}", e);
+            std::process::exit(1);
+        }
+    };
+    // Only errors under the dashboard, so log lines don't scribble over it
+    logging::init(if args.tui { "error" } else { &args.log_level }, args.log_json);
+    if let Some(profile) = &args.profile {
+        tracing::info!(%profile, "Using configuration profile");
+    }
+    // Restarts tasks that panic; /status shows them when the health endpoint is on
+    let mut supervisor = Supervisor::default();
+    if args.health_addr.is_some() {
+        supervisor = supervisor.with_health(Arc::new(HealthMetrics::default()));
+    }
+
+    if let Some(path) = &args.replay_path {
+        let feed = ReplayFeed::open(path).expect("Failed to load recorded session");
+        tracing::info!(path = %path.display(), "Replaying recorded session");
+        run(feed, args, supervisor, Live::default()).await;
+        return;
+    }
+    if let Some(path) = &args.script_path {
+        let feed = SimFeed::load(path).expect("Failed to load simulation script");
+        tracing::info!(path = %path.display(), "Playing simulation script");
+        run(feed, args, supervisor, Live::default()).await;
+        return;
+    }
+    if let Some(path) = &args.walk_path {
+        let snapshot = sim::snapshot(path).expect("Failed to load random walk snapshot");
+        tracing::info!(path = %path.display(), symbols = snapshot.len(), seed = args.sim.seed, steps = args.sim.steps, "Playing a random walk");
+        let feed = SimFeed::random_walk(snapshot, args.sim.clone());
+        run(feed, args, supervisor, Live::default()).await;
+        return;
+    }
+
+    let endpoints = if args.testnet {
+        tracing::info!("Using the Binance spot testnet");
+        BinanceEndpoints::testnet()
+    } else {
+        BinanceEndpoints::live()
+    };
+
+    let proxy = args.proxy.for_venue("binance").cloned();
+    if let Some(proxy) = &proxy {
+        tracing::info!(%proxy, "Connecting to Binance through a proxy");
+    }
+
+    // Connect to the combined WebSocket stream
+    let mut initial_streams = vec!["!ticker@arr".to_string()];
+    initial_streams.extend(args.agg_trades.iter().map(|symbol| format!("{}@aggTrade", symbol)));
+    initial_streams.extend(args.depth_streams.iter().map(|symbol| format!("{}@depth20@100ms", symbol)));
+    let mut feed = match args.failover.clone() {
+        Some(mut config) => {
+            if config.endpoints.is_empty() {
+                config.endpoints = endpoints.market_ws_mirrors.clone();
+            }
+            BinanceFeed::connect_fastest(&initial_streams, proxy.as_ref(), config).await
+        }
+        None => BinanceFeed::connect_via(&endpoints.market_ws, &initial_streams, proxy.as_ref()).await,
+    }
+    .expect("Failed to connect to Binance WebSocket");
+    tracing::info!("Connected to the Binance WebSocket server");
+    if let Some(max_silence) = args.max_silence {
+        feed = feed.with_watchdog(max_silence);
+    }
+    if let Some(path) = &args.record_path {
+        feed = feed.with_recorder(Recorder::create(path).expect("Failed to create recording file"));
+    }
+    // Signed requests and feed lag go by the exchange's clock rather than ours
+    let clock = args.time_sync.map(|_| Arc::new(ServerClock::default()));
+    let mut rest = RestClient::new(&endpoints.rest, RateLimits::default()).with_proxy(proxy.as_ref());
+    if let Some(clock) = &clock {
+        rest = rest.with_clock(clock.clone());
+    }
+    let rest = Arc::new(rest);
+    if let (Some(clock), Some(interval)) = (&clock, args.time_sync) {
+        match clock.sync(&rest).await {
+            Ok(offset_ms) => tracing::info!(offset_ms, "Synced with the exchange clock"),
+            Err(e) => tracing::warn!(error = %e, "Failed to sync with the exchange clock, using the local one"),
+        }
+        clock.spawn_sync(rest.clone(), interval, &supervisor);
+    }
+    if args.rest_snapshot {
+        // Prices for every symbol up front rather than as each one first ticks
+        match feed.bootstrap(&rest).await {
+            Ok(symbols) => tracing::info!(symbols, "Loaded the REST book ticker snapshot"),
+            Err(e) => tracing::warn!(error = %e, "Failed to load the REST book ticker snapshot"),
+        }
+    }
+    if let Some(config) = args.rest_fallback.clone() {
+        feed = feed.with_rest_fallback(rest.clone(), config);
+    }
+    // The dashboard owns the terminal's input
+    if !args.tui {
+        tokio::spawn(read_subscription_commands(feed.commands()));
+    }
+
+    // Reading and parsing run on their own task so a slow detection pass can't stall the socket
+    let mut futures_streams = args.futures_streams.clone();
+    if args.basis.is_some() && !futures_streams.iter().any(|s| s == perp::MARK_PRICE_STREAM) {
+        futures_streams.push(perp::MARK_PRICE_STREAM.to_string());
+    }
+    let futures = if futures_streams.is_empty() {
+        None
+    } else {
+        let mut futures = FuturesFeed::connect(&endpoints.usdm_ws, &futures_streams, proxy.clone())
+            .await
+            .expect("Failed to connect to the USD-M futures WebSocket");
+        if let Some(max_silence) = args.max_silence {
+            futures = futures.with_watchdog(max_silence);
+        }
+        tracing::info!(streams = ?futures_streams, "Connected to the USD-M futures WebSocket");
+        Some(futures)
+    };
+    let uniswap = match args.uniswap.clone() {
+        Some(config) => Some(
+            UniswapFeed::spawn(config, args.proxy.for_venue(uniswap::VENUE))
+                .await
+                .expect("Failed to read the Uniswap pools"),
+        ),
+        None => None,
+    };
+    let queue = args.queue.clone();
+    let feed = match (futures, uniswap) {
+        (None, None) => PipelineFeed::spawn(feed, queue, &supervisor),
+        (Some(futures), None) => PipelineFeed::spawn(MergedFeed::new(feed, futures), queue, &supervisor),
+        (None, Some(uniswap)) => PipelineFeed::spawn(MergedFeed::new(feed, uniswap), queue, &supervisor),
+        (Some(futures), Some(uniswap)) => PipelineFeed::spawn(MergedFeed::new(MergedFeed::new(feed, futures), uniswap), queue, &supervisor),
+    };
+    let metrics = feed.metrics();
+    let pipeline = metrics.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            log_pipeline(&metrics);
+        }
+    });
+
+    // API keys, required by the user data stream; without it they only let FIX orders be
+    // polled over REST, so there they're optional
+    let credentials = match (args.user_stream, args.fix.is_some()) {
+        (true, _) => Some(
+            args.credentials
+                .load(&endpoints)
+                .unwrap_or_else(|e| panic!("--user-stream needs API keys from {}: {}", args.credentials, e)),
+        ),
+        (false, true) => args
+            .credentials
+            .load(&endpoints)
+            .map_err(|e| tracing::info!(error = %e, "No API keys, FIX orders won't be polled over REST"))
+            .ok(),
+        (false, false) => None,
+    };
+    // Live balances and order states from the account's user data stream
+    let user_stream = args.user_stream.then(|| {
+        let credentials = credentials.clone().expect("API keys loaded for --user-stream");
+        let stream = UserStream::spawn(rest.clone(), credentials, &endpoints.user_ws, proxy.clone(), &supervisor);
+        log_balances("default", stream.account());
+        stream
+    });
+
+    // Further accounts executing cycles, each with its own keys, rate limits and balances
+    let mut accounts = Vec::new();
+    for account in &args.accounts {
+        let credentials = account
+            .credentials
+            .load(&endpoints)
+            .unwrap_or_else(|e| panic!("--account {} needs API keys from {}: {}", account.name, account.credentials, e));
+        let mut client = RestClient::new(&endpoints.rest, RateLimits::default()).with_proxy(proxy.as_ref());
+        if let Some(clock) = &clock {
+            client = client.with_clock(clock.clone());
+        }
+        let client = Arc::new(client);
+        let stream = UserStream::spawn(client.clone(), credentials.clone(), &endpoints.user_ws, proxy.clone(), &supervisor);
+        log_balances(&account.name, stream.account());
+        tracing::info!(account = %account.name, "Starting execution account");
+        accounts.push(ExecutionAccount { name: account.name.clone(), client, credentials, stream });
+    }
+    let account_filters = match accounts.is_empty() {
+        false => Some(ExchangeFilters::fetch(&rest).await.expect("Failed to load exchange filters for --account")),
+        true => None,
+    };
+
+    // Orders to, and drop-copy executions from, an institutional OMS
+    let fix = match args.fix.clone() {
+        Some(config) => {
+            let filters = match &args.fix_cycles {
+                Some(_) => Some(ExchangeFilters::fetch(&rest).await.expect("Failed to load exchange filters for --fix-notional")),
+                None => None,
+            };
+            let revalidator = args.revalidate.clone().map(|config| Revalidator::new(config, Some(rest.clone())));
+            tracing::info!(addr = %config.addr, sender = %config.sender_comp_id, target = %config.target_comp_id, "Starting FIX session");
+            Some(FixRoute {
+                session: FixSession::spawn(config),
+                filters,
+                revalidator,
+                rest: credentials.clone().map(|credentials| (rest.clone(), credentials)),
+            })
+        }
+        None => None,
+    };
+    // Lot and tick sizes, so traces round quantities and prices like real orders would
+    let trace_filters = match &args.trace {
+        Some(_) => ExchangeFilters::fetch(&rest)
+            .await
+            .map_err(|e| tracing::warn!(error = %e, "Failed to load exchange filters, traces won't round orders"))
+            .ok(),
+        None => None,
+    };
+    // Lot size of the hedge pair, so hedges go out in whole lots
+    let hedge_filters = match &args.hedge {
+        Some(hedge) => {
+            let filters = ExchangeFilters::fetch(&rest).await.expect("Failed to load exchange filters for --hedge-pair");
+            let pair = filters.get(&hedge.pair).cloned();
+            Some(pair.unwrap_or_else(|| panic!("--hedge-pair {} is not listed on the exchange", hedge.pair)))
+        }
+        None => None,
+    };
+    let live = Live {
+        pipeline: Some(pipeline),
+        user_stream,
+        accounts,
+        account_filters,
+        fix,
+        clock,
+        trace_filters,
+        hedge_filters,
+    };
+    run(feed, args, supervisor, live).await;
 }
 
-// TickerData struct corresponding to Binance ticker format
-#[derive(serde::Deserialize, Debug)]
-struct TickerData {
-    s: String, // Symbol
-    c: String, // Last price as a string to handle precision
-    // You can add more fields if needed
+// What a live run has set up before the engine starts; replays and simulations have none of it
+#[derive(Default)]
+struct Live {
+    pipeline: Option<Arc<PipelineMetrics>>,
+    user_stream: Option<UserStream>,
+    accounts: Vec<ExecutionAccount>,
+    account_filters: Option<ExchangeFilters>,
+    fix: Option<FixRoute>,
+    clock: Option<Arc<ServerClock>>,
+    trace_filters: Option<ExchangeFilters>,
+    hedge_filters: Option<SymbolFilters>,
 }
 
-struct Edge {
-    start: String,
-    end: String,
-    rate: f64,
+// --fix: the session and what its executor checks and sizes cycles with
+struct FixRoute {
+    session: FixSession,
+    filters: Option<ExchangeFilters>,
+    revalidator: Option<Revalidator>,
+    rest: Option<(Arc<RestClient>, ApiCredentials)>, // Polls orders the drop copy is silent on
 }
 
-struct Graph {
-    edges: Vec<Edge>,
-    vertices: HashSet<String>,
+// An --account: its own REST client (and so its own rate limits) and user data stream
+struct ExecutionAccount {
+    name: String,
+    client: Arc<RestClient>,
+    credentials: ApiCredentials,
+    stream: UserStream,
 }
 
-impl Graph {
-    fn new() -> Self {
-        Graph {
-            edges: Vec::new(),
-            vertices: HashSet::new(),
-        }
-    }
-
-    fn add_edge(&mut self, start: String, end: String, rate: f64) {
-        self.vertices.insert(start.clone());
-        self.vertices.insert(end.clone());
-        self.edges.push(Edge { start, end, rate });
-    }
-
-    fn update_edge(&mut self, start: &str, end: &str, rate: f64) {
-        if let Some(edge) = self.edges.iter_mut().find(|e| &e.start == start && &e.end == end) {
-            edge.rate = rate;
-        }
-    }
-
-    fn find_arbitrage(&self) -> Option<Vec<String>> {
-        let mut distances = HashMap::new();
-        let mut predecessors = HashMap::new();
-    
-        // Initialize distances to infinity, and set the distance to a starting node to 0
-        let start_vertex = self.vertices.iter().next()?.clone();
-        for vertex in &self.vertices {
-            distances.insert(vertex.clone(), f64::INFINITY);
-            predecessors.insert(vertex.clone(), None);
-        }
-        distances.insert(start_vertex.clone(), 0.0);
-    
-        // Relax edges repeatedly
-        for _ in 1..self.vertices.len() {
-            for edge in &self.edges {
-                // Compute the new distance considering the logarithm of the edge rate
-                let weight = -edge.rate.log(E);
-                let new_dist = distances[&edge.start] + weight;
-                
-                // Check for overflow/underflow or any other arithmetic issues
-                if new_dist.is_finite() && new_dist < distances[&edge.end] {
-                    distances.insert(edge.end.clone(), new_dist);
-                    predecessors.insert(edge.end.clone(), Some(edge.start.clone()));
+// Builds the engine and its outputs from the command line and runs it until the feed ends
+async fn run<F: Feed>(feed: F, args: Args, supervisor: Supervisor, live: Live) {
+    let Live { pipeline, user_stream, accounts, account_filters, fix, clock, trace_filters, hedge_filters } = live;
+    let mut engine = Engine::new(feed)
+        // DEX prices only reach the graph through the consolidated book, and routing picks from its venues
+        .with_cbbo_detection(args.cbbo || args.uniswap.is_some() || args.routing.is_some())
+        .with_change_epsilon(args.change_epsilon_bps)
+        .with_max_cycle_len(args.max_cycle_len)
+        .with_home_assets(args.home_assets.clone());
+    if let Some(clock) = clock {
+        engine = engine.with_clock(clock);
+    }
+    // Book shape per symbol, for /status and for cycle planners to check legs against
+    let books = (args.book_stats || args.book_limits.is_some()).then(|| Arc::new(BookMetrics::default()));
+    if let Some(books) = &books {
+        engine = engine.with_book_metrics(books.clone());
+    }
+    let book_guard = args.book_limits.clone().zip(books.clone()).map(|(limits, metrics)| BookGuard::new(metrics, limits));
+    // Pre-trade limits, fed the accounts' fills and realized PnL below; the latency budget
+    // shares its kill switch
+    let risk = (args.risk.is_some() || args.latency.is_some()).then(|| {
+        let config = args.risk.clone().unwrap_or_else(|| RiskConfig::new(RISK_REFERENCE, Decimal::ZERO));
+        RiskManager::new(config)
+    });
+    if let Some(risk) = &risk {
+        engine = engine.with_risk(risk.clone());
+        if args.risk.is_some() {
+            let risk = risk.clone();
+            tokio::spawn(async move {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                loop {
+                    interval.tick().await;
+                    let (approved, rejected) = risk.metrics().snapshot();
+                    tracing::info!(approved, rejected, daily_pnl = risk.daily_pnl(), "Risk limits");
                 }
+            });
+        }
+    }
+    // Cycles too slow to get their orders out trip the kill switch, leaving the engine alert-only
+    let latency = args.latency.clone().zip(risk.clone()).map(|(config, kill_switch)| {
+        let budget = LatencyBudget::new(config, kill_switch);
+        log_latency(budget.clone());
+        budget
+    });
+    // Both executors re-check a cycle's final leg against the live graph before sending it
+    let last_look = args.last_look.clone().map(|config| {
+        let mut guard = LastLookGuard::new(config);
+        if let Some(path) = &args.last_look_log {
+            guard = guard.with_abort_log(path).expect("Failed to open last-look log");
+        }
+        let metrics = guard.metrics();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let (passed, aborted) = metrics.snapshot();
+                tracing::info!(passed, aborted, "Last look on final legs");
             }
+        });
+        guard
+    });
+    if let Some(metrics) = supervisor.health() {
+        engine = engine.with_health(metrics);
+    }
+    let audit = args.audit_path.as_ref().map(|path| {
+        tracing::info!(path = %path.display(), "Writing audit log");
+        AuditLog::open(path).expect("Failed to open audit log")
+    });
+    if let Some(log) = audit.clone() {
+        engine = engine.with_audit_log(log);
+    }
+    if let Some(config) = args.trace.clone() {
+        let mut tracer = CycleTracer::new(config);
+        if let Some(filters) = trace_filters {
+            tracer = tracer.with_filters(filters);
+        }
+        engine = engine.with_cycle_trace(tracer);
+    }
+    if let Some(config) = args.valuation.clone() {
+        engine = engine.with_profit_valuation(config);
+    }
+    if let Some(weight) = args.trade_blend {
+        engine = engine.with_trade_blend(weight);
+    }
+    if let Some(config) = args.depth.clone() {
+        engine = engine.with_depth_pricing(config);
+    }
+    if let Some(config) = args.price_guard.clone() {
+        engine = engine.with_price_guard(config);
+    }
+    if let Some(config) = args.liquidity.clone() {
+        engine = engine.with_min_volume(config);
+    }
+    if let Some(config) = args.dedup.clone() {
+        engine = engine.with_dedup(config);
+    }
+    if let Some(config) = args.ttl.clone() {
+        engine = engine.with_opportunity_ttl(config);
+    }
+    if let Some(config) = args.depeg.clone() {
+        engine = engine.with_depeg_monitor(config);
+    }
+    if let Some(config) = args.stable_edges.clone() {
+        engine = engine.with_stable_edges(config);
+    }
+    if let Some(mut config) = args.routing.clone() {
+        if let Some(path) = &args.fees_path {
+            config.fees = FeeTable::load(path).expect("Failed to load fee table");
         }
-    
-        // Check for negative-weight cycles
-        for edge in &self.edges {
-            let weight = -edge.rate.log(E);
-            let new_dist = distances[&edge.start] + weight;
-            
-            if new_dist.is_finite() && new_dist < distances[&edge.end] {
-                // We found a cycle, now reconstruct the path
-                let mut cycle = vec![edge.end.clone()];
-                let mut last = edge.end.clone();
-                while let Some(pred) = predecessors[&last].clone() {
-                    if cycle.contains(&pred) {
-                        cycle.push(pred);
-                        cycle.reverse();
-                        return Some(cycle); // Return the cycle representing the arbitrage opportunity
+        engine = engine.with_smart_routing(config);
+    }
+    if let Some(workers) = args.shards {
+        engine = engine.with_shards(workers);
+    }
+    if let Some(config) = args.throttle.clone() {
+        engine = engine.with_throttle(config);
+    }
+    if let Some(max_age) = args.max_edge_age {
+        engine = engine.with_max_edge_age(max_age);
+    }
+    if let Some(config) = args.sequence.clone() {
+        engine = engine.with_sequence_check(config);
+    }
+    if let Some(metrics) = engine.sequence_metrics() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let (out_of_order, gaps) = metrics.snapshot();
+                tracing::info!(out_of_order, gaps, "Feed sequence check");
+            }
+        });
+    }
+    if let Some(config) = args.persistence.clone() {
+        engine = engine.with_min_time_in_profit(config);
+    }
+    if let Some(metrics) = engine.persistence_metrics() {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let (passed, rejected) = metrics.snapshot();
+                tracing::info!(passed, rejected, "Time-in-profit filter");
+            }
+        });
+    }
+    engine = match args.strategy.as_str() {
+        "two-phase" => engine.with_strategy(TwoPhaseStrategy::new(TwoPhaseConfig {
+            top_n: args.top_n,
+            slippage: args.slippage.clone(),
+            ..Default::default()
+        })),
+        _ => engine.with_strategy(
+            NegativeCycleStrategy::new()
+                .with_top_n(args.top_n.unwrap_or(usize::MAX))
+                .with_max_cycle_len(args.max_cycle_len)
+                .with_parallel(args.parallel_detection),
+        ),
+    };
+    for plugin in &args.plugins {
+        engine = engine.with_strategy(SubprocessStrategy::new(plugin, Vec::new()));
+    }
+    if let Some(config) = args.spread.clone() {
+        engine = engine.with_strategy(SpreadStrategy::new(config));
+    }
+    if let Some(config) = args.basis.clone() {
+        engine = engine.with_strategy(BasisStrategy::new(config));
+    }
+    if let Some(config) = args.lead_lag.clone() {
+        engine = engine.with_strategy(LeadLagStrategy::new(config));
+    }
+    // Net exposure the fills leave behind, offset in one liquid pair
+    if let Some(config) = args.hedge.clone() {
+        let inventory = Arc::new(Mutex::new(Inventory::new()));
+        let sources = user_stream.iter().chain(accounts.iter().map(|a| &a.stream)).map(|s| s.subscribe());
+        for mut events in sources.chain(fix.iter().map(|route| route.session.subscribe())) {
+            let inventory = inventory.clone();
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(AccountEvent::Fill(fill)) => inventory.lock().unwrap().apply(&fill),
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(skipped)) => tracing::warn!(skipped, "Hedger missed account events, exposure may be off"),
+                        Err(RecvError::Closed) => break,
                     }
-                    cycle.push(pred.clone());
-                    last = pred;
                 }
-                break;
+            });
+        }
+        let mut hedger = Hedger::new(config);
+        if let Some(filters) = hedge_filters {
+            hedger = hedger.with_filters(filters);
+        }
+        engine = engine.with_strategy(HedgeStrategy::new(hedger, inventory));
+    }
+    if let Some(path) = &args.calendar_path {
+        engine = engine.with_schedule(Schedule::load(path).expect("Failed to load trading calendar"));
+    }
+    let mut fix_executor = None;
+    let fix = match fix {
+        Some(FixRoute { session, filters, revalidator, rest }) => {
+            let session = match audit.clone() {
+                Some(log) => session.with_audit(log),
+                None => session,
+            };
+            // Orders followed through the session's execution reports, one report per cycle
+            let monitor = ExecutionMonitor::spawn(TrackerConfig::default(), session.subscribe(), rest);
+            let mut reports = monitor.subscribe();
+            tokio::spawn(async move {
+                loop {
+                    match reports.recv().await {
+                        Ok(report) if report.filled() => tracing::info!(cycle = %report.cycle_id, report = %report.to_json(), "Cycle executed"),
+                        Ok(report) => tracing::warn!(cycle = %report.cycle_id, report = %report.to_json(), "Cycle not fully filled"),
+                        Err(RecvError::Lagged(skipped)) => tracing::warn!(skipped, "Missed cycle execution reports"),
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+            let mut executor = FixExecutor::new(&session).with_monitor(monitor);
+            if let (Some(sizing), Some(filters)) = (args.fix_cycles.clone(), filters) {
+                executor = executor.with_cycles(sizing, filters, engine.shared_graph());
+            }
+            if let Some(guard) = &book_guard {
+                executor = executor.with_book_guard(guard.clone());
+            }
+            if let Some(latency) = &latency {
+                executor = executor.with_latency_budget(latency.clone());
+            }
+            if let Some(guard) = &last_look {
+                executor = executor.with_last_look(guard.clone());
             }
+            if let Some(revalidator) = revalidator {
+                let metrics = revalidator.metrics();
+                tokio::spawn(async move {
+                    let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+                    loop {
+                        interval.tick().await;
+                        let (passed, aborted) = metrics.snapshot();
+                        tracing::info!(passed, aborted, "Pre-execution revalidation");
+                    }
+                });
+                executor = executor.with_revalidation(revalidator);
+            }
+            fix_executor = Some(executor);
+            Some(session)
         }
-    
-        // If we reach this point, no arbitrage opportunity was found
-        None
-    }    
-}
-
-// Function to process ticker data and update the graph
-async fn process_ticker_data(graph: &mut Graph, ticker_data: Vec<TickerData>) {
-    for data in ticker_data {
-        let (start, end) = extract_currency_pair(&data.s); // Use 's' for symbol
-        let price: f64 = match data.c.parse() { // Parse the last price from string to f64
-            Ok(p) => p,
-            Err(_) => {
-                eprintln!("Error parsing price for symbol {}", &data.s);
-                continue; // Skip this entry if the price can't be parsed
+        None => None,
+    };
+    // With --account, cycles are spread over the accounts, the FIX session being one of them
+    match (account_filters, fix_executor) {
+        (Some(filters), fix_executor) => {
+            let mut executor = MultiAccountExecutor::new(args.account_policy);
+            if let Some(fix_executor) = fix_executor {
+                executor = executor.with_account("fix", fix_executor, None);
             }
-        };
-        graph.update_edge(&start, &end, price);
+            for account in &accounts {
+                let mut config = MarginConfig::new(args.account_notional.expect("--account-notional checked when parsing"));
+                config.revalidation = args.revalidate.clone();
+                config.books = book_guard.clone();
+                config.latency = latency.clone();
+                config.last_look = last_look.clone();
+                let margin = MarginExecutor::spawn(config, account.client.clone(), account.credentials.clone(), filters.clone(), engine.shared_graph());
+                executor = executor.with_account(account.name.clone(), margin, Some(account.stream.account()));
+            }
+            engine = engine.with_executor(executor);
+        }
+        (None, Some(fix_executor)) => engine = engine.with_executor(fix_executor),
+        (None, None) => {}
     }
 
-    // Here you could check for arbitrage opportunities
-    if let Some(arbitrage_path) = graph.find_arbitrage() {
-        println!("Arbitrage opportunity found: {:?}", arbitrage_path);
-        // Handle the arbitrage opportunity
+    let started = std::time::Instant::now();
+    handle_signals(engine.shutdown_handle());
+
+    // Order updates and fills from every account source, next to the decisions behind them
+    if let Some(log) = &audit {
+        for mut events in user_stream.iter().chain(accounts.iter().map(|a| &a.stream)).map(|s| s.subscribe()).chain(fix.iter().map(|s| s.subscribe())) {
+            let log = log.clone();
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(AccountEvent::Order(update)) => log.record(
+                            "order_update",
+                            serde_json::json!({
+                                "client_order_id": update.client_order_id,
+                                "status": update.status.as_str(),
+                                "executed_qty": update.executed_qty,
+                                "quote_qty": update.quote_qty,
+                            }),
+                        ),
+                        Ok(AccountEvent::Fill(fill)) => log.record("fill", serde_json::json!({ "fill": fill })),
+                        // The log should show where it is incomplete
+                        Err(RecvError::Lagged(skipped)) => log.record("account_events_lost", serde_json::json!({ "skipped": skipped })),
+                        Err(RecvError::Closed) => break,
+                    }
+                }
+            });
+        }
     }
-}
 
-// Function to listen to the WebSocket stream and update the graph
-async fn listen_to_stream_and_update_graph(graph: &mut Graph, ws_stream: WebSocketStream<tokio_tungstenite::MaybeTlsStream<TcpStream>>) {
-    let (_, mut read) = ws_stream.split();
-
-    // Read messages from the stream
-    while let Some(message) = read.next().await {
-        match message {
-            Ok(msg) => {
-                if msg.is_text() || msg.is_binary() {
-                    // println!("Received a message: {:?}", msg);
-                    let ticker_data: Vec<TickerData> = match serde_json::from_str(&msg.to_text().unwrap()) {
-                        Ok(data) => data,
-                        Err(e) => {
-                            eprintln!("Error parsing ticker data: {:?}", e);
-                            continue; // Skip this message and continue with the next
+    // PnL of every account's fills, valued at the graph's rates; FIX drop-copy fills count too
+    let account_events: Vec<_> = user_stream.iter().chain(accounts.iter().map(|a| &a.stream)).map(|s| s.subscribe()).chain(fix.iter().map(|s| s.subscribe())).collect();
+    let pnl = (!account_events.is_empty()).then(|| {
+        let tracker = Arc::new(Mutex::new(PnlTracker::new(&args.pnl_reference)));
+        for mut events in account_events {
+            let graph = engine.shared_graph();
+            let fills = tracker.clone();
+            let (risk, reference) = (risk.clone(), args.pnl_reference.clone());
+            tokio::spawn(async move {
+                loop {
+                    match events.recv().await {
+                        Ok(AccountEvent::Fill(fill)) => {
+                            let graph = graph.load();
+                            let realized = fills.lock().unwrap().record(&fill, None, &graph);
+                            if let Some(risk) = &risk {
+                                risk.record_fill(&fill);
+                                // Unpriced in the limits' asset, a loss can't be counted yet
+                                match to_risk_reference(realized, &graph, &reference) {
+                                    Some(pnl) => risk.record_pnl(pnl, fill.time),
+                                    None => tracing::warn!(%fill.symbol, realized, "No price for realized PnL in the risk limits' asset"),
+                                }
+                            }
                         }
-                    };
-                    process_ticker_data(graph, ticker_data).await;
+                        Ok(_) => {}
+                        Err(RecvError::Lagged(skipped)) => tracing::warn!(skipped, "PnL tracker missed account events"),
+                        Err(RecvError::Closed) => break,
+                    }
                 }
+            });
+        }
+        let summary = tracker.clone();
+        let graph = engine.shared_graph();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+            loop {
+                interval.tick().await;
+                let pnl = summary.lock().unwrap().summary(&graph.load());
+                tracing::info!(
+                    realized = pnl.realized,
+                    unrealized = pnl.unrealized,
+                    fees = pnl.fees,
+                    fills = pnl.fills,
+                    reference = %pnl.reference,
+                    "PnL"
+                );
             }
-            Err(e) => {
-                eprintln!("Error receiving message: {:?}", e);
-                break;
+        });
+        tracker
+    });
+
+    let final_graph = pnl.as_ref().map(|_| engine.shared_graph());
+
+    // Sink tasks, drained after the engine stops
+    let mut outputs = Vec::new();
+
+    // Per-cycle opportunity statistics; without the engine's dedup the tracker works
+    // out on its own when cycles close
+    let opportunity_stats = Arc::new(Mutex::new(OpportunityStats::new(
+        args.dedup.is_none().then(|| DedupConfig::default().close_after),
+    )));
+    let stats = opportunity_stats.clone();
+    let mut events = engine.subscribe();
+    outputs.push(tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => stats.lock().unwrap().record(&event, std::time::Instant::now()),
+                Err(RecvError::Lagged(skipped)) => tracing::warn!(skipped, "Opportunity statistics missed events"),
+                Err(RecvError::Closed) => break,
             }
         }
+    }));
+    let stats = opportunity_stats.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        interval.tick().await;
+        loop {
+            interval.tick().await;
+            let mut stats = stats.lock().unwrap();
+            stats.close_expired(std::time::Instant::now());
+            log_opportunity_stats(&stats);
+        }
+    });
+
+    if let Some(mut config) = TelegramConfig::from_env() {
+        if let Some(min_bps) = args.telegram_min_bps {
+            config.min_profit_bps = min_bps;
+        }
+        config.proxy = args.proxy.default.clone();
+        outputs.push(sinks::spawn(&supervisor, TelegramSink::new(config), engine.subscribe()));
+        tracing::info!("Telegram alerts enabled");
+    }
+
+    if let Some(config) = args.webhook {
+        outputs.push(sinks::spawn(&supervisor, WebhookSink::new(config), engine.subscribe()));
+        tracing::info!("Webhook output enabled");
+    }
+
+    if let Some(config) = args.kafka.clone() {
+        let sink = KafkaSink::new(config).expect("Failed to create Kafka producer");
+        let market = sink.spawn_market(engine.subscribe_market(), engine.feed_mut().venue());
+        outputs.push(sinks::spawn(&supervisor, sink, engine.subscribe()));
+        outputs.extend(market);
+        tracing::info!("Publishing to Kafka");
+    }
+
+    if let Some(config) = args.redis.clone() {
+        let sink = RedisSink::connect(config).await.expect("Failed to connect to Redis");
+        outputs.push(sinks::spawn(&supervisor, sink, engine.subscribe()));
+        tracing::info!("Writing opportunities to Redis");
+    }
+
+    if let Some(config) = args.nats.clone() {
+        let sink = NatsSink::connect(config).await.expect("Failed to connect to NATS");
+        outputs.push(sinks::spawn(&supervisor, sink, engine.subscribe()));
+        tracing::info!("Publishing to NATS");
+    }
+
+    if let Some(path) = &args.sqlite_path {
+        let sink = SqliteSink::open(path).expect("Failed to open SQLite database");
+        outputs.push(sinks::spawn(&supervisor, sink, engine.subscribe()));
+        tracing::info!(path = %path.display(), "Storing opportunities in SQLite");
+    }
+
+    if let Some(addr) = args.web_addr {
+        web::spawn(addr, engine.subscribe(), engine.shared_graph(), pipeline.clone())
+            .await
+            .expect("Failed to start the web dashboard");
+        tracing::info!(%addr, "Serving the web dashboard");
+    }
+
+    if let Some(addr) = args.grpc_addr {
+        let service = GrpcService::new(engine.subscribe(), engine.shared_graph(), engine.detection_control());
+        service.spawn(addr).await.expect("Failed to start the gRPC server");
+        tracing::info!(%addr, "Serving the gRPC API");
+    }
+
+    if let Some(addr) = args.stream_addr {
+        let mut config = StreamServerConfig::from_env()
+            .unwrap_or_else(|| panic!("--stream-server needs client tokens in {}", stream_server::TOKENS_VAR));
+        config.heartbeat = args.stream_heartbeat;
+        let server = StreamServer::new(engine.subscribe(), config);
+        server.spawn(addr).await.expect("Failed to start the opportunity stream server");
+        tracing::info!(%addr, "Streaming opportunities over WebSocket");
+    }
+
+    if let Some(addr) = args.health_addr {
+        let metrics = engine.health();
+        let sources = StatusSources { pnl: pnl.clone(), opportunities: Some(opportunity_stats.clone()), books: books.clone() };
+        health::spawn(addr, metrics, engine.shared_graph(), engine.detection_control(), args.health_max_age, sources)
+            .await
+            .expect("Failed to start the health endpoint");
+        tracing::info!(%addr, "Serving /healthz, /status and /books");
+    }
+
+    let output = match (args.output, &args.output_file) {
+        (OutputMode::Jsonl, Some(path)) => {
+            let sink = JsonlSink::file(path).expect("Failed to open output file");
+            Some(sinks::spawn(&supervisor, sink, engine.subscribe()))
+        }
+        // Stdout belongs to the dashboard
+        _ if args.tui => None,
+        (OutputMode::Jsonl, None) => Some(sinks::spawn(&supervisor, JsonlSink::stdout(), engine.subscribe())),
+        (OutputMode::Text, _) => Some(sinks::spawn(&supervisor, LogSink, engine.subscribe())),
+    };
+    outputs.extend(output);
+
+    if args.tui {
+        let mut dashboard = dashboard::spawn(engine.subscribe(), engine.shared_graph(), pipeline.clone());
+        // Quitting the dashboard stops the bot; when the feed ends first the dashboard
+        // stays up, showing the final state, until the operator quits
+        let shutdown = engine.shutdown_handle();
+        let run = engine.run();
+        tokio::pin!(run);
+        let quit = tokio::select! {
+            _ = &mut run => false,
+            _ = &mut dashboard => true,
+        };
+        if quit {
+            shutdown.trigger("dashboard closed");
+            run.await;
+        } else {
+            let _ = dashboard.await;
+        }
+    } else {
+        // Start listening to the stream and updating the graph
+        engine.run().await;
+    }
+    let stats = engine.stats();
+    drop(engine);
+
+    // Dropping the engine closes the event channel; sinks write out what is still queued
+    // (the Kafka producer flushes as it drops) so nothing detected near the end is lost
+    let drain = async {
+        for output in outputs {
+            let _ = output.await;
+        }
+    };
+    if tokio::time::timeout(OUTPUT_DRAIN_TIMEOUT, drain).await.is_err() {
+        tracing::warn!("Outputs did not finish writing in time");
+    }
+    if let Some(stream) = user_stream {
+        stream.close().await;
+    }
+    for account in accounts {
+        account.stream.close().await;
+    }
+    if let Some(session) = fix {
+        session.logout().await;
+    }
+
+    tracing::info!(
+        uptime_s = started.elapsed().as_secs(),
+        events = stats.events,
+        passes = stats.passes,
+        opportunities = stats.opportunities,
+        executed = stats.executed,
+        signals = stats.signals,
+        "Stopped"
+    );
+    if let Some(metrics) = pipeline {
+        log_pipeline(&metrics);
+    }
+    log_opportunity_stats(&opportunity_stats.lock().unwrap());
+    if let (Some(pnl), Some(graph)) = (pnl, final_graph) {
+        let pnl = pnl.lock().unwrap().summary(&graph.load());
+        tracing::info!(
+            realized = pnl.realized,
+            unrealized = pnl.unrealized,
+            fees = pnl.fees,
+            fills = pnl.fills,
+            reference = %pnl.reference,
+            "PnL"
+        );
     }
 }
 
-#[tokio::main]
-async fn main() {
-    let mut graph = Graph::new();
-
-    // Connect to the WebSocket stream
-    let binance_ws_url = "wss://stream.binance.com:9443/ws/!ticker@arr";
-    let url = Url::parse(binance_ws_url).expect("Failed to parse URL");
-    let (ws_stream, _) = connect_async(url)
-        .await
-        .expect("Failed to connect to Binance WebSocket");
-    println!("Connected to the Binance WebSocket server");
-
-    // Start listening to the stream and updating the graph
-    listen_to_stream_and_update_graph(&mut graph, ws_stream).await;
+// Event throughput and back-pressure of the ingest pipeline since start
+fn log_pipeline(metrics: &PipelineMetrics) {
+    let m = metrics.snapshot();
+    tracing::info!(
+        events = m.events,
+        stalled = m.stalled,
+        max_depth = m.max_depth,
+        coalesced = m.coalesced,
+        dropped = m.dropped,
+        "Ingest pipeline"
+    );
+}
+
+// Logs an account's non-zero balances every minute
+fn log_balances(name: &str, account: Arc<RwLock<AccountState>>) {
+    let name = name.to_string();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let account = account.read().unwrap();
+            let balances: Vec<String> = account
+                .balances
+                .iter()
+                .filter(|(_, b)| !b.free.is_zero() || !b.locked.is_zero())
+                .map(|(asset, b)| format!("{}={}/{}", asset, b.free, b.locked))
+                .collect();
+            tracing::info!(
+                account = %name,
+                connected = account.connected,
+                open_orders = account.open_orders.len(),
+                balances = %balances.join(" "),
+                "Account"
+            );
+        }
+    });
+}
+
+// One line with the overall figures plus the cycles and assets that opened most often
+fn log_opportunity_stats(stats: &OpportunityStats) {
+    if stats.reports() == 0 {
+        return;
+    }
+    let cycles: Vec<String> = stats
+        .top_cycles(LOGGED_TOP_CYCLES)
+        .iter()
+        .map(|cycle| format!("{} x{} {:.1}bps", cycle.cycle, cycle.triggers, cycle.mean_profit_bps().unwrap_or(0.0)))
+        .collect();
+    let assets: Vec<String> = stats
+        .top_assets(LOGGED_TOP_CYCLES)
+        .iter()
+        .map(|(asset, triggers)| format!("{} x{}", asset, triggers))
+        .collect();
+    tracing::info!(
+        reports = stats.reports(),
+        triggers = stats.triggers(),
+        mean_profit_bps = stats.mean_profit_bps(),
+        mean_lifetime_ms = stats.mean_lifetime_ms(),
+        top_cycles = %cycles.join(", "),
+        top_assets = %assets.join(" "),
+        "Opportunity statistics"
+    );
+}
+
+// The first SIGINT or SIGTERM asks the engine to stop; a second one exits at once
+fn handle_signals(shutdown: Shutdown) {
+    tokio::spawn(async move {
+        #[cfg(unix)]
+        let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("Failed to install the SIGTERM handler");
+        loop {
+            #[cfg(unix)]
+            let signal = tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = terminate.recv() => "SIGTERM",
+            };
+            #[cfg(not(unix))]
+            let signal = {
+                let _ = tokio::signal::ctrl_c().await;
+                "Ctrl-C"
+            };
+            if shutdown.is_triggered() {
+                tracing::warn!(signal, "Exiting without cleanup");
+                std::process::exit(130);
+            }
+            tracing::info!(signal, "Shutting down, signal again to exit at once");
+            shutdown.trigger(signal);
+        }
+    });
+}
+
+// Realized PnL from the tracker's reference asset into the risk limits'
+fn to_risk_reference(pnl: f64, graph: &Graph, reference: &str) -> Option<f64> {
+    if reference == RISK_REFERENCE || pnl == 0.0 {
+        return Some(pnl);
+    }
+    graph.rate(reference, RISK_REFERENCE).map(|rate| pnl * rate)
+}
+
+// Logs executed cycles' latencies every minute
+fn log_latency(budget: LatencyBudget) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            let m = budget.snapshot();
+            tracing::info!(
+                cycles = m.cycles,
+                over_budget = m.over_budget,
+                mean_first_order_ms = m.mean_first_order.as_secs_f64() * 1_000.0,
+                worst_first_order_ms = m.worst_first_order.as_secs_f64() * 1_000.0,
+                worst_leg_gap_ms = m.worst_leg_gap.as_secs_f64() * 1_000.0,
+                "Execution latency"
+            );
+        }
+    });
 }
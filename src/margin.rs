@@ -0,0 +1,640 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use futures_util::future::BoxFuture;
+use rust_decimal::Decimal;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::book_stats::BookGuard;
+use crate::events::{Opportunity, Signal};
+use crate::executor::Executor;
+use crate::filters::{ExchangeFilters, FilterViolation, ViolationKind};
+use crate::graph::Graph;
+use crate::last_look::{LastLook, LastLookGuard};
+use crate::latency_budget::{LatencyBudget, LegTimer};
+use crate::order::OrderRequest;
+use crate::order_tracker::{OrderAccount, OrderStatus, OrderTracker, OrderUpdate, TrackerConfig};
+use crate::rest::{ApiCredentials, EndpointCategory, RestClient, RestError};
+use crate::revalidate::{RevalidationConfig, Revalidator};
+use crate::shared_graph::SharedGraph;
+use crate::unwind::{self, Position, UnwindConfig, UnwindPolicy};
+
+// Binance charges margin interest by the hour, counting the hour a loan is opened in
+const INTEREST_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone)]
+pub struct MarginConfig {
+    pub notional: Decimal,                        // Size of each cycle, in `reference`
+    pub reference: String,                        // Asset `notional` is given in
+    pub fee_bps: Decimal,                         // Taker fee per leg
+    pub hold: Duration,                           // How long a loan is expected to stay open; rounds up to whole hours
+    pub min_net_profit_bps: Decimal,              // Required after fees and interest
+    pub unwind: UnwindConfig,                     // Inventory left by a failed or partly filled leg
+    pub revalidation: Option<RevalidationConfig>, // Re-check the cycle at fresh prices before borrowing
+    pub books: Option<BookGuard>,                 // Skip cycles with a leg on a wide, lopsided or thin book
+    pub latency: Option<LatencyBudget>,           // Time each cycle's legs against the budget
+    pub last_look: Option<LastLookGuard>,         // Re-check the last leg's rate before sending it
+    pub tracker: TrackerConfig,                   // Following orders the placement response left open
+}
+
+impl MarginConfig {
+    pub fn new(notional: Decimal) -> Self {
+        MarginConfig {
+            notional,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for MarginConfig {
+    fn default() -> Self {
+        MarginConfig {
+            notional: Decimal::from(100),
+            reference: "USDT".to_string(),
+            fee_bps: Decimal::from(10),
+            hold: Duration::from_secs(60),
+            min_net_profit_bps: Decimal::ZERO,
+            unwind: UnwindConfig::default(),
+            revalidation: None,
+            books: None,
+            latency: None,
+            last_look: None,
+            tracker: TrackerConfig::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MarginError {
+    Rest(RestError),
+    Filter(FilterViolation),
+    NoPrice(String),        // No rate from the reference asset into this one
+    NoInterestRate(String), // The exchange didn't quote a rate for borrowing this asset
+    BadProfit(f64),         // Reported or implied cycle profit that isn't a finite number
+}
+
+impl fmt::Display for MarginError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MarginError::Rest(e) => write!(f, "{}", e),
+            MarginError::Filter(e) => write!(f, "{}", e),
+            MarginError::NoPrice(asset) => write!(f, "no price for {}", asset),
+            MarginError::NoInterestRate(asset) => write!(f, "{} is not borrowable", asset),
+            MarginError::BadProfit(profit) => write!(f, "cycle profit {} is not a number", profit),
+        }
+    }
+}
+
+impl std::error::Error for MarginError {}
+
+impl From<RestError> for MarginError {
+    fn from(e: RestError) -> Self {
+        MarginError::Rest(e)
+    }
+}
+
+impl From<FilterViolation> for MarginError {
+    fn from(e: FilterViolation) -> Self {
+        MarginError::Filter(e)
+    }
+}
+
+// What a margin order does to the loan besides trading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SideEffect {
+    None,
+    MarginBuy, // Borrow whatever the order needs beyond the free balance
+    AutoRepay, // Repay debt in the received asset out of the proceeds
+}
+
+impl SideEffect {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SideEffect::None => "NO_SIDE_EFFECT",
+            SideEffect::MarginBuy => "MARGIN_BUY",
+            SideEffect::AutoRepay => "AUTO_REPAY",
+        }
+    }
+}
+
+// Interest on a loan held for `hold` at `hourly_rate`, as a fraction of the principal
+pub fn interest_cost(hourly_rate: Decimal, hold: Duration) -> Decimal {
+    let hours = hold.as_secs().div_ceil(INTEREST_PERIOD.as_secs()).max(1);
+    hourly_rate * Decimal::from(hours)
+}
+
+// One cycle on the margin account: what to borrow first, the legs, and the profit left
+// once interest on the loan is paid
+#[derive(Debug, Clone)]
+pub struct MarginPlan {
+    pub borrow: Option<(String, Decimal)>, // Asset and amount; None when the balance covers the first leg
+    pub orders: Vec<OrderRequest>,
+    pub interest: Decimal,                 // Expected interest, in the borrowed asset
+    pub net_profit: Decimal,               // Cycle profit after fees, less interest, as a fraction of the amount
+}
+
+// The strategy's profit for the cycle, or what its leg rates multiply out to when it
+// didn't give one
+fn expected_profit(opportunity: &Opportunity) -> f64 {
+    opportunity
+        .profit
+        .unwrap_or_else(|| opportunity.rates.iter().product::<f64>() - 1.0)
+}
+
+// Plans walking the cycle with `amount` of its first asset when only `available` of it
+// is free, borrowing the rest at `hourly_rate`
+pub fn plan_cycle(
+    opportunity: &Opportunity,
+    amount: Decimal,
+    available: Decimal,
+    hourly_rate: Option<Decimal>,
+    graph: &Graph,
+    filters: &ExchangeFilters,
+    config: &MarginConfig,
+) -> Result<MarginPlan, MarginError> {
+    let path = &opportunity.path;
+    let profit = expected_profit(opportunity);
+    let profit = Decimal::try_from(profit).map_err(|_| MarginError::BadProfit(profit))?;
+    let orders = filters.cycle_orders(path, amount, graph, config.fee_bps)?;
+    let shortfall = amount - available.max(Decimal::ZERO);
+    if shortfall <= Decimal::ZERO {
+        return Ok(MarginPlan { borrow: None, orders, interest: Decimal::ZERO, net_profit: profit });
+    }
+    let asset = path[0].clone();
+    let rate = hourly_rate.ok_or_else(|| MarginError::NoInterestRate(asset.clone()))?;
+    let cost = interest_cost(rate, config.hold);
+    Ok(MarginPlan {
+        borrow: Some((asset, shortfall)),
+        orders,
+        interest: shortfall * cost,
+        net_profit: profit - cost * shortfall / amount,
+    })
+}
+
+// Entry of GET /sapi/v1/margin/account
+#[derive(serde::Deserialize)]
+struct UserAsset {
+    asset: String,
+    free: Decimal,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarginAccountResponse {
+    user_assets: Vec<UserAsset>,
+}
+
+// Entry of GET /sapi/v1/margin/next-hourly-interest-rate
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct InterestRate {
+    asset: String,
+    next_hourly_interest_rate: String,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BorrowRepayResponse {
+    tran_id: u64,
+}
+
+// Response of POST /sapi/v1/margin/order with newOrderRespType=RESULT
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarginOrderResponse {
+    client_order_id: String,
+    status: OrderStatus,
+    executed_qty: String,
+    cummulative_quote_qty: String,
+}
+
+fn decode<T: serde::de::DeserializeOwned>(body: &str) -> Result<T, RestError> {
+    serde_json::from_str(body).map_err(|e| RestError::Decode(e.to_string()))
+}
+
+// Free balance per asset on the cross margin account (weight 10)
+pub async fn free_balances(client: &RestClient, credentials: &ApiCredentials) -> Result<HashMap<String, Decimal>, RestError> {
+    let body = client.signed_get(EndpointCategory::Margin, 10, "/sapi/v1/margin/account", &[], credentials).await?;
+    let account: MarginAccountResponse = decode(&body)?;
+    Ok(account.user_assets.into_iter().map(|a| (a.asset, a.free)).collect())
+}
+
+// Hourly rate for borrowing each of `assets` from now on (weight 100)
+pub async fn hourly_interest_rates(
+    client: &RestClient,
+    credentials: &ApiCredentials,
+    assets: &[String],
+) -> Result<HashMap<String, Decimal>, RestError> {
+    let query = [("assets", assets.join(",")), ("isIsolated", "FALSE".to_string())];
+    let body = client
+        .signed_get(EndpointCategory::Margin, 100, "/sapi/v1/margin/next-hourly-interest-rate", &query, credentials)
+        .await?;
+    let rates: Vec<InterestRate> = decode(&body)?;
+    Ok(rates
+        .into_iter()
+        .filter_map(|r| Some((r.asset, r.next_hourly_interest_rate.parse().ok()?)))
+        .collect())
+}
+
+// Borrows or repays `amount` of `asset` on the cross margin account (weight 1500),
+// returning the transaction ID
+async fn borrow_repay(
+    client: &RestClient,
+    credentials: &ApiCredentials,
+    kind: &str,
+    asset: &str,
+    amount: Decimal,
+) -> Result<u64, RestError> {
+    let query = [
+        ("asset", asset.to_string()),
+        ("isIsolated", "FALSE".to_string()),
+        ("amount", amount.normalize().to_string()),
+        ("type", kind.to_string()),
+    ];
+    let body = client.signed_post(EndpointCategory::Margin, 1500, "/sapi/v1/margin/borrow-repay", &query, credentials).await?;
+    Ok(decode::<BorrowRepayResponse>(&body)?.tran_id)
+}
+
+pub async fn borrow(client: &RestClient, credentials: &ApiCredentials, asset: &str, amount: Decimal) -> Result<u64, RestError> {
+    borrow_repay(client, credentials, "BORROW", asset, amount).await
+}
+
+pub async fn repay(client: &RestClient, credentials: &ApiCredentials, asset: &str, amount: Decimal) -> Result<u64, RestError> {
+    borrow_repay(client, credentials, "REPAY", asset, amount).await
+}
+
+// Places a margin order and waits for its result: IOC at the limit price, or market
+pub async fn place_order(
+    client: &RestClient,
+    credentials: &ApiCredentials,
+    order: &OrderRequest,
+    client_order_id: &str,
+    side_effect: SideEffect,
+) -> Result<OrderUpdate, RestError> {
+    let mut query = vec![
+        ("symbol", order.symbol.clone()),
+        ("side", order.side.as_str().to_string()),
+        ("quantity", order.quantity.normalize().to_string()),
+        ("newClientOrderId", client_order_id.to_string()),
+        ("sideEffectType", side_effect.as_str().to_string()),
+        ("newOrderRespType", "RESULT".to_string()),
+    ];
+    match order.price {
+        Some(price) => {
+            query.push(("type", "LIMIT".to_string()));
+            query.push(("timeInForce", "IOC".to_string()));
+            query.push(("price", price.normalize().to_string()));
+        }
+        None => query.push(("type", "MARKET".to_string())),
+    }
+    let body = client.signed_post(EndpointCategory::Order, 6, "/sapi/v1/margin/order", &query, credentials).await?;
+    let response: MarginOrderResponse = decode(&body)?;
+    Ok(OrderUpdate {
+        client_order_id: response.client_order_id,
+        status: response.status,
+        executed_qty: response.executed_qty.parse().unwrap_or_default(),
+        quote_qty: response.cummulative_quote_qty.parse().unwrap_or_default(),
+    })
+}
+
+// Executes cycles on the cross margin account, one at a time on its own task. The start
+// asset is borrowed when the account holds too little of it, and the last leg repays the
+// loan from its proceeds. Cycles are skipped when interest for `hold` eats the profit, and
+// while another is in flight. What a failed or partly filled leg leaves behind is unwound
+// according to `unwind` before the loan is repaid, as is a cycle whose last leg fails
+// `last_look`. Signal orders are placed in turn on the
+// same task, without borrowing.
+pub struct MarginExecutor {
+    jobs: Option<mpsc::Sender<Job>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl MarginExecutor {
+    pub fn spawn(
+        config: MarginConfig,
+        client: Arc<RestClient>,
+        credentials: ApiCredentials,
+        filters: ExchangeFilters,
+        graph: Arc<SharedGraph>,
+    ) -> Self {
+        let (jobs, receiver) = mpsc::channel(1);
+        let revalidator = config.revalidation.clone().map(|r| Revalidator::new(r, Some(client.clone())));
+        let worker = Worker { config, client, credentials, filters, graph, revalidator };
+        MarginExecutor {
+            jobs: Some(jobs),
+            worker: Some(tokio::spawn(worker.run(receiver))),
+        }
+    }
+}
+
+impl Executor for MarginExecutor {
+    fn execute(&mut self, opportunity: &Opportunity) {
+        let Some(jobs) = &self.jobs else {
+            return;
+        };
+        if jobs.try_send(Job::Cycle(Box::new(opportunity.clone()))).is_err() {
+            tracing::debug!(path = ?opportunity.path, "Margin execution busy, skipping cycle");
+        }
+    }
+
+    fn submit(&mut self, signal: &Signal) {
+        let Some(jobs) = &self.jobs else {
+            return;
+        };
+        if jobs.try_send(Job::Orders(signal.orders.clone())).is_err() {
+            tracing::warn!(kind = %signal.kind, "Margin execution busy, signal orders dropped");
+        }
+    }
+
+    // Lets the cycle in flight finish, loan and all
+    fn shutdown(&mut self) -> BoxFuture<'static, ()> {
+        self.jobs = None;
+        let worker = self.worker.take();
+        Box::pin(async move {
+            if let Some(worker) = worker {
+                let _ = worker.await;
+            }
+        })
+    }
+}
+
+enum Job {
+    Cycle(Box<Opportunity>),
+    Orders(Vec<OrderRequest>), // A signal's
+}
+
+struct Worker {
+    config: MarginConfig,
+    client: Arc<RestClient>,
+    credentials: ApiCredentials,
+    filters: ExchangeFilters,
+    graph: Arc<SharedGraph>,
+    revalidator: Option<Revalidator>,
+}
+
+impl Worker {
+    async fn run(self, mut jobs: mpsc::Receiver<Job>) {
+        while let Some(job) = jobs.recv().await {
+            match job {
+                Job::Cycle(opportunity) => {
+                    if let Err(e) = self.execute(&opportunity).await {
+                        tracing::warn!(path = ?opportunity.path, error = %e, "Margin execution failed");
+                    }
+                }
+                Job::Orders(orders) => self.place_signal_orders(&orders).await,
+            }
+        }
+    }
+
+    // In sequence, stopping at the first that fails
+    async fn place_signal_orders(&self, orders: &[OrderRequest]) {
+        let id = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        for (i, order) in orders.iter().enumerate() {
+            let client_order_id = format!("hft3s-{}-{}", id, i);
+            match self.place(order, &client_order_id, SideEffect::None).await {
+                Ok(update) => tracing::info!(symbol = %order.symbol, status = ?update.status, executed_qty = %update.executed_qty, "Signal order placed on margin"),
+                Err(e) => {
+                    tracing::warn!(symbol = %order.symbol, error = %e, "Signal order failed on margin");
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn execute(&self, opportunity: &Opportunity) -> Result<(), MarginError> {
+        let Some(start) = opportunity.path.first() else {
+            return Ok(());
+        };
+        let graph = self.graph.load();
+        let amount = if *start == self.config.reference {
+            self.config.notional
+        } else {
+            let rate = graph.rate(&self.config.reference, start).and_then(|r| Decimal::try_from(r).ok());
+            self.config.notional * rate.ok_or_else(|| MarginError::NoPrice(start.clone()))?
+        };
+        let balances = free_balances(&self.client, &self.credentials).await?;
+        let available = balances.get(start).copied().unwrap_or_default();
+        let rate = if available < amount {
+            let rates = hourly_interest_rates(&self.client, &self.credentials, std::slice::from_ref(start)).await?;
+            rates.get(start).copied()
+        } else {
+            None
+        };
+        let plan = plan_cycle(opportunity, amount, available, rate, &graph, &self.filters, &self.config)?;
+        let net_bps = plan.net_profit * Decimal::from(10_000);
+        if net_bps < self.config.min_net_profit_bps {
+            tracing::info!(path = ?opportunity.path, profit_bps = expected_profit(opportunity) * 10_000.0, %net_bps, "Interest outweighs the cycle, skipping");
+            return Ok(());
+        }
+        if let Some(books) = &self.config.books {
+            if let Err(rejection) = books.check(&plan.orders, Instant::now()) {
+                tracing::info!(path = ?opportunity.path, %rejection, "Book too poor to cross, skipping");
+                return Ok(());
+            }
+        }
+        // The cycle may have waited behind another; prices can have moved since detection
+        if let Some(revalidator) = &self.revalidator {
+            if let Err(e) = revalidator.revalidate(opportunity, &self.graph.load(), &plan.orders).await {
+                tracing::info!(path = ?opportunity.path, error = %e, "Cycle dropped on revalidation");
+                return Ok(());
+            }
+        }
+
+        // Balance and rate lookups take time of their own
+        if opportunity.is_expired(SystemTime::now()) {
+            tracing::info!(path = ?opportunity.path, "Cycle expired before its orders went out");
+            return Ok(());
+        }
+
+        if let Some((asset, amount)) = &plan.borrow {
+            let tran_id = borrow(&self.client, &self.credentials, asset, *amount).await?;
+            tracing::info!(%asset, %amount, tran_id, interest = %plan.interest, "Borrowed on margin");
+        }
+        let id = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+        let last = plan.orders.len().saturating_sub(1);
+        let mut position = Position::default();
+        let mut timer = LegTimer::start(opportunity);
+        for (leg, order) in plan.orders.iter().enumerate() {
+            let side_effect = if leg == last && plan.borrow.is_some() { SideEffect::AutoRepay } else { SideEffect::None };
+            let client_order_id = format!("hft3m-{}-{}", id, leg);
+            // The earlier legs took long enough for the last one's price to have moved
+            let guard = self.config.last_look.as_ref().filter(|_| leg == last && leg > 0);
+            if let Some(guard) = guard {
+                if let LastLook::Abort(_) = guard.check_graph(opportunity, &self.graph.load()) {
+                    self.record_latency(opportunity, &timer);
+                    self.stop(&opportunity.path, &plan, &mut position, Decimal::ZERO, id).await;
+                    return Ok(());
+                }
+            }
+            let held = position.get(start);
+            timer.leg_sent();
+            let result = self.place(order, &client_order_id, side_effect).await;
+            if let Ok(update) = &result {
+                position.record(order, update, &self.filters, self.config.fee_bps);
+            }
+            // With AUTO_REPAY the last leg's proceeds went to the loan
+            let repaid = if leg == last { (position.get(start) - held).max(Decimal::ZERO) } else { Decimal::ZERO };
+            if matches!(&result, Ok(update) if update.status == OrderStatus::Filled) {
+                continue;
+            }
+            self.record_latency(opportunity, &timer);
+            match &result {
+                Ok(update) => tracing::warn!(symbol = %order.symbol, leg, status = ?update.status, "Margin leg not filled, cycle stopped"),
+                Err(e) => tracing::warn!(symbol = %order.symbol, leg, error = %e, "Margin leg failed, cycle stopped"),
+            }
+            self.stop(&opportunity.path, &plan, &mut position, repaid, id).await;
+            result?;
+            return Ok(());
+        }
+        self.record_latency(opportunity, &timer);
+        tracing::info!(path = ?opportunity.path, %net_bps, "Margin cycle filled");
+        Ok(())
+    }
+
+    // Unwinds what a stopped cycle holds and repays whatever of the loan `repaid` didn't
+    async fn stop(&self, path: &[String], plan: &MarginPlan, position: &mut Position, repaid: Decimal, id: u128) {
+        if !position.is_flat() {
+            self.unwind(path, position, id).await;
+        }
+        if let Some((asset, borrowed)) = &plan.borrow {
+            let amount = *borrowed - repaid;
+            if amount > Decimal::ZERO {
+                match repay(&self.client, &self.credentials, asset, amount).await {
+                    Ok(tran_id) => tracing::info!(%asset, %amount, tran_id, "Repaid margin loan of a stopped cycle"),
+                    Err(e) => tracing::error!(%asset, %amount, error = %e, "Failed to repay margin loan, loan left open"),
+                }
+            }
+        }
+    }
+
+    // Places an order and follows it until it ends. The placement response can leave it
+    // open, in which case its status is polled until it ends or the tracker gives up on
+    // it; margin orders have no user data stream here, so polling starts at once.
+    async fn place(&self, order: &OrderRequest, client_order_id: &str, side_effect: SideEffect) -> Result<OrderUpdate, RestError> {
+        let update = place_order(&self.client, &self.credentials, order, client_order_id, side_effect).await?;
+        Ok(self.settle(order, update).await)
+    }
+
+    async fn settle(&self, order: &OrderRequest, update: OrderUpdate) -> OrderUpdate {
+        if update.status.is_terminal() {
+            return update;
+        }
+        let config = TrackerConfig { stream_timeout: Duration::ZERO, ..self.config.tracker.clone() };
+        let mut poll = tokio::time::interval(config.poll_interval);
+        let mut tracker = OrderTracker::new(config);
+        tracker.track(&order.symbol, &update.client_order_id, order.side, order.quantity, None);
+        tracker.apply(&update);
+        loop {
+            poll.tick().await;
+            let mut reports = tracker.reconcile_overdue_on(&self.client, &self.credentials, OrderAccount::Margin).await;
+            reports.extend(tracker.expire_stuck());
+            let Some(report) = reports.pop() else {
+                continue;
+            };
+            if report.timed_out {
+                tracing::warn!(symbol = %report.symbol, client_order_id = %report.client_order_id, status = ?report.status, "Margin order still open, left to the exchange");
+            }
+            return OrderUpdate {
+                client_order_id: report.client_order_id,
+                status: report.status,
+                executed_qty: report.executed_qty,
+                quote_qty: report.quote_qty,
+            };
+        }
+    }
+
+    fn record_latency(&self, opportunity: &Opportunity, timer: &LegTimer) {
+        if let Some(latency) = &self.config.latency {
+            latency.record(&opportunity.cycle_key(), timer);
+        }
+    }
+
+    // Clears what a stopped cycle left behind according to the unwind policy: first
+    // retrying the rest of the cycle if allowed, then selling into the home asset
+    async fn unwind(&self, path: &[String], position: &mut Position, id: u128) {
+        let config = &self.config.unwind;
+        let home = config.home(path);
+        let keep = [path[0].as_str(), home];
+        let mut attempt = 0;
+        if let UnwindPolicy::Retry(window) = config.policy {
+            let deadline = Instant::now() + window;
+            'retry: while Instant::now() < deadline {
+                // Furthest along the path first, so earlier proceeds don't overtake it
+                let Some((asset, amount)) = position
+                    .stranded(&keep)
+                    .into_iter()
+                    .filter_map(|(asset, amount)| Some((path.iter().rposition(|a| *a == asset)?, asset, amount)))
+                    .max_by_key(|(leg, _, _)| *leg)
+                    .map(|(_, asset, amount)| (asset, amount))
+                else {
+                    break;
+                };
+                let graph = self.graph.load();
+                let orders = match unwind::resume_orders(path, &asset, amount, &graph, &self.filters, self.config.fee_bps) {
+                    Some(Ok(orders)) => orders,
+                    Some(Err(FilterViolation { kind: ViolationKind::MinQuantity { .. } | ViolationKind::MinNotional { .. }, .. })) => break,
+                    Some(Err(e)) => {
+                        tracing::info!(%asset, %amount, error = %e, "Can't resume the cycle, unwinding at market");
+                        break;
+                    }
+                    None => break,
+                };
+                for order in &orders {
+                    attempt += 1;
+                    let client_order_id = format!("hft3m-{}-r{}", id, attempt);
+                    match self.place(order, &client_order_id, SideEffect::None).await {
+                        Ok(update) => {
+                            position.record(order, &update, &self.filters, self.config.fee_bps);
+                            if update.status != OrderStatus::Filled {
+                                tokio::time::sleep(config.retry_interval).await;
+                                continue 'retry;
+                            }
+                        }
+                        Err(e) => {
+                            tracing::warn!(symbol = %order.symbol, error = %e, "Retry of the cycle failed");
+                            tokio::time::sleep(config.retry_interval).await;
+                            continue 'retry;
+                        }
+                    }
+                }
+                tracing::info!(%asset, "Completed the cycle on retry");
+            }
+        }
+
+        let stranded = position.stranded(&keep);
+        if stranded.is_empty() {
+            return;
+        }
+        if config.policy == UnwindPolicy::Hold {
+            tracing::error!(position = ?stranded, "Cycle stopped partway, position held");
+            return;
+        }
+        let graph = self.graph.load();
+        for (asset, amount) in stranded {
+            let order = match unwind::market_out(&asset, amount, home, &graph, &self.filters) {
+                Ok(order) => order,
+                // Rounding leftovers too small to trade
+                Err(FilterViolation { kind: ViolationKind::MinQuantity { .. } | ViolationKind::MinNotional { .. }, .. }) => {
+                    tracing::debug!(%asset, %amount, "Dust left after the cycle");
+                    continue;
+                }
+                Err(e) => {
+                    tracing::error!(%asset, %amount, %home, error = %e, "Can't unwind, position left open");
+                    continue;
+                }
+            };
+            attempt += 1;
+            let client_order_id = format!("hft3m-{}-u{}", id, attempt);
+            match self.place(&order, &client_order_id, SideEffect::None).await {
+                Ok(update) => {
+                    position.record(&order, &update, &self.filters, self.config.fee_bps);
+                    tracing::info!(%asset, quantity = %update.executed_qty, %home, status = ?update.status, "Unwound at market");
+                }
+                Err(e) => tracing::error!(%asset, %amount, %home, error = %e, "Unwind order failed, position left open"),
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::events::MarketEvent;
+use crate::feed::Feed;
+
+// Two feeds read as one, events passed on in arrival order. The primary decides the
+// venue and when the merged feed ends; a secondary that ends is dropped from then on.
+// Both feeds' next_event must survive cancellation, as the Feed contract asks.
+pub struct MergedFeed<A, B> {
+    primary: A,
+    secondary: Option<B>,
+}
+
+impl<A: Feed, B: Feed> MergedFeed<A, B> {
+    pub fn new(primary: A, secondary: B) -> Self {
+        MergedFeed {
+            primary,
+            secondary: Some(secondary),
+        }
+    }
+}
+
+impl<A: Feed, B: Feed> Feed for MergedFeed<A, B> {
+    fn venue(&self) -> &str {
+        self.primary.venue()
+    }
+
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        loop {
+            let Some(secondary) = self.secondary.as_mut() else {
+                return self.primary.next_event().await;
+            };
+            tokio::select! {
+                event = self.primary.next_event() => return event,
+                event = secondary.next_event() => match event {
+                    Some(event) => return Some(event),
+                    None => {
+                        tracing::warn!(venue = secondary.venue(), "Secondary feed ended");
+                        self.secondary = None;
+                    }
+                },
+            }
+        }
+    }
+
+    async fn close(&mut self) {
+        if let Some(secondary) = self.secondary.as_mut() {
+            secondary.close().await;
+        }
+        self.primary.close().await;
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::dedup::{DedupConfig, Deduplicator};
+use crate::events::{EngineEvent, Opportunity, OpportunitySummary};
+
+// Upper bounds of the histogram buckets; the last one catches everything above
+const PROFIT_BUCKETS_BPS: [f64; 8] = [1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, f64::INFINITY];
+const LIFETIME_BUCKETS_MS: [u64; 7] = [10, 100, 1_000, 5_000, 30_000, 300_000, u64::MAX];
+
+// Count, sum and maximum plus bucket counts
+#[derive(Debug, Clone, Default)]
+struct Distribution {
+    count: u64,
+    sum: f64,
+    max: f64,
+    buckets: Vec<u64>,
+}
+
+impl Distribution {
+    fn record(&mut self, value: f64, bucket: usize, buckets: usize) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![0; buckets];
+        }
+        self.buckets[bucket] += 1;
+        self.max = if self.count == 0 { value } else { self.max.max(value) };
+        self.count += 1;
+        self.sum += value;
+    }
+
+    fn mean(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+
+    fn to_json(&self, bounds: impl Iterator<Item = Option<f64>>) -> Value {
+        let histogram: Vec<Value> = bounds
+            .enumerate()
+            .map(|(i, le)| json!({ "le": le, "count": self.buckets.get(i).copied().unwrap_or(0) }))
+            .collect();
+        json!({
+            "count": self.count,
+            "mean": self.mean(),
+            "max": (self.count > 0).then_some(self.max),
+            "histogram": histogram,
+        })
+    }
+}
+
+// What one cycle has done since startup
+#[derive(Debug, Clone, Default)]
+pub struct CycleStats {
+    pub cycle: String,
+    pub triggers: u64,   // Times the cycle opened
+    pub reports: u64,    // Opportunity events, repeats while open included
+    pub detections: u64, // Detections of closed streaks, including repeats dedup suppressed
+    pub executed: u64,
+    profit_bps: Distribution,
+    lifetime_ms: Distribution,
+}
+
+impl CycleStats {
+    pub fn mean_profit_bps(&self) -> Option<f64> {
+        self.profit_bps.mean()
+    }
+
+    pub fn mean_lifetime_ms(&self) -> Option<f64> {
+        self.lifetime_ms.mean()
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "cycle": self.cycle,
+            "triggers": self.triggers,
+            "reports": self.reports,
+            "detections": self.detections,
+            "executed": self.executed,
+            "mean_profit_bps": self.mean_profit_bps(),
+            "best_profit_bps": (self.profit_bps.count > 0).then_some(self.profit_bps.max),
+            "mean_lifetime_ms": self.mean_lifetime_ms(),
+            "longest_lifetime_ms": (self.lifetime_ms.count > 0).then_some(self.lifetime_ms.max),
+        })
+    }
+}
+
+// In-memory analytics over reported opportunities: how often each cycle opens, how
+// profitable reports are and how long cycles stay open, overall and per cycle and asset.
+// Lifetimes come from the engine's OpportunityClosed events when deduplication runs;
+// without it, `new` is given the close timeout and tracks streaks itself.
+pub struct OpportunityStats {
+    started: Instant,
+    cycles: HashMap<String, CycleStats>,
+    assets: HashMap<String, u64>, // Triggers of cycles through each asset
+    open: HashSet<String>,
+    tracker: Option<Deduplicator>, // Own streak tracking when the engine doesn't dedup
+    profit_bps: Distribution,
+    lifetime_ms: Distribution,
+    reports: u64,
+    triggers: u64,
+}
+
+impl OpportunityStats {
+    // `close_after` is None when the engine deduplicates and reports closed cycles itself
+    pub fn new(close_after: Option<Duration>) -> Self {
+        OpportunityStats {
+            started: Instant::now(),
+            cycles: HashMap::new(),
+            assets: HashMap::new(),
+            open: HashSet::new(),
+            tracker: close_after.map(|close_after| {
+                Deduplicator::new(DedupConfig {
+                    cooldown: Duration::ZERO,
+                    close_after,
+                })
+            }),
+            profit_bps: Distribution::default(),
+            lifetime_ms: Distribution::default(),
+            reports: 0,
+            triggers: 0,
+        }
+    }
+
+    pub fn record(&mut self, event: &EngineEvent, now: Instant) {
+        match event {
+            EngineEvent::Opportunity(opportunity) => {
+                if let Some(tracker) = self.tracker.as_mut() {
+                    tracker.observe(opportunity, now);
+                }
+                self.record_opportunity(opportunity);
+            }
+            EngineEvent::OpportunityClosed(summary) if self.tracker.is_none() => self.record_closed(summary),
+            _ => {}
+        }
+        self.close_expired(now);
+    }
+
+    // Closes streaks this tracker ran out of time on; call periodically when events are rare
+    pub fn close_expired(&mut self, now: Instant) {
+        let Some(tracker) = self.tracker.as_mut() else {
+            return;
+        };
+        for summary in tracker.close_expired(now) {
+            self.record_closed(&summary);
+        }
+    }
+
+    fn record_opportunity(&mut self, opportunity: &Opportunity) {
+        let key = opportunity.cycle_key();
+        let cycle = self.cycles.entry(key.clone()).or_insert_with(|| CycleStats {
+            cycle: key.clone(),
+            ..Default::default()
+        });
+        cycle.reports += 1;
+        self.reports += 1;
+        if opportunity.executed {
+            cycle.executed += 1;
+        }
+        if let Some(profit) = opportunity.profit {
+            let bps = profit * 10_000.0;
+            let bucket = PROFIT_BUCKETS_BPS.iter().position(|&bound| bps <= bound).unwrap_or(PROFIT_BUCKETS_BPS.len() - 1);
+            cycle.profit_bps.record(bps, bucket, PROFIT_BUCKETS_BPS.len());
+            self.profit_bps.record(bps, bucket, PROFIT_BUCKETS_BPS.len());
+        }
+        if self.open.insert(key.clone()) {
+            cycle.triggers += 1;
+            self.triggers += 1;
+            for asset in key.split('>') {
+                *self.assets.entry(asset.to_string()).or_default() += 1;
+            }
+        }
+    }
+
+    fn record_closed(&mut self, summary: &OpportunitySummary) {
+        self.open.remove(&summary.cycle);
+        let ms = summary.duration.as_millis() as u64;
+        let bucket = LIFETIME_BUCKETS_MS.iter().position(|&bound| ms <= bound).unwrap_or(LIFETIME_BUCKETS_MS.len() - 1);
+        if let Some(cycle) = self.cycles.get_mut(&summary.cycle) {
+            cycle.detections += summary.detections;
+            cycle.lifetime_ms.record(ms as f64, bucket, LIFETIME_BUCKETS_MS.len());
+        }
+        self.lifetime_ms.record(ms as f64, bucket, LIFETIME_BUCKETS_MS.len());
+    }
+
+    // Cycles that opened most often, most profitable on average first among ties
+    pub fn top_cycles(&self, limit: usize) -> Vec<&CycleStats> {
+        let mut cycles: Vec<&CycleStats> = self.cycles.values().collect();
+        cycles.sort_by(|a, b| {
+            b.triggers
+                .cmp(&a.triggers)
+                .then(b.mean_profit_bps().unwrap_or(f64::MIN).total_cmp(&a.mean_profit_bps().unwrap_or(f64::MIN)))
+                .then(a.cycle.cmp(&b.cycle))
+        });
+        cycles.truncate(limit);
+        cycles
+    }
+
+    // Assets on the cycles that opened most often, with their trigger counts
+    pub fn top_assets(&self, limit: usize) -> Vec<(&str, u64)> {
+        let mut assets: Vec<(&str, u64)> = self.assets.iter().map(|(asset, n)| (asset.as_str(), *n)).collect();
+        assets.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        assets.truncate(limit);
+        assets
+    }
+
+    pub fn reports(&self) -> u64 {
+        self.reports
+    }
+
+    pub fn triggers(&self) -> u64 {
+        self.triggers
+    }
+
+    pub fn mean_profit_bps(&self) -> Option<f64> {
+        self.profit_bps.mean()
+    }
+
+    pub fn mean_lifetime_ms(&self) -> Option<f64> {
+        self.lifetime_ms.mean()
+    }
+
+    pub fn summary(&self, top: usize) -> Value {
+        let profit_bounds = PROFIT_BUCKETS_BPS.iter().map(|&b| b.is_finite().then_some(b));
+        let lifetime_bounds = LIFETIME_BUCKETS_MS.iter().map(|&b| (b != u64::MAX).then_some(b as f64));
+        json!({
+            "since_s": self.started.elapsed().as_secs(),
+            "reports": self.reports,
+            "triggers": self.triggers,
+            "cycles": self.cycles.len(),
+            "open": self.open.len(),
+            "profit_bps": self.profit_bps.to_json(profit_bounds),
+            "lifetime_ms": self.lifetime_ms.to_json(lifetime_bounds),
+            "top_cycles": self.top_cycles(top).iter().map(|c| c.to_json()).collect::<Vec<_>>(),
+            "top_assets": self
+                .top_assets(top)
+                .iter()
+                .map(|(asset, triggers)| json!({ "asset": asset, "triggers": triggers }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
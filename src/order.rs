@@ -0,0 +1,26 @@
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+impl Side {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Side::Buy => "BUY",
+            Side::Sell => "SELL",
+        }
+    }
+}
+
+// An order we want placed; quantity is in base asset units
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: Decimal,
+    pub price: Option<Decimal>, // Limit price (IOC); None for a market order
+}
@@ -0,0 +1,541 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::order::Side;
+use crate::rest::{ApiCredentials, EndpointCategory, RestClient, RestError};
+use crate::user_stream::AccountEvent;
+
+const REPORT_CAPACITY: usize = 256;
+
+// Binance order states
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStatus {
+    New,
+    PendingNew,
+    PartiallyFilled,
+    Filled,
+    Canceled,
+    PendingCancel,
+    Rejected,
+    Expired,
+    ExpiredInMatch,
+}
+
+impl OrderStatus {
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            OrderStatus::Filled | OrderStatus::Canceled | OrderStatus::Rejected | OrderStatus::Expired | OrderStatus::ExpiredInMatch
+        )
+    }
+
+    // Whether an order in this state can move to `next`: NEW → PARTIALLY_FILLED → FILLED,
+    // or off to CANCELED, REJECTED or EXPIRED on the way. Terminal states are final.
+    pub fn can_become(&self, next: OrderStatus) -> bool {
+        use OrderStatus::*;
+        match self {
+            PendingNew => next != PendingNew,
+            New => !matches!(next, PendingNew | New),
+            PartiallyFilled => !matches!(next, PendingNew | New | Rejected),
+            PendingCancel => !matches!(next, PendingNew | New | PendingCancel | Rejected),
+            Filled | Canceled | Rejected | Expired | ExpiredInMatch => false,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrderStatus::New => "NEW",
+            OrderStatus::PendingNew => "PENDING_NEW",
+            OrderStatus::PartiallyFilled => "PARTIALLY_FILLED",
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Canceled => "CANCELED",
+            OrderStatus::PendingCancel => "PENDING_CANCEL",
+            OrderStatus::Rejected => "REJECTED",
+            OrderStatus::Expired => "EXPIRED",
+            OrderStatus::ExpiredInMatch => "EXPIRED_IN_MATCH",
+        }
+    }
+}
+
+// Latest known state of an order, from either the user data stream or a REST poll
+#[derive(Debug, Clone)]
+pub struct OrderUpdate {
+    pub client_order_id: String,
+    pub status: OrderStatus,
+    pub executed_qty: Decimal,
+    pub quote_qty: Decimal, // Cumulative quote spent or received
+}
+
+#[derive(Debug, Clone)]
+pub struct InFlightOrder {
+    pub symbol: String,
+    pub client_order_id: String,
+    pub cycle: Option<String>, // Cycle the order is a leg of
+    pub side: Side,
+    pub quantity: Decimal,
+    pub status: OrderStatus,
+    pub executed_qty: Decimal,
+    pub quote_qty: Decimal,
+    pub submitted: Instant,
+    pub last_heard: Instant, // Submission or the latest update from any source
+    pub polls: u32,
+    pub transitions: Vec<OrderStatus>, // Every state the order went through, in order
+}
+
+// How a tracked order ended
+#[derive(Debug, Clone)]
+pub struct ExecutionReport {
+    pub symbol: String,
+    pub client_order_id: String,
+    pub side: Side,
+    pub quantity: Decimal,
+    pub status: OrderStatus, // Last known; not terminal when the order timed out
+    pub executed_qty: Decimal,
+    pub quote_qty: Decimal,
+    pub timed_out: bool,
+    pub elapsed: Duration, // Submission to the terminal update or the timeout
+    pub transitions: Vec<OrderStatus>,
+}
+
+impl ExecutionReport {
+    fn new(order: InFlightOrder, timed_out: bool, now: Instant) -> Self {
+        ExecutionReport {
+            symbol: order.symbol,
+            client_order_id: order.client_order_id,
+            side: order.side,
+            quantity: order.quantity,
+            status: order.status,
+            executed_qty: order.executed_qty,
+            quote_qty: order.quote_qty,
+            timed_out,
+            elapsed: now.duration_since(order.submitted),
+            transitions: order.transitions,
+        }
+    }
+
+    pub fn filled(&self) -> bool {
+        self.status == OrderStatus::Filled
+    }
+
+    pub fn avg_price(&self) -> Option<Decimal> {
+        (!self.executed_qty.is_zero()).then(|| self.quote_qty / self.executed_qty)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "symbol": self.symbol,
+            "client_order_id": self.client_order_id,
+            "side": self.side.as_str(),
+            "quantity": self.quantity.to_string(),
+            "status": self.status.as_str(),
+            "executed_qty": self.executed_qty.to_string(),
+            "quote_qty": self.quote_qty.to_string(),
+            "avg_price": self.avg_price().map(|p| p.normalize().to_string()),
+            "timed_out": self.timed_out,
+            "elapsed_ms": self.elapsed.as_millis() as u64,
+            "transitions": self.transitions.iter().map(OrderStatus::as_str).collect::<Vec<_>>(),
+        })
+    }
+}
+
+// Every leg of one cycle execution, once all of them have ended
+#[derive(Debug, Clone)]
+pub struct CycleReport {
+    pub cycle_id: String,
+    pub path: Vec<String>,
+    pub legs: Vec<ExecutionReport>, // In submission order
+    pub elapsed: Duration,          // First submission to the last leg ending
+}
+
+impl CycleReport {
+    pub fn filled(&self) -> bool {
+        !self.legs.is_empty() && self.legs.iter().all(ExecutionReport::filled)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "cycle_id": self.cycle_id,
+            "path": self.path,
+            "filled": self.filled(),
+            "elapsed_ms": self.elapsed.as_millis() as u64,
+            "legs": self.legs.iter().map(ExecutionReport::to_json).collect::<Vec<_>>(),
+        })
+    }
+}
+
+// A cycle whose legs are still being submitted or haven't all ended
+struct OpenCycle {
+    path: Vec<String>,
+    started: Instant,
+    order_ids: Vec<String>, // Submission order
+    done: HashMap<String, ExecutionReport>,
+    submitted: bool, // No more legs coming
+}
+
+#[derive(Debug, Clone)]
+pub struct TrackerConfig {
+    pub stream_timeout: Duration, // Silence after which the order is polled over REST
+    pub poll_interval: Duration,  // Between polls while it stays silent
+    pub order_timeout: Duration,  // Orders not done by then are given up on as stuck
+}
+
+impl Default for TrackerConfig {
+    fn default() -> Self {
+        TrackerConfig {
+            stream_timeout: Duration::from_secs(2),
+            poll_interval: Duration::from_secs(1),
+            order_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+// Response of GET /api/v3/order
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QueryOrderResponse {
+    client_order_id: String,
+    status: OrderStatus,
+    executed_qty: String,
+    cummulative_quote_qty: String,
+}
+
+// Follows in-flight orders through their states and falls back to polling order status
+// over REST when the user data stream goes quiet, so an execution never waits forever on a
+// dropped event. Orders still open after the order timeout are reported as stuck. Orders
+// tracked as legs of a cycle are reported together once the whole cycle is done.
+pub struct OrderTracker {
+    config: TrackerConfig,
+    orders: HashMap<String, InFlightOrder>,
+    cycles: HashMap<String, OpenCycle>,
+    finished: Vec<CycleReport>,
+}
+
+impl OrderTracker {
+    pub fn new(config: TrackerConfig) -> Self {
+        OrderTracker {
+            config,
+            orders: HashMap::new(),
+            cycles: HashMap::new(),
+            finished: Vec::new(),
+        }
+    }
+
+    // Opens a cycle; its legs are tracked with its ID until `end_cycle`
+    pub fn begin_cycle(&mut self, cycle_id: &str, path: &[String]) {
+        self.cycles.insert(
+            cycle_id.to_string(),
+            OpenCycle {
+                path: path.to_vec(),
+                started: Instant::now(),
+                order_ids: Vec::new(),
+                done: HashMap::new(),
+                submitted: false,
+            },
+        );
+    }
+
+    // No more legs will be submitted for the cycle
+    pub fn end_cycle(&mut self, cycle_id: &str) {
+        if let Some(cycle) = self.cycles.get_mut(cycle_id) {
+            cycle.submitted = true;
+        }
+        self.finish_cycle(cycle_id);
+    }
+
+    pub fn track(&mut self, symbol: &str, client_order_id: &str, side: Side, quantity: Decimal, cycle: Option<&str>) {
+        let cycle = cycle.filter(|id| self.cycles.contains_key(*id));
+        if let Some(open) = cycle.and_then(|id| self.cycles.get_mut(id)) {
+            open.order_ids.push(client_order_id.to_string());
+        }
+        let now = Instant::now();
+        self.orders.insert(
+            client_order_id.to_string(),
+            InFlightOrder {
+                symbol: symbol.to_string(),
+                client_order_id: client_order_id.to_string(),
+                cycle: cycle.map(str::to_string),
+                side,
+                quantity,
+                status: OrderStatus::PendingNew,
+                executed_qty: Decimal::ZERO,
+                quote_qty: Decimal::ZERO,
+                submitted: now,
+                last_heard: now,
+                polls: 0,
+                transitions: vec![OrderStatus::PendingNew],
+            },
+        );
+    }
+
+    pub fn get(&self, client_order_id: &str) -> Option<&InFlightOrder> {
+        self.orders.get(client_order_id)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.orders.len()
+    }
+
+    // Folds in an update from any source. Returns the order's report once it reaches a
+    // terminal state, at which point it stops being tracked. Updates only move an order
+    // forward: a state it can't reach from where it is, or less executed quantity, is an
+    // older update arriving late and is ignored.
+    pub fn apply(&mut self, update: &OrderUpdate) -> Option<ExecutionReport> {
+        let order = self.orders.get_mut(&update.client_order_id)?;
+        order.last_heard = Instant::now();
+        let progressed = update.status == order.status && update.executed_qty > order.executed_qty;
+        if !order.status.can_become(update.status) && !progressed {
+            if update.status != order.status {
+                tracing::debug!(client_order_id = %order.client_order_id, from = ?order.status, to = ?update.status, "Ignoring stale order update");
+            }
+            return None;
+        }
+        if update.executed_qty >= order.executed_qty {
+            order.executed_qty = update.executed_qty;
+            order.quote_qty = update.quote_qty;
+        }
+        if update.status != order.status {
+            order.status = update.status;
+            order.transitions.push(update.status);
+        }
+        if !order.status.is_terminal() {
+            return None;
+        }
+        let order = self.orders.remove(&update.client_order_id)?;
+        Some(self.complete(order, false))
+    }
+
+    // Gives up on orders still open after the order timeout, reporting them as stuck
+    pub fn expire_stuck(&mut self) -> Vec<ExecutionReport> {
+        let now = Instant::now();
+        let stuck: Vec<String> = self
+            .orders
+            .values()
+            .filter(|order| now.duration_since(order.submitted) >= self.config.order_timeout)
+            .map(|order| order.client_order_id.clone())
+            .collect();
+        let mut reports = Vec::new();
+        for client_order_id in stuck {
+            if let Some(order) = self.orders.remove(&client_order_id) {
+                tracing::warn!(symbol = %order.symbol, %client_order_id, status = ?order.status, "Order stuck, no longer tracked");
+                reports.push(self.complete(order, true));
+            }
+        }
+        reports
+    }
+
+    // Cycles whose every leg has ended since the last call
+    pub fn take_cycle_reports(&mut self) -> Vec<CycleReport> {
+        std::mem::take(&mut self.finished)
+    }
+
+    fn complete(&mut self, order: InFlightOrder, timed_out: bool) -> ExecutionReport {
+        let cycle = order.cycle.clone();
+        let report = ExecutionReport::new(order, timed_out, Instant::now());
+        if let Some(id) = cycle {
+            if let Some(open) = self.cycles.get_mut(&id) {
+                open.done.insert(report.client_order_id.clone(), report.clone());
+            }
+            self.finish_cycle(&id);
+        }
+        report
+    }
+
+    fn finish_cycle(&mut self, cycle_id: &str) {
+        let ready = self
+            .cycles
+            .get(cycle_id)
+            .is_some_and(|open| open.submitted && open.order_ids.iter().all(|id| open.done.contains_key(id)));
+        if !ready {
+            return;
+        }
+        let Some(mut open) = self.cycles.remove(cycle_id) else {
+            return;
+        };
+        let legs = open.order_ids.iter().filter_map(|id| open.done.remove(id)).collect();
+        self.finished.push(CycleReport {
+            cycle_id: cycle_id.to_string(),
+            path: open.path,
+            legs,
+            elapsed: open.started.elapsed(),
+        });
+    }
+
+    // Orders that haven't been heard from within the stream timeout
+    fn overdue(&self, now: Instant) -> Vec<(String, String)> {
+        self.orders
+            .values()
+            .filter(|order| {
+                let wait = if order.polls == 0 { self.config.stream_timeout } else { self.config.poll_interval };
+                now.duration_since(order.last_heard) >= wait
+            })
+            .map(|order| (order.symbol.clone(), order.client_order_id.clone()))
+            .collect()
+    }
+
+    // Polls every overdue order and reconciles it; returns the orders that completed
+    pub async fn reconcile_overdue(&mut self, client: &RestClient, credentials: &ApiCredentials) -> Vec<ExecutionReport> {
+        self.reconcile_overdue_on(client, credentials, OrderAccount::Spot).await
+    }
+
+    // `reconcile_overdue` for orders placed on `account`
+    pub async fn reconcile_overdue_on(
+        &mut self,
+        client: &RestClient,
+        credentials: &ApiCredentials,
+        account: OrderAccount,
+    ) -> Vec<ExecutionReport> {
+        let mut completed = Vec::new();
+        for (symbol, client_order_id) in self.overdue(Instant::now()) {
+            if let Some(order) = self.orders.get_mut(&client_order_id) {
+                order.polls += 1;
+                tracing::warn!(%symbol, %client_order_id, polls = order.polls, "No stream update for order, polling REST");
+            }
+            match query_order_on(client, credentials, account, &symbol, &client_order_id).await {
+                Ok(update) => completed.extend(self.apply(&update)),
+                Err(e) => {
+                    tracing::warn!(%symbol, %client_order_id, error = %e, "Order status poll failed");
+                    if let Some(order) = self.orders.get_mut(&client_order_id) {
+                        order.last_heard = Instant::now(); // Back off for a poll interval
+                    }
+                }
+            }
+        }
+        completed
+    }
+}
+
+enum MonitorCommand {
+    BeginCycle(String, Vec<String>),
+    Track { symbol: String, client_order_id: String, side: Side, quantity: Decimal, cycle: Option<String> },
+    EndCycle(String),
+}
+
+// An order tracker on its own task, fed by an account event stream (the user data stream
+// or a FIX session's execution reports). Executors register orders as they submit them;
+// finished cycles are broadcast as reports. With REST access, silent orders are polled.
+#[derive(Clone)]
+pub struct ExecutionMonitor {
+    commands: mpsc::UnboundedSender<MonitorCommand>,
+    reports: broadcast::Sender<CycleReport>,
+}
+
+impl ExecutionMonitor {
+    pub fn spawn(
+        config: TrackerConfig,
+        events: broadcast::Receiver<AccountEvent>,
+        rest: Option<(Arc<RestClient>, ApiCredentials)>,
+    ) -> Self {
+        let (commands, receiver) = mpsc::unbounded_channel();
+        let (reports, _) = broadcast::channel(REPORT_CAPACITY);
+        tokio::spawn(monitor(OrderTracker::new(config), events, receiver, rest, reports.clone()));
+        ExecutionMonitor { commands, reports }
+    }
+
+    pub fn begin_cycle(&self, cycle_id: &str, path: &[String]) {
+        let _ = self.commands.send(MonitorCommand::BeginCycle(cycle_id.to_string(), path.to_vec()));
+    }
+
+    pub fn track(&self, symbol: &str, client_order_id: &str, side: Side, quantity: Decimal, cycle: Option<&str>) {
+        let _ = self.commands.send(MonitorCommand::Track {
+            symbol: symbol.to_string(),
+            client_order_id: client_order_id.to_string(),
+            side,
+            quantity,
+            cycle: cycle.map(str::to_string),
+        });
+    }
+
+    pub fn end_cycle(&self, cycle_id: &str) {
+        let _ = self.commands.send(MonitorCommand::EndCycle(cycle_id.to_string()));
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<CycleReport> {
+        self.reports.subscribe()
+    }
+}
+
+async fn monitor(
+    mut tracker: OrderTracker,
+    mut events: broadcast::Receiver<AccountEvent>,
+    mut commands: mpsc::UnboundedReceiver<MonitorCommand>,
+    rest: Option<(Arc<RestClient>, ApiCredentials)>,
+    reports: broadcast::Sender<CycleReport>,
+) {
+    let mut ticker = tokio::time::interval(tracker.config.poll_interval);
+    loop {
+        tokio::select! {
+            command = commands.recv() => match command {
+                Some(MonitorCommand::BeginCycle(id, path)) => tracker.begin_cycle(&id, &path),
+                Some(MonitorCommand::Track { symbol, client_order_id, side, quantity, cycle }) => {
+                    tracker.track(&symbol, &client_order_id, side, quantity, cycle.as_deref());
+                }
+                Some(MonitorCommand::EndCycle(id)) => tracker.end_cycle(&id),
+                None => break,
+            },
+            event = events.recv() => match event {
+                Ok(AccountEvent::Order(update)) => {
+                    tracker.apply(&update);
+                }
+                Ok(AccountEvent::Fill(_)) => {}
+                // Missed updates are caught by polling or the order timeout
+                Err(RecvError::Lagged(skipped)) => tracing::warn!(skipped, "Execution monitor missed account events"),
+                Err(RecvError::Closed) => break,
+            },
+            _ = ticker.tick() => {
+                if let Some((client, credentials)) = &rest {
+                    tracker.reconcile_overdue(client, credentials).await;
+                }
+                tracker.expire_stuck();
+            }
+        }
+        for report in tracker.take_cycle_reports() {
+            let _ = reports.send(report);
+        }
+    }
+}
+
+// Which of the account's wallets an order was placed on; each has its own order endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderAccount {
+    Spot,
+    Margin, // Cross margin
+}
+
+// GET /api/v3/order by client order id (weight 4)
+pub async fn query_order(
+    client: &RestClient,
+    credentials: &ApiCredentials,
+    symbol: &str,
+    client_order_id: &str,
+) -> Result<OrderUpdate, RestError> {
+    query_order_on(client, credentials, OrderAccount::Spot, symbol, client_order_id).await
+}
+
+// `query_order` on `account`; GET /sapi/v1/margin/order (weight 10) for margin
+pub async fn query_order_on(
+    client: &RestClient,
+    credentials: &ApiCredentials,
+    account: OrderAccount,
+    symbol: &str,
+    client_order_id: &str,
+) -> Result<OrderUpdate, RestError> {
+    let query = [("symbol", symbol.to_string()), ("origClientOrderId", client_order_id.to_string())];
+    let body = match account {
+        OrderAccount::Spot => client.signed_get(EndpointCategory::Account, 4, "/api/v3/order", &query, credentials).await?,
+        OrderAccount::Margin => client.signed_get(EndpointCategory::Margin, 10, "/sapi/v1/margin/order", &query, credentials).await?,
+    };
+    let response: QueryOrderResponse = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+    Ok(OrderUpdate {
+        client_order_id: response.client_order_id,
+        status: response.status,
+        executed_qty: response.executed_qty.parse().unwrap_or_default(),
+        quote_qty: response.cummulative_quote_qty.parse().unwrap_or_default(),
+    })
+}
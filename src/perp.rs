@@ -0,0 +1,229 @@
+use std::time::{Duration, Instant};
+
+use futures_util::stream::StreamExt;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use crate::events::MarketEvent;
+use crate::feed::Feed;
+use crate::graph::extract_currency_pair;
+use crate::proxy::{connect_websocket, Proxy, WsStream};
+use crate::ticker::TickerData;
+use crate::watchdog::{self, Watchdog};
+
+pub const BINANCE_USDM_WS_URL: &str = "wss://fstream.binance.com/stream";
+pub const BINANCE_USDM_TESTNET_WS_URL: &str = "wss://stream.binancefuture.com/stream";
+
+// Venue name of USD-M futures market data, in health metrics and logs
+pub const VENUE: &str = "binance-usdm";
+
+// Mark price and funding of every USD-M perpetual, once a second
+pub const MARK_PRICE_STREAM: &str = "!markPrice@arr@1s";
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Mark price and funding of one USD-M perpetual, from the `markPriceUpdate` stream.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct MarkPrice {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p")]
+    pub mark_price: String,
+    #[serde(rename = "i", default)]
+    pub index_price: String,
+    #[serde(rename = "r", default)]
+    pub funding_rate: String, // Rate of the funding due at `next_funding_time`, as a fraction
+    #[serde(rename = "T", default)]
+    pub next_funding_time: u64, // ms
+    #[serde(rename = "E", default)]
+    pub event_time: u64, // ms
+}
+
+// Futures bookTicker payload; unlike spot's it carries the symbol, times and update ID
+#[derive(serde::Deserialize)]
+struct BookTicker {
+    s: String,
+    b: String,
+    #[serde(rename = "B", default)]
+    bid_qty: String,
+    a: String,
+    #[serde(rename = "A", default)]
+    ask_qty: String,
+    #[serde(rename = "E", default)]
+    event_time: u64,
+    #[serde(rename = "u", default)]
+    update_id: Option<u64>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    Many(Vec<T>),
+    One(T),
+}
+
+impl<T> OneOrMany<T> {
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            OneOrMany::Many(items) => items,
+            OneOrMany::One(item) => vec![item],
+        }
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct CombinedMessage {
+    stream: String,
+    data: serde_json::Value,
+}
+
+/// Graph symbol of a USD-M contract, kept apart from the spot pair it tracks: the base
+/// gets the delivery date, or `PERP` for a perpetual, so `BTCUSDT` trades as `BTC-PERP`
+/// against USDT and `BTCUSDT_250627` as `BTC-250627`.
+pub fn instrument_symbol(symbol: &str) -> String {
+    let (pair, expiry) = match symbol.split_once('_') {
+        Some((pair, expiry)) => (pair, expiry),
+        None => (symbol, "PERP"),
+    };
+    let (base, quote) = extract_currency_pair(pair);
+    format!("{}-{}{}", base, expiry, quote)
+}
+
+// One combined stream message as an event, by the stream it came from. Prices become
+// tickers under their instrument symbol; mark prices keep the exchange's.
+fn parse_message(text: &str) -> Result<Option<MarketEvent>, serde_json::Error> {
+    let message: CombinedMessage = serde_json::from_str(text)?;
+    let kinds: Vec<&str> = message.stream.trim_start_matches('!').split('@').collect();
+    let event = if kinds.contains(&"markPrice") {
+        let marks: OneOrMany<MarkPrice> = serde_json::from_value(message.data)?;
+        MarketEvent::MarkPrices(marks.into_vec())
+    } else if kinds.contains(&"bookTicker") {
+        let book: OneOrMany<BookTicker> = serde_json::from_value(message.data)?;
+        let tickers = book.into_vec().into_iter().filter_map(|book| {
+            let mid = (book.b.parse::<f64>().ok()? + book.a.parse::<f64>().ok()?) / 2.0;
+            Some(TickerData {
+                s: instrument_symbol(&book.s),
+                c: mid.to_string(),
+                event_time: book.event_time,
+                b: Some(book.b),
+                bid_qty: Some(book.bid_qty),
+                a: Some(book.a),
+                ask_qty: Some(book.ask_qty),
+                update_id: book.update_id,
+                quote_volume: None,
+                trade: false,
+            })
+        });
+        MarketEvent::Tickers(tickers.collect())
+    } else if kinds.contains(&"ticker") || kinds.contains(&"miniTicker") {
+        // 24h tickers carry the last price but, on futures, no top of book
+        let tickers: OneOrMany<TickerData> = serde_json::from_value(message.data)?;
+        let tickers = tickers.into_vec().into_iter().map(|ticker| TickerData {
+            s: instrument_symbol(&ticker.s),
+            ..ticker
+        });
+        MarketEvent::Tickers(tickers.collect())
+    } else {
+        return Ok(None);
+    };
+    Ok(Some(event))
+}
+
+// USD-M futures market data: mark prices and funding, top of book and 24h tickers, on
+// whichever streams it is given. Reconnects after a dropped connection: the stream is
+// secondary to the spot feed, which decides when the engine stops.
+pub struct FuturesFeed {
+    endpoint: String,
+    streams: Vec<String>,
+    proxy: Option<Proxy>,
+    ws: Option<WsStream>,
+    watchdog: Option<Watchdog>,
+}
+
+impl FuturesFeed {
+    pub async fn connect(
+        endpoint: &str,
+        streams: &[String],
+        proxy: Option<Proxy>,
+    ) -> Result<Self, tokio_tungstenite::tungstenite::Error> {
+        let mut feed = FuturesFeed {
+            endpoint: endpoint.to_string(),
+            streams: streams.to_vec(),
+            proxy,
+            ws: None,
+            watchdog: None,
+        };
+        feed.ws = Some(feed.open().await?);
+        Ok(feed)
+    }
+
+    // Reconnects when the stream delivers nothing for `max_silence`
+    pub fn with_watchdog(mut self, max_silence: Duration) -> Self {
+        self.watchdog = Some(Watchdog::new(max_silence));
+        self
+    }
+
+    async fn open(&self) -> Result<WsStream, tokio_tungstenite::tungstenite::Error> {
+        let url = format!("{}?streams={}", self.endpoint, self.streams.join("/"));
+        connect_websocket(&url, self.proxy.as_ref()).await.map(|(ws, _)| ws)
+    }
+}
+
+impl Feed for FuturesFeed {
+    fn venue(&self) -> &str {
+        VENUE
+    }
+
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        loop {
+            let Some(ws) = self.ws.as_mut() else {
+                tokio::time::sleep(RECONNECT_DELAY).await;
+                match self.open().await {
+                    Ok(ws) => {
+                        tracing::info!("Reconnected to the USD-M futures stream");
+                        self.ws = Some(ws);
+                        if let Some(watchdog) = self.watchdog.as_mut() {
+                            watchdog.reset();
+                        }
+                    }
+                    Err(e) => tracing::warn!(error = %e, "Failed to reconnect to the USD-M futures stream"),
+                }
+                continue;
+            };
+            let message = tokio::select! {
+                message = ws.next() => message,
+                _ = watchdog::expired(self.watchdog.as_ref()) => {
+                    let silent_ms = self.watchdog.as_ref().map_or(0, |w| w.silent_for(Instant::now()).as_millis() as u64);
+                    tracing::warn!(silent_ms, "No messages from the USD-M futures stream, reconnecting");
+                    self.ws = None;
+                    return Some(MarketEvent::FeedStalled { venue: VENUE.to_string(), silent_ms });
+                }
+            };
+            if let Some(watchdog) = self.watchdog.as_mut() {
+                watchdog.reset();
+            }
+            match message {
+                Some(Ok(Message::Text(text))) => match parse_message(&text) {
+                    Ok(Some(event)) => return Some(event),
+                    Ok(None) => tracing::debug!("Ignoring futures stream message"),
+                    Err(e) => tracing::warn!(error = %e, "Error parsing futures stream message"),
+                },
+                Some(Ok(Message::Close(_))) | None => {
+                    tracing::warn!("USD-M futures stream closed, reconnecting");
+                    self.ws = None;
+                }
+                Some(Ok(_)) => {}
+                Some(Err(e)) => {
+                    tracing::warn!(error = %e, "USD-M futures stream failed, reconnecting");
+                    self.ws = None;
+                }
+            }
+        }
+    }
+
+    async fn close(&mut self) {
+        if let Some(ws) = self.ws.as_mut() {
+            if let Err(e) = ws.close(None).await {
+                tracing::debug!(error = %e, "Error closing the futures WebSocket");
+            }
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone)]
+pub struct PersistenceConfig {
+    pub min_duration: Duration, // How long a cycle must stay profitable
+    pub min_updates: u32,       // Consecutive detection passes it must appear in
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        PersistenceConfig {
+            min_duration: Duration::from_millis(100),
+            min_updates: 2,
+        }
+    }
+}
+
+// Counters readable while the engine runs
+#[derive(Debug, Default)]
+pub struct PersistenceMetrics {
+    pub passed: AtomicU64,   // Cycles that persisted long enough to execute
+    pub rejected: AtomicU64, // Cycles that vanished before qualifying
+}
+
+impl PersistenceMetrics {
+    pub fn snapshot(&self) -> (u64, u64) {
+        (self.passed.load(Ordering::Relaxed), self.rejected.load(Ordering::Relaxed))
+    }
+}
+
+struct Streak {
+    first_seen: Instant,
+    updates: u32,
+    passed: bool,
+    seen_this_pass: bool,
+}
+
+// Holds execution back until a cycle has been profitable for `min_duration` across
+// `min_updates` consecutive passes, filtering one-tick artifacts from thin books.
+// A pass that doesn't report the cycle resets its streak.
+pub struct PersistenceFilter {
+    config: PersistenceConfig,
+    streaks: HashMap<String, Streak>,
+    metrics: Arc<PersistenceMetrics>,
+}
+
+impl PersistenceFilter {
+    pub fn new(config: PersistenceConfig) -> Self {
+        PersistenceFilter {
+            config,
+            streaks: HashMap::new(),
+            metrics: Arc::new(PersistenceMetrics::default()),
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<PersistenceMetrics> {
+        self.metrics.clone()
+    }
+
+    // Records a detection of `cycle`; true exactly once per streak, when it first qualifies
+    pub fn observe(&mut self, cycle: &str, now: Instant) -> bool {
+        let streak = self.streaks.entry(cycle.to_string()).or_insert(Streak {
+            first_seen: now,
+            updates: 0,
+            passed: false,
+            seen_this_pass: false,
+        });
+        if !streak.seen_this_pass {
+            streak.seen_this_pass = true;
+            streak.updates += 1;
+        }
+        if streak.passed
+            || streak.updates < self.config.min_updates
+            || now.duration_since(streak.first_seen) < self.config.min_duration
+        {
+            return false;
+        }
+        streak.passed = true;
+        self.metrics.passed.fetch_add(1, Ordering::Relaxed);
+        true
+    }
+
+    // Ends a detection pass, breaking the streak of every cycle it didn't report
+    pub fn end_pass(&mut self) {
+        let metrics = &self.metrics;
+        self.streaks.retain(|cycle, streak| {
+            if std::mem::take(&mut streak.seen_this_pass) {
+                return true;
+            }
+            if !streak.passed {
+                tracing::debug!(%cycle, updates = streak.updates, "Opportunity vanished before execution");
+                metrics.rejected.fetch_add(1, Ordering::Relaxed);
+            }
+            false
+        });
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::cbbo::VenueQuote;
+use crate::depth::BookDepth;
+use crate::events::MarketEvent;
+use crate::feed::Feed;
+use crate::perp::MarkPrice;
+use crate::supervisor::Supervisor;
+use crate::ticker::TickerData;
+
+// Default depth of the queue between ingestion and detection
+pub const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+// What ingestion does with an update that finds the queue full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueuePolicy {
+    Block, // Wait for detection, leaving messages unread on the socket meanwhile
+    #[default]
+    Coalesce, // Hold only the latest price per symbol until there's room
+    Drop, // Discard price updates until there's room
+}
+
+impl QueuePolicy {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "block" => Some(QueuePolicy::Block),
+            "coalesce" => Some(QueuePolicy::Coalesce),
+            "drop" => Some(QueuePolicy::Drop),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct QueueConfig {
+    pub capacity: usize,     // Events queued between ingestion and detection
+    pub policy: QueuePolicy, // Once they are full
+}
+
+impl Default for QueueConfig {
+    fn default() -> Self {
+        QueueConfig { capacity: DEFAULT_QUEUE_CAPACITY, policy: QueuePolicy::default() }
+    }
+}
+
+// Counters for the ingest stage, readable while it runs
+#[derive(Debug, Default)]
+pub struct PipelineMetrics {
+    pub events: AtomicU64,    // Events handed to the detection stage
+    pub stalled: AtomicU64,   // Events that found the queue full
+    pub max_depth: AtomicU64,
+    pub coalesced: AtomicU64, // Symbol updates overwritten by a later one while the queue was full
+    pub dropped: AtomicU64,   // Symbol updates discarded while the queue was full
+}
+
+// A point-in-time copy of PipelineMetrics
+#[derive(Debug, Clone, Copy)]
+pub struct PipelineSnapshot {
+    pub events: u64,
+    pub stalled: u64,
+    pub max_depth: u64,
+    pub coalesced: u64,
+    pub dropped: u64,
+}
+
+impl PipelineMetrics {
+    pub fn snapshot(&self) -> PipelineSnapshot {
+        PipelineSnapshot {
+            events: self.events.load(Ordering::Relaxed),
+            stalled: self.stalled.load(Ordering::Relaxed),
+            max_depth: self.max_depth.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+// Latest update per key, in the order keys were first seen
+struct Latest<T> {
+    items: Vec<T>,
+    index: HashMap<String, usize>,
+}
+
+impl<T> Default for Latest<T> {
+    fn default() -> Self {
+        Latest { items: Vec::new(), index: HashMap::new() }
+    }
+}
+
+impl<T> Latest<T> {
+    // True if it replaced an earlier update for the key
+    fn insert(&mut self, key: String, item: T) -> bool {
+        match self.index.get(&key) {
+            Some(&i) => {
+                self.items[i] = item;
+                true
+            }
+            None => {
+                self.index.insert(key, self.items.len());
+                self.items.push(item);
+                false
+            }
+        }
+    }
+
+    fn take(&mut self) -> Vec<T> {
+        self.index.clear();
+        std::mem::take(&mut self.items)
+    }
+}
+
+// Price updates coalesced while the queue was full
+#[derive(Default)]
+struct Prices {
+    tickers: Latest<TickerData>,
+    quotes: Latest<VenueQuote>,
+    marks: Latest<MarkPrice>,
+    depth: Latest<BookDepth>,
+}
+
+impl Prices {
+    // Folds in the event's updates, returning how many replaced earlier ones
+    fn merge(&mut self, event: MarketEvent) -> u64 {
+        let replaced = match event {
+            // A symbol's book ticker, 24h ticker and trade prints carry different fields, so
+            // each only replaces its own kind
+            MarketEvent::Tickers(tickers) => tickers
+                .into_iter()
+                .map(|t| {
+                    let kind = if t.trade { "trade" } else if t.b.is_some() { "book" } else { "24h" };
+                    self.tickers.insert(format!("{}:{}", kind, t.s), t)
+                })
+                .filter(|&replaced| replaced)
+                .count(),
+            MarketEvent::Quotes(quotes) => quotes
+                .into_iter()
+                .map(|q| self.quotes.insert(format!("{}:{}{}", q.venue, q.base, q.quote), q))
+                .filter(|&replaced| replaced)
+                .count(),
+            MarketEvent::MarkPrices(marks) => {
+                marks.into_iter().map(|m| self.marks.insert(m.symbol.clone(), m)).filter(|&replaced| replaced).count()
+            }
+            MarketEvent::Depth(depth) => {
+                depth.into_iter().map(|d| self.depth.insert(d.symbol.clone(), d)).filter(|&replaced| replaced).count()
+            }
+            _ => 0,
+        };
+        replaced as u64
+    }
+
+    // The next batch to send, None once everything has been
+    fn next(&mut self) -> Option<MarketEvent> {
+        if !self.tickers.items.is_empty() {
+            Some(MarketEvent::Tickers(self.tickers.take()))
+        } else if !self.quotes.items.is_empty() {
+            Some(MarketEvent::Quotes(self.quotes.take()))
+        } else if !self.marks.items.is_empty() {
+            Some(MarketEvent::MarkPrices(self.marks.take()))
+        } else if !self.depth.items.is_empty() {
+            Some(MarketEvent::Depth(self.depth.take()))
+        } else {
+            None
+        }
+    }
+}
+
+enum Held {
+    Event(MarketEvent),
+    Prices(Box<Prices>),
+}
+
+// Events read while the queue was full, waiting for room. Price updates are coalesced
+// between other events (symbol removals, feed state changes) but never across one, so
+// those still reach detection in order with the prices around them.
+#[derive(Default)]
+struct Backlog {
+    held: VecDeque<Held>,
+}
+
+impl Backlog {
+    fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+
+    fn push(&mut self, event: MarketEvent, policy: QueuePolicy, metrics: &PipelineMetrics) {
+        let updates = match &event {
+            MarketEvent::Tickers(tickers) => tickers.len(),
+            MarketEvent::Quotes(quotes) => quotes.len(),
+            MarketEvent::MarkPrices(marks) => marks.len(),
+            MarketEvent::Depth(depth) => depth.len(),
+            MarketEvent::SymbolRemoved(_) | MarketEvent::FeedState { .. } | MarketEvent::FeedStalled { .. } => {
+                self.held.push_back(Held::Event(event));
+                return;
+            }
+        };
+        if policy == QueuePolicy::Drop {
+            metrics.dropped.fetch_add(updates as u64, Ordering::Relaxed);
+            return;
+        }
+        if !matches!(self.held.back(), Some(Held::Prices(_))) {
+            self.held.push_back(Held::Prices(Box::default()));
+        }
+        if let Some(Held::Prices(prices)) = self.held.back_mut() {
+            let replaced = prices.merge(event);
+            metrics.coalesced.fetch_add(replaced, Ordering::Relaxed);
+        }
+    }
+
+    fn pop(&mut self) -> Option<MarketEvent> {
+        loop {
+            match self.held.front_mut()? {
+                Held::Prices(prices) => match prices.next() {
+                    Some(event) => return Some(event),
+                    None => {
+                        self.held.pop_front();
+                    }
+                },
+                Held::Event(_) => match self.held.pop_front() {
+                    Some(Held::Event(event)) => return Some(event),
+                    _ => unreachable!(),
+                },
+            }
+        }
+    }
+}
+
+// The ingest task's state, kept across restarts
+struct Ingest<F: Feed> {
+    feed: F,
+    tx: mpsc::Sender<MarketEvent>,
+    policy: QueuePolicy,
+    backlog: Backlog,
+    stopped: oneshot::Receiver<()>,
+    metrics: Arc<PipelineMetrics>,
+}
+
+impl<F: Feed> Ingest<F> {
+    async fn run(&mut self) {
+        loop {
+            let event = tokio::select! {
+                event = self.feed.next_event() => event,
+                permit = self.tx.reserve(), if !self.backlog.is_empty() => match permit {
+                    Ok(permit) => {
+                        if let Some(event) = self.backlog.pop() {
+                            permit.send(event);
+                            self.metrics.events.fetch_add(1, Ordering::Relaxed);
+                        }
+                        continue;
+                    }
+                    // The engine stopped
+                    Err(_) => break,
+                },
+                _ = &mut self.stopped => break,
+            };
+            // The feed ended; what it delivered before still reaches detection
+            let Some(event) = event else {
+                while let Some(event) = self.backlog.pop() {
+                    if self.tx.send(event).await.is_err() {
+                        break;
+                    }
+                    self.metrics.events.fetch_add(1, Ordering::Relaxed);
+                }
+                break;
+            };
+            let depth = (self.tx.max_capacity() - self.tx.capacity()) as u64 + 1;
+            self.metrics.max_depth.fetch_max(depth, Ordering::Relaxed);
+            // Anything newer waits behind what's held back
+            if !self.backlog.is_empty() {
+                self.metrics.stalled.fetch_add(1, Ordering::Relaxed);
+                self.backlog.push(event, self.policy, &self.metrics);
+                continue;
+            }
+            let sent = match self.tx.try_send(event) {
+                Ok(()) => true,
+                Err(TrySendError::Full(event)) => {
+                    self.metrics.stalled.fetch_add(1, Ordering::Relaxed);
+                    match self.policy {
+                        QueuePolicy::Block => self.tx.send(event).await.is_ok(),
+                        QueuePolicy::Coalesce | QueuePolicy::Drop => {
+                            self.backlog.push(event, self.policy, &self.metrics);
+                            continue;
+                        }
+                    }
+                }
+                Err(TrySendError::Closed(_)) => false,
+            };
+            // The engine stopped
+            if !sent {
+                break;
+            }
+            self.metrics.events.fetch_add(1, Ordering::Relaxed);
+        }
+        self.feed.close().await;
+    }
+}
+
+// Runs a feed (socket reads and parsing into market events) on its own task, connected
+// to the engine by a bounded queue. A slow detection pass then delays only detection:
+// ingestion keeps draining the socket until the queue fills. What happens then is up to
+// the policy: waiting, so the socket backs up; keeping only the newest price of each
+// symbol until there's room, which bounds what is held by the number of symbols; or
+// discarding price updates. Either way stalls show up in the metrics, not silently.
+pub struct PipelineFeed {
+    venue: String,
+    events: mpsc::Receiver<MarketEvent>,
+    metrics: Arc<PipelineMetrics>,
+    stop: Option<oneshot::Sender<()>>,
+    task: Option<JoinHandle<()>>, // Ingest task; ends after closing the inner feed
+}
+
+impl PipelineFeed {
+    // Must be called inside a Tokio runtime. A panic while reading restarts ingestion
+    // with the same feed, which picks up with its next message.
+    pub fn spawn<F: Feed + 'static>(feed: F, config: QueueConfig, supervisor: &Supervisor) -> Self {
+        let venue = feed.venue().to_string();
+        let (tx, events) = mpsc::channel(config.capacity.max(1));
+        let metrics = Arc::new(PipelineMetrics::default());
+        let (stop, stopped) = oneshot::channel();
+        let ingest = Arc::new(Mutex::new(Ingest {
+            feed,
+            tx,
+            policy: config.policy,
+            backlog: Backlog::default(),
+            stopped,
+            metrics: metrics.clone(),
+        }));
+        let task = supervisor.spawn("feed", move || {
+            let ingest = ingest.clone();
+            async move { ingest.lock().await.run().await }
+        });
+        PipelineFeed {
+            venue,
+            events,
+            metrics,
+            stop: Some(stop),
+            task: Some(task),
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<PipelineMetrics> {
+        self.metrics.clone()
+    }
+}
+
+impl Feed for PipelineFeed {
+    fn venue(&self) -> &str {
+        &self.venue
+    }
+
+    // Cancel-safe: an event is either received or left in the queue
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        self.events.recv().await
+    }
+
+    // Stops ingestion, whether it is waiting on the socket or on a full queue, and waits
+    // for the inner feed to close
+    async fn close(&mut self) {
+        self.events.close();
+        if let Some(stop) = self.stop.take() {
+            let _ = stop.send(());
+        }
+        if let Some(task) = self.task.take() {
+            let _ = task.await;
+        }
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant, SystemTime};
+
+use serde_json::json;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+use crate::events::{MarketEvent, Opportunity, Signal};
+use crate::graph::Graph;
+use crate::order::OrderRequest;
+use crate::strategy::Strategy;
+use crate::ticker::TickerData;
+
+// How often the plugin binary is checked for a rebuild, and the minimum gap between restarts
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const RESTART_BACKOFF: Duration = Duration::from_secs(5);
+
+// Message a plugin writes to stdout, one JSON object per line
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PluginMessage {
+    Opportunity {
+        path: Vec<String>,
+        #[serde(default)]
+        profit: Option<f64>,
+    },
+    Signal {
+        kind: String,
+        #[serde(default)]
+        instruments: Vec<String>,
+        #[serde(default)]
+        value: f64,
+        #[serde(default)]
+        orders: Vec<OrderRequest>,
+        #[serde(default)]
+        details: serde_json::Map<String, serde_json::Value>,
+    },
+    Log {
+        message: String,
+    },
+}
+
+// What the plugin's reader task hands back to the strategy
+enum PluginOutput {
+    Opportunity(Opportunity),
+    Signal(Signal),
+}
+
+struct Running {
+    child: Child,
+    input: mpsc::UnboundedSender<String>,
+    output: mpsc::UnboundedReceiver<PluginOutput>,
+}
+
+// A strategy running as a separate process, speaking JSON lines:
+//   host -> plugin: {"type":"tickers","tickers":[...]}, {"type":"symbol_removed","symbol":"..."}
+//                   and, with perpetuals enabled, {"type":"mark_prices","marks":[...]}
+//   plugin -> host: {"type":"opportunity","path":[...],"profit":0.001},
+//                   {"type":"signal","kind":"...","instruments":[...],"value":1.5,"orders":[...],"details":{...}}
+//                   and {"type":"log","message":"..."}
+// where an order is {"symbol":"BTCUSDT","side":"BUY","quantity":"0.01","price":"65000"} (no price for market).
+// Plugins can be rebuilt and swapped while the feed keeps running: when the executable
+// changes it is restarted and sent the latest ticker for every symbol so it starts warm.
+// Results arrive asynchronously, so an opportunity is reported on the update after it was found.
+pub struct SubprocessStrategy {
+    name: String,
+    program: PathBuf,
+    args: Vec<String>,
+    running: Option<Running>,
+    latest: HashMap<String, TickerData>, // Last ticker per symbol, replayed on restart
+    modified: Option<SystemTime>,
+    last_check: Instant,
+    last_start: Option<Instant>,
+    signals: Vec<Signal>, // Received since the engine last collected them
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+impl SubprocessStrategy {
+    pub fn new(program: &Path, args: Vec<String>) -> Self {
+        let name = program
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "plugin".to_string());
+        SubprocessStrategy {
+            name,
+            program: program.to_path_buf(),
+            args,
+            running: None,
+            latest: HashMap::new(),
+            modified: modified_time(program),
+            last_check: Instant::now(),
+            last_start: None,
+            signals: Vec::new(),
+        }
+    }
+
+    fn start(&mut self) {
+        if let Some(last) = self.last_start {
+            if last.elapsed() < RESTART_BACKOFF {
+                return;
+            }
+        }
+        self.last_start = Some(Instant::now());
+        self.modified = modified_time(&self.program);
+
+        let mut child = match Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::error!(plugin = %self.name, error = %e, "Failed to start strategy plugin");
+                return;
+            }
+        };
+        let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return;
+        };
+
+        let (input, mut input_rx) = mpsc::unbounded_channel::<String>();
+        tokio::spawn(async move {
+            while let Some(line) = input_rx.recv().await {
+                if stdin.write_all(line.as_bytes()).await.is_err() || stdin.write_all(b"\n").await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (output_tx, output) = mpsc::unbounded_channel();
+        let name = self.name.clone();
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(stdout).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match serde_json::from_str(&line) {
+                    Ok(PluginMessage::Opportunity { path, profit }) => {
+                        let mut opportunity = Opportunity::new(path);
+                        opportunity.profit = profit;
+                        if output_tx.send(PluginOutput::Opportunity(opportunity)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(PluginMessage::Signal { kind, instruments, value, orders, details }) => {
+                        let mut signal = Signal::new(kind, instruments, value).with_orders(orders);
+                        signal.details = details;
+                        if output_tx.send(PluginOutput::Signal(signal)).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(PluginMessage::Log { message }) => tracing::info!(plugin = %name, "{}", message),
+                    Err(e) => tracing::warn!(plugin = %name, error = %e, "Unparsable plugin output"),
+                }
+            }
+        });
+
+        // Warm start from the latest known prices
+        if !self.latest.is_empty() {
+            let tickers: Vec<&TickerData> = self.latest.values().collect();
+            let _ = input.send(json!({ "type": "tickers", "tickers": tickers }).to_string());
+        }
+        tracing::info!(plugin = %self.name, "Strategy plugin started");
+        self.running = Some(Running { child, input, output });
+    }
+
+    // Restarts the plugin if its executable was rebuilt or the process exited
+    fn supervise(&mut self) {
+        if self.last_check.elapsed() < RELOAD_CHECK_INTERVAL && self.running.is_some() {
+            return;
+        }
+        self.last_check = Instant::now();
+        let exited = match self.running.as_mut() {
+            Some(running) => !matches!(running.child.try_wait(), Ok(None)),
+            None => true,
+        };
+        let rebuilt = modified_time(&self.program) != self.modified;
+        if rebuilt {
+            tracing::info!(plugin = %self.name, "Plugin binary changed, reloading");
+        } else if exited && self.running.is_some() {
+            tracing::warn!(plugin = %self.name, "Strategy plugin exited, restarting");
+        }
+        if rebuilt || exited {
+            // Dropping the child kills it
+            self.running = None;
+            self.start();
+        }
+    }
+}
+
+impl Strategy for SubprocessStrategy {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn on_update(&mut self, _graph: &Graph) -> Vec<Opportunity> {
+        Vec::new()
+    }
+
+    fn on_event(&mut self, _graph: &Graph, event: &MarketEvent) -> Vec<Opportunity> {
+        let message = match event {
+            MarketEvent::Tickers(tickers) => {
+                for ticker in tickers {
+                    self.latest.insert(ticker.s.clone(), ticker.clone());
+                }
+                Some(json!({ "type": "tickers", "tickers": tickers }))
+            }
+            MarketEvent::SymbolRemoved(symbol) => {
+                self.latest.remove(symbol);
+                Some(json!({ "type": "symbol_removed", "symbol": symbol }))
+            }
+            MarketEvent::MarkPrices(marks) => Some(json!({ "type": "mark_prices", "marks": marks })),
+            MarketEvent::FeedState { venue, degraded } => Some(json!({ "type": "feed_state", "venue": venue, "degraded": degraded })),
+            MarketEvent::FeedStalled { venue, silent_ms } => Some(json!({ "type": "feed_stalled", "venue": venue, "silent_ms": silent_ms })),
+            MarketEvent::Depth(depths) => Some(json!({ "type": "depth", "depths": depths })),
+            MarketEvent::Quotes(_) => None,
+        };
+
+        self.supervise();
+        let Some(running) = self.running.as_mut() else {
+            return Vec::new();
+        };
+        if let Some(message) = message {
+            let _ = running.input.send(message.to_string());
+        }
+        let mut opportunities = Vec::new();
+        while let Ok(output) = running.output.try_recv() {
+            match output {
+                PluginOutput::Opportunity(opportunity) => opportunities.push(opportunity),
+                PluginOutput::Signal(signal) => self.signals.push(signal),
+            }
+        }
+        opportunities
+    }
+
+    fn take_signals(&mut self) -> Vec<Signal> {
+        std::mem::take(&mut self.signals)
+    }
+}
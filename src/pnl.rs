@@ -0,0 +1,222 @@
+use std::collections::{HashMap, VecDeque};
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+
+use crate::graph::Graph;
+use crate::ledger::Fill;
+use crate::order::Side;
+use crate::slippage::value_in;
+
+const RECENT_CYCLES: usize = 100;
+
+// Holding in one asset; `cost` is what it was bought for, in the reference asset
+#[derive(Debug, Clone, Copy, Default)]
+struct Position {
+    quantity: Decimal,
+    cost: f64,
+}
+
+// PnL of one executed cycle
+#[derive(serde::Serialize, Debug, Clone, Default)]
+pub struct CyclePnl {
+    pub cycle: String,
+    pub fills: u32,
+    pub realized: f64, // Net of fees
+    pub fees: f64,
+}
+
+// Cumulative figures, all in the reference asset
+#[derive(serde::Serialize, Debug, Clone, Default)]
+pub struct PnlSummary {
+    pub reference: String,
+    pub realized: f64,         // Net of fees
+    pub unrealized: f64,       // Open positions marked at the current rates
+    pub fees: f64,
+    pub fills: u64,
+    pub cycles: u64,           // Closed cycles
+    pub unpriced: Vec<String>, // Assets without a rate to the reference, left out of the figures
+}
+
+impl PnlSummary {
+    pub fn total(&self) -> f64 {
+        self.realized + self.unrealized
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "reference": self.reference,
+            "realized": self.realized,
+            "unrealized": self.unrealized,
+            "total": self.total(),
+            "fees": self.fees,
+            "fills": self.fills,
+            "cycles": self.cycles,
+            "unpriced": self.unpriced,
+        })
+    }
+}
+
+// Average-cost PnL over fills, valued in a reference asset. Every fill moves two or
+// three assets (base, quote, fee); the quote is valued at the graph's rate when the fill
+// is recorded and the base at the fill's price on top of that. Reducing a position
+// realizes the difference from its average cost, and fees are charged as they are paid. Fills tagged with a cycle add up into
+// that cycle's PnL, reported when the executor closes it.
+pub struct PnlTracker {
+    reference: String,
+    positions: HashMap<String, Position>,
+    realized: f64,
+    fees: f64,
+    fills: u64,
+    open_cycles: HashMap<String, CyclePnl>,
+    closed_cycles: VecDeque<CyclePnl>, // Newest first
+    cycles: u64,
+    unpriced: Vec<String>,
+}
+
+impl PnlTracker {
+    pub fn new(reference: &str) -> Self {
+        PnlTracker {
+            reference: reference.to_string(),
+            positions: HashMap::new(),
+            realized: 0.0,
+            fees: 0.0,
+            fills: 0,
+            open_cycles: HashMap::new(),
+            closed_cycles: VecDeque::with_capacity(RECENT_CYCLES),
+            cycles: 0,
+            unpriced: Vec::new(),
+        }
+    }
+
+    pub fn reference(&self) -> &str {
+        &self.reference
+    }
+
+    // Value of one unit of `asset` in the reference asset
+    fn price(&self, graph: &Graph, asset: &str) -> Option<f64> {
+        value_in(graph, asset, &self.reference)
+    }
+
+    // Records a fill, returning the realized PnL (net of fees) it produced, e.g. for
+    // `RiskManager::record_pnl`
+    pub fn record(&mut self, fill: &Fill, cycle: Option<&str>, graph: &Graph) -> f64 {
+        let notional = fill.quantity * fill.price;
+        let (base_delta, quote_delta) = match fill.side {
+            Side::Buy => (fill.quantity, -notional),
+            Side::Sell => (-fill.quantity, notional),
+        };
+        // The quote side moves at its current rate and the base at the fill's price in
+        // those terms, so the edge a cycle captures shows up as it closes
+        let quote_price = self.price(graph, &fill.quote);
+        let base_price = quote_price.map(|p| fill.price.to_f64().unwrap_or(0.0) * p);
+        let mut realized = self.apply(&fill.base, base_delta, base_price) + self.apply(&fill.quote, quote_delta, quote_price);
+        let mut fee = 0.0;
+        if !fill.fee.is_zero() {
+            // A fee is a sale at the current rate with nothing received for it
+            let fee_asset = fill.fee_asset_or_quote().to_string();
+            let fee_price = if fee_asset == fill.base { base_price } else { self.price(graph, &fee_asset) };
+            fee = fill.fee.to_f64().unwrap_or(0.0) * fee_price.unwrap_or(0.0);
+            realized += self.apply(&fee_asset, -fill.fee, fee_price) - fee;
+        }
+        self.realized += realized;
+        self.fees += fee;
+        self.fills += 1;
+        if let Some(cycle) = cycle {
+            let tally = self.open_cycles.entry(cycle.to_string()).or_insert_with(|| CyclePnl {
+                cycle: cycle.to_string(),
+                ..Default::default()
+            });
+            tally.fills += 1;
+            tally.realized += realized;
+            tally.fees += fee;
+        }
+        realized
+    }
+
+    // Moves `delta` of `asset` at `price` per unit, returning what the move realized
+    fn apply(&mut self, asset: &str, delta: Decimal, price: Option<f64>) -> f64 {
+        if asset == self.reference || delta.is_zero() {
+            return 0.0;
+        }
+        let Some(price) = price else {
+            if !self.unpriced.iter().any(|a| a == asset) {
+                tracing::warn!(%asset, reference = %self.reference, "No rate to value fills in");
+                self.unpriced.push(asset.to_string());
+            }
+            let position = self.positions.entry(asset.to_string()).or_default();
+            position.quantity += delta;
+            return 0.0;
+        };
+        let position = self.positions.entry(asset.to_string()).or_default();
+        let held = position.quantity.to_f64().unwrap_or(0.0);
+        let moved = delta.to_f64().unwrap_or(0.0);
+        let mut realized = 0.0;
+        if held == 0.0 || held.signum() == moved.signum() {
+            position.cost += moved * price;
+        } else {
+            // Closes up to the whole position; any remainder opens one the other way
+            let closed = moved.abs().min(held.abs());
+            let average = position.cost / held;
+            realized = closed * (price - average) * held.signum();
+            position.cost -= closed * average * held.signum();
+            let remainder = moved.abs() - closed;
+            if remainder > 0.0 {
+                position.cost = remainder * moved.signum() * price;
+            }
+        }
+        position.quantity += delta;
+        if position.quantity.is_zero() {
+            self.positions.remove(asset);
+        }
+        realized
+    }
+
+    // Ends a cycle's tally and returns it; None if no fill was tagged with it
+    pub fn close_cycle(&mut self, cycle: &str) -> Option<CyclePnl> {
+        let tally = self.open_cycles.remove(cycle)?;
+        tracing::info!(
+            cycle = %tally.cycle,
+            fills = tally.fills,
+            realized = tally.realized,
+            fees = tally.fees,
+            reference = %self.reference,
+            "Cycle closed"
+        );
+        if self.closed_cycles.len() == RECENT_CYCLES {
+            self.closed_cycles.pop_back();
+        }
+        self.closed_cycles.push_front(tally.clone());
+        self.cycles += 1;
+        Some(tally)
+    }
+
+    // The most recently closed cycles, newest first
+    pub fn recent_cycles(&self) -> impl Iterator<Item = &CyclePnl> {
+        self.closed_cycles.iter()
+    }
+
+    // Cumulative PnL with open positions marked at the rates in `graph`
+    pub fn summary(&self, graph: &Graph) -> PnlSummary {
+        let mut unrealized = 0.0;
+        let mut unpriced = self.unpriced.clone();
+        for (asset, position) in &self.positions {
+            match self.price(graph, asset) {
+                Some(price) => unrealized += position.quantity.to_f64().unwrap_or(0.0) * price - position.cost,
+                None if !unpriced.contains(asset) => unpriced.push(asset.clone()),
+                None => {}
+            }
+        }
+        unpriced.sort();
+        PnlSummary {
+            reference: self.reference.clone(),
+            realized: self.realized,
+            unrealized,
+            fees: self.fees,
+            fills: self.fills,
+            cycles: self.cycles,
+            unpriced,
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::Value;
+
+// Config file looked for in the working directory when --profile is given without --config
+pub const DEFAULT_CONFIG_PATH: &str = "hft3.json";
+
+// One named set of command-line flags, without the leading dashes. `true` turns a switch
+// on, `false` or `null` drops what the parent set, arrays repeat the flag per element.
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct Profile {
+    #[serde(default)]
+    pub inherits: Option<String>,
+    #[serde(flatten)]
+    pub flags: BTreeMap<String, Value>,
+}
+
+// Config file of named profiles, e.g.
+// {"profiles":{"paper":{"max-edge-age-ms":5000,"cbbo":true},
+//              "testnet":{"inherits":"paper","testnet":true,"user-stream":true},
+//              "prod":{"inherits":"paper","fix":"10.0.0.5:9876","fix-sender":"HFT3","fix-target":"OMS"}}}
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct ConfigFile {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Debug)]
+pub enum ProfileError {
+    Io(io::Error),
+    Unknown(String),       // No profile of that name
+    Cycle(Vec<String>),    // Profiles inheriting from each other, in order
+    Value(String, String), // Profile and flag whose value can't be a command-line argument
+}
+
+impl fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProfileError::Io(e) => write!(f, "{}", e),
+            ProfileError::Unknown(name) => write!(f, "no profile named {}", name),
+            ProfileError::Cycle(chain) => write!(f, "profiles inherit from each other: {}", chain.join(" -> ")),
+            ProfileError::Value(profile, flag) => write!(f, "unsupported value for {} in profile {}", flag, profile),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+impl From<io::Error> for ProfileError {
+    fn from(e: io::Error) -> Self {
+        ProfileError::Io(e)
+    }
+}
+
+impl ConfigFile {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    // Flags of `name` merged over those of its ancestors, nearest first
+    pub fn resolve(&self, name: &str) -> Result<BTreeMap<String, Value>, ProfileError> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut next = Some(name.to_string());
+        while let Some(name) = next {
+            if chain.contains(&name) {
+                chain.push(name);
+                return Err(ProfileError::Cycle(chain));
+            }
+            let profile = self.profiles.get(&name).ok_or_else(|| ProfileError::Unknown(name.clone()))?;
+            next = profile.inherits.clone();
+            chain.push(name);
+        }
+        let mut flags = BTreeMap::new();
+        for name in chain.iter().rev() {
+            flags.extend(self.profiles[name].flags.clone());
+        }
+        Ok(flags)
+    }
+
+    // The profile as command-line arguments, to go before the real ones so those win
+    pub fn args(&self, name: &str) -> Result<Vec<String>, ProfileError> {
+        let mut args = Vec::new();
+        for (flag, value) in self.resolve(name)? {
+            let values = match value {
+                Value::Array(values) => values,
+                value => vec![value],
+            };
+            for value in values {
+                let flag_arg = format!("--{}", flag);
+                match value {
+                    Value::Bool(true) => args.push(flag_arg),
+                    Value::Bool(false) | Value::Null => {}
+                    Value::String(s) => args.extend([flag_arg, s]),
+                    Value::Number(n) => args.extend([flag_arg, n.to_string()]),
+                    Value::Array(_) | Value::Object(_) => return Err(ProfileError::Value(name.to_string(), flag)),
+                }
+            }
+        }
+        Ok(args)
+    }
+}
+
+// Profile `name` of the config file at `path` as command-line arguments
+pub fn load_args(path: &Path, name: &str) -> Result<Vec<String>, ProfileError> {
+    ConfigFile::load(path)?.args(name)
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io;
+
+use base64::Engine as _;
+use percent_encoding::percent_decode_str;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::tungstenite::error::UrlError;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use tokio_tungstenite::{client_async, MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::credentials::Secret;
+use crate::deflate::{self, DeflateStream};
+
+// Largest CONNECT response header we wait for
+const MAX_CONNECT_RESPONSE: usize = 8192;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyKind {
+    Http,                        // HTTP CONNECT tunnel
+    Socks5 { remote_dns: bool }, // socks5:// resolves targets locally, socks5h:// on the proxy
+}
+
+// An outbound proxy, parsed from http://, socks5:// or socks5h:// URLs with optional
+// user:password. Display and Debug leave the password out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Proxy {
+    pub kind: ProxyKind,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<Secret>,
+}
+
+impl Proxy {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let url = Url::parse(value).map_err(|e| format!("invalid proxy URL: {}", e))?;
+        let (kind, default_port) = match url.scheme() {
+            "http" => (ProxyKind::Http, 8080),
+            "socks5" => (ProxyKind::Socks5 { remote_dns: false }, 1080),
+            "socks5h" => (ProxyKind::Socks5 { remote_dns: true }, 1080),
+            other => return Err(format!("unsupported proxy scheme {}, expected http, socks5 or socks5h", other)),
+        };
+        let host = url.host_str().filter(|h| !h.is_empty()).ok_or("proxy URL has no host")?;
+        let decode = |s: &str| percent_decode_str(s).decode_utf8_lossy().into_owned();
+        Ok(Proxy {
+            kind,
+            host: host.trim_start_matches('[').trim_end_matches(']').to_string(),
+            port: url.port().unwrap_or(default_port),
+            username: Some(url.username()).filter(|u| !u.is_empty()).map(decode),
+            password: url.password().map(|p| Secret::new(decode(p))),
+        })
+    }
+
+    fn scheme(&self) -> &'static str {
+        match self.kind {
+            ProxyKind::Http => "http",
+            ProxyKind::Socks5 { remote_dns: false } => "socks5",
+            ProxyKind::Socks5 { remote_dns: true } => "socks5h",
+        }
+    }
+
+    // The proxy for reqwest, credentials included
+    pub fn reqwest(&self) -> reqwest::Proxy {
+        let host = if self.host.contains(':') { format!("[{}]", self.host) } else { self.host.clone() };
+        let mut url = Url::parse(&format!("{}://{}:{}", self.scheme(), host, self.port)).expect("proxy URL was valid when parsed");
+        if let Some(username) = &self.username {
+            let _ = url.set_username(username);
+            let _ = url.set_password(self.password.as_ref().map(Secret::expose));
+        }
+        reqwest::Proxy::all(url.as_str()).expect("reqwest accepts http and socks5 proxies")
+    }
+
+    // A TCP stream to `host:port` tunnelled through the proxy
+    pub async fn connect(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        match self.kind {
+            ProxyKind::Http => self.connect_http(host, port).await,
+            ProxyKind::Socks5 { remote_dns } => {
+                let proxy = (self.host.as_str(), self.port);
+                let stream = match (remote_dns, &self.username) {
+                    (true, None) => Socks5Stream::connect(proxy, (host, port)).await,
+                    (true, Some(user)) => Socks5Stream::connect_with_password(proxy, (host, port), user, self.password()).await,
+                    (false, user) => {
+                        let target = tokio::net::lookup_host((host, port))
+                            .await?
+                            .next()
+                            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("no address for {}", host)))?;
+                        match user {
+                            None => Socks5Stream::connect(proxy, target).await,
+                            Some(user) => Socks5Stream::connect_with_password(proxy, target, user, self.password()).await,
+                        }
+                    }
+                };
+                stream.map(Socks5Stream::into_inner).map_err(io::Error::other)
+            }
+        }
+    }
+
+    fn password(&self) -> &str {
+        self.password.as_ref().map(Secret::expose).unwrap_or("")
+    }
+
+    async fn connect_http(&self, host: &str, port: u16) -> io::Result<TcpStream> {
+        let mut stream = TcpStream::connect((self.host.as_str(), self.port)).await?;
+        let target = if host.contains(':') { format!("[{}]:{}", host, port) } else { format!("{}:{}", host, port) };
+        let mut request = format!("CONNECT {} HTTP/1.1\r\nHost: {}\r\n", target, target);
+        if let Some(user) = &self.username {
+            let token = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, self.password()));
+            request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).await?;
+
+        // Read the response header byte by byte so nothing of the tunnelled stream is consumed
+        let mut response = Vec::with_capacity(256);
+        let mut byte = [0u8; 1];
+        while !response.ends_with(b"\r\n\r\n") {
+            if response.len() >= MAX_CONNECT_RESPONSE || stream.read(&mut byte).await? == 0 {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "proxy closed or sent an oversized CONNECT response"));
+            }
+            response.push(byte[0]);
+        }
+        let status_line = String::from_utf8_lossy(&response);
+        let status_line = status_line.lines().next().unwrap_or_default();
+        match status_line.split_whitespace().nth(1) {
+            Some(code) if code.starts_with('2') => Ok(stream),
+            _ => Err(io::Error::new(io::ErrorKind::ConnectionRefused, format!("proxy refused CONNECT: {}", status_line))),
+        }
+    }
+}
+
+impl fmt::Display for Proxy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}://", self.scheme())?;
+        if let Some(user) = &self.username {
+            write!(f, "{}@", user)?;
+        }
+        if self.host.contains(':') {
+            write!(f, "[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{}:{}", self.host, self.port)
+        }
+    }
+}
+
+// Proxy settings for all outbound connections: one default plus per-venue overrides,
+// where an override of None means connecting directly
+#[derive(Debug, Clone, Default)]
+pub struct ProxyConfig {
+    pub default: Option<Proxy>,
+    pub venues: HashMap<String, Option<Proxy>>,
+}
+
+impl ProxyConfig {
+    // The proxy for connections to `venue` (e.g. "binance"), if any
+    pub fn for_venue(&self, venue: &str) -> Option<&Proxy> {
+        match self.venues.get(venue) {
+            Some(proxy) => proxy.as_ref(),
+            None => self.default.as_ref(),
+        }
+    }
+
+    // Parses a `<venue>=<url>` or `<venue>=direct` override
+    pub fn add_override(&mut self, value: &str) -> Result<(), String> {
+        let (venue, proxy) = value.split_once('=').ok_or("expected <venue>=<proxy URL> or <venue>=direct")?;
+        let proxy = match proxy {
+            "direct" => None,
+            url => Some(Proxy::parse(url)?),
+        };
+        self.venues.insert(venue.trim().to_lowercase(), proxy);
+        Ok(())
+    }
+}
+
+// An HTTP client that goes through `proxy`, or reqwest's default (which honours the
+// HTTP_PROXY family of variables) without one
+pub fn http_client(proxy: Option<&Proxy>) -> reqwest::Client {
+    match proxy {
+        None => reqwest::Client::new(),
+        Some(proxy) => reqwest::Client::builder()
+            .proxy(proxy.reqwest())
+            .build()
+            .expect("HTTP client with a proxy"),
+    }
+}
+
+pub type WsStream = WebSocketStream<DeflateStream<MaybeTlsStream<TcpStream>>>;
+
+// Opens a WebSocket to `url`, through `proxy` when given, offering permessage-deflate.
+// TLS runs end to end inside the tunnel, so the proxy never sees the traffic.
+pub async fn connect_websocket(url: &str, proxy: Option<&Proxy>) -> Result<(WsStream, Response), WsError> {
+    let mut request = url.into_client_request()?;
+    request.headers_mut().insert("Sec-WebSocket-Extensions", HeaderValue::from_static(deflate::OFFER));
+    let uri = request.uri();
+    let tls = match uri.scheme_str() {
+        Some("wss") => true,
+        Some("ws") => false,
+        _ => return Err(WsError::Url(UrlError::UnsupportedUrlScheme)),
+    };
+    let host = uri
+        .host()
+        .ok_or(WsError::Url(UrlError::NoHostName))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = uri.port_u16().unwrap_or(if tls { 443 } else { 80 });
+    let stream = match proxy {
+        Some(proxy) => proxy.connect(&host, port).await,
+        None => TcpStream::connect((host.as_str(), port)).await,
+    }
+    .map_err(WsError::Io)?;
+    // Compression lives inside TLS, so TLS can't be left to tungstenite
+    let stream = if tls {
+        let connector = native_tls::TlsConnector::new().map_err(|e| WsError::Tls(e.into()))?;
+        let stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(&host, stream)
+            .await
+            .map_err(|e| WsError::Tls(e.into()))?;
+        MaybeTlsStream::NativeTls(stream)
+    } else {
+        MaybeTlsStream::Plain(stream)
+    };
+    let (ws, response) = client_async(request, DeflateStream::new(stream)).await?;
+    tracing::debug!(%url, compressed = ws.get_ref().negotiated(), "WebSocket connected");
+    Ok((ws, response))
+}
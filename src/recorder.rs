@@ -0,0 +1,88 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use crate::events::MarketEvent;
+use crate::feed::Feed;
+use crate::ticker::{StreamMessage, TickerData, TickerPayload};
+
+// Writes every raw socket message as one line, so a session can be replayed later
+pub struct Recorder {
+    writer: BufWriter<File>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Recorder {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+
+    pub fn record(&mut self, raw: &str) -> io::Result<()> {
+        self.writer.write_all(raw.trim_end().as_bytes())?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+// One recorded message worth of tickers
+pub struct SessionStep {
+    pub event_time: u64, // Latest exchange event time in the step, ms
+    pub tickers: Vec<TickerData>,
+}
+
+// Loads a recorded session. Each line is either a combined stream message or a bare
+// ticker payload (like tick_data.txt); subscription acks and unparsable lines are skipped.
+pub fn load_session(path: &Path) -> io::Result<Vec<SessionStep>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut steps = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tickers = match StreamMessage::parse(&line) {
+            Ok(StreamMessage::Event { data, .. }) => data.into_vec(),
+            // Replays price from tickers; order books aren't played back
+            Ok(StreamMessage::Depth { .. } | StreamMessage::Response(_)) => continue,
+            Err(_) => match TickerPayload::parse(&line) {
+                Ok(data) => data.into_vec(),
+                Err(e) => {
+                    tracing::warn!(error = %e, "Skipping unparsable session line");
+                    continue;
+                }
+            },
+        };
+        let event_time = tickers.iter().map(|t| t.event_time).max().unwrap_or(0);
+        steps.push(SessionStep { event_time, tickers });
+    }
+    Ok(steps)
+}
+
+// Plays a recorded session back through the engine as fast as it is consumed
+pub struct ReplayFeed {
+    steps: std::vec::IntoIter<SessionStep>,
+}
+
+impl ReplayFeed {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(ReplayFeed {
+            steps: load_session(path)?.into_iter(),
+        })
+    }
+}
+
+impl Feed for ReplayFeed {
+    fn venue(&self) -> &str {
+        "binance"
+    }
+
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        // Sinks run on their own tasks; yielding per step keeps them from lagging behind
+        tokio::task::yield_now().await;
+        self.steps.next().map(|step| MarketEvent::Tickers(step.tickers))
+    }
+}
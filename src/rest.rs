@@ -0,0 +1,380 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use hmac::{Hmac, Mac};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use sha2::Sha256;
+use tokio::sync::Mutex;
+
+use crate::clock::{self, ServerClock};
+use crate::credentials::Secret;
+use crate::proxy::Proxy;
+
+pub const BINANCE_REST_URL: &str = "https://api.binance.com";
+pub const BINANCE_TESTNET_REST_URL: &str = "https://testnet.binance.vision";
+
+// Where one Binance spot environment lives: market data, user data and REST, plus the
+// environment variables and keyring account holding its API keys
+#[derive(Debug, Clone)]
+pub struct BinanceEndpoints {
+    pub rest: String,
+    pub market_ws: String,              // Combined stream endpoint
+    pub market_ws_mirrors: Vec<String>, // Every host serving the same streams, for failover
+    pub user_ws: String,                // Raw stream endpoint the listen key is appended to
+    pub usdm_ws: String,                // USD-M futures combined stream endpoint
+    pub key_var: &'static str,
+    pub secret_var: &'static str,
+    pub keyring_account: &'static str, // Default account under the "hft3" keyring service
+}
+
+impl BinanceEndpoints {
+    pub fn live() -> Self {
+        BinanceEndpoints {
+            rest: BINANCE_REST_URL.to_string(),
+            market_ws: crate::feed::BINANCE_WS_URL.to_string(),
+            market_ws_mirrors: crate::failover::BINANCE_WS_MIRRORS.iter().map(|url| url.to_string()).collect(),
+            user_ws: crate::user_stream::BINANCE_USER_WS_URL.to_string(),
+            usdm_ws: crate::perp::BINANCE_USDM_WS_URL.to_string(),
+            key_var: "BINANCE_API_KEY",
+            secret_var: "BINANCE_API_SECRET",
+            keyring_account: "binance",
+        }
+    }
+
+    // The spot test network: same API, test funds, separate keys
+    // (https://testnet.binance.vision)
+    pub fn testnet() -> Self {
+        BinanceEndpoints {
+            rest: BINANCE_TESTNET_REST_URL.to_string(),
+            market_ws: "wss://stream.testnet.binance.vision/stream".to_string(),
+            market_ws_mirrors: vec!["wss://stream.testnet.binance.vision/stream".to_string()],
+            user_ws: "wss://stream.testnet.binance.vision/ws".to_string(),
+            usdm_ws: crate::perp::BINANCE_USDM_TESTNET_WS_URL.to_string(),
+            key_var: "BINANCE_TESTNET_API_KEY",
+            secret_var: "BINANCE_TESTNET_API_SECRET",
+            keyring_account: "binance-testnet",
+        }
+    }
+
+    // This environment's keys; None if either variable is missing
+    pub fn credentials(&self) -> Option<ApiCredentials> {
+        Some(ApiCredentials::new(std::env::var(self.key_var).ok()?, std::env::var(self.secret_var).ok()?))
+    }
+}
+
+// Binance counts limits separately for market data weight and order placement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointCategory {
+    Market,  // exchangeInfo, depth, tickers, klines (REQUEST_WEIGHT)
+    Account, // balances, order status (REQUEST_WEIGHT)
+    Order,   // order placement and cancellation (ORDERS)
+    Margin,  // /sapi margin account, borrowing and repayment (separate SAPI IP weight)
+}
+
+// A weight budget that refills every `window`
+#[derive(Debug, Clone, Copy)]
+pub struct Budget {
+    pub limit: u32,
+    pub window: Duration,
+}
+
+// Per-category budgets, defaulting to a safety margin under Binance's published spot limits
+pub struct RateLimits {
+    pub budgets: HashMap<EndpointCategory, Budget>,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        let minute = Duration::from_secs(60);
+        let mut budgets = HashMap::new();
+        // Market and account share the 6000/min IP weight limit
+        budgets.insert(EndpointCategory::Market, Budget { limit: 4000, window: minute });
+        budgets.insert(EndpointCategory::Account, Budget { limit: 1000, window: minute });
+        budgets.insert(EndpointCategory::Order, Budget { limit: 80, window: Duration::from_secs(10) });
+        // SAPI weight is counted apart from the spot endpoints, 12000/min per IP
+        budgets.insert(EndpointCategory::Margin, Budget { limit: 10000, window: minute });
+        RateLimits { budgets }
+    }
+}
+
+struct WindowCounter {
+    budget: Budget,
+    window_start: Instant,
+    used: u32,
+}
+
+impl WindowCounter {
+    fn roll(&mut self, now: Instant) {
+        if now.duration_since(self.window_start) >= self.budget.window {
+            self.window_start = now;
+            self.used = 0;
+        }
+    }
+}
+
+// Tracks used weight per category and any ban imposed by the exchange
+pub struct RateLimiter {
+    counters: HashMap<EndpointCategory, WindowCounter>,
+    banned_until: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        let now = Instant::now();
+        let counters = limits
+            .budgets
+            .into_iter()
+            .map(|(category, budget)| (category, WindowCounter { budget, window_start: now, used: 0 }))
+            .collect();
+        RateLimiter { counters, banned_until: None }
+    }
+
+    // How long to wait before `weight` can be spent in `category`; zero if it can go now.
+    // On zero the weight is reserved.
+    fn try_reserve(&mut self, category: EndpointCategory, weight: u32) -> Duration {
+        let now = Instant::now();
+        if let Some(until) = self.banned_until {
+            if until > now {
+                return until - now;
+            }
+            self.banned_until = None;
+        }
+        let Some(counter) = self.counters.get_mut(&category) else {
+            return Duration::ZERO; // Unbudgeted category
+        };
+        counter.roll(now);
+        if counter.used + weight <= counter.budget.limit || counter.used == 0 {
+            counter.used += weight;
+            return Duration::ZERO;
+        }
+        counter.budget.window - now.duration_since(counter.window_start)
+    }
+
+    // Folds the exchange's view of our usage and any ban back into the limiter
+    fn on_response(&mut self, category: EndpointCategory, status: StatusCode, headers: &HeaderMap) {
+        if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            let retry_after = header_u64(headers, "retry-after").unwrap_or(60);
+            tracing::warn!(%status, retry_after, "REST rate limit hit, backing off");
+            self.banned_until = Some(Instant::now() + Duration::from_secs(retry_after));
+        }
+        let used = match category {
+            EndpointCategory::Order => header_u64(headers, "x-mbx-order-count-10s"),
+            EndpointCategory::Margin => header_u64(headers, "x-sapi-used-ip-weight-1m"),
+            _ => header_u64(headers, "x-mbx-used-weight-1m"),
+        };
+        if let (Some(used), Some(counter)) = (used, self.counters.get_mut(&category)) {
+            // Only ever raise our count; the header is shared by categories on the same IP limit
+            counter.used = counter.used.max(used as u32);
+        }
+    }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+// Key pair for SIGNED endpoints (account and order). Both halves are `Secret`s, so
+// neither shows up in Debug output.
+#[derive(Debug, Clone)]
+pub struct ApiCredentials {
+    pub api_key: Secret,
+    pub secret: Secret,
+}
+
+impl ApiCredentials {
+    pub fn new(api_key: impl Into<String>, secret: impl Into<String>) -> Self {
+        ApiCredentials {
+            api_key: Secret::new(api_key),
+            secret: Secret::new(secret),
+        }
+    }
+
+    // BINANCE_API_KEY and BINANCE_API_SECRET; None if either is missing
+    pub fn from_env() -> Option<Self> {
+        BinanceEndpoints::live().credentials()
+    }
+
+    // Hex HMAC-SHA256 of the query string, as Binance expects in `signature`
+    pub fn sign(&self, payload: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.expose().as_bytes()).expect("HMAC accepts any key length");
+        mac.update(payload.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+}
+
+// How long a signed request stays valid on the exchange side
+const RECV_WINDOW_MS: u64 = 5000;
+
+#[derive(Debug)]
+pub enum RestError {
+    Http(reqwest::Error),
+    Status(StatusCode, String),
+    Decode(String),
+}
+
+impl fmt::Display for RestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RestError::Http(e) => write!(f, "HTTP error: {}", e),
+            RestError::Status(status, body) => write!(f, "{}: {}", status, body),
+            RestError::Decode(e) => write!(f, "Unexpected response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RestError {}
+
+impl From<reqwest::Error> for RestError {
+    fn from(e: reqwest::Error) -> Self {
+        RestError::Http(e)
+    }
+}
+
+// The single entry point for REST calls; every request waits for budget first
+pub struct RestClient {
+    http: reqwest::Client,
+    base_url: String,
+    limiter: Mutex<RateLimiter>,
+    clock: Option<Arc<ServerClock>>, // Timestamps signed requests; the local clock when None
+}
+
+impl RestClient {
+    pub fn new(base_url: &str, limits: RateLimits) -> Self {
+        RestClient {
+            http: reqwest::Client::new(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            limiter: Mutex::new(RateLimiter::new(limits)),
+            clock: None,
+        }
+    }
+
+    // Sends every request through `proxy`
+    pub fn with_proxy(mut self, proxy: Option<&Proxy>) -> Self {
+        self.http = crate::proxy::http_client(proxy);
+        self
+    }
+
+    // Timestamps signed requests with the exchange's time, so local clock drift can't push
+    // them outside recvWindow
+    pub fn with_clock(mut self, clock: Arc<ServerClock>) -> Self {
+        self.clock = Some(clock);
+        self
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    // Waits until `weight` fits in the category budget (and any ban has expired)
+    pub async fn acquire(&self, category: EndpointCategory, weight: u32) {
+        loop {
+            let wait = self.limiter.lock().await.try_reserve(category, weight);
+            if wait.is_zero() {
+                return;
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Sends a prepared request through the limiter and returns the response body
+    pub async fn send(
+        &self,
+        category: EndpointCategory,
+        weight: u32,
+        request: reqwest::RequestBuilder,
+    ) -> Result<String, RestError> {
+        self.acquire(category, weight).await;
+        let response = request.send().await?;
+        let status = response.status();
+        self.limiter.lock().await.on_response(category, status, response.headers());
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(RestError::Status(status, body));
+        }
+        Ok(body)
+    }
+
+    // Builds the query string for a SIGNED endpoint: parameters, timestamp and recvWindow,
+    // then the signature over all of them
+    fn signed_query(&self, credentials: &ApiCredentials, query: &[(&str, String)]) -> String {
+        let timestamp = self.clock.as_ref().map_or_else(clock::local_ms, |clock| clock.now_ms());
+        let mut serializer = url::form_urlencoded::Serializer::new(String::new());
+        for (name, value) in query {
+            serializer.append_pair(name, value);
+        }
+        serializer.append_pair("timestamp", &timestamp.to_string());
+        serializer.append_pair("recvWindow", &RECV_WINDOW_MS.to_string());
+        let payload = serializer.finish();
+        let signature = credentials.sign(&payload);
+        format!("{}&signature={}", payload, signature)
+    }
+
+    // GET a SIGNED endpoint
+    pub async fn signed_get(
+        &self,
+        category: EndpointCategory,
+        weight: u32,
+        path: &str,
+        query: &[(&str, String)],
+        credentials: &ApiCredentials,
+    ) -> Result<String, RestError> {
+        let url = format!("{}{}?{}", self.base_url, path, self.signed_query(credentials, query));
+        let request = self.http.get(url).header("X-MBX-APIKEY", credentials.api_key.expose());
+        self.send(category, weight, request).await
+    }
+
+    // POST a SIGNED endpoint, parameters in the body
+    pub async fn signed_post(
+        &self,
+        category: EndpointCategory,
+        weight: u32,
+        path: &str,
+        query: &[(&str, String)],
+        credentials: &ApiCredentials,
+    ) -> Result<String, RestError> {
+        let request = self
+            .http
+            .post(format!("{}{}", self.base_url, path))
+            .header("X-MBX-APIKEY", credentials.api_key.expose())
+            .header(reqwest::header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(self.signed_query(credentials, query));
+        self.send(category, weight, request).await
+    }
+
+    // USER_STREAM endpoints take the API key header but no signature
+    pub async fn keyed(
+        &self,
+        method: reqwest::Method,
+        category: EndpointCategory,
+        weight: u32,
+        path: &str,
+        query: &[(&str, String)],
+        credentials: &ApiCredentials,
+    ) -> Result<String, RestError> {
+        let request = self
+            .http
+            .request(method, format!("{}{}", self.base_url, path))
+            .query(query)
+            .header("X-MBX-APIKEY", credentials.api_key.expose());
+        self.send(category, weight, request).await
+    }
+
+    // GET `path` with query parameters
+    pub async fn get(
+        &self,
+        category: EndpointCategory,
+        weight: u32,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<String, RestError> {
+        let request = self.http.get(format!("{}{}", self.base_url, path)).query(query);
+        self.send(category, weight, request).await
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use crate::events::Opportunity;
+use crate::fallback;
+use crate::graph::Graph;
+use crate::order::{OrderRequest, Side};
+use crate::rest::{RestClient, RestError};
+
+#[derive(Debug, Clone)]
+pub struct RevalidationConfig {
+    pub min_profit_bps: f64, // Least the cycle must still return at the fresh prices
+    pub rest_quotes: bool,   // Fetch each leg's top of book over REST instead of trusting the graph
+}
+
+impl Default for RevalidationConfig {
+    fn default() -> Self {
+        RevalidationConfig {
+            min_profit_bps: 0.0,
+            rest_quotes: false,
+        }
+    }
+}
+
+// Counters readable while the executor runs
+#[derive(Debug, Default)]
+pub struct RevalidationMetrics {
+    pub passed: AtomicU64,  // Cycles sent after the check
+    pub aborted: AtomicU64, // Cycles dropped because the profit had gone
+}
+
+impl RevalidationMetrics {
+    pub fn snapshot(&self) -> (u64, u64) {
+        (self.passed.load(Ordering::Relaxed), self.aborted.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug)]
+pub enum RevalidationError {
+    Rest(RestError),
+    NoQuote { from: String, to: String },         // A leg has no fresh price to check against
+    Decayed { profit_bps: f64, expected_bps: f64 }, // Below the threshold at fresh prices
+}
+
+impl fmt::Display for RevalidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RevalidationError::Rest(e) => write!(f, "{}", e),
+            RevalidationError::NoQuote { from, to } => write!(f, "no fresh quote for {} -> {}", from, to),
+            RevalidationError::Decayed { profit_bps, expected_bps } => {
+                write!(f, "profit decayed from {:.2} to {:.2} bps", expected_bps, profit_bps)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RevalidationError {}
+
+impl From<RestError> for RevalidationError {
+    fn from(e: RestError) -> Self {
+        RevalidationError::Rest(e)
+    }
+}
+
+// What the cycle returns if each leg now trades at `current[i]` instead of the rate it was
+// detected at. Fees stay as they were priced into the detected profit.
+pub fn repriced_profit(opportunity: &Opportunity, current: &[f64]) -> Option<f64> {
+    if opportunity.rates.len() != current.len() {
+        return None;
+    }
+    let expected = opportunity
+        .profit
+        .unwrap_or_else(|| opportunity.rates.iter().product::<f64>() - 1.0);
+    let drift: f64 = opportunity.rates.iter().zip(current).map(|(decided, now)| now / decided).product();
+    Some((1.0 + expected) * drift - 1.0).filter(|p| p.is_finite())
+}
+
+// Re-checks every leg of a cycle against the freshest prices available just before its
+// orders go out, since the book can move while detection runs and orders queue. Prices
+// come from the graph as last published, or with `rest_quotes`, from a bookTicker request
+// for the cycle's symbols: the bid on sells and the ask on buys, so the spread counts too.
+pub struct Revalidator {
+    config: RevalidationConfig,
+    rest: Option<Arc<RestClient>>,
+    metrics: Arc<RevalidationMetrics>,
+}
+
+impl Revalidator {
+    pub fn new(config: RevalidationConfig, rest: Option<Arc<RestClient>>) -> Self {
+        Revalidator {
+            config,
+            rest,
+            metrics: Arc::new(RevalidationMetrics::default()),
+        }
+    }
+
+    pub fn metrics(&self) -> Arc<RevalidationMetrics> {
+        self.metrics.clone()
+    }
+
+    // Whether checks go to the exchange, and so have to be awaited off the engine loop
+    pub fn uses_rest(&self) -> bool {
+        self.config.rest_quotes && self.rest.is_some()
+    }
+
+    // Profit at the graph's current rates, or why the cycle shouldn't go out
+    pub fn check(&self, opportunity: &Opportunity, graph: &Graph) -> Result<f64, RevalidationError> {
+        let current = opportunity
+            .path
+            .windows(2)
+            .map(|leg| {
+                graph.rate(&leg[0], &leg[1]).ok_or_else(|| RevalidationError::NoQuote { from: leg[0].clone(), to: leg[1].clone() })
+            })
+            .collect::<Result<Vec<f64>, _>>();
+        self.judge(opportunity, current)
+    }
+
+    // `check` against REST quotes for the symbols of `orders`, one per leg, when enabled;
+    // against the graph otherwise
+    pub async fn revalidate(&self, opportunity: &Opportunity, graph: &Graph, orders: &[OrderRequest]) -> Result<f64, RevalidationError> {
+        let Some(rest) = self.rest.as_ref().filter(|_| self.config.rest_quotes) else {
+            return self.check(opportunity, graph);
+        };
+        let current = quoted_rates(rest, &opportunity.path, orders).await;
+        self.judge(opportunity, current)
+    }
+
+    fn judge(&self, opportunity: &Opportunity, current: Result<Vec<f64>, RevalidationError>) -> Result<f64, RevalidationError> {
+        let result = current.and_then(|current| {
+            let expected_bps = opportunity.profit.unwrap_or_default() * 10_000.0;
+            let profit = repriced_profit(opportunity, &current).unwrap_or(f64::NEG_INFINITY);
+            if profit * 10_000.0 < self.config.min_profit_bps {
+                return Err(RevalidationError::Decayed { profit_bps: profit * 10_000.0, expected_bps });
+            }
+            Ok(profit)
+        });
+        let counter = if result.is_ok() { &self.metrics.passed } else { &self.metrics.aborted };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+}
+
+// Rate of each leg of `path` at the top of the REST book for the symbol its order trades
+async fn quoted_rates(rest: &RestClient, path: &[String], orders: &[OrderRequest]) -> Result<Vec<f64>, RevalidationError> {
+    let mut symbols: Vec<String> = orders.iter().map(|order| order.symbol.clone()).collect();
+    symbols.sort();
+    symbols.dedup();
+    let tickers = fallback::quote_symbols(rest, &symbols).await?;
+    path.windows(2)
+        .enumerate()
+        .map(|(i, leg)| {
+            let order = orders.get(i);
+            let ticker = order.and_then(|order| tickers.iter().find(|t| t.s == order.symbol));
+            let price = match order.map(|order| order.side) {
+                Some(Side::Sell) => ticker.and_then(|t| t.b.as_deref()?.parse::<f64>().ok()),
+                Some(Side::Buy) => ticker.and_then(|t| t.a.as_deref()?.parse::<f64>().ok()).map(|ask| 1.0 / ask),
+                None => None,
+            };
+            price.ok_or_else(|| RevalidationError::NoQuote { from: leg[0].clone(), to: leg[1].clone() })
+        })
+        .collect()
+}
@@ -0,0 +1,280 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+
+use crate::events::Opportunity;
+use crate::graph::{split_pair, Graph};
+use crate::inventory::Inventory;
+use crate::ledger::Fill;
+use crate::order::{OrderRequest, Side};
+
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone)]
+pub struct RiskConfig {
+    pub reference: String,                   // Asset notionals, exposure and losses are valued in
+    pub trade_notional: Decimal,             // What one cycle commits, as the executor sizes them
+    pub max_trade_notional: Option<Decimal>, // Largest notional a single execution may commit
+    pub max_asset_exposure: Option<Decimal>, // Largest net position per asset, counting the trade in flight
+    pub max_trades_per_minute: Option<u32>,  // Executions allowed in any 60 s window
+    pub daily_loss_limit: Option<f64>,       // Realized loss since 00:00 UTC that trips the kill switch
+}
+
+impl RiskConfig {
+    pub fn new(reference: &str, trade_notional: Decimal) -> Self {
+        RiskConfig {
+            reference: reference.to_string(),
+            trade_notional,
+            max_trade_notional: None,
+            max_asset_exposure: None,
+            max_trades_per_minute: None,
+            daily_loss_limit: None,
+        }
+    }
+}
+
+// Why an execution was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum RiskRejection {
+    KillSwitch(String),
+    TradeNotional { notional: Decimal, max: Decimal },
+    AssetExposure { asset: String, exposure: Decimal, max: Decimal },
+    UnpricedAsset(String), // No rate to the reference asset, so exposure can't be checked
+    TradeRate { trades: usize, max: u32 },
+}
+
+impl fmt::Display for RiskRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RiskRejection::KillSwitch(reason) => write!(f, "kill switch tripped: {}", reason),
+            RiskRejection::TradeNotional { notional, max } => write!(f, "trade notional {} over limit {}", notional, max),
+            RiskRejection::AssetExposure { asset, exposure, max } => {
+                write!(f, "{} exposure {} over limit {}", asset, exposure.round_dp(2), max)
+            }
+            RiskRejection::UnpricedAsset(asset) => write!(f, "no price for {} in the reference asset", asset),
+            RiskRejection::TradeRate { trades, max } => write!(f, "{} trades in the last minute, limit {}", trades, max),
+        }
+    }
+}
+
+// Counters readable while the engine runs
+#[derive(Debug, Default)]
+pub struct RiskMetrics {
+    pub approved: AtomicU64, // Executions the limits allowed
+    pub rejected: AtomicU64, // Executions refused, kill switch included
+}
+
+impl RiskMetrics {
+    pub fn snapshot(&self) -> (u64, u64) {
+        (self.approved.load(Ordering::Relaxed), self.rejected.load(Ordering::Relaxed))
+    }
+}
+
+struct RiskState {
+    positions: Inventory,          // Net change per asset from recorded fills
+    executions: VecDeque<Instant>, // Approved executions inside the rate window
+    day: Option<NaiveDate>,
+    day_pnl: f64,
+}
+
+struct Shared {
+    config: RiskConfig,
+    tripped: AtomicBool,
+    reason: Mutex<Option<String>>,
+    state: Mutex<RiskState>,
+    metrics: Arc<RiskMetrics>,
+}
+
+// Pre-trade limits the engine consults before handing an opportunity or a signal's orders
+// to the executor, plus a kill switch that blocks every execution until it is reset. Detection and
+// reporting carry on while it is tripped. Clones share state, so the executor (or
+// whatever sees fills) records fills and realized PnL on its own handle; the daily loss
+// limit trips the switch when crossed.
+#[derive(Clone)]
+pub struct RiskManager {
+    shared: Arc<Shared>,
+}
+
+impl RiskManager {
+    pub fn new(config: RiskConfig) -> Self {
+        RiskManager {
+            shared: Arc::new(Shared {
+                config,
+                tripped: AtomicBool::new(false),
+                reason: Mutex::new(None),
+                state: Mutex::new(RiskState {
+                    positions: Inventory::new(),
+                    executions: VecDeque::new(),
+                    day: None,
+                    day_pnl: 0.0,
+                }),
+                metrics: Arc::default(),
+            }),
+        }
+    }
+
+    pub fn config(&self) -> &RiskConfig {
+        &self.shared.config
+    }
+
+    pub fn metrics(&self) -> Arc<RiskMetrics> {
+        self.shared.metrics.clone()
+    }
+
+    // Approves or refuses executing `opportunity` now. Approvals count towards the rate
+    // limit, so only call this when the execution will actually go ahead.
+    pub fn check(&self, opportunity: &Opportunity, graph: &Graph, now: Instant) -> Result<(), RiskRejection> {
+        let notional = self.shared.config.trade_notional;
+        // Every asset on the cycle holds the trade's notional while its legs are in flight
+        let exposure = |_: &str, position: Decimal| position.abs() + notional;
+        self.count(self.evaluate(notional, &opportunity.path, exposure, graph, now))
+    }
+
+    // Same for a signal's orders (hedges and the like), which commit what their base
+    // quantities are worth and move each asset's position by as much either way
+    pub fn check_orders(&self, orders: &[OrderRequest], graph: &Graph, now: Instant) -> Result<(), RiskRejection> {
+        let result = self.order_changes(orders, graph).and_then(|(notional, changes)| {
+            let assets: Vec<String> = changes.keys().cloned().collect();
+            let exposure = |asset: &str, position: Decimal| (position + changes[asset]).abs();
+            self.evaluate(notional, &assets, exposure, graph, now)
+        });
+        self.count(result)
+    }
+
+    fn count(&self, result: Result<(), RiskRejection>) -> Result<(), RiskRejection> {
+        let counter = match result {
+            Ok(()) => &self.shared.metrics.approved,
+            Err(_) => &self.shared.metrics.rejected,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    // Notional of the orders and the change they make to each asset's position, both in
+    // the reference asset
+    fn order_changes(&self, orders: &[OrderRequest], graph: &Graph) -> Result<(Decimal, HashMap<String, Decimal>), RiskRejection> {
+        let reference = &self.shared.config.reference;
+        let mut notional = Decimal::ZERO;
+        let mut changes: HashMap<String, Decimal> = HashMap::new();
+        for order in orders {
+            let (base, quote) = split_pair(&order.symbol);
+            let rate = match base == reference.as_str() {
+                true => Some(Decimal::ONE),
+                false => graph.rate(base, reference).and_then(|rate| Decimal::try_from(rate).ok()),
+            };
+            let value = order.quantity * rate.ok_or_else(|| RiskRejection::UnpricedAsset(base.to_string()))?;
+            notional += value;
+            let bought = match order.side {
+                Side::Buy => value,
+                Side::Sell => -value,
+            };
+            *changes.entry(base.to_string()).or_default() += bought;
+            *changes.entry(quote.to_string()).or_default() -= bought;
+        }
+        Ok((notional, changes))
+    }
+
+    // Checks an execution committing `notional` (in the reference asset) across `assets`,
+    // `exposure` giving what an asset's position (valued in it) comes to with the execution
+    fn evaluate(
+        &self,
+        notional: Decimal,
+        assets: &[String],
+        exposure: impl Fn(&str, Decimal) -> Decimal,
+        graph: &Graph,
+        now: Instant,
+    ) -> Result<(), RiskRejection> {
+        if let Some(reason) = self.kill_reason() {
+            return Err(RiskRejection::KillSwitch(reason));
+        }
+        let config = &self.shared.config;
+        if let Some(max) = config.max_trade_notional.filter(|&max| notional > max) {
+            return Err(RiskRejection::TradeNotional { notional, max });
+        }
+
+        let mut state = self.shared.state.lock().unwrap();
+        if let Some(max) = config.max_asset_exposure {
+            for asset in assets.iter().filter(|asset| **asset != config.reference) {
+                let Some(rate) = graph.rate(asset, &config.reference).and_then(|rate| Decimal::try_from(rate).ok()) else {
+                    return Err(RiskRejection::UnpricedAsset(asset.clone()));
+                };
+                let exposure = exposure(asset, state.positions.balance(asset) * rate);
+                if exposure > max {
+                    return Err(RiskRejection::AssetExposure { asset: asset.clone(), exposure, max });
+                }
+            }
+        }
+        if let Some(max) = config.max_trades_per_minute {
+            while state.executions.front().is_some_and(|&t| now.duration_since(t) >= RATE_WINDOW) {
+                state.executions.pop_front();
+            }
+            if state.executions.len() >= max as usize {
+                return Err(RiskRejection::TradeRate { trades: state.executions.len(), max });
+            }
+            state.executions.push_back(now);
+        }
+        Ok(())
+    }
+
+    // Updates the net position per asset
+    pub fn record_fill(&self, fill: &Fill) {
+        self.shared.state.lock().unwrap().positions.apply(fill);
+    }
+
+    // Adds realized PnL (in the reference asset) for the UTC day of `time`
+    pub fn record_pnl(&self, pnl: f64, time: DateTime<Utc>) {
+        let day_pnl = {
+            let mut state = self.shared.state.lock().unwrap();
+            let day = time.date_naive();
+            if state.day != Some(day) {
+                state.day = Some(day);
+                state.day_pnl = 0.0;
+            }
+            state.day_pnl += pnl;
+            state.day_pnl
+        };
+        let config = &self.shared.config;
+        if let Some(limit) = config.daily_loss_limit.filter(|&limit| day_pnl <= -limit) {
+            self.trip(&format!("daily loss {:.2} {} reached the {} limit", -day_pnl, config.reference, limit));
+        }
+    }
+
+    // Realized PnL so far today
+    pub fn daily_pnl(&self) -> f64 {
+        self.shared.state.lock().unwrap().day_pnl
+    }
+
+    // Blocks all executions until `reset`; the first reason is kept
+    pub fn trip(&self, reason: &str) {
+        let mut current = self.shared.reason.lock().unwrap();
+        if current.is_none() {
+            tracing::error!(%reason, "Kill switch tripped");
+            *current = Some(reason.to_string());
+            self.shared.tripped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn reset(&self) {
+        let mut current = self.shared.reason.lock().unwrap();
+        if current.take().is_some() {
+            tracing::warn!("Kill switch reset");
+            self.shared.tripped.store(false, Ordering::Relaxed);
+        }
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.shared.tripped.load(Ordering::Relaxed)
+    }
+
+    pub fn kill_reason(&self) -> Option<String> {
+        if !self.is_tripped() {
+            return None;
+        }
+        self.shared.reason.lock().unwrap().clone()
+    }
+}
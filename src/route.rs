@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rust_decimal::Decimal;
+
+use crate::cbbo::VenueQuote;
+use crate::order::Side;
+
+// Default taker fee for venues missing from the fee table
+const DEFAULT_FEE_BPS: Decimal = Decimal::TEN;
+
+// Moving an asset between venues: a fixed fee in units of the asset
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TransferCost {
+    pub asset: String,
+    pub from: String,
+    pub to: String,
+    pub fee: Decimal,
+    #[serde(default)]
+    pub minutes: f64, // Typical settlement time, reported but not optimised
+}
+
+// Fee and transfer tables, e.g.
+// {"trading_fee_bps":{"binance":7.5,"kraken":26},
+//  "transfers":[{"asset":"USDT","from":"binance","to":"kraken","fee":1.0,"minutes":5}]}
+#[derive(serde::Deserialize, Debug, Clone, Default)]
+pub struct FeeTable {
+    #[serde(default)]
+    pub trading_fee_bps: HashMap<String, Decimal>,
+    #[serde(default)]
+    pub transfers: Vec<TransferCost>,
+}
+
+impl FeeTable {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn fee_bps(&self, venue: &str) -> Decimal {
+        self.trading_fee_bps.get(venue).copied().unwrap_or(DEFAULT_FEE_BPS)
+    }
+}
+
+// An asset held at a venue, written "USDT@binance"
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub asset: String,
+    pub venue: String,
+}
+
+impl Location {
+    pub fn parse(value: &str) -> Option<Self> {
+        let (asset, venue) = value.split_once('@')?;
+        if asset.is_empty() || venue.is_empty() {
+            return None;
+        }
+        Some(Location {
+            asset: asset.to_uppercase(),
+            venue: venue.to_lowercase(),
+        })
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}@{}", self.asset, self.venue)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum RouteStep {
+    Trade {
+        venue: String,
+        symbol: String,
+        side: Side,
+        price: Decimal,
+        fee_bps: Decimal,
+    },
+    Transfer {
+        asset: String,
+        from: String,
+        to: String,
+        fee: Decimal,
+        minutes: f64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub steps: Vec<RouteStep>,
+    pub amounts: Vec<Decimal>, // Amount held before the first step and after each step
+}
+
+impl Route {
+    pub fn final_amount(&self) -> Decimal {
+        self.amounts.last().copied().unwrap_or_default()
+    }
+}
+
+// An edge in the (asset, venue) graph that transforms the amount held
+struct Hop {
+    to: Location,
+    step: RouteStep,
+}
+
+impl Hop {
+    // None if the amount overflows, which only happens with nonsensical quotes
+    fn apply(&self, amount: Decimal) -> Option<Decimal> {
+        match &self.step {
+            RouteStep::Trade { side, price, fee_bps, .. } => {
+                let gross = match side {
+                    Side::Sell => amount.checked_mul(*price)?,
+                    Side::Buy => amount.checked_div(*price)?,
+                };
+                gross.checked_mul(Decimal::ONE - fee_bps / Decimal::from(10_000))
+            }
+            RouteStep::Transfer { fee, .. } => amount.checked_sub(*fee),
+        }
+    }
+}
+
+// Cheapest trade-and-transfer path between two locations over top-of-book quotes
+pub struct RoutePlanner {
+    hops: HashMap<Location, Vec<Hop>>,
+}
+
+impl RoutePlanner {
+    pub fn new(quotes: &[VenueQuote], fees: &FeeTable) -> Self {
+        let mut hops: HashMap<Location, Vec<Hop>> = HashMap::new();
+        for quote in quotes {
+            // Quotes arrive as f64; crossing into Decimal here keeps every amount exact downstream
+            let (Ok(bid), Ok(ask)) = (Decimal::try_from(quote.bid), Decimal::try_from(quote.ask)) else {
+                continue;
+            };
+            if bid <= Decimal::ZERO || ask <= Decimal::ZERO {
+                continue;
+            }
+            let fee_bps = fees.fee_bps(&quote.venue);
+            let symbol = format!("{}{}", quote.base, quote.quote);
+            let base = Location { asset: quote.base.clone(), venue: quote.venue.clone() };
+            let quote_location = Location { asset: quote.quote.clone(), venue: quote.venue.clone() };
+            // Selling base hits the bid, buying base lifts the ask
+            hops.entry(base.clone()).or_default().push(Hop {
+                to: quote_location.clone(),
+                step: RouteStep::Trade { venue: quote.venue.clone(), symbol: symbol.clone(), side: Side::Sell, price: bid, fee_bps },
+            });
+            hops.entry(quote_location).or_default().push(Hop {
+                to: base,
+                step: RouteStep::Trade { venue: quote.venue.clone(), symbol, side: Side::Buy, price: ask, fee_bps },
+            });
+        }
+        for transfer in &fees.transfers {
+            let from = Location { asset: transfer.asset.clone(), venue: transfer.from.clone() };
+            let to = Location { asset: transfer.asset.clone(), venue: transfer.to.clone() };
+            hops.entry(from).or_default().push(Hop {
+                to,
+                step: RouteStep::Transfer {
+                    asset: transfer.asset.clone(),
+                    from: transfer.from.clone(),
+                    to: transfer.to.clone(),
+                    fee: transfer.fee,
+                    minutes: transfer.minutes,
+                },
+            });
+        }
+        RoutePlanner { hops }
+    }
+
+    // Maximises the amount arriving at `target` using at most `max_steps` trades and transfers.
+    // Amounts in different assets aren't comparable, so this relaxes by step count rather
+    // than running Dijkstra; fixed transfer fees make the result depend on `amount`.
+    pub fn plan(&self, source: &Location, target: &Location, amount: Decimal, max_steps: usize) -> Option<Route> {
+        // Best (amount, route) reaching each location after the steps taken so far
+        let mut best: HashMap<Location, (Decimal, Vec<&Hop>)> = HashMap::new();
+        best.insert(source.clone(), (amount, Vec::new()));
+        let mut frontier = vec![source.clone()];
+
+        for _ in 0..max_steps {
+            let mut next = Vec::new();
+            for location in &frontier {
+                let (held, path) = best[location].clone();
+                for hop in self.hops.get(location).into_iter().flatten() {
+                    let Some(arrived) = hop.apply(held) else { continue };
+                    if arrived <= Decimal::ZERO || hop.to == *source {
+                        continue;
+                    }
+                    let improves = best.get(&hop.to).is_none_or(|(current, _)| arrived > *current);
+                    if improves {
+                        let mut route = path.clone();
+                        route.push(hop);
+                        best.insert(hop.to.clone(), (arrived, route));
+                        next.push(hop.to.clone());
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        let (_, hops) = best.get(target)?;
+        let mut amounts = vec![amount];
+        for hop in hops {
+            let held = *amounts.last().unwrap();
+            amounts.push(hop.apply(held)?);
+        }
+        Some(Route {
+            steps: hops.iter().map(|hop| hop.step.clone()).collect(),
+            amounts,
+        })
+    }
+}
@@ -0,0 +1,76 @@
+use rust_decimal::prelude::ToPrimitive;
+
+use crate::cbbo::ConsolidatedBook;
+use crate::events::LegRoute;
+use crate::graph::Graph;
+use crate::route::FeeTable;
+
+#[derive(Debug, Clone)]
+pub struct RoutingConfig {
+    pub fees: FeeTable,    // Taker fee per venue
+    pub notional: f64,     // Size of the trade routed through the cycle, in `reference`
+    pub reference: String, // Asset `notional` is given in
+}
+
+impl RoutingConfig {
+    pub fn new(notional: f64) -> Self {
+        RoutingConfig {
+            fees: FeeTable::default(),
+            notional,
+            reference: "USDT".to_string(),
+        }
+    }
+}
+
+// Picks the venue for each leg of a cycle from every venue quoting the pair, rather than
+// the one with the best top-of-book price: a venue's rate is taken net of its taker fee
+// and scaled down by the share of the leg its top of book can't fill, so a slightly worse
+// price with the depth for the whole trade beats a better one for a fraction of it
+pub struct SmartRouter {
+    config: RoutingConfig,
+}
+
+impl SmartRouter {
+    pub fn new(config: RoutingConfig) -> Self {
+        SmartRouter { config }
+    }
+
+    // One route per leg, or None when the cycle can't be sized or a leg has no venue
+    pub fn route(&self, book: &ConsolidatedBook, graph: &Graph, path: &[String]) -> Option<Vec<LegRoute>> {
+        let start = path.first()?;
+        let mut amount = if *start == self.config.reference {
+            self.config.notional
+        } else {
+            self.config.notional * graph.rate(&self.config.reference, start)?
+        };
+        let mut routes = Vec::with_capacity(path.len().saturating_sub(1));
+        for leg in path.windows(2) {
+            let route = self.route_leg(book, &leg[0], &leg[1], amount)?;
+            // Whatever the chosen venue can't fill doesn't reach the next leg
+            amount *= route.rate * route.fill;
+            routes.push(route);
+        }
+        Some(routes)
+    }
+
+    // Best venue for converting `amount` of `from` into `to`
+    fn route_leg(&self, book: &ConsolidatedBook, from: &str, to: &str, amount: f64) -> Option<LegRoute> {
+        // Selling `from` as base hits bids; buying `to` as base lifts asks. Depth is
+        // measured in units of `from` either way.
+        let sells = book.quotes(from, to).filter(|q| q.bid > 0.0).map(|q| (q.venue.as_str(), q.bid, q.bid_qty));
+        let buys = book.quotes(to, from).filter(|q| q.ask > 0.0).map(|q| (q.venue.as_str(), 1.0 / q.ask, q.ask_qty * q.ask));
+        sells
+            .chain(buys)
+            .map(|(venue, rate, depth)| {
+                let fee_bps = self.config.fees.fee_bps(venue).to_f64().unwrap_or_default();
+                let fill = if amount > 0.0 { (depth / amount).min(1.0) } else { 1.0 };
+                LegRoute {
+                    venue: venue.to_string(),
+                    rate: rate * (1.0 - fee_bps / 10_000.0),
+                    fee_bps,
+                    fill,
+                }
+            })
+            .max_by(|a, b| (a.rate * a.fill).total_cmp(&(b.rate * b.fill)))
+    }
+}
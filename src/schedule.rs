@@ -0,0 +1,161 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use chrono::{DateTime, Datelike, NaiveTime, Utc, Weekday};
+
+// How often the calendar file is checked for edits
+const RELOAD_INTERVAL: Duration = Duration::from_secs(30);
+
+// A window that recurs every day (or on selected weekdays), in UTC. Windows may wrap
+// midnight, e.g. 22:00-02:00.
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct QuietHours {
+    pub start: String, // "HH:MM"
+    pub end: String,   // "HH:MM"
+    #[serde(default)]
+    pub weekdays: Vec<Weekday>, // Empty means every day
+    #[serde(default)]
+    pub label: String,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BlackoutKind {
+    Maintenance, // Announced exchange maintenance
+    Event,       // Economic releases and similar
+    User,
+}
+
+// A one-off no-trade period
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct Blackout {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub kind: BlackoutKind,
+    #[serde(default)]
+    pub reason: String,
+}
+
+// On-disk calendar, e.g.
+// {"quiet_hours":[{"start":"23:30","end":"00:30","label":"daily settlement"}],
+//  "blackouts":[{"start":"2026-11-04T12:55:00Z","end":"2026-11-04T13:30:00Z","kind":"event","reason":"FOMC"}]}
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone, Default)]
+pub struct TradingCalendar {
+    #[serde(default)]
+    pub quiet_hours: Vec<QuietHours>,
+    #[serde(default)]
+    pub blackouts: Vec<Blackout>,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+impl TradingCalendar {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let calendar: TradingCalendar = serde_json::from_str(&fs::read_to_string(path)?)?;
+        for window in &calendar.quiet_hours {
+            if parse_time(&window.start).is_none() || parse_time(&window.end).is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("quiet hours must be HH:MM, got {}-{}", window.start, window.end),
+                ));
+            }
+        }
+        Ok(calendar)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+
+    // Reason trading is blocked at `now`, or None if trading is allowed
+    pub fn blocked_at(&self, now: DateTime<Utc>) -> Option<String> {
+        if let Some(blackout) = self.blackouts.iter().find(|b| b.start <= now && now < b.end) {
+            return Some(format!("{:?} blackout: {}", blackout.kind, blackout.reason));
+        }
+        let time = now.time();
+        let weekday = now.weekday();
+        for window in &self.quiet_hours {
+            let (Some(start), Some(end)) = (parse_time(&window.start), parse_time(&window.end)) else {
+                continue;
+            };
+            let inside = if start <= end {
+                start <= time && time < end
+            } else {
+                time >= start || time < end
+            };
+            // A window wrapping midnight belongs to the weekday it started on
+            let day = if start > end && time < end { weekday.pred() } else { weekday };
+            if inside && (window.weekdays.is_empty() || window.weekdays.contains(&day)) {
+                return Some(format!("quiet hours {}-{} {}", window.start, window.end, window.label));
+            }
+        }
+        None
+    }
+
+    // Drops blackouts that have already ended
+    pub fn prune(&mut self, now: DateTime<Utc>) {
+        self.blackouts.retain(|b| b.end > now);
+    }
+}
+
+// A calendar backed by a file that is picked up again when edited
+pub struct Schedule {
+    path: PathBuf,
+    calendar: TradingCalendar,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl Schedule {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let calendar = TradingCalendar::load(path)?;
+        Ok(Schedule {
+            path: path.to_path_buf(),
+            calendar,
+            modified: fs::metadata(path).and_then(|m| m.modified()).ok(),
+            last_check: Instant::now(),
+        })
+    }
+
+    pub fn calendar(&self) -> &TradingCalendar {
+        &self.calendar
+    }
+
+    // Adds a no-trade period and persists it
+    pub fn add_blackout(&mut self, blackout: Blackout) -> io::Result<()> {
+        self.calendar.prune(Utc::now());
+        self.calendar.blackouts.push(blackout);
+        self.calendar.save(&self.path)?;
+        self.modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        Ok(())
+    }
+
+    pub fn blocked_at(&mut self, now: DateTime<Utc>) -> Option<String> {
+        self.reload_if_changed();
+        self.calendar.blocked_at(now)
+    }
+
+    fn reload_if_changed(&mut self) {
+        if self.last_check.elapsed() < RELOAD_INTERVAL {
+            return;
+        }
+        self.last_check = Instant::now();
+        let modified = fs::metadata(&self.path).and_then(|m| m.modified()).ok();
+        if modified == self.modified {
+            return;
+        }
+        match TradingCalendar::load(&self.path) {
+            Ok(calendar) => {
+                tracing::info!(path = %self.path.display(), "Reloaded trading calendar");
+                self.calendar = calendar;
+                self.modified = modified;
+            }
+            // Keep the previous calendar rather than trading through a broken file
+            Err(e) => tracing::error!(error = %e, "Failed to reload trading calendar"),
+        }
+    }
+}
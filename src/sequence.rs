@@ -0,0 +1,111 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::ticker::TickerData;
+
+#[derive(Debug, Clone)]
+pub struct SequenceConfig {
+    pub max_gap: Duration, // Largest event-time step between two updates of a symbol
+    pub invalidate: bool,  // Take the symbol's edge out until an update arrives in order
+}
+
+impl Default for SequenceConfig {
+    fn default() -> Self {
+        SequenceConfig {
+            max_gap: Duration::from_secs(30),
+            invalidate: false,
+        }
+    }
+}
+
+// Counters readable while the engine runs
+#[derive(Debug, Default)]
+pub struct SequenceMetrics {
+    pub out_of_order: AtomicU64, // Updates older than one already applied, dropped
+    pub gaps: AtomicU64,         // Updates arriving more than `max_gap` after the previous one
+}
+
+impl SequenceMetrics {
+    pub fn snapshot(&self) -> (u64, u64) {
+        (self.out_of_order.load(Ordering::Relaxed), self.gaps.load(Ordering::Relaxed))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceIssue {
+    OutOfOrder, // Older than the last update seen, by event time or update id
+    Gap,        // In order, but after a silence longer than `max_gap`
+}
+
+#[derive(Clone, Copy)]
+struct Last {
+    event_time: u64,
+    update_id: Option<u64>,
+}
+
+// Last exchange event time and book update id per symbol. Updates without either (REST
+// polls, some replays) can't be ordered and pass through untracked.
+pub struct SequenceTracker {
+    config: SequenceConfig,
+    last: HashMap<String, Last>,
+    metrics: Arc<SequenceMetrics>,
+}
+
+impl SequenceTracker {
+    pub fn new(config: SequenceConfig) -> Self {
+        SequenceTracker {
+            config,
+            last: HashMap::new(),
+            metrics: Arc::new(SequenceMetrics::default()),
+        }
+    }
+
+    pub fn config(&self) -> &SequenceConfig {
+        &self.config
+    }
+
+    pub fn metrics(&self) -> Arc<SequenceMetrics> {
+        self.metrics.clone()
+    }
+
+    // Checks one update against the last one of its symbol and remembers it unless it is
+    // out of order. A repeat of the last event time with no newer update id is in order.
+    pub fn observe(&mut self, ticker: &TickerData) -> Option<SequenceIssue> {
+        if ticker.event_time == 0 && ticker.update_id.is_none() {
+            return None;
+        }
+        let next = Last { event_time: ticker.event_time, update_id: ticker.update_id };
+        let Some(last) = self.last.get_mut(&ticker.s) else {
+            self.last.insert(ticker.s.clone(), next);
+            return None;
+        };
+        let older_id = matches!((next.update_id, last.update_id), (Some(id), Some(seen)) if id < seen);
+        let older_time = next.event_time > 0 && next.event_time < last.event_time;
+        if older_id || older_time {
+            self.metrics.out_of_order.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(
+                symbol = %ticker.s,
+                event_time = next.event_time,
+                last_event_time = last.event_time,
+                update_id = next.update_id,
+                last_update_id = last.update_id,
+                "Out-of-order update"
+            );
+            return Some(SequenceIssue::OutOfOrder);
+        }
+        let step = next.event_time.saturating_sub(last.event_time);
+        let gap = last.event_time > 0 && step > self.config.max_gap.as_millis() as u64;
+        *last = Last {
+            event_time: if next.event_time > 0 { next.event_time } else { last.event_time },
+            update_id: next.update_id.or(last.update_id),
+        };
+        if gap {
+            self.metrics.gaps.fetch_add(1, Ordering::Relaxed);
+            tracing::warn!(symbol = %ticker.s, gap_ms = step, "Gap in updates");
+            return Some(SequenceIssue::Gap);
+        }
+        None
+    }
+}
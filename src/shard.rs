@@ -0,0 +1,153 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+use crate::cbbo::VenueQuote;
+use crate::graph::extract_currency_pair;
+use crate::ticker::{to_venue_quote, TickerData};
+
+// Points per worker on the hash ring; enough to keep shards within a few percent of even
+const VIRTUAL_NODES: usize = 64;
+
+// Consistent hashing of symbols onto workers, so resizing the pool only moves the
+// symbols on the affected arcs of the ring
+pub struct HashRing {
+    points: Vec<(u64, usize)>, // (hash, worker), sorted by hash
+}
+
+impl HashRing {
+    pub fn new(workers: usize) -> Self {
+        let mut points: Vec<(u64, usize)> = (0..workers)
+            .flat_map(|worker| (0..VIRTUAL_NODES).map(move |node| (hash(&(worker, node)), worker)))
+            .collect();
+        points.sort_unstable();
+        HashRing { points }
+    }
+
+    pub fn worker(&self, symbol: &str) -> usize {
+        let h = hash(&symbol);
+        let index = self.points.partition_point(|(point, _)| *point < h);
+        self.points[index % self.points.len()].1
+    }
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+// A symbol's parsed state as owned by its shard
+#[derive(Debug, Clone)]
+pub struct ShardEntry {
+    pub base: String,
+    pub quote: String,
+    pub last: f64,
+    pub top_of_book: Option<VenueQuote>,
+}
+
+#[derive(Default)]
+struct ShardState {
+    entries: HashMap<String, (ShardEntry, bool)>, // Entry and whether it's in `changed`
+    changed: Vec<String>,                          // Symbols updated since the scanner last merged
+}
+
+// Ticker ingestion spread over a fixed pool of tasks. Each worker owns the parsed price
+// and book state for its shard of symbols; the scanner collects what changed from every
+// shard before a detection pass.
+pub struct ShardPool {
+    ring: HashRing,
+    senders: Vec<mpsc::UnboundedSender<Vec<TickerData>>>,
+    states: Vec<Arc<Mutex<ShardState>>>,
+}
+
+impl ShardPool {
+    // Spawns the workers; must be called inside a Tokio runtime
+    pub fn spawn(workers: usize, venue: &str) -> Self {
+        let workers = workers.max(1);
+        let mut senders = Vec::with_capacity(workers);
+        let mut states = Vec::with_capacity(workers);
+        for shard in 0..workers {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let state = Arc::new(Mutex::new(ShardState::default()));
+            tokio::spawn(run_worker(shard, venue.to_string(), rx, state.clone()));
+            senders.push(tx);
+            states.push(state);
+        }
+        ShardPool {
+            ring: HashRing::new(workers),
+            senders,
+            states,
+        }
+    }
+
+    // Routes each ticker to the worker owning its symbol
+    pub fn dispatch(&self, tickers: &[TickerData]) {
+        let mut batches: Vec<Vec<TickerData>> = vec![Vec::new(); self.senders.len()];
+        for ticker in tickers {
+            batches[self.ring.worker(&ticker.s)].push(ticker.clone());
+        }
+        for (sender, batch) in self.senders.iter().zip(batches) {
+            if !batch.is_empty() {
+                let _ = sender.send(batch);
+            }
+        }
+    }
+
+    // Takes every entry changed since the last merge, across all shards
+    pub fn merge(&self) -> Vec<ShardEntry> {
+        let mut merged = Vec::new();
+        for state in &self.states {
+            let mut state = state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            for symbol in mem::take(&mut state.changed) {
+                if let Some((entry, pending)) = state.entries.get_mut(&symbol) {
+                    *pending = false;
+                    merged.push(entry.clone());
+                }
+            }
+        }
+        merged
+    }
+}
+
+async fn run_worker(
+    shard: usize,
+    venue: String,
+    mut batches: mpsc::UnboundedReceiver<Vec<TickerData>>,
+    state: Arc<Mutex<ShardState>>,
+) {
+    while let Some(batch) = batches.recv().await {
+        // Parse outside the lock; the scanner only waits for the inserts
+        let parsed: Vec<(String, ShardEntry)> = batch
+            .iter()
+            .filter_map(|ticker| {
+                let Ok(last) = ticker.c.parse::<f64>() else {
+                    tracing::warn!(shard, symbol = %ticker.s, price = %ticker.c, "Error parsing price");
+                    return None;
+                };
+                let (base, quote) = extract_currency_pair(&ticker.s);
+                let top_of_book = to_venue_quote(ticker, &venue);
+                Some((ticker.s.clone(), ShardEntry { base, quote, last, top_of_book }))
+            })
+            .collect();
+        let mut state = state.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        for (symbol, entry) in parsed {
+            match state.entries.get_mut(&symbol) {
+                Some((current, pending)) => {
+                    *current = entry;
+                    if !mem::replace(pending, true) {
+                        state.changed.push(symbol);
+                    }
+                }
+                None => {
+                    state.entries.insert(symbol.clone(), (entry, true));
+                    state.changed.push(symbol);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+use crate::graph::Graph;
+
+// Read-copy-update view of the market graph for code outside the engine loop (execution,
+// validation, dashboards). The engine keeps mutating its own graph and publishes an
+// immutable snapshot once per detection pass; readers load the latest snapshot without
+// locking, and a reader holding an old one never blocks the writer.
+pub struct SharedGraph {
+    current: ArcSwap<Graph>,
+    epoch: AtomicU64, // Number of snapshots published
+}
+
+impl SharedGraph {
+    pub fn new(graph: Graph) -> Self {
+        SharedGraph {
+            current: ArcSwap::from_pointee(graph),
+            epoch: AtomicU64::new(0),
+        }
+    }
+
+    // The latest snapshot; cheap enough to call per lookup, but hold on to it when
+    // several reads need to agree with each other
+    pub fn load(&self) -> Arc<Graph> {
+        self.current.load_full()
+    }
+
+    // Replaces the snapshot with a copy of `graph`
+    pub fn publish(&self, graph: &Graph) {
+        self.current.store(Arc::new(graph.clone()));
+        self.epoch.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use crate::events::MarketEvent;
+use crate::feed::Feed;
+use crate::recorder::load_session;
+use crate::ticker::{StreamMessage, TickerData, TickerPayload};
+
+// xorshift64*, so runs are repeatable for a given seed without pulling in a dependency
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // Zero is the one state xorshift never leaves
+        Rng(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    // Uniform in [0, n)
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    // Uniform in [-1, 1)
+    pub fn signed_unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 52) as f64 - 1.0
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SimConfig {
+    pub seed: u64,         // Random walk seed; the same seed plays the same session
+    pub steps: usize,      // Updates the random walk generates before the feed ends
+    pub batch: usize,      // Symbols moved per update
+    pub max_move_bps: f64, // Largest price move of one symbol in one update
+    pub spread_bps: f64,   // Bid/ask spread quoted around the last price, none if zero
+    pub start_time: u64,   // Event time stamped on the first update, ms
+    pub step_ms: u64,      // Event time between updates
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        SimConfig {
+            seed: 1,
+            steps: 1_000,
+            batch: 20,
+            max_move_bps: 5.0,
+            spread_bps: 2.0,
+            start_time: 1_704_067_200_000, // 2024-01-01T00:00:00Z
+            step_ms: 100,
+        }
+    }
+}
+
+// Control lines of a script, for the events a recorded session can't express
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Directive {
+    remove: Option<String>, // The symbol was delisted
+    degraded: Option<bool>, // The feed went degraded or recovered
+    venue: Option<String>,  // Venue of the feed state change, the simulated feed's by default
+}
+
+enum Source {
+    Script(std::vec::IntoIter<MarketEvent>),
+    Walk { rng: Rng, tickers: Vec<TickerData>, prices: Vec<f64>, left: usize },
+}
+
+// Feed for simulations and integration runs that needs no network and plays the same
+// events in the same order every time: either a script of market events, or a seeded
+// random walk over a starting snapshot's prices. Updates are stamped with a simulated
+// clock instead of the wall clock, so opportunities carry the same event times on every
+// run. What the engine itself times by the wall clock (dedup, TTLs, throttling, edge
+// expiry, breaker cooldowns) still does, so leave those off for runs that must match
+// exactly.
+pub struct SimFeed {
+    config: SimConfig,
+    source: Source,
+    step: u64,
+}
+
+impl SimFeed {
+    pub fn scripted(events: Vec<MarketEvent>) -> Self {
+        SimFeed {
+            config: SimConfig::default(),
+            source: Source::Script(events.into_iter()),
+            step: 0,
+        }
+    }
+
+    // Loads a script, one step per line: anything a recorded session holds (combined stream
+    // messages or bare ticker payloads), `{"remove": "<symbol>"}` to delist a symbol or
+    // `{"degraded": true|false}` to change the feed's state. Blank lines and
+    // lines starting with `#` are skipped; anything else is an error, so a typo can't
+    // quietly change what a test plays.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut events = Vec::new();
+        for (number, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let event = parse_step(line)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, e)))?;
+            events.extend(event);
+        }
+        Ok(SimFeed::scripted(events))
+    }
+
+    // Random walk starting from the latest price of every symbol in `snapshot`
+    pub fn random_walk(snapshot: Vec<TickerData>, config: SimConfig) -> Self {
+        let prices = snapshot.iter().map(|t| t.c.parse().unwrap_or_default()).collect();
+        SimFeed {
+            source: Source::Walk {
+                rng: Rng::new(config.seed),
+                tickers: snapshot,
+                prices,
+                left: config.steps,
+            },
+            config,
+            step: 0,
+        }
+    }
+
+    // Sets the simulated clock; scripted tickers that carry an event time keep it
+    pub fn with_clock(mut self, start_time: u64, step_ms: u64) -> Self {
+        self.config.start_time = start_time;
+        self.config.step_ms = step_ms;
+        self
+    }
+
+    fn next_step(&mut self) -> Option<MarketEvent> {
+        let now = self.config.start_time + self.step * self.config.step_ms;
+        self.step += 1;
+        match &mut self.source {
+            Source::Script(events) => {
+                let mut event = events.next()?;
+                if let MarketEvent::Tickers(tickers) = &mut event {
+                    for ticker in tickers.iter_mut().filter(|t| t.event_time == 0) {
+                        ticker.event_time = now;
+                    }
+                }
+                Some(event)
+            }
+            Source::Walk { rng, tickers, prices, left } => {
+                if *left == 0 || tickers.is_empty() {
+                    return None;
+                }
+                *left -= 1;
+                let half_spread = self.config.spread_bps / 20_000.0;
+                let mut moved = Vec::with_capacity(self.config.batch);
+                for _ in 0..self.config.batch {
+                    let i = rng.below(tickers.len());
+                    prices[i] *= 1.0 + rng.signed_unit() * self.config.max_move_bps / 10_000.0;
+                    let mut ticker = tickers[i].clone();
+                    ticker.c = prices[i].to_string();
+                    ticker.event_time = now;
+                    ticker.update_id = None;
+                    (ticker.b, ticker.a) = if half_spread > 0.0 {
+                        (Some((prices[i] * (1.0 - half_spread)).to_string()), Some((prices[i] * (1.0 + half_spread)).to_string()))
+                    } else {
+                        (None, None)
+                    };
+                    moved.push(ticker);
+                }
+                Some(MarketEvent::Tickers(moved))
+            }
+        }
+    }
+}
+
+impl Feed for SimFeed {
+    fn venue(&self) -> &str {
+        "binance"
+    }
+
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        // Sinks run on their own tasks; yielding per step keeps them from lagging behind
+        tokio::task::yield_now().await;
+        self.next_step()
+    }
+}
+
+// One script line as a market event, None for subscription acks
+fn parse_step(line: &str) -> Result<Option<MarketEvent>, String> {
+    if let Ok(directive) = serde_json::from_str::<Directive>(line) {
+        return match directive {
+            Directive { remove: Some(symbol), degraded: None, venue: None } => Ok(Some(MarketEvent::SymbolRemoved(symbol))),
+            Directive { remove: None, degraded: Some(degraded), venue } => Ok(Some(MarketEvent::FeedState {
+                venue: venue.unwrap_or_else(|| "binance".to_string()),
+                degraded,
+            })),
+            _ => Err("expected exactly one of \"remove\" or \"degraded\"".to_string()),
+        };
+    }
+    match StreamMessage::parse(line) {
+        Ok(StreamMessage::Event { data, .. }) => Ok(Some(MarketEvent::Tickers(data.into_vec()))),
+        Ok(StreamMessage::Depth { depth, .. }) => Ok(Some(MarketEvent::Depth(vec![depth]))),
+        Ok(StreamMessage::Response(_)) => Ok(None),
+        Err(_) => TickerPayload::parse(line).map(|data| Some(MarketEvent::Tickers(data.into_vec()))).map_err(|e| e.to_string()),
+    }
+}
+
+// Latest ticker per symbol with a usable price across a recorded session, by symbol
+pub fn snapshot(path: &Path) -> io::Result<Vec<TickerData>> {
+    let mut latest: HashMap<String, TickerData> = HashMap::new();
+    for ticker in load_session(path)?.into_iter().flat_map(|step| step.tickers) {
+        latest.insert(ticker.s.clone(), ticker);
+    }
+    let mut tickers: Vec<TickerData> = latest.into_values().filter(|t| t.c.parse::<f64>().is_ok_and(|p| p > 0.0)).collect();
+    tickers.sort_by(|a, b| a.s.cmp(&b.s));
+    Ok(tickers)
+}
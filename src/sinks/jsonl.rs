@@ -0,0 +1,86 @@
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::events::EngineEvent;
+use crate::sinks::Sink;
+
+// JSON record for an engine event, tagged with its `type`; shared by every JSON output
+pub fn event_json(event: &EngineEvent) -> Value {
+    match event {
+        EngineEvent::Opportunity(opportunity) => {
+            let mut line = opportunity.to_json();
+            line["type"] = json!("opportunity");
+            line
+        }
+        EngineEvent::Signal(signal) => {
+            let mut line = signal.to_json();
+            line["type"] = json!("signal");
+            line
+        }
+        EngineEvent::OpportunityClosed(summary) => {
+            let mut line = summary.to_json();
+            line["type"] = json!("opportunity_closed");
+            line
+        }
+        EngineEvent::StablecoinDepeg { asset, deviation_bps } => {
+            json!({ "type": "stablecoin_depeg", "asset": asset, "deviation_bps": deviation_bps })
+        }
+        EngineEvent::StablecoinRepegged { asset } => json!({ "type": "stablecoin_repegged", "asset": asset }),
+        EngineEvent::TradingHalted { reason } => json!({ "type": "trading_halted", "reason": reason }),
+        EngineEvent::TradingResumed => json!({ "type": "trading_resumed" }),
+        EngineEvent::KillSwitchTripped { reason } => json!({ "type": "kill_switch_tripped", "reason": reason }),
+        EngineEvent::KillSwitchReset => json!({ "type": "kill_switch_reset" }),
+        EngineEvent::PriceBreakerTripped { symbol, reason } => {
+            json!({ "type": "price_breaker_tripped", "symbol": symbol, "reason": reason })
+        }
+        EngineEvent::PriceBreakerReset { symbol } => json!({ "type": "price_breaker_reset", "symbol": symbol }),
+        EngineEvent::FeedStalled { venue, silent_ms } => json!({ "type": "feed_stalled", "venue": venue, "silent_ms": silent_ms }),
+        EngineEvent::FeedClosed => json!({ "type": "feed_closed" }),
+    }
+}
+
+// Writes one JSON object per line for every opportunity (and calendar transitions),
+// to stdout or an append-only file
+pub struct JsonlSink {
+    writer: Box<dyn Write + Send>,
+}
+
+impl JsonlSink {
+    pub fn stdout() -> Self {
+        JsonlSink {
+            writer: Box::new(io::stdout()),
+        }
+    }
+
+    pub fn file(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(JsonlSink {
+            writer: Box::new(BufWriter::new(file)),
+        })
+    }
+
+    fn write_line(&mut self, value: serde_json::Value) {
+        // Flush per line so consumers tailing the output see complete records immediately
+        let result = writeln!(self.writer, "{}", value).and_then(|_| self.writer.flush());
+        if let Err(e) = result {
+            tracing::error!(error = %e, "Failed to write JSON line");
+        }
+    }
+}
+
+impl Sink for JsonlSink {
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    fn keeps_expired(&self) -> bool {
+        true
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        self.write_line(event_json(event));
+    }
+}
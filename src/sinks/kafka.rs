@@ -0,0 +1,224 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use rdkafka::config::ClientConfig;
+use rdkafka::error::KafkaResult;
+use rdkafka::message::DeliveryResult;
+use rdkafka::producer::{BaseRecord, Producer, ProducerContext, ThreadedProducer};
+use rdkafka::ClientContext;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+
+use crate::events::{EngineEvent, MarketEvent};
+use crate::graph::extract_currency_pair;
+use crate::sinks::jsonl::event_json;
+use crate::sinks::Sink;
+
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct KafkaConfig {
+    pub brokers: String,                   // bootstrap.servers, comma separated
+    pub opportunity_topic: String,         // Engine events, keyed by cycle
+    pub market_topic: Option<String>,      // Normalized market updates, keyed by symbol; None skips them
+    pub properties: Vec<(String, String)>, // Extra librdkafka settings, e.g. security.protocol
+}
+
+impl KafkaConfig {
+    pub fn new(brokers: &str) -> Self {
+        KafkaConfig {
+            brokers: brokers.to_string(),
+            opportunity_topic: "hft3.opportunities".to_string(),
+            market_topic: Some("hft3.market".to_string()),
+            properties: Vec::new(),
+        }
+    }
+}
+
+// Reports failed deliveries; librdkafka retries on its own before giving up
+struct DeliveryLog;
+
+impl ClientContext for DeliveryLog {}
+
+impl ProducerContext for DeliveryLog {
+    type DeliveryOpaque = ();
+
+    fn delivery(&self, result: &DeliveryResult<'_>, _: ()) {
+        if let Err((e, _)) = result {
+            tracing::error!(error = %e, "Kafka delivery failed");
+        }
+    }
+}
+
+type KafkaProducer = ThreadedProducer<DeliveryLog>;
+
+// Enqueues without waiting; a full local queue drops the record rather than stalling
+fn publish(producer: &KafkaProducer, topic: &str, key: Option<&str>, value: &Value) {
+    let payload = value.to_string();
+    let mut record = BaseRecord::<str, String>::to(topic).payload(&payload);
+    if let Some(key) = key {
+        record = record.key(key);
+    }
+    if let Err((e, _)) = producer.send(record) {
+        tracing::warn!(%topic, error = %e, "Dropped Kafka record");
+    }
+}
+
+// One record per symbol, in the same shape whatever the feed
+fn normalize(event: &MarketEvent, venue: &str) -> Vec<(String, Value)> {
+    match event {
+        MarketEvent::Tickers(tickers) => tickers
+            .iter()
+            .map(|t| {
+                let (base, quote) = extract_currency_pair(&t.s);
+                let number = |v: &Option<String>| v.as_deref().and_then(|v| v.parse::<f64>().ok());
+                let record = json!({
+                    "type": "ticker",
+                    "venue": venue,
+                    "symbol": t.s,
+                    "base": base,
+                    "quote": quote,
+                    "last": t.c.parse::<f64>().ok(),
+                    "bid": number(&t.b),
+                    "bid_qty": number(&t.bid_qty),
+                    "ask": number(&t.a),
+                    "ask_qty": number(&t.ask_qty),
+                    "event_time": (t.event_time > 0).then_some(t.event_time),
+                });
+                (t.s.clone(), record)
+            })
+            .collect(),
+        MarketEvent::Quotes(quotes) => quotes
+            .iter()
+            .map(|q| {
+                let symbol = format!("{}{}", q.base, q.quote);
+                let record = json!({
+                    "type": "quote",
+                    "venue": q.venue,
+                    "symbol": symbol,
+                    "base": q.base,
+                    "quote": q.quote,
+                    "bid": q.bid,
+                    "bid_qty": q.bid_qty,
+                    "ask": q.ask,
+                    "ask_qty": q.ask_qty,
+                });
+                (symbol, record)
+            })
+            .collect(),
+        MarketEvent::SymbolRemoved(symbol) => {
+            vec![(symbol.clone(), json!({ "type": "symbol_removed", "venue": venue, "symbol": symbol }))]
+        }
+        MarketEvent::Depth(depths) => depths
+            .iter()
+            .map(|d| {
+                let (base, quote) = extract_currency_pair(&d.symbol);
+                let record = json!({
+                    "type": "depth",
+                    "venue": venue,
+                    "symbol": d.symbol,
+                    "base": base,
+                    "quote": quote,
+                    "bids": d.bids,
+                    "asks": d.asks,
+                });
+                (d.symbol.clone(), record)
+            })
+            .collect(),
+        MarketEvent::FeedState { venue, degraded } => {
+            vec![(venue.clone(), json!({ "type": "feed_state", "venue": venue, "degraded": degraded }))]
+        }
+        MarketEvent::FeedStalled { venue, silent_ms } => {
+            vec![(venue.clone(), json!({ "type": "feed_stalled", "venue": venue, "silent_ms": silent_ms }))]
+        }
+        MarketEvent::MarkPrices(marks) => marks
+            .iter()
+            .map(|m| {
+                let record = json!({
+                    "type": "mark_price",
+                    "venue": crate::perp::VENUE,
+                    "symbol": m.symbol,
+                    "mark": m.mark_price.parse::<f64>().ok(),
+                    "index": m.index_price.parse::<f64>().ok(),
+                    "funding_rate": m.funding_rate.parse::<f64>().ok(),
+                    "next_funding_time": m.next_funding_time,
+                    "event_time": (m.event_time > 0).then_some(m.event_time),
+                });
+                (m.symbol.clone(), record)
+            })
+            .collect(),
+    }
+}
+
+// Publishes engine events (the `--output jsonl` records) to one topic and, through
+// `spawn_market`, every market update to another
+pub struct KafkaSink {
+    producer: Arc<KafkaProducer>,
+    config: KafkaConfig,
+}
+
+impl KafkaSink {
+    pub fn new(config: KafkaConfig) -> KafkaResult<Self> {
+        let mut client = ClientConfig::new();
+        client.set("bootstrap.servers", &config.brokers);
+        for (key, value) in &config.properties {
+            client.set(key, value);
+        }
+        let producer = client.create_with_context(DeliveryLog)?;
+        Ok(KafkaSink {
+            producer: Arc::new(producer),
+            config,
+        })
+    }
+
+    // Publishes `updates` from a feed for `venue` on the market topic until the channel
+    // closes. Does nothing without a market topic.
+    pub fn spawn_market(&self, mut updates: broadcast::Receiver<Arc<MarketEvent>>, venue: &str) -> Option<JoinHandle<()>> {
+        let topic = self.config.market_topic.clone()?;
+        let producer = self.producer.clone();
+        let venue = venue.to_string();
+        Some(tokio::spawn(async move {
+            loop {
+                match updates.recv().await {
+                    Ok(event) => {
+                        for (symbol, record) in normalize(&event, &venue) {
+                            publish(&producer, &topic, Some(&symbol), &record);
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => tracing::warn!(skipped, "Kafka market publisher fell behind"),
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        }))
+    }
+}
+
+impl Drop for KafkaSink {
+    // Delivers what is still queued when the engine stops
+    fn drop(&mut self) {
+        if let Err(e) = self.producer.flush(FLUSH_TIMEOUT) {
+            tracing::error!(error = %e, "Failed to flush Kafka producer");
+        }
+    }
+}
+
+impl Sink for KafkaSink {
+    fn name(&self) -> &str {
+        "kafka"
+    }
+
+    fn keeps_expired(&self) -> bool {
+        true
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        let key = match event {
+            EngineEvent::Opportunity(opportunity) => Some(opportunity.cycle_key()),
+            EngineEvent::OpportunityClosed(summary) => Some(summary.cycle.clone()),
+            EngineEvent::Signal(signal) => Some(signal.instruments.join(",")),
+            _ => None,
+        };
+        publish(&self.producer, &self.config.opportunity_topic, key.as_deref(), &event_json(event));
+    }
+}
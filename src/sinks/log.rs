@@ -0,0 +1,90 @@
+use crate::events::EngineEvent;
+use crate::sinks::Sink;
+
+// Human-readable output through the tracing subscriber
+pub struct LogSink;
+
+impl Sink for LogSink {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn keeps_expired(&self) -> bool {
+        true
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        match event {
+            EngineEvent::Opportunity(opportunity) => {
+                let profit_bps = opportunity.profit.map(|p| p * 10_000.0);
+                let latency = opportunity.latency.unwrap_or_default();
+                tracing::info!(
+                    path = ?opportunity.path,
+                    profit_bps,
+                    profit = opportunity.reference_profit.as_ref().map(|p| p.amount),
+                    profit_asset = opportunity.reference_profit.as_ref().map(|p| p.asset.as_str()),
+                    venues = ?opportunity.venues,
+                    low_confidence = opportunity.low_confidence,
+                    exchange_lag_ms = latency.exchange_to_receive_ms,
+                    graph_us = latency.receive_to_graph.as_micros() as u64,
+                    detect_us = latency.graph_to_detect.as_micros() as u64,
+                    "Arbitrage opportunity found"
+                );
+                if let Some(trace) = &opportunity.trace {
+                    for leg in &trace.legs {
+                        tracing::info!(
+                            from = %leg.from,
+                            to = %leg.to,
+                            order = ?leg.order,
+                            rate = leg.rate,
+                            price = leg.price,
+                            quantity = leg.quantity,
+                            amount_in = leg.amount_in,
+                            residual = leg.residual,
+                            fee_bps = leg.fee_bps,
+                            amount_out = leg.amount_out,
+                            "  leg"
+                        );
+                    }
+                    tracing::info!(
+                        start = trace.start_amount,
+                        end = trace.final_amount,
+                        profit_bps = trace.profit() * 10_000.0,
+                        "  simulated pass"
+                    );
+                }
+            }
+            EngineEvent::Signal(signal) => {
+                tracing::info!(
+                    strategy = %signal.strategy,
+                    instruments = ?signal.instruments,
+                    value = signal.value,
+                    orders = signal.orders.len(),
+                    executed = signal.executed,
+                    "Signal {}",
+                    signal.kind
+                );
+            }
+            EngineEvent::OpportunityClosed(summary) => {
+                tracing::info!(
+                    path = ?summary.path,
+                    detections = summary.detections,
+                    best_profit_bps = summary.best_profit.map(|p| p * 10_000.0),
+                    "Opportunity persisted for {} ms",
+                    summary.duration.as_millis()
+                );
+            }
+            EngineEvent::StablecoinDepeg { asset, deviation_bps } => {
+                tracing::warn!(%asset, deviation_bps, "Stablecoin depeg");
+            }
+            EngineEvent::KillSwitchTripped { reason } => tracing::error!(%reason, "Execution stopped by kill switch"),
+            EngineEvent::KillSwitchReset => tracing::warn!("Kill switch reset, execution allowed again"),
+            EngineEvent::PriceBreakerTripped { symbol, reason } => {
+                tracing::error!(%symbol, %reason, "Bad price data, symbol quarantined");
+            }
+            EngineEvent::PriceBreakerReset { symbol } => tracing::warn!(%symbol, "Price breaker reset, symbol back in the graph"),
+            EngineEvent::FeedStalled { venue, silent_ms } => tracing::warn!(%venue, silent_ms, "Feed went quiet and was reconnected"),
+            _ => {}
+        }
+    }
+}
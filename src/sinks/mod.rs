@@ -0,0 +1,63 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use crate::events::EngineEvent;
+use crate::supervisor::Supervisor;
+
+pub mod jsonl;
+pub mod kafka;
+pub mod log;
+pub mod nats;
+pub mod redis;
+pub mod sqlite;
+pub mod telegram;
+pub mod webhook;
+
+// An output that consumes engine events on its own task
+pub trait Sink: Send + 'static {
+    fn name(&self) -> &str;
+
+    // Whether opportunities past their expiry still reach `handle`. Alerting and
+    // publishing sinks shouldn't pass on one that is already gone; ones that keep a
+    // record of everything found say yes.
+    fn keeps_expired(&self) -> bool {
+        false
+    }
+
+    fn handle(&mut self, event: &EngineEvent) -> impl Future<Output = ()> + Send;
+}
+
+// Runs `sink` until the engine's event channel closes, restarting it when handling an
+// event panics. The sink and its place in the channel carry over, so only the event it
+// panicked on is lost.
+pub fn spawn<S: Sink>(supervisor: &Supervisor, sink: S, events: broadcast::Receiver<EngineEvent>) -> JoinHandle<()> {
+    let name = format!("{} sink", sink.name());
+    let state = Arc::new(Mutex::new((sink, events)));
+    supervisor.spawn(name, move || {
+        let state = state.clone();
+        async move {
+            let (sink, events) = &mut *state.lock().await;
+            drain(sink, events).await;
+        }
+    })
+}
+
+async fn drain<S: Sink>(sink: &mut S, events: &mut broadcast::Receiver<EngineEvent>) {
+    loop {
+        match events.recv().await {
+            Ok(EngineEvent::Opportunity(opportunity)) if !sink.keeps_expired() && opportunity.is_expired(SystemTime::now()) => {
+                tracing::debug!(sink = sink.name(), cycle = %opportunity.cycle_key(), "Expired opportunity not passed on");
+            }
+            Ok(event) => sink.handle(&event).await,
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!(sink = sink.name(), skipped, "Sink fell behind");
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::{Duration, Instant};
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::json;
+use tokio::task::JoinHandle;
+
+use crate::events::EngineEvent;
+use crate::sinks::jsonl::event_json;
+use crate::sinks::Sink;
+
+#[derive(Debug, Clone)]
+pub struct NatsConfig {
+    pub url: String,                          // e.g. nats://127.0.0.1:4222; credentials may go in the URL
+    pub opportunity_subject: String,          // Every opportunity, as its JSON record
+    pub heartbeat_subject: String,            // Liveness message, sent every `heartbeat_interval`
+    pub heartbeat_interval: Option<Duration>, // None disables heartbeats
+}
+
+impl NatsConfig {
+    pub fn new(url: &str) -> Self {
+        NatsConfig {
+            url: url.to_string(),
+            opportunity_subject: "hft3.opportunities".to_string(),
+            heartbeat_subject: "hft3.heartbeat".to_string(),
+            heartbeat_interval: Some(Duration::from_secs(5)),
+        }
+    }
+}
+
+// Publishes opportunities on one subject and a periodic heartbeat on another, so
+// consumers can tell a quiet market from a dead bot. The client reconnects on its own
+// and buffers publishes while disconnected.
+pub struct NatsSink {
+    config: NatsConfig,
+    client: async_nats::Client,
+    heartbeat: Option<JoinHandle<()>>,
+}
+
+impl NatsSink {
+    pub async fn connect(config: NatsConfig) -> Result<Self, async_nats::ConnectError> {
+        let client = async_nats::ConnectOptions::new().name("hft3").connect(config.url.as_str()).await?;
+        let heartbeat = config.heartbeat_interval.map(|interval| {
+            let client = client.clone();
+            let subject = config.heartbeat_subject.clone();
+            tokio::spawn(async move {
+                let started = Instant::now();
+                let mut ticker = tokio::time::interval(interval);
+                for sequence in 0u64.. {
+                    ticker.tick().await;
+                    let heartbeat = json!({
+                        "type": "heartbeat",
+                        "time": Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+                        "uptime_s": started.elapsed().as_secs(),
+                        "sequence": sequence,
+                    });
+                    if let Err(e) = client.publish(subject.clone(), heartbeat.to_string().into()).await {
+                        tracing::warn!(error = %e, "Failed to publish NATS heartbeat");
+                    }
+                }
+            })
+        });
+        Ok(NatsSink { config, client, heartbeat })
+    }
+}
+
+impl Drop for NatsSink {
+    // Heartbeats stop with the engine, so silence means the bot is gone
+    fn drop(&mut self) {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+        }
+    }
+}
+
+impl Sink for NatsSink {
+    fn name(&self) -> &str {
+        "nats"
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        if !matches!(event, EngineEvent::Opportunity(_)) {
+            return;
+        }
+        let payload = event_json(event).to_string();
+        let subject = self.config.opportunity_subject.clone();
+        if let Err(e) = self.client.publish(subject, payload.into()).await {
+            tracing::error!(error = %e, "Failed to publish opportunity to NATS");
+        }
+    }
+}
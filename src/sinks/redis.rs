@@ -0,0 +1,86 @@
+use redis::aio::ConnectionManager;
+use redis::RedisResult;
+use serde_json::json;
+
+use crate::events::EngineEvent;
+use crate::sinks::jsonl::event_json;
+use crate::sinks::Sink;
+
+#[derive(Debug, Clone)]
+pub struct RedisConfig {
+    pub url: String,             // e.g. redis://127.0.0.1:6379/0
+    pub stream: String,          // Stream every opportunity is XADDed to
+    pub channel: Option<String>, // Pub/sub channel notified after each XADD; None skips it
+    pub max_len: Option<usize>,  // Approximate cap on the stream length (MAXLEN ~)
+}
+
+impl RedisConfig {
+    pub fn new(url: &str) -> Self {
+        RedisConfig {
+            url: url.to_string(),
+            stream: "hft3:opportunities".to_string(),
+            channel: Some("hft3:opportunities".to_string()),
+            max_len: Some(100_000),
+        }
+    }
+}
+
+// Appends opportunities to a Redis stream, so consumers can read history and resume
+// from an ID, and publishes a short notification (stream ID, cycle, profit) for
+// consumers that only want a push. The connection reconnects on its own after errors.
+pub struct RedisSink {
+    config: RedisConfig,
+    connection: ConnectionManager,
+}
+
+impl RedisSink {
+    pub async fn connect(config: RedisConfig) -> RedisResult<Self> {
+        let connection = redis::Client::open(config.url.as_str())?.get_connection_manager().await?;
+        Ok(RedisSink { config, connection })
+    }
+
+    async fn add(&mut self, cycle: &str, profit_bps: Option<f64>, data: &str) -> RedisResult<()> {
+        let mut xadd = redis::cmd("XADD");
+        xadd.arg(&self.config.stream);
+        if let Some(max_len) = self.config.max_len {
+            xadd.arg("MAXLEN").arg("~").arg(max_len);
+        }
+        xadd.arg("*").arg("cycle").arg(cycle).arg("data").arg(data);
+        if let Some(bps) = profit_bps {
+            xadd.arg("profit_bps").arg(bps);
+        }
+        let id: String = xadd.query_async(&mut self.connection).await?;
+
+        if let Some(channel) = &self.config.channel {
+            let notification = json!({
+                "stream": self.config.stream,
+                "id": id,
+                "cycle": cycle,
+                "profit_bps": profit_bps,
+            });
+            redis::cmd("PUBLISH")
+                .arg(channel)
+                .arg(notification.to_string())
+                .query_async::<()>(&mut self.connection)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+impl Sink for RedisSink {
+    fn name(&self) -> &str {
+        "redis"
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        let EngineEvent::Opportunity(opportunity) = event else {
+            return;
+        };
+        let data = event_json(event).to_string();
+        let profit_bps = opportunity.profit.map(|p| p * 10_000.0);
+        if let Err(e) = self.add(&opportunity.cycle_key(), profit_bps, &data).await {
+            tracing::error!(error = %e, "Failed to write opportunity to Redis");
+        }
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::Path;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, Connection};
+
+use crate::events::EngineEvent;
+use crate::sinks::Sink;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS opportunities (
+    id                   INTEGER PRIMARY KEY,
+    detected_at          TEXT NOT NULL,
+    strategy             TEXT NOT NULL,
+    cycle                TEXT NOT NULL,
+    path                 TEXT NOT NULL,
+    rates                TEXT NOT NULL,
+    venues               TEXT NOT NULL,
+    gross_return         REAL,
+    profit_bps           REAL,
+    detection_latency_us INTEGER,
+    executed             INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS opportunities_cycle ON opportunities (cycle);
+CREATE INDEX IF NOT EXISTS opportunities_detected_at ON opportunities (detected_at);
+";
+
+// Stores every opportunity for later analysis of where and how often arbitrage appears.
+// Path, rates and venues are stored as JSON arrays.
+pub struct SqliteSink {
+    connection: Connection,
+}
+
+impl SqliteSink {
+    pub fn open(path: &Path) -> rusqlite::Result<Self> {
+        let connection = Connection::open(path)?;
+        // WAL keeps readers (notebooks, the CLI) from blocking inserts
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.execute_batch(SCHEMA)?;
+        Ok(SqliteSink { connection })
+    }
+}
+
+impl Sink for SqliteSink {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    fn keeps_expired(&self) -> bool {
+        true
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        let EngineEvent::Opportunity(opportunity) = event else {
+            return;
+        };
+        let detected_at: DateTime<Utc> = opportunity.detected_at.into();
+        let gross_return = (!opportunity.rates.is_empty()).then(|| opportunity.rates.iter().product::<f64>() - 1.0);
+        let result = self.connection.execute(
+            "INSERT INTO opportunities (detected_at, strategy, cycle, path, rates, venues, gross_return, profit_bps,
+                                        detection_latency_us, executed)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                detected_at.to_rfc3339_opts(SecondsFormat::Micros, true),
+                opportunity.strategy,
+                opportunity.cycle_key(),
+                serde_json::to_string(&opportunity.path).unwrap_or_default(),
+                serde_json::to_string(&opportunity.rates).unwrap_or_default(),
+                serde_json::to_string(&opportunity.venues).unwrap_or_default(),
+                gross_return,
+                opportunity.profit.map(|p| p * 10_000.0),
+                opportunity.detection_latency.map(|d| d.as_micros() as i64),
+                opportunity.executed,
+            ],
+        );
+        if let Err(e) = result {
+            tracing::error!(error = %e, "Failed to store opportunity");
+        }
+    }
+}
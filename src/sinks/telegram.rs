@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::events::{EngineEvent, Opportunity};
+use crate::proxy::{http_client, Proxy};
+use crate::sinks::Sink;
+
+pub struct TelegramConfig {
+    pub bot_token: String,
+    pub chat_id: String,
+    pub min_profit_bps: f64,      // Opportunities below this aren't sent
+    pub cycle_cooldown: Duration, // Minimum time between alerts for the same cycle
+    pub max_per_minute: usize,    // Hard cap on messages across all cycles
+    pub proxy: Option<Proxy>,
+}
+
+impl TelegramConfig {
+    // Reads TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID; None if either is unset
+    pub fn from_env() -> Option<Self> {
+        Some(TelegramConfig {
+            bot_token: std::env::var("TELEGRAM_BOT_TOKEN").ok()?,
+            chat_id: std::env::var("TELEGRAM_CHAT_ID").ok()?,
+            min_profit_bps: 0.0,
+            cycle_cooldown: Duration::from_secs(60),
+            max_per_minute: 20, // Telegram allows roughly 20 messages per minute to a group
+            proxy: None,
+        })
+    }
+}
+
+pub struct TelegramSink {
+    config: TelegramConfig,
+    http: reqwest::Client,
+    last_sent: HashMap<String, Instant>, // Cycle key -> last alert
+    recent: Vec<Instant>,                // Send times within the last minute
+    suppressed: u64,                     // Alerts dropped by rate limiting since the last send
+}
+
+impl TelegramSink {
+    pub fn new(config: TelegramConfig) -> Self {
+        TelegramSink {
+            http: http_client(config.proxy.as_ref()),
+            config,
+            last_sent: HashMap::new(),
+            recent: Vec::new(),
+            suppressed: 0,
+        }
+    }
+
+    fn above_threshold(&self, opportunity: &Opportunity) -> bool {
+        match opportunity.profit {
+            Some(profit) => profit * 10_000.0 >= self.config.min_profit_bps,
+            // Unknown profit can only satisfy a zero threshold
+            None => self.config.min_profit_bps <= 0.0,
+        }
+    }
+
+    // Applies the per-cycle cooldown and the global cap; records the send if allowed
+    fn allow(&mut self, key: &str) -> bool {
+        let now = Instant::now();
+        if let Some(last) = self.last_sent.get(key) {
+            if now.duration_since(*last) < self.config.cycle_cooldown {
+                return false;
+            }
+        }
+        self.recent.retain(|t| now.duration_since(*t) < Duration::from_secs(60));
+        if self.recent.len() >= self.config.max_per_minute {
+            return false;
+        }
+        self.recent.push(now);
+        self.last_sent.insert(key.to_string(), now);
+        true
+    }
+
+    fn format(&self, opportunity: &Opportunity) -> String {
+        let detected: DateTime<Utc> = opportunity.detected_at.into();
+        let mut text = format!("Arbitrage: {}\n", opportunity.path.join(" → "));
+        match opportunity.profit {
+            Some(profit) => text.push_str(&format!("Expected profit: {:.2} bps\n", profit * 10_000.0)),
+            None => text.push_str("Expected profit: unknown\n"),
+        }
+        if let Some(profit) = &opportunity.reference_profit {
+            text.push_str(&format!("Per {:.0} {}: {:+.2} {}\n", profit.notional, profit.asset, profit.amount, profit.asset));
+        }
+        text.push_str(&format!("Detected: {}", detected.format("%Y-%m-%d %H:%M:%S%.3f UTC")));
+        if self.suppressed > 0 {
+            text.push_str(&format!("\n({} alerts suppressed by rate limiting)", self.suppressed));
+        }
+        text
+    }
+
+    async fn send(&self, text: String) {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.config.bot_token);
+        let body = json!({ "chat_id": self.config.chat_id, "text": text });
+        match self.http.post(url).json(&body).send().await {
+            Ok(response) if response.status().is_success() => {}
+            Ok(response) => tracing::warn!(status = %response.status(), "Telegram rejected alert"),
+            // reqwest errors include the URL, which contains the bot token
+            Err(e) => tracing::warn!(error = %e.without_url(), "Failed to send Telegram alert"),
+        }
+    }
+}
+
+impl Sink for TelegramSink {
+    fn name(&self) -> &str {
+        "telegram"
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        let opportunity = match event {
+            EngineEvent::Opportunity(opportunity) => opportunity,
+            // Rare and worth knowing about; not subject to the opportunity limits
+            EngineEvent::StablecoinDepeg { asset, deviation_bps } => {
+                return self.send(format!("Stablecoin depeg: {} is {:+.1} bps from its peers", asset, deviation_bps)).await;
+            }
+            EngineEvent::StablecoinRepegged { asset } => {
+                return self.send(format!("Stablecoin {} is back on peg", asset)).await;
+            }
+            EngineEvent::KillSwitchTripped { reason } => {
+                return self.send(format!("Kill switch tripped, execution stopped: {}", reason)).await;
+            }
+            EngineEvent::KillSwitchReset => return self.send("Kill switch reset, execution resumed".to_string()).await,
+            EngineEvent::PriceBreakerTripped { symbol, reason } => {
+                return self.send(format!("Bad price data on {}, quarantined: {}", symbol, reason)).await;
+            }
+            EngineEvent::PriceBreakerReset { symbol } => return self.send(format!("{} prices accepted again", symbol)).await,
+            EngineEvent::FeedStalled { venue, silent_ms } => {
+                return self.send(format!("{} feed silent for {} s, reconnected", venue, silent_ms / 1000)).await;
+            }
+            EngineEvent::Signal(signal) => {
+                let key = format!("{} {}", signal.kind, signal.instruments.join(" "));
+                if self.allow(&key) {
+                    let text = format!("Signal {} on {}: {:+.2}", signal.kind, signal.instruments.join(" / "), signal.value);
+                    self.send(text).await;
+                }
+                return;
+            }
+            _ => return,
+        };
+        if !self.above_threshold(opportunity) {
+            return;
+        }
+        if !self.allow(&opportunity.cycle_key()) {
+            self.suppressed += 1;
+            return;
+        }
+        let text = self.format(opportunity);
+        self.suppressed = 0;
+        self.send(text).await;
+    }
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::events::EngineEvent;
+use crate::proxy::{http_client, Proxy};
+use crate::sinks::Sink;
+
+// Body shape to POST; Slack and Discord incoming webhooks expect their own fields
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebhookFormat {
+    Json,
+    Slack,
+    Discord,
+}
+
+impl WebhookFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "json" => Some(WebhookFormat::Json),
+            "slack" => Some(WebhookFormat::Slack),
+            "discord" => Some(WebhookFormat::Discord),
+            _ => None,
+        }
+    }
+}
+
+pub struct WebhookConfig {
+    pub url: String,
+    pub format: WebhookFormat,
+    pub headers: Vec<(String, String)>, // Extra headers, e.g. Authorization
+    pub timeout: Duration,
+    pub retries: u32,
+    pub proxy: Option<Proxy>,
+}
+
+impl WebhookConfig {
+    pub fn new(url: &str) -> Self {
+        WebhookConfig {
+            url: url.to_string(),
+            format: WebhookFormat::Json,
+            headers: Vec::new(),
+            timeout: Duration::from_secs(5),
+            retries: 2,
+            proxy: None,
+        }
+    }
+}
+
+// POSTs every opportunity to a user-supplied URL
+pub struct WebhookSink {
+    config: WebhookConfig,
+    http: reqwest::Client,
+}
+
+impl WebhookSink {
+    pub fn new(config: WebhookConfig) -> Self {
+        WebhookSink {
+            http: http_client(config.proxy.as_ref()),
+            config,
+        }
+    }
+
+    fn body(&self, payload: Value) -> Value {
+        let summary = || {
+            let path: Vec<&str> = payload["path"]
+                .as_array()
+                .map(|p| p.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            match payload["profit_bps"].as_f64() {
+                Some(bps) => format!("Arbitrage {} ({:.2} bps)", path.join(" → "), bps),
+                None => format!("Arbitrage {}", path.join(" → ")),
+            }
+        };
+        match self.config.format {
+            WebhookFormat::Json => payload,
+            WebhookFormat::Slack => json!({ "text": summary() }),
+            WebhookFormat::Discord => json!({ "content": summary() }),
+        }
+    }
+
+    async fn post(&self, body: &Value) {
+        for attempt in 0..=self.config.retries {
+            let mut request = self.http.post(&self.config.url).timeout(self.config.timeout).json(body);
+            for (name, value) in &self.config.headers {
+                request = request.header(name, value);
+            }
+            match request.send().await {
+                Ok(response) if response.status().is_success() => return,
+                // Client errors won't succeed on retry
+                Ok(response) if response.status().is_client_error() => {
+                    tracing::warn!(status = %response.status(), "Webhook rejected payload");
+                    return;
+                }
+                Ok(response) => tracing::warn!(status = %response.status(), attempt, "Webhook failed"),
+                Err(e) => tracing::warn!(error = %e, attempt, "Webhook request failed"),
+            }
+            tokio::time::sleep(Duration::from_millis(200 * 2u64.pow(attempt))).await;
+        }
+    }
+}
+
+impl Sink for WebhookSink {
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    async fn handle(&mut self, event: &EngineEvent) {
+        let EngineEvent::Opportunity(opportunity) = event else {
+            return;
+        };
+        let mut payload = opportunity.to_json();
+        payload["type"] = json!("opportunity");
+        let body = self.body(payload);
+        self.post(&body).await;
+    }
+}
@@ -0,0 +1,60 @@
+use crate::graph::Graph;
+
+// Expected cost of executing a leg beyond its quoted price, deducted from profit
+// estimates before they are compared against a threshold
+#[derive(Debug, Clone, Default)]
+pub enum SlippageModel {
+    #[default]
+    None,
+    // The same cost on every leg
+    Fixed { bps: f64 },
+    // Sizes the order against the quantity displayed at the best price: the part that
+    // fits there fills at the quote, the rest `beyond_top_bps` worse. Legs whose depth or
+    // value is unknown cost `fallback_bps`.
+    Depth {
+        notional: f64,     // Order size, valued in `reference`
+        reference: String, // e.g. USDT
+        beyond_top_bps: f64,
+        fallback_bps: f64,
+    },
+}
+
+impl SlippageModel {
+    // Slippage on one leg in bps. `top_qty` is the base quantity at the best price on the
+    // side the leg takes (the bid when selling base, the ask when buying) and `base` the
+    // leg's base asset.
+    pub fn leg_bps(&self, graph: &Graph, base: &str, top_qty: Option<f64>) -> f64 {
+        match self {
+            SlippageModel::None => 0.0,
+            SlippageModel::Fixed { bps } => *bps,
+            SlippageModel::Depth { notional, reference, beyond_top_bps, fallback_bps } => {
+                let (Some(top_qty), Some(value)) = (top_qty, value_in(graph, base, reference)) else {
+                    return *fallback_bps;
+                };
+                let size = notional / value;
+                if size <= 0.0 || !size.is_finite() {
+                    return *fallback_bps;
+                }
+                beyond_top_bps * ((size - top_qty) / size).clamp(0.0, 1.0)
+            }
+        }
+    }
+
+    pub fn is_none(&self) -> bool {
+        matches!(self, SlippageModel::None)
+    }
+
+    pub fn needs_depth(&self) -> bool {
+        matches!(self, SlippageModel::Depth { .. })
+    }
+}
+
+// Value of one unit of `asset` in `reference`, directly or through the inverse pair
+pub fn value_in(graph: &Graph, asset: &str, reference: &str) -> Option<f64> {
+    if asset == reference {
+        return Some(1.0);
+    }
+    graph
+        .rate(asset, reference)
+        .or_else(|| graph.rate(reference, asset).filter(|&r| r > 0.0).map(|r| 1.0 / r))
+}
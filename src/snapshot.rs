@@ -0,0 +1,42 @@
+use crate::cbbo::VenueQuote;
+use crate::graph::extract_currency_pair;
+use crate::rest::{EndpointCategory, RestClient, RestError};
+
+// Entry of /api/v3/ticker/bookTicker
+#[derive(serde::Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTicker {
+    pub symbol: String,
+    pub bid_price: String,
+    pub bid_qty: String,
+    pub ask_price: String,
+    pub ask_qty: String,
+}
+
+impl BookTicker {
+    // None for symbols with an empty book (delisted or halted pairs report zeros)
+    pub fn to_venue_quote(&self, venue: &str) -> Option<VenueQuote> {
+        let bid: f64 = self.bid_price.parse().ok()?;
+        let ask: f64 = self.ask_price.parse().ok()?;
+        if bid <= 0.0 || ask <= 0.0 {
+            return None;
+        }
+        let (base, quote) = extract_currency_pair(&self.symbol);
+        Some(VenueQuote {
+            venue: venue.to_string(),
+            base,
+            quote,
+            bid,
+            bid_qty: self.bid_qty.parse().unwrap_or(0.0),
+            ask,
+            ask_qty: self.ask_qty.parse().unwrap_or(0.0),
+            updated: std::time::Instant::now(),
+        })
+    }
+}
+
+// Top of book for every symbol in one request (weight 4)
+pub async fn fetch_book_tickers(client: &RestClient) -> Result<Vec<BookTicker>, RestError> {
+    let body = client.get(EndpointCategory::Market, 4, "/api/v3/ticker/bookTicker", &[]).await?;
+    serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))
+}
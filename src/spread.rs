@@ -0,0 +1,184 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::events::{MarketEvent, Opportunity, Signal};
+use crate::graph::{extract_currency_pair, Graph};
+use crate::strategy::Strategy;
+
+// Floor on the spread's standard deviation, so a series that has been flat doesn't turn
+// the first tick of movement into an enormous z-score
+const MIN_STD_BPS: f64 = 0.1;
+
+/// Settings for [`SpreadStrategy`].
+#[derive(Debug, Clone)]
+pub struct SpreadConfig {
+    /// Quote assets treated as the same dollar. An asset quoted in two of them has two
+    /// routes whose prices should agree once converted.
+    pub stablecoins: Vec<String>,
+    /// Stablecoin prices are converted into before comparing routes.
+    pub reference: String,
+    /// Alert when a spread is at least this many standard deviations from its mean.
+    pub z_threshold: f64,
+    /// Half-life of the moving mean and variance, in samples of the spread.
+    pub half_life: f64,
+    /// Samples a spread needs before it can alert.
+    pub min_samples: u64,
+}
+
+impl Default for SpreadConfig {
+    fn default() -> Self {
+        SpreadConfig {
+            stablecoins: ["USDT", "USDC", "FDUSD", "DAI"].iter().map(|s| s.to_string()).collect(),
+            reference: "USDT".to_string(),
+            z_threshold: 3.0,
+            half_life: 500.0,
+            min_samples: 100,
+        }
+    }
+}
+
+// Exponentially weighted mean and variance of one spread, plus whether it is alerting
+#[derive(Debug, Default)]
+struct SpreadState {
+    samples: u64,
+    mean: f64,
+    variance: f64,
+    alerting: bool,
+}
+
+/// Watches one asset's price across redundant stablecoin routes (BTCUSDT, BTCFDUSD,
+/// BTCUSDC, ...), each converted into the reference stablecoin at the graph's rates.
+///
+/// Every route pair's spread is tracked against its own moving mean and deviation, since
+/// pairs differ in fees and liquidity; a pair whose z-score reaches `z_threshold` emits a
+/// `spread_zscore` [`Signal`] valued at the z-score, positive when the first route is the
+/// dearer one. It alerts again only after the z-score has fallen back under half the
+/// threshold. No cycles are reported.
+pub struct SpreadStrategy {
+    config: SpreadConfig,
+    stable: HashSet<String>,
+    routes: HashMap<String, BTreeSet<String>>,               // Asset -> stablecoins it is quoted in
+    spreads: HashMap<(String, String, String), SpreadState>, // (asset, quote, other quote)
+    decay: f64,                                              // Weight of each new sample
+    signals: Vec<Signal>,
+}
+
+impl SpreadStrategy {
+    pub fn new(config: SpreadConfig) -> Self {
+        let decay = 1.0 - 0.5f64.powf(1.0 / config.half_life.max(1.0));
+        SpreadStrategy {
+            stable: config.stablecoins.iter().cloned().collect(),
+            config,
+            routes: HashMap::new(),
+            spreads: HashMap::new(),
+            decay,
+            signals: Vec::new(),
+        }
+    }
+
+    // Assets whose routes a pair update affects: its base when quoted in a stablecoin, or
+    // every asset when it is a rate between stablecoins
+    fn touched(&mut self, base: &str, quote: &str) -> Touched {
+        match (self.stable.contains(base), self.stable.contains(quote)) {
+            (true, true) => Touched::All,
+            (false, true) => {
+                self.routes.entry(base.to_string()).or_default().insert(quote.to_string());
+                Touched::Asset(base.to_string())
+            }
+            _ => Touched::None,
+        }
+    }
+
+    // Price of `asset` in the reference stablecoin through `quote`
+    fn reference_price(&self, graph: &Graph, asset: &str, quote: &str) -> Option<f64> {
+        let price = graph.rate(asset, quote)?;
+        let conversion = if quote == self.config.reference { 1.0 } else { graph.rate(quote, &self.config.reference)? };
+        Some(price * conversion).filter(|p| p.is_finite() && *p > 0.0)
+    }
+
+    fn sample(&mut self, graph: &Graph, asset: &str) {
+        let Some(quotes) = self.routes.get(asset) else {
+            return;
+        };
+        let prices: Vec<(String, f64)> = quotes
+            .iter()
+            .filter_map(|quote| Some((quote.clone(), self.reference_price(graph, asset, quote)?)))
+            .collect();
+        for (i, (quote, price)) in prices.iter().enumerate() {
+            for (other, other_price) in &prices[i + 1..] {
+                let spread_bps = (price / other_price - 1.0) * 10_000.0;
+                let key = (asset.to_string(), quote.clone(), other.clone());
+                let state = self.spreads.entry(key).or_default();
+                let std = state.variance.sqrt().max(MIN_STD_BPS);
+                let z = (spread_bps - state.mean) / std;
+                let ready = state.samples >= self.config.min_samples;
+                if ready && !state.alerting && z.abs() >= self.config.z_threshold {
+                    state.alerting = true;
+                    let signal = Signal::new("spread_zscore", vec![format!("{}{}", asset, quote), format!("{}{}", asset, other)], z)
+                        .with_detail("spread_bps", spread_bps)
+                        .with_detail("mean_bps", state.mean)
+                        .with_detail("std_bps", std);
+                    self.signals.push(signal);
+                } else if state.alerting && z.abs() < self.config.z_threshold / 2.0 {
+                    state.alerting = false;
+                }
+
+                // Equal weights until the window fills, so early estimates aren't biased to zero
+                let weight = (1.0 / (state.samples + 1) as f64).max(self.decay);
+                let delta = spread_bps - state.mean;
+                state.mean += weight * delta;
+                state.variance = (1.0 - weight) * (state.variance + weight * delta * delta);
+                state.samples += 1;
+            }
+        }
+    }
+}
+
+enum Touched {
+    None,
+    Asset(String),
+    All,
+}
+
+impl Strategy for SpreadStrategy {
+    fn name(&self) -> &str {
+        "spread"
+    }
+
+    fn on_update(&mut self, graph: &Graph) -> Vec<Opportunity> {
+        let assets: Vec<String> = self.routes.keys().cloned().collect();
+        for asset in assets {
+            self.sample(graph, &asset);
+        }
+        Vec::new()
+    }
+
+    fn on_event(&mut self, graph: &Graph, event: &MarketEvent) -> Vec<Opportunity> {
+        let pairs: Vec<(String, String)> = match event {
+            MarketEvent::Tickers(tickers) => tickers.iter().map(|t| extract_currency_pair(&t.s)).collect(),
+            MarketEvent::Quotes(quotes) => quotes.iter().map(|q| (q.base.clone(), q.quote.clone())).collect(),
+            _ => return Vec::new(),
+        };
+        let mut assets = BTreeSet::new();
+        let mut all = false;
+        for (base, quote) in &pairs {
+            match self.touched(base, quote) {
+                Touched::All => all = true,
+                Touched::Asset(asset) => {
+                    assets.insert(asset);
+                }
+                Touched::None => {}
+            }
+        }
+        if all {
+            return self.on_update(graph);
+        }
+        for asset in assets {
+            self.sample(graph, &asset);
+        }
+        Vec::new()
+    }
+
+    fn take_signals(&mut self) -> Vec<Signal> {
+        std::mem::take(&mut self.signals)
+    }
+}
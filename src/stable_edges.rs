@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+use crate::events::MarketEvent;
+use crate::graph::{extract_currency_pair, Graph};
+
+#[derive(Debug, Clone)]
+pub struct StableEdgeConfig {
+    pub stablecoins: Vec<String>,
+    pub haircut_bps: f64, // Charged on every synthetic conversion, for the spread and transfer risk
+}
+
+impl Default for StableEdgeConfig {
+    fn default() -> Self {
+        StableEdgeConfig {
+            stablecoins: ["USDT", "USDC", "FDUSD", "TUSD"].iter().map(|s| s.to_string()).collect(),
+            haircut_bps: 5.0,
+        }
+    }
+}
+
+// Treats stablecoins as interchangeable where the exchange lists no pair between them:
+// each such pair gets edges both ways at 1.0 less the haircut, so cycles can route
+// through a conversion the symbol graph would otherwise lack. Listed pairs keep their
+// market rates; a synthetic pair gives way as soon as a market update for it arrives and
+// comes back if that market disappears.
+pub struct StableEdges {
+    config: StableEdgeConfig,
+    synthetic: HashSet<(String, String)>, // Both orders of every pair currently synthetic
+}
+
+impl StableEdges {
+    pub fn new(config: StableEdgeConfig) -> Self {
+        StableEdges {
+            config,
+            synthetic: HashSet::new(),
+        }
+    }
+
+    fn rate(&self) -> f64 {
+        1.0 - self.config.haircut_bps / 10_000.0
+    }
+
+    // Pairs whose edges are synthetic, in both orders
+    pub fn pairs(&self) -> &HashSet<(String, String)> {
+        &self.synthetic
+    }
+
+    // Called with every market update before it reaches the graph: a real quote between
+    // two stablecoins replaces their synthetic edges
+    pub fn observe(&mut self, graph: &mut Graph, event: &MarketEvent) {
+        if self.synthetic.is_empty() {
+            return;
+        }
+        let pairs: Vec<(String, String)> = match event {
+            MarketEvent::Tickers(tickers) => tickers.iter().map(|t| extract_currency_pair(&t.s)).collect(),
+            MarketEvent::Quotes(quotes) => quotes.iter().map(|q| (q.base.clone(), q.quote.clone())).collect(),
+            _ => return,
+        };
+        for (base, quote) in pairs {
+            if self.synthetic.remove(&(base.clone(), quote.clone())) {
+                self.synthetic.remove(&(quote.clone(), base.clone()));
+                graph.remove_edge(&base, &quote);
+                graph.remove_edge(&quote, &base);
+                tracing::info!(%base, %quote, "Market pair replaces synthetic stablecoin edges");
+            }
+        }
+    }
+
+    // Adds edges for stablecoin pairs without a market and refreshes existing ones, so
+    // they never count as stale. Unchanged rates don't mark the graph dirty.
+    pub fn refresh(&mut self, graph: &mut Graph) {
+        let rate = self.rate();
+        for (i, from) in self.config.stablecoins.iter().enumerate() {
+            for to in &self.config.stablecoins[i + 1..] {
+                let key = (from.clone(), to.clone());
+                if !self.synthetic.contains(&key) {
+                    // Coins the feed hasn't carried stay out of the graph
+                    if graph.id(from).is_none() || graph.id(to).is_none() || graph.rate(from, to).is_some() {
+                        continue;
+                    }
+                    tracing::info!(%from, %to, rate, "No market between stablecoins, adding synthetic edges");
+                    self.synthetic.insert((to.clone(), from.clone()));
+                    self.synthetic.insert(key);
+                }
+                graph.set_edge(from, to, rate);
+                graph.set_edge(to, from, rate);
+            }
+        }
+    }
+}
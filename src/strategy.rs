@@ -0,0 +1,132 @@
+use crate::events::{MarketEvent, Opportunity, Signal};
+use crate::graph::{Cycle, Graph};
+
+/// Decision logic run by the [`Engine`](crate::Engine) after every graph update.
+///
+/// Strategies see each normalized [`MarketEvent`] together with the graph it was applied
+/// to. Arbitrage cycles are returned as [`Opportunity`]s; anything else, from a directional
+/// view to a list of orders, is emitted as a [`Signal`].
+pub trait Strategy: Send {
+    /// Short identifier used in logs.
+    fn name(&self) -> &str;
+
+    /// Inspects the updated graph and returns any opportunities found.
+    fn on_update(&mut self, graph: &Graph) -> Vec<Opportunity>;
+
+    /// Like [`on_update`](Strategy::on_update) but also sees the event that caused it,
+    /// so incremental strategies can limit work to what changed.
+    fn on_event(&mut self, graph: &Graph, event: &MarketEvent) -> Vec<Opportunity> {
+        let _ = event;
+        self.on_update(graph)
+    }
+
+    /// Signals produced since the last call, collected by the engine after every pass.
+    /// Strategies that only report cycles keep the default, which has none.
+    fn take_signals(&mut self) -> Vec<Signal> {
+        Vec::new()
+    }
+}
+
+/// Bellman-Ford negative cycle detection over log-price edge weights.
+///
+/// Reports every distinct cycle found in a pass (or the `top_n` most profitable), best first.
+/// Cycles longer than `max_cycle_len` legs (3 by default) are never reported; every leg
+/// costs a fee and a round trip, so long cycles rarely survive execution.
+/// Detection is incremental: only vertices whose edges changed since the last pass are
+/// searched from, and cycles that don't touch them are carried over unchanged.
+/// With [`with_parallel`](NegativeCycleStrategy::with_parallel) each pass searches the
+/// quote-asset clusters of the market concurrently instead of the whole graph at once.
+#[derive(Debug)]
+pub struct NegativeCycleStrategy {
+    top_n: usize,
+    max_cycle_len: usize,
+    parallel: bool,
+    generation: Option<u64>,                 // Graph generation at the last pass
+    cycles: Vec<Cycle>,                      // Result of the last pass
+    dirty: Vec<u32>,                         // Vertices changed since the last pass, reused between passes
+    clusters: Option<(u64, Vec<Vec<u32>>)>, // Quote clusters and the graph topology they were built for
+}
+
+impl NegativeCycleStrategy {
+    pub fn new() -> Self {
+        NegativeCycleStrategy {
+            top_n: usize::MAX,
+            max_cycle_len: 3,
+            parallel: false,
+            generation: None,
+            cycles: Vec::new(),
+            dirty: Vec::new(),
+            clusters: None,
+        }
+    }
+
+    pub fn with_top_n(mut self, top_n: usize) -> Self {
+        self.top_n = top_n.max(1);
+        self
+    }
+
+    pub fn with_max_cycle_len(mut self, max_cycle_len: usize) -> Self {
+        self.max_cycle_len = max_cycle_len.max(2);
+        self
+    }
+
+    /// Runs detection on every quote cluster (USDT- and BTC-quoted pairs plus the bridge
+    /// between them, and so on) in parallel. Finds every triangle; cycles longer than that
+    /// are only found when they stay within one cluster.
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    fn search(&mut self, graph: &Graph, sources: &[u32]) -> Vec<Cycle> {
+        if self.parallel {
+            // Clusters only change when pairs are listed or removed
+            if self.clusters.as_ref().is_none_or(|(topology, _)| *topology != graph.topology()) {
+                self.clusters = Some((graph.topology(), graph.quote_clusters()));
+            }
+            let clusters = self.clusters.as_ref().map_or(&[][..], |(_, clusters)| clusters);
+            graph.find_cycles_parallel(clusters, sources, self.top_n, self.max_cycle_len)
+        } else {
+            graph.find_cycles_from(sources, self.top_n, self.max_cycle_len)
+        }
+    }
+}
+
+impl Default for NegativeCycleStrategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Strategy for NegativeCycleStrategy {
+    fn name(&self) -> &str {
+        "negative-cycle"
+    }
+
+    fn on_update(&mut self, graph: &Graph) -> Vec<Opportunity> {
+        match self.generation {
+            Some(generation) if generation == graph.generation() => {}
+            Some(generation) => {
+                let mut dirty = std::mem::take(&mut self.dirty);
+                graph.dirty_since(generation, &mut dirty);
+                let mut cycles = self.search(graph, &dirty);
+                for cycle in self.cycles.drain(..) {
+                    if !cycle.touches(&dirty) && !cycles.iter().any(|found| found.assets() == cycle.assets()) {
+                        cycles.push(cycle);
+                    }
+                }
+                cycles.sort_by(|a, b| b.gross_return.total_cmp(&a.gross_return));
+                cycles.truncate(self.top_n);
+                self.cycles = cycles;
+                self.dirty = dirty;
+            }
+            None => self.cycles = self.search(graph, &graph.live_vertices()),
+        }
+        self.generation = Some(graph.generation());
+        // Names are only materialized for cycles actually reported
+        self.cycles
+            .iter()
+            .map(|cycle| Opportunity::new(cycle.path(graph)).with_profit(cycle.gross_return))
+            .collect()
+    }
+}
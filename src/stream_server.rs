@@ -0,0 +1,149 @@
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::time::MissedTickBehavior;
+
+use crate::events::EngineEvent;
+use crate::sinks::jsonl::event_json;
+
+// Comma-separated tokens clients authenticate with
+pub const TOKENS_VAR: &str = "HFT3_STREAM_TOKENS";
+
+pub const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone)]
+pub struct StreamServerConfig {
+    pub tokens: Vec<String>, // A client presenting any one of these is let in
+    pub heartbeat: Duration, // Time between heartbeats on every connection
+}
+
+impl StreamServerConfig {
+    // Reads HFT3_STREAM_TOKENS; None if it is unset or holds no token
+    pub fn from_env() -> Option<Self> {
+        let tokens: Vec<String> = std::env::var(TOKENS_VAR)
+            .ok()?
+            .split(',')
+            .map(str::trim)
+            .filter(|token| !token.is_empty())
+            .map(String::from)
+            .collect();
+        (!tokens.is_empty()).then_some(StreamServerConfig { tokens, heartbeat: DEFAULT_HEARTBEAT })
+    }
+}
+
+// Opportunity stream for downstream consumers (`--stream-server <addr>`): a WebSocket on
+// `/opportunities` that pushes every detected opportunity still within its TTL, as the
+// JSON record `--output jsonl` writes, to each authenticated client. Clients send their
+// token as `Authorization: Bearer <token>`, or as `?token=` where they can't set headers.
+//
+// A connection starts with `hello`, gets a `heartbeat` every interval so a consumer can
+// tell a quiet market from a dead link, `lagged` with the count when it fell too far
+// behind and missed some, and `feed_closed` before the server closes it at shutdown.
+pub struct StreamServer {
+    events: broadcast::Receiver<EngineEvent>, // Never read; each connection resubscribes from it
+    config: StreamServerConfig,
+    clients: AtomicUsize,
+}
+
+impl StreamServer {
+    pub fn new(events: broadcast::Receiver<EngineEvent>, config: StreamServerConfig) -> Self {
+        StreamServer { events, config, clients: AtomicUsize::new(0) }
+    }
+
+    // Binds `addr` and serves on a background task
+    pub async fn spawn(self, addr: SocketAddr) -> std::io::Result<()> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let app = Router::new().route("/opportunities", get(upgrade)).with_state(Arc::new(self));
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await {
+                tracing::error!(error = %e, "Opportunity stream server stopped");
+            }
+        });
+        Ok(())
+    }
+
+    fn authorized(&self, token: &str) -> bool {
+        // No short cut on the first match either, so timing says nothing about which token it was
+        self.config.tokens.iter().fold(false, |found, expected| found | tokens_match(token, expected))
+    }
+}
+
+#[derive(serde::Deserialize)]
+struct Auth {
+    token: Option<String>,
+}
+
+async fn upgrade(
+    ws: WebSocketUpgrade,
+    State(server): State<Arc<StreamServer>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(auth): Query<Auth>,
+) -> Response {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if !bearer.or(auth.token.as_deref()).is_some_and(|token| server.authorized(token)) {
+        tracing::warn!(%peer, "Rejected opportunity stream client without a valid token");
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    ws.on_upgrade(move |socket| stream(socket, server, peer))
+}
+
+async fn stream(mut socket: WebSocket, server: Arc<StreamServer>, peer: SocketAddr) {
+    let mut events = server.events.resubscribe();
+    let clients = server.clients.fetch_add(1, Ordering::Relaxed) + 1;
+    tracing::info!(%peer, clients, "Opportunity stream client connected");
+    let mut heartbeat = tokio::time::interval_at(tokio::time::Instant::now() + server.config.heartbeat, server.config.heartbeat);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut sent = 0u64;
+    let mut open = send(&mut socket, json!({ "type": "hello", "heartbeat_ms": server.config.heartbeat.as_millis() as u64 })).await;
+    while open {
+        open = tokio::select! {
+            event = events.recv() => match event {
+                // Downstream would only act on it late
+                Ok(EngineEvent::Opportunity(opportunity)) if opportunity.is_expired(SystemTime::now()) => true,
+                Ok(event @ EngineEvent::Opportunity(_)) => {
+                    sent += 1;
+                    send(&mut socket, event_json(&event)).await
+                }
+                Ok(EngineEvent::FeedClosed) | Err(RecvError::Closed) => {
+                    send(&mut socket, json!({ "type": "feed_closed" })).await;
+                    let _ = socket.send(Message::Close(None)).await;
+                    false
+                }
+                Ok(_) => true,
+                // A slow client misses opportunities rather than holding up the others, but is told so
+                Err(RecvError::Lagged(skipped)) => send(&mut socket, json!({ "type": "lagged", "skipped": skipped })).await,
+            },
+            _ = heartbeat.tick() => {
+                let time_ms = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_millis() as u64);
+                send(&mut socket, json!({ "type": "heartbeat", "time_ms": time_ms, "opportunities": sent })).await
+            }
+            incoming = socket.recv() => matches!(incoming, Some(Ok(message)) if !matches!(message, Message::Close(_))),
+        };
+    }
+    let clients = server.clients.fetch_sub(1, Ordering::Relaxed) - 1;
+    tracing::info!(%peer, clients, opportunities = sent, "Opportunity stream client disconnected");
+}
+
+// False once the client is gone
+async fn send(socket: &mut WebSocket, message: Value) -> bool {
+    socket.send(Message::Text(message.to_string().into())).await.is_ok()
+}
+
+// Compares every byte whatever the first mismatch, so response times don't give a token away
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len() && given.bytes().zip(expected.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
@@ -0,0 +1,146 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::json;
+
+// Binance caps a single connection at 1024 streams
+const MAX_STREAMS: usize = 1024;
+
+// A runtime change to the set of streams on the live socket
+#[derive(Debug, Clone)]
+pub enum SubscriptionCommand {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+// Reply to a SUBSCRIBE/UNSUBSCRIBE request, e.g. {"result":null,"id":1}
+#[derive(serde::Deserialize, Debug)]
+pub struct RpcResponse {
+    pub id: u64,
+    #[serde(default)]
+    pub error: Option<RpcError>,
+}
+
+#[derive(serde::Deserialize, Debug)]
+pub struct RpcError {
+    pub code: i64,
+    pub msg: String,
+}
+
+// Tracks which streams are live and which requests are still waiting for an ack
+pub struct SubscriptionManager {
+    next_id: u64,
+    active: HashSet<String>,
+    pending: HashMap<u64, SubscriptionCommand>,
+}
+
+impl SubscriptionManager {
+    pub fn new(initial: &[String]) -> Self {
+        SubscriptionManager {
+            next_id: 1,
+            active: initial.iter().cloned().collect(),
+            pending: HashMap::new(),
+        }
+    }
+
+    // Builds the JSON-RPC message for a command, dropping streams that would be no-ops.
+    // Returns None if there is nothing to send.
+    pub fn request(&mut self, command: SubscriptionCommand) -> Option<String> {
+        let (method, streams) = match command {
+            SubscriptionCommand::Subscribe(streams) => {
+                let mut new: Vec<String> = streams
+                    .into_iter()
+                    .filter(|s| !self.active.contains(s))
+                    .collect();
+                new.sort();
+                new.dedup();
+                let room = MAX_STREAMS.saturating_sub(self.active.len());
+                if new.len() > room {
+                    tracing::warn!(dropped = new.len() - room, "Stream limit reached, dropping subscriptions");
+                    new.truncate(room);
+                }
+                ("SUBSCRIBE", new)
+            }
+            SubscriptionCommand::Unsubscribe(streams) => {
+                let mut old: Vec<String> = streams
+                    .into_iter()
+                    .filter(|s| self.active.contains(s))
+                    .collect();
+                old.sort();
+                old.dedup();
+                ("UNSUBSCRIBE", old)
+            }
+        };
+        if streams.is_empty() {
+            return None;
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        let message = json!({ "method": method, "params": streams, "id": id }).to_string();
+        let pending = if method == "SUBSCRIBE" {
+            SubscriptionCommand::Subscribe(streams)
+        } else {
+            SubscriptionCommand::Unsubscribe(streams)
+        };
+        self.pending.insert(id, pending);
+        Some(message)
+    }
+
+    // Applies an acknowledged request to the active set and returns it so the caller
+    // can react (e.g. drop graph edges for removed symbols)
+    pub fn handle_response(&mut self, response: RpcResponse) -> Option<SubscriptionCommand> {
+        let command = self.pending.remove(&response.id)?;
+        if let Some(error) = response.error {
+            tracing::error!(id = response.id, code = error.code, msg = %error.msg, "Subscription request failed");
+            return None;
+        }
+        match &command {
+            SubscriptionCommand::Subscribe(streams) => {
+                self.active.extend(streams.iter().cloned());
+            }
+            SubscriptionCommand::Unsubscribe(streams) => {
+                for stream in streams {
+                    self.active.remove(stream);
+                }
+            }
+        }
+        Some(command)
+    }
+
+    pub fn is_active(&self, stream: &str) -> bool {
+        self.active.contains(stream)
+    }
+
+    // Live streams, sorted, e.g. for resubscribing after a reconnect
+    pub fn active(&self) -> Vec<String> {
+        let mut streams: Vec<String> = self.active.iter().cloned().collect();
+        streams.sort();
+        streams
+    }
+}
+
+// Parses an operator command such as "subscribe btcusdt@ticker ethbtc@ticker"
+// or "unsubscribe ethbtc@ticker". Stream names are lowercased as Binance requires.
+pub fn parse_command(line: &str) -> Option<SubscriptionCommand> {
+    let mut parts = line.split_whitespace();
+    let verb = parts.next()?;
+    let streams: Vec<String> = parts.map(|s| s.to_lowercase()).collect();
+    if streams.is_empty() {
+        return None;
+    }
+    match verb {
+        "subscribe" | "sub" => Some(SubscriptionCommand::Subscribe(streams)),
+        "unsubscribe" | "unsub" => Some(SubscriptionCommand::Unsubscribe(streams)),
+        _ => None,
+    }
+}
+
+// Maps a stream name like "btcusdt@ticker" to its symbol "BTCUSDT".
+// Market-wide streams such as "!ticker@arr" have no single symbol.
+pub fn stream_symbol(stream: &str) -> Option<String> {
+    let name = stream.split('@').next()?;
+    if name.is_empty() || name.starts_with('!') {
+        return None;
+    }
+    Some(name.to_uppercase())
+}
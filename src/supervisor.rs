@@ -0,0 +1,147 @@
+use std::any::Any;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+
+use crate::health::HealthMetrics;
+
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    pub initial_backoff: Duration, // Wait before restarting after the first failure
+    pub max_backoff: Duration,     // Cap on the wait, which doubles with every failure in a row
+    pub reset_after: Duration,     // A run this long counts as healthy and starts the wait over
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        RestartPolicy {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            reset_after: Duration::from_secs(60),
+        }
+    }
+}
+
+// Waits between restarts of one component
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    policy: RestartPolicy,
+    delay: Duration,
+    started: Instant,
+}
+
+impl Backoff {
+    pub fn new(policy: RestartPolicy, now: Instant) -> Self {
+        Backoff {
+            delay: policy.initial_backoff,
+            policy,
+            started: now,
+        }
+    }
+
+    pub fn started(&mut self, now: Instant) {
+        self.started = now;
+    }
+
+    // How long to wait before starting again after failing at `now`
+    pub fn failed(&mut self, now: Instant) -> Duration {
+        if now.saturating_duration_since(self.started) >= self.policy.reset_after {
+            self.delay = self.policy.initial_backoff;
+        }
+        let delay = self.delay;
+        self.delay = (self.delay * 2).min(self.policy.max_backoff);
+        delay
+    }
+}
+
+// Runs components on tasks of their own and starts them again when they panic, after a
+// backoff, so one poisoned message costs that message rather than the component. Every
+// start, failure and exit is logged and, with health metrics, shown on /status.
+#[derive(Clone, Default)]
+pub struct Supervisor {
+    policy: RestartPolicy,
+    health: Option<Arc<HealthMetrics>>,
+}
+
+impl Supervisor {
+    pub fn new(policy: RestartPolicy) -> Self {
+        Supervisor { policy, health: None }
+    }
+
+    pub fn with_health(mut self, metrics: Arc<HealthMetrics>) -> Self {
+        self.health = Some(metrics);
+        self
+    }
+
+    pub fn health(&self) -> Option<Arc<HealthMetrics>> {
+        self.health.clone()
+    }
+
+    // Runs the future `task` makes until it finishes, making and running a new one each
+    // time it panics. State that must outlive a restart belongs behind something the
+    // futures share; a tokio Mutex, unlike std's, stays usable after a panic under it.
+    pub fn spawn<T, Fut>(&self, name: impl Into<String>, task: T) -> JoinHandle<()>
+    where
+        T: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.start(name.into(), task, false)
+    }
+
+    // Same, for tasks meant to run for the life of the process: finishing counts as a failure
+    pub fn spawn_service<T, Fut>(&self, name: impl Into<String>, task: T) -> JoinHandle<()>
+    where
+        T: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.start(name.into(), task, true)
+    }
+
+    fn start<T, Fut>(&self, name: String, mut task: T, forever: bool) -> JoinHandle<()>
+    where
+        T: FnMut() -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let policy = self.policy.clone();
+        let health = self.health.clone();
+        tokio::spawn(async move {
+            let mut backoff = Backoff::new(policy, Instant::now());
+            loop {
+                if let Some(health) = &health {
+                    health.record_task_started(&name);
+                }
+                let failure = match tokio::spawn(task()).await {
+                    Ok(()) if forever => "exited".to_string(),
+                    Ok(()) => break,
+                    Err(e) if e.is_panic() => panic_message(e.into_panic()),
+                    // Aborted from outside, which is a way of stopping it
+                    Err(_) => break,
+                };
+                let now = Instant::now();
+                let delay = backoff.failed(now);
+                tracing::error!(task = %name, %failure, restart_in_ms = delay.as_millis() as u64, "Task failed, restarting");
+                if let Some(health) = &health {
+                    health.record_task_failed(&name, &failure, now);
+                }
+                tokio::time::sleep(delay).await;
+                backoff.started(Instant::now());
+            }
+            if let Some(health) = &health {
+                health.record_task_finished(&name);
+            }
+        })
+    }
+}
+
+// What a panic was raised with, when it was a message
+pub fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "panicked".to_string(),
+        },
+    }
+}
@@ -0,0 +1,235 @@
+use std::borrow::Cow;
+use std::time::Instant;
+
+use serde_json::value::RawValue;
+
+use crate::cbbo::VenueQuote;
+use crate::depth::{depth_stream_symbol, BookDepth};
+use crate::graph::{split_pair, Graph};
+use crate::subscription::RpcResponse;
+
+// TickerData struct corresponding to Binance ticker format
+#[derive(serde::Deserialize, serde::Serialize, Debug, Clone)]
+pub struct TickerData {
+    pub s: String, // Symbol
+    pub c: String, // Last price as a string to handle precision
+    #[serde(rename = "E", default)]
+    pub event_time: u64, // Event time in ms
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b: Option<String>, // Best bid price
+    #[serde(rename = "B", default, skip_serializing_if = "Option::is_none")]
+    pub bid_qty: Option<String>, // Best bid quantity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub a: Option<String>, // Best ask price
+    #[serde(rename = "A", default, skip_serializing_if = "Option::is_none")]
+    pub ask_qty: Option<String>, // Best ask quantity
+    #[serde(rename = "u", default, skip_serializing_if = "Option::is_none")]
+    pub update_id: Option<u64>, // Order book update id, on book ticker streams
+    #[serde(rename = "q", default, skip_serializing_if = "Option::is_none")]
+    pub quote_volume: Option<String>, // 24h volume in the quote asset, on 24h ticker streams
+    #[serde(skip)]
+    pub trade: bool, // `c` is an aggregated trade print rather than a ticker's last price
+    // You can add more fields if needed
+}
+
+// Borrowed view of one ticker inside a message. Only the fields the engine uses are read,
+// straight from the message buffer; the twenty-odd others are skipped without allocating.
+#[derive(serde::Deserialize)]
+struct RawTicker<'a> {
+    #[serde(borrow)]
+    s: Cow<'a, str>,
+    #[serde(borrow)]
+    c: Cow<'a, str>,
+    #[serde(rename = "E", default)]
+    event_time: u64,
+    #[serde(borrow, default)]
+    b: Option<Cow<'a, str>>,
+    #[serde(rename = "B", borrow, default)]
+    bid_qty: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    a: Option<Cow<'a, str>>,
+    #[serde(rename = "A", borrow, default)]
+    ask_qty: Option<Cow<'a, str>>,
+    #[serde(rename = "u", default)]
+    update_id: Option<u64>,
+    #[serde(rename = "q", borrow, default)]
+    quote_volume: Option<Cow<'a, str>>,
+}
+
+impl From<RawTicker<'_>> for TickerData {
+    fn from(raw: RawTicker<'_>) -> Self {
+        TickerData {
+            s: raw.s.into_owned(),
+            c: raw.c.into_owned(),
+            event_time: raw.event_time,
+            b: raw.b.map(Cow::into_owned),
+            bid_qty: raw.bid_qty.map(Cow::into_owned),
+            a: raw.a.map(Cow::into_owned),
+            ask_qty: raw.ask_qty.map(Cow::into_owned),
+            update_id: raw.update_id,
+            quote_volume: raw.quote_volume.map(Cow::into_owned),
+            trade: false,
+        }
+    }
+}
+
+// One `<symbol>@aggTrade` event; the trade price becomes the ticker's last price
+#[derive(serde::Deserialize)]
+struct RawAggTrade<'a> {
+    #[serde(borrow)]
+    s: Cow<'a, str>,
+    #[serde(borrow)]
+    p: Cow<'a, str>,
+    #[serde(rename = "E", default)]
+    event_time: u64,
+}
+
+impl From<RawAggTrade<'_>> for TickerData {
+    fn from(raw: RawAggTrade<'_>) -> Self {
+        TickerData {
+            s: raw.s.into_owned(),
+            c: raw.p.into_owned(),
+            event_time: raw.event_time,
+            b: None,
+            bid_qty: None,
+            a: None,
+            ask_qty: None,
+            update_id: None,
+            quote_volume: None,
+            trade: true,
+        }
+    }
+}
+
+// Payload of a combined stream event: `!ticker@arr` delivers an array,
+// per-symbol `<symbol>@ticker` streams deliver a single object
+#[derive(Debug)]
+pub enum TickerPayload {
+    Many(Vec<TickerData>),
+    One(TickerData),
+}
+
+impl TickerPayload {
+    // Picks the shape from the first byte rather than trying both, which would buffer
+    // every field of the payload before deciding
+    pub fn parse(json: &str) -> serde_json::Result<Self> {
+        if json.trim_start().starts_with('[') {
+            let raw: Vec<RawTicker> = serde_json::from_str(json)?;
+            Ok(TickerPayload::Many(raw.into_iter().map(TickerData::from).collect()))
+        } else {
+            Ok(TickerPayload::One(serde_json::from_str::<RawTicker>(json)?.into()))
+        }
+    }
+
+    // Trades from an `@aggTrade` stream, as tickers marked `trade`
+    pub fn parse_trades(json: &str) -> serde_json::Result<Self> {
+        if json.trim_start().starts_with('[') {
+            let raw: Vec<RawAggTrade> = serde_json::from_str(json)?;
+            Ok(TickerPayload::Many(raw.into_iter().map(TickerData::from).collect()))
+        } else {
+            Ok(TickerPayload::One(serde_json::from_str::<RawAggTrade>(json)?.into()))
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<TickerData> {
+        match self {
+            TickerPayload::Many(data) => data,
+            TickerPayload::One(data) => vec![data],
+        }
+    }
+}
+
+// Anything that can arrive on the combined stream socket
+#[derive(Debug)]
+pub enum StreamMessage {
+    Event { stream: String, data: TickerPayload },
+    Depth { stream: String, depth: BookDepth },
+    Response(RpcResponse),
+}
+
+// Outer object of a socket message, with the payload left unparsed in the buffer
+#[derive(serde::Deserialize)]
+struct Envelope<'a> {
+    #[serde(borrow, default)]
+    stream: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    data: Option<&'a RawValue>,
+}
+
+impl StreamMessage {
+    pub fn parse(text: &str) -> serde_json::Result<Self> {
+        let envelope: Envelope = serde_json::from_str(text)?;
+        match (envelope.stream, envelope.data) {
+            (Some(stream), Some(data)) if depth_stream_symbol(&stream).is_some() => {
+                let depth = BookDepth::parse(&stream, data.get())?;
+                Ok(StreamMessage::Depth { stream: stream.into_owned(), depth })
+            }
+            (Some(stream), Some(data)) => {
+                let data = if stream.ends_with("@aggTrade") {
+                    TickerPayload::parse_trades(data.get())?
+                } else {
+                    TickerPayload::parse(data.get())?
+                };
+                Ok(StreamMessage::Event { stream: stream.into_owned(), data })
+            }
+            _ => Ok(StreamMessage::Response(serde_json::from_str(text)?)),
+        }
+    }
+}
+
+// Applies a batch of tickers to the graph, adding edges for symbols seen for the first time.
+// Both directions are priced: from the book the ticker carries when it has one, else at the
+// last price.
+pub fn apply_ticker_data(graph: &mut Graph, ticker_data: &[TickerData]) {
+    for data in ticker_data {
+        let (start, end) = split_pair(&data.s); // Use 's' for symbol
+        let price: f64 = match data.c.parse() { // Parse the last price from string to f64
+            Ok(p) => p,
+            Err(_) => {
+                tracing::warn!(symbol = %data.s, price = %data.c, "Error parsing price");
+                continue; // Skip this entry if the price can't be parsed
+            }
+        };
+        let (bid, ask) = book_top(data)
+            .map(|((bid, _), (ask, _))| (bid, ask))
+            .filter(|&(bid, ask)| bid > 0.0 && ask >= bid)
+            .unwrap_or((price, price));
+        // Symbols subscribed at runtime have no edge yet
+        set_pair_edges(graph, start, end, bid, ask);
+    }
+}
+
+// Selling base into the bid and buying it at the ask
+pub fn set_pair_edges(graph: &mut Graph, base: &str, quote: &str, bid: f64, ask: f64) {
+    if bid > 0.0 {
+        graph.set_edge(base, quote, bid);
+    }
+    if ask > 0.0 {
+        graph.set_edge(quote, base, 1.0 / ask);
+    }
+}
+
+// Top of book carried by a ticker, attributed to `venue`; None if the payload has no bid/ask
+pub fn to_venue_quote(data: &TickerData, venue: &str) -> Option<VenueQuote> {
+    let ((bid, bid_qty), (ask, ask_qty)) = book_top(data)?;
+    let (base, quote) = split_pair(&data.s);
+    Some(VenueQuote {
+        venue: venue.to_string(),
+        base: base.to_string(),
+        quote: quote.to_string(),
+        bid,
+        bid_qty,
+        ask,
+        ask_qty,
+        updated: Instant::now(),
+    })
+}
+
+// (price, quantity) of the best bid and ask a ticker carries; None if the payload has no bid/ask
+pub fn book_top(data: &TickerData) -> Option<((f64, f64), (f64, f64))> {
+    let parse = |field: &Option<String>| field.as_deref().and_then(|v| v.parse::<f64>().ok());
+    Some((
+        (parse(&data.b)?, parse(&data.bid_qty).unwrap_or(0.0)),
+        (parse(&data.a)?, parse(&data.ask_qty).unwrap_or(0.0)),
+    ))
+}
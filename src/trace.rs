@@ -0,0 +1,114 @@
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::events::{CycleTrace, LegTrace, Opportunity};
+use crate::filters::ExchangeFilters;
+use crate::graph::Graph;
+use crate::order::Side;
+use crate::slippage::value_in;
+
+#[derive(Debug, Clone)]
+pub struct TraceConfig {
+    pub notional: f64,     // Starting amount, in `reference`
+    pub reference: String, // Asset `notional` is given in
+    pub fee_bps: f64,      // Taker fee per leg, for legs without a routed venue fee
+}
+
+impl TraceConfig {
+    pub fn new(notional: f64) -> Self {
+        TraceConfig {
+            notional,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for TraceConfig {
+    fn default() -> Self {
+        TraceConfig {
+            notional: 1000.0,
+            reference: "USDT".to_string(),
+            fee_bps: 10.0,
+        }
+    }
+}
+
+// Simulates walking each opportunity with the configured notional, at the rates it was
+// detected at. Every leg pays the fee the smart router chose its venue for, or the
+// configured one, on what it returns. With the exchange's filters each leg also becomes
+// the order that would be sent: price rounded onto the tick grid on the safe side and
+// quantity down to the lot step, with whatever the rounding leaves unspent reported as
+// the leg's residual.
+pub struct CycleTracer {
+    config: TraceConfig,
+    filters: Option<ExchangeFilters>,
+}
+
+impl CycleTracer {
+    pub fn new(config: TraceConfig) -> Self {
+        CycleTracer { config, filters: None }
+    }
+
+    pub fn with_filters(mut self, filters: ExchangeFilters) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    // None when the starting asset can't be valued or a leg has no rate
+    pub fn trace(&self, opportunity: &Opportunity, graph: &Graph) -> Option<CycleTrace> {
+        let start = opportunity.path.first()?;
+        let start_amount = self.config.notional / value_in(graph, start, &self.config.reference)?;
+        let mut amount = start_amount;
+        let mut legs = Vec::with_capacity(opportunity.path.len().saturating_sub(1));
+        for (i, leg) in opportunity.path.windows(2).enumerate() {
+            let (from, to) = (&leg[0], &leg[1]);
+            let rate = opportunity.rates.get(i).copied().or_else(|| graph.rate(from, to)).filter(|r| r.is_finite() && *r > 0.0)?;
+            let fee_bps = opportunity.routing.get(i).map_or(self.config.fee_bps, |route| route.fee_bps);
+            let mut step = LegTrace {
+                from: from.clone(),
+                to: to.clone(),
+                order: None,
+                rate,
+                price: None,
+                quantity: None,
+                amount_in: amount,
+                residual: 0.0,
+                fee_bps,
+                amount_out: amount * rate,
+            };
+            if let Some((filters, side)) = self.filters.as_ref().and_then(|filters| filters.leg(from, to)) {
+                // Quote per base unit, whichever way the leg goes
+                let quoted = match side {
+                    Side::Sell => rate,
+                    Side::Buy => 1.0 / rate,
+                };
+                let price = filters.round_price(Decimal::try_from(quoted).ok()?, side);
+                let available = Decimal::try_from(amount).ok()?;
+                let (quantity, spent, received) = match side {
+                    Side::Sell => {
+                        let quantity = filters.round_quantity(available, false);
+                        (quantity, quantity, quantity * price)
+                    }
+                    Side::Buy if price > Decimal::ZERO => {
+                        let quantity = filters.round_quantity(available / price, false);
+                        (quantity, quantity * price, quantity)
+                    }
+                    Side::Buy => return None,
+                };
+                step.order = Some((filters.symbol.clone(), side));
+                step.price = price.to_f64();
+                step.quantity = quantity.to_f64();
+                step.residual = (available - spent).to_f64().unwrap_or_default();
+                step.amount_out = received.to_f64().unwrap_or_default();
+            }
+            step.amount_out *= 1.0 - fee_bps / 10_000.0;
+            amount = step.amount_out;
+            legs.push(step);
+        }
+        Some(CycleTrace {
+            start_amount,
+            final_amount: amount,
+            legs,
+        })
+    }
+}
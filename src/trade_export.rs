@@ -0,0 +1,187 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+use chrono::SecondsFormat;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::Value;
+
+use crate::graph::Graph;
+use crate::ledger::Fill;
+use crate::pnl::PnlTracker;
+
+// One column of the trade export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TradeColumn {
+    Time,        // RFC 3339, UTC
+    TimeMs,      // Unix milliseconds
+    Venue,
+    Pair,        // The venue's symbol, e.g. ETHBTC
+    Base,
+    Quote,
+    Side,        // buy or sell
+    Quantity,    // Base units
+    Price,       // Quote per base
+    Total,       // Quantity times price, in quote
+    Fee,
+    FeeAsset,
+    RealizedPnl, // In the export's reference asset, net of the fee
+    PnlAsset,
+    OrderId,
+}
+
+impl TradeColumn {
+    pub const ALL: [TradeColumn; 15] = [
+        TradeColumn::Time,
+        TradeColumn::TimeMs,
+        TradeColumn::Venue,
+        TradeColumn::Pair,
+        TradeColumn::Base,
+        TradeColumn::Quote,
+        TradeColumn::Side,
+        TradeColumn::Quantity,
+        TradeColumn::Price,
+        TradeColumn::Total,
+        TradeColumn::Fee,
+        TradeColumn::FeeAsset,
+        TradeColumn::RealizedPnl,
+        TradeColumn::PnlAsset,
+        TradeColumn::OrderId,
+    ];
+
+    pub const DEFAULT: [TradeColumn; 8] = [
+        TradeColumn::Time,
+        TradeColumn::Pair,
+        TradeColumn::Side,
+        TradeColumn::Quantity,
+        TradeColumn::Price,
+        TradeColumn::Fee,
+        TradeColumn::FeeAsset,
+        TradeColumn::RealizedPnl,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TradeColumn::Time => "time",
+            TradeColumn::TimeMs => "time_ms",
+            TradeColumn::Venue => "venue",
+            TradeColumn::Pair => "pair",
+            TradeColumn::Base => "base",
+            TradeColumn::Quote => "quote",
+            TradeColumn::Side => "side",
+            TradeColumn::Quantity => "quantity",
+            TradeColumn::Price => "price",
+            TradeColumn::Total => "total",
+            TradeColumn::Fee => "fee",
+            TradeColumn::FeeAsset => "fee_asset",
+            TradeColumn::RealizedPnl => "realized_pnl",
+            TradeColumn::PnlAsset => "pnl_asset",
+            TradeColumn::OrderId => "order_id",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        TradeColumn::ALL.into_iter().find(|column| column.name() == value)
+    }
+
+    // `name[=header]`, comma separated, e.g. `time=Date,pair=Market,side`
+    pub fn parse_list(value: &str) -> Result<Vec<(TradeColumn, String)>, String> {
+        value
+            .split(',')
+            .filter(|field| !field.is_empty())
+            .map(|field| {
+                let (name, header) = field.split_once('=').unwrap_or((field, field));
+                TradeColumn::parse(name)
+                    .map(|column| (column, header.to_string()))
+                    .ok_or_else(|| format!("unknown column {}", name))
+            })
+            .collect()
+    }
+}
+
+// Every fill in an audit log (its `fill` records) or a fill ledger, in file order. Other
+// audit records are skipped.
+pub fn load_trades(path: &Path) -> io::Result<Vec<Fill>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut fills = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let invalid = |e: serde_json::Error| io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {}", number + 1, e));
+        let mut record: Value = serde_json::from_str(&line).map_err(invalid)?;
+        let fill = match record.get("kind").and_then(Value::as_str) {
+            Some("fill") => record["fill"].take(),
+            Some(_) => continue,
+            None => record,
+        };
+        fills.push(serde_json::from_value(fill).map_err(invalid)?);
+    }
+    Ok(fills)
+}
+
+// Trades as CSV, oldest first, with a header row. Realized PnL is kept at average cost in
+// `reference` over every fill given, each asset valued at the latest fill price linking
+// it to the reference (directly or through the other asset of a pair): assets never
+// traded against it contribute nothing until they are.
+pub fn export_csv(fills: &[Fill], columns: &[(TradeColumn, String)], reference: &str, delimiter: char) -> String {
+    let mut fills: Vec<&Fill> = fills.iter().collect();
+    fills.sort_by_key(|fill| fill.time);
+    let mut graph = Graph::new();
+    let mut pnl = PnlTracker::new(reference);
+
+    let mut out = String::new();
+    write_row(&mut out, columns.iter().map(|(_, header)| header.clone()), delimiter);
+    for fill in fills {
+        let price = fill.price.to_f64().unwrap_or(0.0);
+        if price > 0.0 {
+            graph.set_edge(&fill.base, &fill.quote, price);
+            graph.set_edge(&fill.quote, &fill.base, 1.0 / price);
+        }
+        let realized = pnl.record(fill, None, &graph);
+        let row = columns.iter().map(|(column, _)| match column {
+            TradeColumn::Time => fill.time.to_rfc3339_opts(SecondsFormat::Millis, true),
+            TradeColumn::TimeMs => fill.time.timestamp_millis().to_string(),
+            TradeColumn::Venue => fill.venue.clone(),
+            TradeColumn::Pair => fill.symbol.clone(),
+            TradeColumn::Base => fill.base.clone(),
+            TradeColumn::Quote => fill.quote.clone(),
+            TradeColumn::Side => fill.side.as_str().to_lowercase(),
+            TradeColumn::Quantity => number(fill.quantity),
+            TradeColumn::Price => number(fill.price),
+            TradeColumn::Total => number(fill.quantity * fill.price),
+            TradeColumn::Fee => number(fill.fee),
+            TradeColumn::FeeAsset => fill.fee_asset_or_quote().to_string(),
+            TradeColumn::RealizedPnl => format!("{:.8}", realized),
+            TradeColumn::PnlAsset => reference.to_string(),
+            TradeColumn::OrderId => fill.order_id.clone().unwrap_or_default(),
+        });
+        write_row(&mut out, row, delimiter);
+    }
+    out
+}
+
+// Exact amount without trailing zeros
+fn number(value: Decimal) -> String {
+    let value = value.normalize();
+    if value.is_zero() { "0".to_string() } else { value.to_string() }
+}
+
+// Fields holding the delimiter, a quote or a line break are quoted, quotes doubled
+fn write_row(out: &mut String, fields: impl Iterator<Item = String>, delimiter: char) {
+    for (i, field) in fields.enumerate() {
+        if i > 0 {
+            out.push(delimiter);
+        }
+        if field.contains([delimiter, '"', '\n', '\r']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(&field);
+        }
+    }
+    out.push('\n');
+}
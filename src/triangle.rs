@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::events::{MarketEvent, Opportunity};
+use crate::graph::{extract_currency_pair, split_pair, Graph};
+use crate::slippage::SlippageModel;
+use crate::strategy::Strategy;
+
+/// Settings for [`TwoPhaseStrategy`].
+#[derive(Debug, Clone)]
+pub struct TwoPhaseConfig {
+    /// A triangle becomes a candidate when its better direction, less `fee_bps` on every
+    /// leg, is within this many bps of break-even.
+    pub coarse_bps: f64,
+    /// How long a triangle stays a candidate after last passing the coarse filter.
+    pub candidate_ttl: Duration,
+    /// Taker fee charged on every leg, in bps.
+    pub fee_bps: f64,
+    /// Expected slippage on every leg, deducted along with fees before the profit is
+    /// compared against `min_profit_bps`.
+    pub slippage: SlippageModel,
+    /// Minimum net profit to report, in bps.
+    pub min_profit_bps: f64,
+    /// Report at most this many opportunities per update, most profitable first.
+    pub top_n: Option<usize>,
+}
+
+impl Default for TwoPhaseConfig {
+    fn default() -> Self {
+        TwoPhaseConfig {
+            coarse_bps: 50.0,
+            candidate_ttl: Duration::from_secs(1),
+            fee_bps: 10.0,
+            slippage: SlippageModel::None,
+            min_profit_bps: 0.0,
+            top_n: None,
+        }
+    }
+}
+
+// One leg of a triangle: the symbol and whether walking the triangle sells its base
+struct Leg {
+    symbol: String,
+    sells_base: bool,
+}
+
+// Assets a -> b -> c -> a and the symbols connecting them
+struct Triangle {
+    assets: [String; 3],
+    legs: [Leg; 3],
+}
+
+/// Triangular arbitrage detector that does minimal work per update.
+///
+/// Phase one multiplies cached last prices for just the triangles touching the updated
+/// symbols and marks those within `coarse_bps` of break-even after fees as candidates. Phase two runs
+/// the fee-aware evaluation only for candidates seen within `candidate_ttl`.
+pub struct TwoPhaseStrategy {
+    config: TwoPhaseConfig,
+    prices: HashMap<String, (f64, Decimal)>,            // Last price per symbol, approximate and exact
+    depth: HashMap<String, (Option<f64>, Option<f64>)>, // Best bid and ask quantity, for depth-based slippage
+    pairs: HashMap<String, HashMap<String, String>>,    // Asset -> neighbour asset -> symbol
+    triangles: Vec<Triangle>,
+    by_symbol: HashMap<String, Vec<usize>>, // Symbol -> triangles using it
+    known: HashSet<[String; 3]>,            // Sorted asset sets already indexed
+    candidates: HashMap<usize, Instant>,    // Triangle -> last time it passed phase one
+    touched: HashSet<usize>,                // Triangles an event touches, reused between events
+    coarse_checks: u64,
+    exact_checks: u64,
+}
+
+impl TwoPhaseStrategy {
+    pub fn new(config: TwoPhaseConfig) -> Self {
+        TwoPhaseStrategy {
+            config,
+            prices: HashMap::new(),
+            depth: HashMap::new(),
+            pairs: HashMap::new(),
+            triangles: Vec::new(),
+            by_symbol: HashMap::new(),
+            known: HashSet::new(),
+            candidates: HashMap::new(),
+            touched: HashSet::new(),
+            coarse_checks: 0,
+            exact_checks: 0,
+        }
+    }
+
+    /// Number of (phase one, phase two) evaluations so far.
+    pub fn stats(&self) -> (u64, u64) {
+        (self.coarse_checks, self.exact_checks)
+    }
+
+    // Registers a newly seen symbol and every triangle it closes
+    fn index_symbol(&mut self, symbol: &str) {
+        let (base, quote) = extract_currency_pair(symbol);
+        self.pairs.entry(base.clone()).or_default().insert(quote.clone(), symbol.to_string());
+        self.pairs.entry(quote.clone()).or_default().insert(base.clone(), symbol.to_string());
+
+        let base_neighbours: Vec<String> = self.pairs[&base].keys().cloned().collect();
+        for third in base_neighbours {
+            if third == quote || !self.pairs[&quote].contains_key(&third) {
+                continue;
+            }
+            let mut key = [base.clone(), quote.clone(), third.clone()];
+            key.sort();
+            if !self.known.insert(key) {
+                continue;
+            }
+            let assets = [base.clone(), quote.clone(), third];
+            let legs = [
+                self.leg(&assets[0], &assets[1]),
+                self.leg(&assets[1], &assets[2]),
+                self.leg(&assets[2], &assets[0]),
+            ];
+            let index = self.triangles.len();
+            for leg in &legs {
+                self.by_symbol.entry(leg.symbol.clone()).or_default().push(index);
+            }
+            self.triangles.push(Triangle { assets, legs });
+        }
+    }
+
+    fn leg(&self, from: &str, to: &str) -> Leg {
+        let symbol = self.pairs[from][to].clone();
+        let sells_base = extract_currency_pair(&symbol).0 == from;
+        Leg { symbol, sells_base }
+    }
+
+    // Gross product of walking the triangle forward, or None if a price is missing
+    fn product(&self, triangle: &Triangle) -> Option<f64> {
+        let mut product = 1.0;
+        for leg in &triangle.legs {
+            let (price, _) = *self.prices.get(&leg.symbol)?;
+            if price <= 0.0 {
+                return None;
+            }
+            product *= if leg.sells_base { price } else { 1.0 / price };
+        }
+        Some(product)
+    }
+
+    // The same product in exact decimal arithmetic, for the profit that gets reported
+    fn exact_product(&self, triangle: &Triangle) -> Option<Decimal> {
+        let mut product = Decimal::ONE;
+        for leg in &triangle.legs {
+            let (_, price) = *self.prices.get(&leg.symbol)?;
+            if price <= Decimal::ZERO {
+                return None;
+            }
+            product = if leg.sells_base { product.checked_mul(price)? } else { product.checked_div(price)? };
+        }
+        Some(product)
+    }
+
+    // Fee-aware evaluation; returns the opportunity in whichever direction pays
+    fn evaluate(&self, triangle: &Triangle, graph: &Graph) -> Option<Opportunity> {
+        let forward = self.exact_product(triangle)?;
+        let (gross, reverse) = if forward >= Decimal::ONE { (forward, false) } else { (Decimal::ONE.checked_div(forward)?, true) };
+        let bps = Decimal::from(10_000);
+        // Either direction enters every asset once, so depeg premiums apply the same way
+        let mut net = gross;
+        for asset in &triangle.assets {
+            net *= Decimal::ONE - Decimal::try_from(graph.premium(asset)).ok()?;
+        }
+        let fee = Decimal::ONE - Decimal::try_from(self.config.fee_bps).ok()? / bps;
+        net = net * fee * fee * fee;
+        if !self.config.slippage.is_none() {
+            // Walking the triangle backwards takes the other side of every book
+            for leg in &triangle.legs {
+                let sells_base = leg.sells_base != reverse;
+                let depth = self.depth.get(&leg.symbol).and_then(|&(bid, ask)| if sells_base { bid } else { ask });
+                let (base, _) = split_pair(&leg.symbol);
+                let slippage = self.config.slippage.leg_bps(graph, base, depth);
+                net *= Decimal::ONE - Decimal::try_from(slippage).ok()? / bps;
+            }
+        }
+        net -= Decimal::ONE;
+        if net * bps <= Decimal::try_from(self.config.min_profit_bps).ok()? {
+            return None;
+        }
+        let [a, b, c] = &triangle.assets;
+        let path = if reverse {
+            vec![a.clone(), c.clone(), b.clone(), a.clone()]
+        } else {
+            vec![a.clone(), b.clone(), c.clone(), a.clone()]
+        };
+        Some(Opportunity::new(path).with_profit(net.to_f64()?))
+    }
+}
+
+impl Default for TwoPhaseStrategy {
+    fn default() -> Self {
+        Self::new(TwoPhaseConfig::default())
+    }
+}
+
+impl Strategy for TwoPhaseStrategy {
+    fn name(&self) -> &str {
+        "two-phase-triangle"
+    }
+
+    // Without knowing what changed there is nothing to narrow down; events drive this strategy
+    fn on_update(&mut self, _graph: &Graph) -> Vec<Opportunity> {
+        Vec::new()
+    }
+
+    fn on_event(&mut self, graph: &Graph, event: &MarketEvent) -> Vec<Opportunity> {
+        let tickers = match event {
+            MarketEvent::Tickers(tickers) => tickers,
+            MarketEvent::SymbolRemoved(symbol) => {
+                // Triangles through the symbol stop evaluating until it ticks again
+                self.prices.remove(symbol);
+                self.depth.remove(symbol);
+                return Vec::new();
+            }
+            // Venue quotes are keyed by pair, not symbol; this strategy works on Binance tickers
+            MarketEvent::Quotes(_) => return Vec::new(),
+            MarketEvent::MarkPrices(_) | MarketEvent::FeedState { .. } | MarketEvent::FeedStalled { .. } | MarketEvent::Depth(_) => {
+                return Vec::new()
+            }
+        };
+
+        // Phase one: cheap gross product on triangles touching the updated symbols
+        let now = Instant::now();
+        // Walking either way, the better direction's gross product is at least 1; fees are
+        // what stand between it and break-even
+        let fees_bps = 3.0 * self.config.fee_bps;
+        let mut touched = std::mem::take(&mut self.touched);
+        for ticker in tickers {
+            let Ok(exact) = ticker.c.parse::<Decimal>() else { continue };
+            let Some(price) = exact.to_f64() else { continue };
+            // Known symbols are updated in place; only a new one costs a key
+            match self.prices.get_mut(&ticker.s) {
+                Some(known) => *known = (price, exact),
+                None => {
+                    self.prices.insert(ticker.s.clone(), (price, exact));
+                    self.index_symbol(&ticker.s);
+                }
+            }
+            if self.config.slippage.needs_depth() {
+                let quantity = |qty: &Option<String>| qty.as_deref().and_then(|q| q.parse::<f64>().ok());
+                let top = (quantity(&ticker.bid_qty), quantity(&ticker.ask_qty));
+                match self.depth.get_mut(&ticker.s) {
+                    Some(known) => *known = top,
+                    None => {
+                        self.depth.insert(ticker.s.clone(), top);
+                    }
+                }
+            }
+            if let Some(indices) = self.by_symbol.get(&ticker.s) {
+                touched.extend(indices.iter().copied());
+            }
+        }
+        for index in touched.drain() {
+            self.coarse_checks += 1;
+            if let Some(product) = self.product(&self.triangles[index]) {
+                let edge_bps = (product.max(1.0 / product) - 1.0) * 10_000.0 - fees_bps;
+                if edge_bps >= -self.config.coarse_bps {
+                    self.candidates.insert(index, now);
+                }
+            }
+        }
+        self.touched = touched;
+
+        // Phase two: exact check for recent candidates only
+        let ttl = self.config.candidate_ttl;
+        self.candidates.retain(|_, seen| now.duration_since(*seen) <= ttl);
+        self.exact_checks += self.candidates.len() as u64;
+        let mut opportunities: Vec<Opportunity> = self
+            .candidates
+            .keys()
+            .filter_map(|index| self.evaluate(&self.triangles[*index], graph))
+            .collect();
+        opportunities.sort_by(|a, b| b.profit.unwrap_or(0.0).total_cmp(&a.profit.unwrap_or(0.0)));
+        if let Some(top_n) = self.config.top_n {
+            opportunities.truncate(top_n);
+        }
+        opportunities
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::dedup::{DedupConfig, Deduplicator};
+use crate::events::Opportunity;
+
+// Weight of the newest interval in a symbol's moving average of quote updates
+const QUOTE_ALPHA: f64 = 0.2;
+
+#[derive(Debug, Clone)]
+pub struct TtlConfig {
+    pub min_ttl: Duration,     // Floor on the estimate, for cycles that never outlive one update
+    pub max_ttl: Duration,     // Cap on the estimate, and the TTL before anything has been observed
+    pub min_samples: u64,      // Closed streaks a cycle needs before its own lifetimes are used
+    pub close_after: Duration, // A cycle not re-detected for this long counts as closed
+}
+
+impl Default for TtlConfig {
+    fn default() -> Self {
+        TtlConfig {
+            min_ttl: Duration::from_millis(50),
+            max_ttl: Duration::from_secs(5),
+            min_samples: 5,
+            close_after: Duration::from_secs(1),
+        }
+    }
+}
+
+// Running mean of streak lengths, ms
+#[derive(Debug, Clone, Copy, Default)]
+struct Lifetime {
+    count: u64,
+    mean_ms: f64,
+}
+
+impl Lifetime {
+    fn record(&mut self, ms: f64) {
+        self.count += 1;
+        self.mean_ms += (ms - self.mean_ms) / self.count as f64;
+    }
+}
+
+// Moving average of the time between a symbol's quotes
+#[derive(Debug, Clone, Copy)]
+struct QuoteRate {
+    last: Instant,
+    interval_ms: Option<f64>,
+}
+
+// How long a detected opportunity can be expected to last. A streak's measured length
+// ends at its last detection, and the cycle lived on until the next update of one of its
+// legs closed it, so the estimate is the mean streak length (the cycle's own once it has
+// `min_samples` of them, across all cycles until then) plus the update interval of the
+// cycle's fastest-moving leg. Streaks are tracked here rather than taken from the
+// engine's dedup, so it works whether or not reports are deduplicated.
+pub struct TtlEstimator {
+    config: TtlConfig,
+    streaks: Deduplicator,
+    cycles: HashMap<String, Lifetime>,
+    overall: Lifetime,
+    quotes: HashMap<String, QuoteRate>, // By symbol
+}
+
+impl TtlEstimator {
+    pub fn new(config: TtlConfig) -> Self {
+        TtlEstimator {
+            streaks: Deduplicator::new(DedupConfig { cooldown: Duration::ZERO, close_after: config.close_after }),
+            config,
+            cycles: HashMap::new(),
+            overall: Lifetime::default(),
+            quotes: HashMap::new(),
+        }
+    }
+
+    // A quote for `symbol` arrived at `now`
+    pub fn observe_quote(&mut self, symbol: &str, now: Instant) {
+        match self.quotes.get_mut(symbol) {
+            Some(rate) => {
+                let interval = now.saturating_duration_since(rate.last).as_secs_f64() * 1_000.0;
+                rate.interval_ms = Some(rate.interval_ms.map_or(interval, |mean| mean + QUOTE_ALPHA * (interval - mean)));
+                rate.last = now;
+            }
+            None => {
+                self.quotes.insert(symbol.to_string(), QuoteRate { last: now, interval_ms: None });
+            }
+        }
+    }
+
+    // A detection of the cycle at `now`, reported or not
+    pub fn observe(&mut self, opportunity: &Opportunity, now: Instant) {
+        self.streaks.observe(opportunity, now);
+    }
+
+    // Folds the streaks that closed by `now` into the lifetimes; once per detection pass
+    pub fn end_pass(&mut self, now: Instant) {
+        for summary in self.streaks.close_expired(now) {
+            let ms = summary.duration.as_secs_f64() * 1_000.0;
+            self.overall.record(ms);
+            self.cycles.entry(summary.cycle).or_default().record(ms);
+        }
+    }
+
+    pub fn estimate(&self, opportunity: &Opportunity) -> Duration {
+        let min_samples = self.config.min_samples.max(1);
+        let lifetime = self
+            .cycles
+            .get(&opportunity.cycle_key())
+            .filter(|cycle| cycle.count >= min_samples)
+            .or(Some(&self.overall).filter(|overall| overall.count >= min_samples))
+            .map(|lifetime| lifetime.mean_ms);
+        let update = opportunity
+            .path
+            .windows(2)
+            .filter_map(|leg| self.update_interval(&leg[0], &leg[1]))
+            .min_by(f64::total_cmp);
+        if lifetime.is_none() && update.is_none() {
+            return self.config.max_ttl;
+        }
+        let ms = lifetime.unwrap_or(0.0) + update.unwrap_or(0.0);
+        Duration::from_secs_f64(ms / 1_000.0).max(self.config.min_ttl).min(self.config.max_ttl)
+    }
+
+    // Mean time between quotes of the pair, listed either way round
+    fn update_interval(&self, from: &str, to: &str) -> Option<f64> {
+        [format!("{}{}", from, to), format!("{}{}", to, from)]
+            .iter()
+            .find_map(|symbol| self.quotes.get(symbol))
+            .and_then(|rate| rate.interval_ms)
+    }
+}
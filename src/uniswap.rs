@@ -0,0 +1,309 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use crate::cbbo::VenueQuote;
+use crate::events::MarketEvent;
+use crate::feed::Feed;
+use crate::graph::quote_priority;
+use crate::proxy::Proxy;
+
+// Venue name of Uniswap v3 quotes, in the consolidated book and health metrics
+pub const VENUE: &str = "uniswap-v3";
+
+// Function selectors of the pool and ERC-20 calls used
+const SLOT0: &str = "0x3850c7bd";
+const TOKEN0: &str = "0x0dfe1681";
+const TOKEN1: &str = "0xd21220a7";
+const FEE: &str = "0xddca3f43";
+const DECIMALS: &str = "0x313ce567";
+const SYMBOL: &str = "0x95d89b41";
+
+const Q96: f64 = 79_228_162_514_264_337_593_543_950_336.0; // 2^96
+
+#[derive(Debug, Clone)]
+pub struct UniswapConfig {
+    pub rpc_url: String,
+    pub pools: Vec<String>,               // Pool contract addresses
+    pub poll_interval: Duration,          // Between price reads; blocks come every 12 s
+    pub gas_units: u64,                   // Gas one swap is expected to use
+    pub notional_eth: f64,                // Trade size gas is spread over, in ETH
+    pub aliases: HashMap<String, String>, // Token symbol -> asset name on the graph (WETH -> ETH)
+}
+
+impl UniswapConfig {
+    pub fn new(rpc_url: &str) -> Self {
+        UniswapConfig {
+            rpc_url: rpc_url.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for UniswapConfig {
+    fn default() -> Self {
+        UniswapConfig {
+            rpc_url: String::new(),
+            pools: Vec::new(),
+            poll_interval: Duration::from_secs(3),
+            gas_units: 150_000,
+            notional_eth: 1.0,
+            aliases: [("WETH", "ETH"), ("WBTC", "BTC")].iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RpcError {
+    Http(reqwest::Error),
+    Node(String), // Error object returned by the node
+    Decode(String),
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RpcError::Http(e) => write!(f, "HTTP error: {}", e),
+            RpcError::Node(e) => write!(f, "node error: {}", e),
+            RpcError::Decode(e) => write!(f, "decode error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl From<reqwest::Error> for RpcError {
+    fn from(e: reqwest::Error) -> Self {
+        RpcError::Http(e)
+    }
+}
+
+// Minimal Ethereum JSON-RPC client: read-only calls sent as one batch per round
+struct RpcClient {
+    http: reqwest::Client,
+    url: String,
+}
+
+impl RpcClient {
+    // Sends `calls` (method, params) as one batch and returns the results in order
+    async fn batch(&self, calls: &[(&str, Value)]) -> Result<Vec<Value>, RpcError> {
+        let body: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(id, (method, params))| json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }))
+            .collect();
+        let response: Vec<Value> = self.http.post(&self.url).json(&body).send().await?.error_for_status()?.json().await?;
+        let mut results = vec![Value::Null; calls.len()];
+        for entry in response {
+            let id = entry["id"].as_u64().map(|id| id as usize).filter(|&id| id < calls.len());
+            let id = id.ok_or_else(|| RpcError::Decode(format!("unexpected response id {}", entry["id"])))?;
+            if let Some(error) = entry.get("error") {
+                return Err(RpcError::Node(error.to_string()));
+            }
+            results[id] = entry["result"].clone();
+        }
+        Ok(results)
+    }
+}
+
+fn eth_call(to: &str, data: &str) -> (&'static str, Value) {
+    ("eth_call", json!([{ "to": to, "data": data }, "latest"]))
+}
+
+// 32-byte words of an ABI-encoded return value
+fn words(result: &Value) -> Result<Vec<&str>, RpcError> {
+    let hex = result.as_str().and_then(|r| r.strip_prefix("0x")).ok_or_else(|| RpcError::Decode(format!("not hex: {}", result)))?;
+    if hex.is_empty() || hex.len() % 64 != 0 {
+        return Err(RpcError::Decode(format!("bad ABI length: {}", result)));
+    }
+    Ok((0..hex.len() / 64).map(|i| &hex[i * 64..(i + 1) * 64]).collect())
+}
+
+fn word_u64(word: &str) -> Result<u64, RpcError> {
+    u64::from_str_radix(&word[48..], 16).map_err(|e| RpcError::Decode(e.to_string()))
+}
+
+// Unsigned integer of any width as f64; sqrtPriceX96 is 160 bits
+fn word_f64(word: &str) -> Result<f64, RpcError> {
+    word.chars().try_fold(0.0, |acc, c| {
+        let digit = c.to_digit(16).ok_or_else(|| RpcError::Decode(format!("bad hex digit {}", c)))?;
+        Ok(acc * 16.0 + digit as f64)
+    })
+}
+
+fn word_address(word: &str) -> String {
+    format!("0x{}", &word[24..])
+}
+
+// symbol() returns a string, or bytes32 on some older tokens
+fn decode_symbol(result: &Value) -> Result<String, RpcError> {
+    let words = words(result)?;
+    let bytes = |hex: &str| -> Vec<u8> { (0..hex.len() / 2).filter_map(|i| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()).collect() };
+    let raw = if words.len() >= 3 {
+        let len = word_u64(words[1])? as usize;
+        bytes(&words[2..].concat()).into_iter().take(len).collect()
+    } else {
+        bytes(words[0]).into_iter().take_while(|b| *b != 0).collect()
+    };
+    String::from_utf8(raw).map_err(|e| RpcError::Decode(e.to_string()))
+}
+
+// A pool as it maps onto the graph: `base` priced in `quote`, whichever of the two tokens
+// is token0
+#[derive(Debug, Clone)]
+pub struct Pool {
+    pub address: String,
+    pub base: String,
+    pub quote: String,
+    pub fee: f64, // Swap fee as a fraction
+    base_is_token0: bool,
+    decimals0: u32,
+    decimals1: u32,
+}
+
+impl Pool {
+    // Mid price of base in quote from slot0's sqrtPriceX96
+    fn price(&self, sqrt_price_x96: f64) -> Option<f64> {
+        let ratio = sqrt_price_x96 / Q96;
+        let token0_in_token1 = ratio * ratio * 10f64.powi(self.decimals0 as i32 - self.decimals1 as i32);
+        let price = if self.base_is_token0 { token0_in_token1 } else { 1.0 / token0_in_token1 };
+        Some(price).filter(|p| p.is_finite() && *p > 0.0)
+    }
+}
+
+// Reads each pool's tokens, fee and decimals once, at startup
+async fn load_pools(rpc: &RpcClient, config: &UniswapConfig) -> Result<Vec<Pool>, RpcError> {
+    let calls: Vec<(&str, Value)> = config
+        .pools
+        .iter()
+        .flat_map(|address| [eth_call(address, TOKEN0), eth_call(address, TOKEN1), eth_call(address, FEE)])
+        .collect();
+    let results = rpc.batch(&calls).await?;
+    let mut layout = Vec::new();
+    for (address, result) in config.pools.iter().zip(results.chunks(3)) {
+        let token0 = word_address(words(&result[0])?[0]);
+        let token1 = word_address(words(&result[1])?[0]);
+        let fee = word_u64(words(&result[2])?[0])?;
+        layout.push((address.clone(), token0, token1, fee));
+    }
+
+    let mut tokens: Vec<String> = layout.iter().flat_map(|(_, t0, t1, _)| [t0.clone(), t1.clone()]).collect();
+    tokens.sort();
+    tokens.dedup();
+    let calls: Vec<(&str, Value)> = tokens.iter().flat_map(|t| [eth_call(t, DECIMALS), eth_call(t, SYMBOL)]).collect();
+    let results = rpc.batch(&calls).await?;
+    let mut info = HashMap::new();
+    for (token, result) in tokens.iter().zip(results.chunks(2)) {
+        let decimals = word_u64(words(&result[0])?[0])? as u32;
+        let symbol = decode_symbol(&result[1])?.to_uppercase();
+        let asset = config.aliases.get(&symbol).cloned().unwrap_or(symbol);
+        info.insert(token.clone(), (asset, decimals));
+    }
+
+    let pools = layout
+        .into_iter()
+        .map(|(address, token0, token1, fee)| {
+            let (asset0, decimals0) = info[&token0].clone();
+            let (asset1, decimals1) = info[&token1].clone();
+            // Quote the pair the way the exchange would, so both venues land on the same book entry
+            let base_is_token0 = match (quote_priority(&asset0), quote_priority(&asset1)) {
+                (Some(p0), Some(p1)) => p0 > p1,
+                (Some(_), None) => false,
+                _ => true,
+            };
+            let (base, quote) = if base_is_token0 { (asset0, asset1) } else { (asset1, asset0) };
+            Pool { address, base, quote, fee: fee as f64 / 1_000_000.0, base_is_token0, decimals0, decimals1 }
+        })
+        .collect();
+    Ok(pools)
+}
+
+// Gas for one swap as a fraction of the notional, given the gas price in wei
+fn gas_cost(config: &UniswapConfig, gas_price_wei: f64) -> f64 {
+    config.gas_units as f64 * gas_price_wei / 1e18 / config.notional_eth.max(f64::MIN_POSITIVE)
+}
+
+// One round of prices: every pool's slot0 and the gas price, in a single batch
+async fn poll(rpc: &RpcClient, config: &UniswapConfig, pools: &[Pool]) -> Result<Vec<VenueQuote>, RpcError> {
+    let mut calls: Vec<(&str, Value)> = pools.iter().map(|pool| eth_call(&pool.address, SLOT0)).collect();
+    calls.push(("eth_gasPrice", json!([])));
+    let results = rpc.batch(&calls).await?;
+    let gas_price = results[pools.len()].as_str().and_then(|p| p.strip_prefix("0x")).and_then(|p| u128::from_str_radix(p, 16).ok());
+    let gas_price = gas_price.ok_or_else(|| RpcError::Decode(format!("bad gas price: {}", results[pools.len()])))?;
+    let keep = 1.0 - gas_cost(config, gas_price as f64);
+    let updated = Instant::now();
+    let mut quotes = Vec::with_capacity(pools.len());
+    for (pool, result) in pools.iter().zip(&results) {
+        let Some(mid) = pool.price(word_f64(words(result)?[0])?) else {
+            continue;
+        };
+        // Selling base receives the mid less the fee and gas, buying it costs the mid plus both
+        let keep = (1.0 - pool.fee) * keep;
+        if keep <= 0.0 {
+            continue;
+        }
+        quotes.push(VenueQuote {
+            venue: VENUE.to_string(),
+            base: pool.base.clone(),
+            quote: pool.quote.clone(),
+            bid: mid * keep,
+            bid_qty: 0.0,
+            ask: mid / keep,
+            ask_qty: 0.0,
+            updated,
+        });
+    }
+    Ok(quotes)
+}
+
+// Uniswap v3 pool prices polled over JSON-RPC, as quotes net of the swap fee and gas.
+// Polling runs on its own task, so the feed survives being cancelled mid-request.
+pub struct UniswapFeed {
+    quotes: mpsc::Receiver<Vec<VenueQuote>>,
+    poller: tokio::task::JoinHandle<()>,
+}
+
+impl UniswapFeed {
+    pub async fn spawn(config: UniswapConfig, proxy: Option<&Proxy>) -> Result<Self, RpcError> {
+        let rpc = RpcClient { http: crate::proxy::http_client(proxy), url: config.rpc_url.clone() };
+        let pools = load_pools(&rpc, &config).await?;
+        for pool in &pools {
+            tracing::info!(address = %pool.address, base = %pool.base, quote = %pool.quote, fee = pool.fee, "Watching Uniswap v3 pool");
+        }
+        let (tx, quotes) = mpsc::channel(16);
+        let poller = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.poll_interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match poll(&rpc, &config, &pools).await {
+                    Ok(quotes) if quotes.is_empty() => {}
+                    Ok(quotes) => {
+                        if tx.send(quotes).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => tracing::warn!(error = %e, "Failed to poll Uniswap pools"),
+                }
+            }
+        });
+        Ok(UniswapFeed { quotes, poller })
+    }
+}
+
+impl Feed for UniswapFeed {
+    fn venue(&self) -> &str {
+        VENUE
+    }
+
+    async fn next_event(&mut self) -> Option<MarketEvent> {
+        self.quotes.recv().await.map(MarketEvent::Quotes)
+    }
+
+    async fn close(&mut self) {
+        self.poller.abort();
+    }
+}
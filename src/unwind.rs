@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rust_decimal::Decimal;
+
+use crate::filters::{ExchangeFilters, FilterViolation, ViolationKind};
+use crate::graph::Graph;
+use crate::order::{OrderRequest, Side};
+use crate::order_tracker::OrderUpdate;
+
+// Market buys are sized off the last rate, so leave room for the price having moved
+const MARKET_BUY_HEADROOM: Decimal = Decimal::from_parts(98, 0, 0, false, 2);
+
+// What to do with inventory a cycle leaves behind when a leg fails or only partly fills
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnwindPolicy {
+    Hold,            // Leave it where it is and log the position
+    MarketOut,       // Sell it into the home asset at market straight away
+    Retry(Duration), // Retry the rest of the cycle at current prices for this long, then market out
+}
+
+impl UnwindPolicy {
+    // "hold", "market" or "retry:<ms>"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hold" => Some(UnwindPolicy::Hold),
+            "market" => Some(UnwindPolicy::MarketOut),
+            _ => {
+                let ms: u64 = value.strip_prefix("retry:")?.parse().ok()?;
+                Some(UnwindPolicy::Retry(Duration::from_millis(ms)))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct UnwindConfig {
+    pub policy: UnwindPolicy,
+    pub home: Option<String>,     // Asset to unwind into; the cycle's start asset when None
+    pub retry_interval: Duration, // Pause between attempts at the rest of the cycle
+}
+
+impl Default for UnwindConfig {
+    fn default() -> Self {
+        UnwindConfig {
+            policy: UnwindPolicy::MarketOut,
+            home: None,
+            retry_interval: Duration::from_millis(500),
+        }
+    }
+}
+
+impl UnwindConfig {
+    pub fn home<'a>(&'a self, path: &'a [String]) -> &'a str {
+        self.home.as_deref().or(path.first().map(String::as_str)).unwrap_or_default()
+    }
+}
+
+// Net change in each asset from the orders a cycle has executed so far
+#[derive(Debug, Clone, Default)]
+pub struct Position {
+    deltas: HashMap<String, Decimal>,
+}
+
+impl Position {
+    // Books what an order executed; the received side counts net of `fee_bps`, which the
+    // exchange takes out of the proceeds
+    pub fn record(&mut self, order: &OrderRequest, update: &OrderUpdate, filters: &ExchangeFilters, fee_bps: Decimal) {
+        let Some(symbol) = filters.get(&order.symbol) else {
+            return;
+        };
+        let keep = Decimal::ONE - fee_bps / Decimal::from(10_000);
+        let (base, quote) = match order.side {
+            Side::Buy => (update.executed_qty * keep, -update.quote_qty),
+            Side::Sell => (-update.executed_qty, update.quote_qty * keep),
+        };
+        *self.deltas.entry(symbol.base.clone()).or_default() += base;
+        *self.deltas.entry(symbol.quote.clone()).or_default() += quote;
+    }
+
+    pub fn get(&self, asset: &str) -> Decimal {
+        self.deltas.get(asset).copied().unwrap_or_default()
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.deltas.values().all(|d| d.is_zero())
+    }
+
+    // Assets the cycle picked up and didn't pass on, apart from those in `keep`
+    pub fn stranded(&self, keep: &[&str]) -> Vec<(String, Decimal)> {
+        let mut stranded: Vec<(String, Decimal)> = self
+            .deltas
+            .iter()
+            .filter(|(asset, delta)| **delta > Decimal::ZERO && !keep.contains(&asset.as_str()))
+            .map(|(asset, delta)| (asset.clone(), *delta))
+            .collect();
+        stranded.sort_by(|a, b| a.0.cmp(&b.0));
+        stranded
+    }
+}
+
+// Limit orders walking the rest of `path` from `amount` of `asset`, which has to be one of
+// its intermediate assets
+pub fn resume_orders(
+    path: &[String],
+    asset: &str,
+    amount: Decimal,
+    graph: &Graph,
+    filters: &ExchangeFilters,
+    fee_bps: Decimal,
+) -> Option<Result<Vec<OrderRequest>, FilterViolation>> {
+    let last = path.len().checked_sub(1)?;
+    let leg = (1..last).find(|&i| path[i] == asset)?;
+    Some(filters.cycle_orders(&path[leg..], amount, graph, fee_bps))
+}
+
+// A market order converting `amount` of `asset` into `home` over their direct pair
+pub fn market_out(asset: &str, amount: Decimal, home: &str, graph: &Graph, filters: &ExchangeFilters) -> Result<OrderRequest, FilterViolation> {
+    let Some((symbol, side)) = filters.leg(asset, home) else {
+        return Err(FilterViolation {
+            symbol: String::new(),
+            kind: ViolationKind::UnknownPair { from: asset.to_string(), to: home.to_string() },
+        });
+    };
+    // Quote per base unit, as an estimate for the notional check
+    let rate = match side {
+        Side::Sell => graph.rate(asset, home),
+        Side::Buy => graph.rate(asset, home).filter(|&r| r > 0.0).map(|r| 1.0 / r),
+    };
+    let Some(price) = rate.and_then(|r| Decimal::try_from(r).ok()).filter(|p| *p > Decimal::ZERO) else {
+        return Err(FilterViolation { symbol: symbol.symbol.clone(), kind: ViolationKind::NoPrice });
+    };
+    let quantity = match side {
+        Side::Sell => symbol.round_quantity(amount, true),
+        Side::Buy => symbol.round_quantity(amount / price * MARKET_BUY_HEADROOM, true),
+    };
+    symbol.check(quantity, price, true)?;
+    Ok(OrderRequest {
+        symbol: symbol.symbol.clone(),
+        side,
+        quantity,
+        price: None,
+    })
+}
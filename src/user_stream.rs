@@ -0,0 +1,359 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures_util::stream::StreamExt;
+use reqwest::Method;
+use rust_decimal::Decimal;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use crate::engine::Shutdown;
+use crate::graph::extract_currency_pair;
+use crate::ledger::Fill;
+use crate::order::Side;
+use crate::order_tracker::{OrderStatus, OrderUpdate};
+use crate::proxy::{connect_websocket, Proxy};
+use crate::rest::{ApiCredentials, EndpointCategory, RestClient, RestError};
+use crate::supervisor::Supervisor;
+
+pub const BINANCE_USER_WS_URL: &str = "wss://stream.binance.com:9443/ws";
+
+const LISTEN_KEY_PATH: &str = "/api/v3/userDataStream";
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60); // Keys expire after 60 minutes without one
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const EVENT_CAPACITY: usize = 1024;
+
+#[derive(serde::Serialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Balance {
+    pub free: Decimal,
+    pub locked: Decimal,
+}
+
+// The account as the exchange last reported it
+#[derive(Debug, Default)]
+pub struct AccountState {
+    pub balances: HashMap<String, Balance>,
+    pub open_orders: HashMap<String, OrderUpdate>, // By client order id; dropped once terminal
+    pub updated_at: Option<u64>,                    // Exchange time (ms) of the latest event applied
+    pub connected: bool,                            // Whether the stream is currently up
+}
+
+impl AccountState {
+    pub fn free(&self, asset: &str) -> Decimal {
+        self.balances.get(asset).map_or(Decimal::ZERO, |b| b.free)
+    }
+}
+
+// What the stream reports besides balances
+#[derive(Debug, Clone)]
+pub enum AccountEvent {
+    Order(OrderUpdate), // Any change of an order's state, for the order tracker
+    Fill(Fill),         // One execution against an order
+}
+
+// Response of POST /api/v3/userDataStream
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListenKey {
+    listen_key: String,
+}
+
+// Response of GET /api/v3/account
+#[derive(serde::Deserialize)]
+struct AccountSnapshot {
+    balances: Vec<SnapshotBalance>,
+}
+
+#[derive(serde::Deserialize)]
+struct SnapshotBalance {
+    asset: String,
+    free: Decimal,
+    locked: Decimal,
+}
+
+#[derive(serde::Deserialize)]
+struct PositionBalance {
+    a: String,
+    f: Decimal,
+    l: Decimal,
+}
+
+#[derive(serde::Deserialize)]
+struct ExecutionReport {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "c")]
+    client_order_id: String,
+    #[serde(rename = "C", default)]
+    original_client_order_id: String, // Set on cancels, where `c` is the cancel request's id
+    #[serde(rename = "S")]
+    side: Side,
+    #[serde(rename = "x")]
+    execution_type: String,
+    #[serde(rename = "X")]
+    status: OrderStatus,
+    #[serde(rename = "l")]
+    last_qty: Decimal,
+    #[serde(rename = "L")]
+    last_price: Decimal,
+    #[serde(rename = "n", default)]
+    commission: Decimal,
+    #[serde(rename = "N", default)]
+    commission_asset: Option<String>,
+    #[serde(rename = "z")]
+    executed_qty: Decimal,
+    #[serde(rename = "Z")]
+    quote_qty: Decimal,
+    #[serde(rename = "T")]
+    transaction_time: i64,
+}
+
+// Events on the user data stream that affect tracked state
+#[derive(serde::Deserialize)]
+#[serde(tag = "e")]
+enum UserEvent {
+    #[serde(rename = "outboundAccountPosition")]
+    AccountPosition {
+        #[serde(rename = "E")]
+        time: u64,
+        #[serde(rename = "B")]
+        balances: Vec<PositionBalance>,
+    },
+    #[serde(rename = "balanceUpdate")]
+    BalanceUpdate {
+        #[serde(rename = "E")]
+        time: u64,
+        a: String,
+        d: Decimal,
+    },
+    #[serde(rename = "executionReport")]
+    Execution {
+        #[serde(rename = "E")]
+        time: u64,
+        #[serde(flatten)]
+        report: ExecutionReport,
+    },
+    #[serde(rename = "listenKeyExpired")]
+    ListenKeyExpired,
+    #[serde(other)]
+    Other,
+}
+
+// Keeps an in-memory copy of the account from Binance's user data stream: a REST
+// snapshot on every (re)connect, then balance and order events as they happen. Manages
+// the listen key (create, keepalive every 30 minutes, replace on expiry) and reconnects
+// on its own.
+pub struct UserStream {
+    account: Arc<RwLock<AccountState>>,
+    events: broadcast::Sender<AccountEvent>,
+    stop: Shutdown,
+    task: JoinHandle<()>,
+}
+
+impl UserStream {
+    // Must be called inside a Tokio runtime. `proxy` applies to the WebSocket; REST calls
+    // go through whatever `rest` was built with. A panic restarts the session, which
+    // reconnects and takes a fresh snapshot.
+    pub fn spawn(rest: Arc<RestClient>, credentials: ApiCredentials, ws_url: &str, proxy: Option<Proxy>, supervisor: &Supervisor) -> Self {
+        let account = Arc::new(RwLock::new(AccountState::default()));
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let stop = Shutdown::default();
+        let session = Arc::new(Session {
+            rest,
+            credentials,
+            ws_url: ws_url.trim_end_matches('/').to_string(),
+            proxy,
+            account: account.clone(),
+            events: events.clone(),
+            stop: stop.clone(),
+        });
+        let task = supervisor.spawn("user stream", move || {
+            let session = session.clone();
+            async move { session.run().await }
+        });
+        UserStream { account, events, stop, task }
+    }
+
+    // Closes the WebSocket, releases the listen key and waits for the session to end
+    pub async fn close(self) {
+        self.stop.trigger("closed");
+        let _ = self.task.await;
+    }
+
+    pub fn account(&self) -> Arc<RwLock<AccountState>> {
+        self.account.clone()
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<AccountEvent> {
+        self.events.subscribe()
+    }
+}
+
+struct Session {
+    rest: Arc<RestClient>,
+    credentials: ApiCredentials,
+    ws_url: String,
+    proxy: Option<Proxy>,
+    account: Arc<RwLock<AccountState>>,
+    events: broadcast::Sender<AccountEvent>,
+    stop: Shutdown,
+}
+
+impl Session {
+    async fn run(&self) {
+        loop {
+            match self.connect_once().await {
+                Ok(()) if self.stop.is_triggered() => tracing::info!("User data stream closed"),
+                Ok(()) => tracing::warn!("User data stream closed, reconnecting"),
+                Err(e) => tracing::error!(error = %e, "User data stream failed, reconnecting"),
+            }
+            self.account.write().unwrap().connected = false;
+            // Closed, or nobody is listening for the account any more
+            if self.stop.is_triggered() || (self.events.receiver_count() == 0 && Arc::strong_count(&self.account) == 1) {
+                return;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+                _ = self.stop.wait() => return,
+            }
+        }
+    }
+
+    // One listen key's lifetime: snapshot, stream until it ends, then release the key
+    async fn connect_once(&self) -> Result<(), String> {
+        let body = self
+            .rest
+            .keyed(Method::POST, EndpointCategory::Account, 2, LISTEN_KEY_PATH, &[], &self.credentials)
+            .await
+            .map_err(|e| format!("creating listen key: {}", e))?;
+        let listen_key = serde_json::from_str::<ListenKey>(&body)
+            .map_err(|e| format!("creating listen key: unexpected response: {}", e))?
+            .listen_key;
+        let (mut ws, _) = connect_websocket(&format!("{}/{}", self.ws_url, listen_key), self.proxy.as_ref())
+            .await
+            .map_err(|e| format!("connecting: {}", e))?;
+
+        // Snapshot after subscribing, so nothing that happens in between is missed
+        self.load_snapshot().await.map_err(|e| format!("loading account: {}", e))?;
+        self.account.write().unwrap().connected = true;
+        tracing::info!("User data stream connected");
+
+        let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+        keepalive.tick().await;
+        let result = loop {
+            tokio::select! {
+                message = ws.next() => match message {
+                    Some(Ok(Message::Text(text))) => {
+                        if !self.handle(&text) {
+                            tracing::info!("Listen key expired");
+                            break Ok(());
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => break Err(format!("reading: {}", e)),
+                    None => break Ok(()),
+                },
+                _ = self.stop.wait() => {
+                    if let Err(e) = ws.close(None).await {
+                        tracing::debug!(error = %e, "Error closing the user data stream");
+                    }
+                    break Ok(());
+                }
+                _ = keepalive.tick() => {
+                    let query = [("listenKey", listen_key.clone())];
+                    if let Err(e) = self.rest.keyed(Method::PUT, EndpointCategory::Account, 2, LISTEN_KEY_PATH, &query, &self.credentials).await {
+                        break Err(format!("keeping listen key alive: {}", e));
+                    }
+                }
+            }
+        };
+        let query = [("listenKey", listen_key)];
+        let _ = self.rest.keyed(Method::DELETE, EndpointCategory::Account, 2, LISTEN_KEY_PATH, &query, &self.credentials).await;
+        result
+    }
+
+    async fn load_snapshot(&self) -> Result<(), RestError> {
+        let query = [("omitZeroBalances", "true".to_string())];
+        let body = self
+            .rest
+            .signed_get(EndpointCategory::Account, 20, "/api/v3/account", &query, &self.credentials)
+            .await?;
+        let snapshot: AccountSnapshot = serde_json::from_str(&body).map_err(|e| RestError::Decode(e.to_string()))?;
+        let mut account = self.account.write().unwrap();
+        account.balances = snapshot
+            .balances
+            .into_iter()
+            .map(|b| (b.asset, Balance { free: b.free, locked: b.locked }))
+            .collect();
+        Ok(())
+    }
+
+    // Applies one stream message; false when the listen key has expired
+    fn handle(&self, text: &str) -> bool {
+        let event = match serde_json::from_str::<UserEvent>(text) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!(error = %e, "Unparsable user data event");
+                return true;
+            }
+        };
+        let mut account = self.account.write().unwrap();
+        match event {
+            UserEvent::AccountPosition { time, balances } => {
+                for b in balances {
+                    account.balances.insert(b.a, Balance { free: b.f, locked: b.l });
+                }
+                account.updated_at = Some(time);
+            }
+            UserEvent::BalanceUpdate { time, a, d } => {
+                account.balances.entry(a).or_default().free += d;
+                account.updated_at = Some(time);
+            }
+            UserEvent::Execution { time, report } => {
+                let client_order_id = match report.original_client_order_id.as_str() {
+                    "" => report.client_order_id.clone(),
+                    original => original.to_string(),
+                };
+                let update = OrderUpdate {
+                    client_order_id: client_order_id.clone(),
+                    status: report.status,
+                    executed_qty: report.executed_qty,
+                    quote_qty: report.quote_qty,
+                };
+                if report.status.is_terminal() {
+                    account.open_orders.remove(&client_order_id);
+                } else {
+                    account.open_orders.insert(client_order_id.clone(), update.clone());
+                }
+                account.updated_at = Some(time);
+                drop(account);
+
+                if report.execution_type == "TRADE" {
+                    let (base, quote) = extract_currency_pair(&report.symbol);
+                    let fill = Fill {
+                        time: DateTime::<Utc>::from_timestamp_millis(report.transaction_time).unwrap_or_else(Utc::now),
+                        venue: "binance".to_string(),
+                        symbol: report.symbol,
+                        base,
+                        quote,
+                        side: report.side,
+                        quantity: report.last_qty,
+                        price: report.last_price,
+                        fee: report.commission,
+                        fee_asset: report.commission_asset.unwrap_or_default(),
+                        order_id: Some(client_order_id),
+                    };
+                    let _ = self.events.send(AccountEvent::Fill(fill));
+                }
+                // No subscribers is fine
+                let _ = self.events.send(AccountEvent::Order(update));
+            }
+            UserEvent::ListenKeyExpired => return false,
+            UserEvent::Other => {}
+        }
+        true
+    }
+}
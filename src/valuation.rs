@@ -0,0 +1,71 @@
+use crate::events::{Opportunity, ReferenceProfit};
+use crate::graph::Graph;
+
+#[derive(Debug, Clone)]
+pub struct ValuationConfig {
+    pub reference: String, // Asset profits are expressed in
+    pub notional: f64,     // Size of the pass the profit is for, in `reference`
+}
+
+impl ValuationConfig {
+    pub fn new(reference: &str) -> Self {
+        ValuationConfig {
+            reference: reference.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ValuationConfig {
+    fn default() -> Self {
+        ValuationConfig {
+            reference: "USDT".to_string(),
+            notional: 1000.0,
+        }
+    }
+}
+
+// Expresses each opportunity's expected profit as an amount of one reference asset, so
+// cycles starting in BTC, ETH or USDT can be compared directly. The pass is sized at the
+// notional: converted into the starting asset at the rate of buying it, run around the
+// cycle at the expected profit (or the simulated one, when the opportunity has a trace of
+// its own size), and the surplus converted back at the rate of selling it.
+pub struct ProfitValuation {
+    config: ValuationConfig,
+}
+
+impl ProfitValuation {
+    pub fn new(config: ValuationConfig) -> Self {
+        ProfitValuation { config }
+    }
+
+    // None when the profit is unknown or the starting asset can't be priced
+    pub fn value(&self, opportunity: &Opportunity, graph: &Graph) -> Option<ReferenceProfit> {
+        let start = opportunity.path.first()?;
+        let reference = &self.config.reference;
+        let (surplus, notional) = match &opportunity.trace {
+            Some(trace) => {
+                let surplus = trace.final_amount - trace.start_amount;
+                (surplus, trace.start_amount * convert(graph, start, reference)?)
+            }
+            None => {
+                let start_amount = self.config.notional * convert(graph, reference, start)?;
+                (start_amount * opportunity.profit?, self.config.notional)
+            }
+        };
+        let amount = surplus * convert(graph, start, reference)?;
+        amount.is_finite().then(|| ReferenceProfit {
+            asset: reference.clone(),
+            notional,
+            amount,
+        })
+    }
+}
+
+// Amount of `to` one unit of `from` converts into at current rates
+fn convert(graph: &Graph, from: &str, to: &str) -> Option<f64> {
+    if from == to {
+        return Some(1.0);
+    }
+    graph.rate(from, to).filter(|rate| *rate > 0.0)
+}
@@ -0,0 +1,37 @@
+use std::time::{Duration, Instant};
+
+// How long a stream may stay quiet before its connection is presumed dead. Binance's
+// full-market ticker stream sends every second and the server pings every few minutes at
+// most, so only a half-open socket stays quiet this long.
+pub const DEFAULT_MAX_SILENCE: Duration = Duration::from_secs(30);
+
+// Notices a connection that has stopped delivering. A half-open TCP connection never
+// errors on read; without this the feed would wait on it forever while prices go stale.
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    max_silence: Duration,
+    last_message: Instant, // Or when the connection was opened, until something arrives
+}
+
+impl Watchdog {
+    pub fn new(max_silence: Duration) -> Self {
+        Watchdog { max_silence, last_message: Instant::now() }
+    }
+
+    // Something arrived, or a fresh connection was opened
+    pub fn reset(&mut self) {
+        self.last_message = Instant::now();
+    }
+
+    pub fn silent_for(&self, now: Instant) -> Duration {
+        now.saturating_duration_since(self.last_message)
+    }
+}
+
+// Resolves once the connection has been quiet too long; never without a watchdog
+pub async fn expired(watchdog: Option<&Watchdog>) {
+    match watchdog {
+        Some(watchdog) => tokio::time::sleep_until((watchdog.last_message + watchdog.max_silence).into()).await,
+        None => std::future::pending().await,
+    }
+}
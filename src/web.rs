@@ -0,0 +1,161 @@
+// Web dashboard (`--web <addr>`): a single page at `/` that opens a WebSocket on `/ws`
+// and renders what the bot pushes over it.
+//
+// Every message is one JSON object with a `type`: engine events use the same records as
+// `--output jsonl` (so opportunities carry their `executed` flag), `stats` reports the
+// graph and feed once a second, and a new connection first receives `hello` with the
+// latest stats and the recent events, so the page isn't empty until the next detection.
+
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::{Html, IntoResponse};
+use axum::routing::get;
+use axum::Router;
+use hft3::pipeline::PipelineMetrics;
+use hft3::shared_graph::SharedGraph;
+use hft3::sinks::jsonl::event_json;
+use hft3::EngineEvent;
+use serde_json::{json, Value};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+const PAGE: &str = include_str!("web.html");
+const STATS_INTERVAL: Duration = Duration::from_secs(1);
+const RECENT: usize = 200; // Events replayed to a newly connected browser
+const CLIENT_BUFFER: usize = 1024;
+
+struct Shared {
+    recent: Mutex<VecDeque<Arc<str>>>,
+    stats: Mutex<Value>,
+    updates: broadcast::Sender<Arc<str>>, // Serialized messages for every connected browser
+}
+
+fn stats(graph: &SharedGraph, pipeline: Option<&PipelineMetrics>, started: Instant, feed_closed: bool) -> Value {
+    let snapshot = graph.load();
+    json!({
+        "type": "stats",
+        "uptime_s": started.elapsed().as_secs(),
+        "feed_closed": feed_closed,
+        "assets": snapshot.vertex_count(),
+        "edges": snapshot.edge_count(),
+        "passes": graph.epoch(),
+        "ingested": pipeline.map(|m| m.snapshot().events),
+    })
+}
+
+// Turns engine events and periodic stats into messages, keeping the recent ones for new
+// connections. Runs until the engine's event channel closes.
+async fn publish(
+    shared: Arc<Shared>,
+    mut events: broadcast::Receiver<EngineEvent>,
+    graph: Arc<SharedGraph>,
+    pipeline: Option<Arc<PipelineMetrics>>,
+) {
+    let started = Instant::now();
+    let mut interval = tokio::time::interval(STATS_INTERVAL);
+    let mut feed_closed = false;
+    loop {
+        let message = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => {
+                    feed_closed |= matches!(event, EngineEvent::FeedClosed);
+                    let message: Arc<str> = event_json(&event).to_string().into();
+                    let mut recent = shared.recent.lock().unwrap();
+                    if recent.len() == RECENT {
+                        recent.pop_front();
+                    }
+                    recent.push_back(message.clone());
+                    message
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Web dashboard fell behind");
+                    continue;
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = interval.tick() => {
+                let stats = stats(&graph, pipeline.as_deref(), started, feed_closed);
+                let message = stats.to_string().into();
+                *shared.stats.lock().unwrap() = stats;
+                message
+            }
+        };
+        // No receivers just means nobody has the page open
+        let _ = shared.updates.send(message);
+    }
+    let final_stats = stats(&graph, pipeline.as_deref(), started, true);
+    let _ = shared.updates.send(final_stats.to_string().into());
+    *shared.stats.lock().unwrap() = final_stats;
+}
+
+async fn page() -> Html<&'static str> {
+    Html(PAGE)
+}
+
+async fn upgrade(ws: WebSocketUpgrade, State(shared): State<Arc<Shared>>) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| stream(socket, shared))
+}
+
+async fn stream(mut socket: WebSocket, shared: Arc<Shared>) {
+    // Subscribe before taking the backlog so nothing falls between the two
+    let mut updates = shared.updates.subscribe();
+    let hello = {
+        let recent: Vec<Value> = shared
+            .recent
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|message| serde_json::from_str(message).ok())
+            .collect();
+        json!({ "type": "hello", "stats": *shared.stats.lock().unwrap(), "recent": recent })
+    };
+    if socket.send(Message::Text(hello.to_string().into())).await.is_err() {
+        return;
+    }
+    loop {
+        tokio::select! {
+            update = updates.recv() => match update {
+                Ok(message) => {
+                    if socket.send(Message::Text(message.as_ref().into())).await.is_err() {
+                        return;
+                    }
+                }
+                // A slow browser skips ahead rather than holding messages for everyone
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            },
+            incoming = socket.recv() => match incoming {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+// Binds `addr` and serves the dashboard on a background task
+pub async fn spawn(
+    addr: SocketAddr,
+    events: broadcast::Receiver<EngineEvent>,
+    graph: Arc<SharedGraph>,
+    pipeline: Option<Arc<PipelineMetrics>>,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    let shared = Arc::new(Shared {
+        recent: Mutex::new(VecDeque::with_capacity(RECENT)),
+        stats: Mutex::new(json!({ "type": "stats" })),
+        updates: broadcast::channel(CLIENT_BUFFER).0,
+    });
+    tokio::spawn(publish(shared.clone(), events, graph, pipeline));
+
+    let app = Router::new().route("/", get(page)).route("/ws", get(upgrade)).with_state(shared);
+    tokio::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!(error = %e, "Web dashboard stopped");
+        }
+    });
+    Ok(())
+}
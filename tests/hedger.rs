@@ -0,0 +1,39 @@
+use std::sync::{Arc, Mutex};
+
+use hft3::hedger::{HedgeConfig, HedgeStrategy, Hedger};
+use hft3::inventory::Inventory;
+use hft3::order::Side;
+use hft3::{Graph, MarketEvent, Strategy};
+use rust_decimal::Decimal;
+
+fn graph() -> Graph {
+    let mut graph = Graph::new();
+    graph.set_edge("BTC", "USDT", 60_000.0);
+    graph.set_edge("USDT", "BTC", 1.0 / 60_000.0);
+    graph
+}
+
+#[test]
+fn exposure_outside_the_band_is_offset() {
+    let inventory = Arc::new(Mutex::new(Inventory::new()));
+    let mut strategy = HedgeStrategy::new(Hedger::new(HedgeConfig::new("BTCUSDT")), inventory.clone());
+    let event = MarketEvent::FeedStalled { venue: "binance".to_string(), silent_ms: 0 };
+
+    // 60 USDT of BTC is within the default 100 band
+    inventory.lock().unwrap().set_balance("BTC", Decimal::new(1, 3));
+    strategy.on_event(&graph(), &event);
+    assert!(strategy.take_signals().is_empty());
+
+    // 600 USDT isn't
+    inventory.lock().unwrap().set_balance("BTC", Decimal::new(1, 2));
+    strategy.on_event(&graph(), &event);
+    let signals = strategy.take_signals();
+    assert_eq!(signals.len(), 1);
+    let order = &signals[0].orders[0];
+    assert_eq!((order.symbol.as_str(), order.side, order.quantity), ("BTCUSDT", Side::Sell, Decimal::new(1, 2)));
+    assert_eq!(signals[0].value, 600.0, "signal carries the exposure in USDT");
+
+    // Nothing more until the interval has passed and the fill has landed
+    strategy.on_event(&graph(), &event);
+    assert!(strategy.take_signals().is_empty());
+}
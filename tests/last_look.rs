@@ -0,0 +1,34 @@
+use hft3::last_look::{LastLook, LastLookConfig, LastLookGuard};
+use hft3::{Graph, Opportunity};
+
+fn opportunity() -> Opportunity {
+    let path = ["USDT", "BTC", "ETH", "USDT"].map(String::from).to_vec();
+    let mut opportunity = Opportunity::new(path).with_profit(0.002);
+    opportunity.rates = vec![1.0 / 60_000.0, 20.0, 3_006.0];
+    opportunity
+}
+
+#[test]
+fn final_leg_within_tolerance_proceeds() {
+    let guard = LastLookGuard::new(LastLookConfig { tolerance_bps: 2.0 });
+    let mut graph = Graph::new();
+    // 1 bps against the cycle
+    graph.set_edge("ETH", "USDT", 3_006.0 * 0.9999);
+    assert!(matches!(guard.check_graph(&opportunity(), &graph), LastLook::Proceed));
+}
+
+#[test]
+fn adverse_or_missing_final_rate_aborts() {
+    let guard = LastLookGuard::new(LastLookConfig { tolerance_bps: 2.0 });
+    let shared = guard.clone();
+    let mut graph = Graph::new();
+    graph.set_edge("ETH", "USDT", 3_006.0 * 0.999);
+    let LastLook::Abort(abort) = guard.check_graph(&opportunity(), &graph) else {
+        panic!("10 bps against the cycle went through");
+    };
+    assert_eq!((abort.from.as_str(), abort.to.as_str()), ("ETH", "USDT"));
+    assert!((abort.move_bps.unwrap() - 10.0).abs() < 1e-6);
+    assert!(matches!(shared.check_graph(&opportunity(), &Graph::new()), LastLook::Abort(_)));
+    // Clones count into the same metrics
+    assert_eq!(guard.metrics().snapshot(), (0, 2));
+}
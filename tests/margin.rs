@@ -0,0 +1,84 @@
+use hft3::filters::ExchangeFilters;
+use hft3::margin::{plan_cycle, MarginConfig, MarginError};
+use hft3::ticker::{apply_ticker_data, TickerData};
+use hft3::{Graph, NegativeCycleStrategy, Opportunity, Strategy};
+use rust_decimal::Decimal;
+use serde_json::json;
+
+fn book_ticker(symbol: &str, bid: f64, ask: f64) -> TickerData {
+    serde_json::from_value(json!({ "s": symbol, "c": bid.to_string(), "b": bid.to_string(), "a": ask.to_string() })).unwrap()
+}
+
+fn filters() -> ExchangeFilters {
+    let symbol = |symbol: &str, base: &str, quote: &str| {
+        json!({
+            "symbol": symbol,
+            "status": "TRADING",
+            "baseAsset": base,
+            "quoteAsset": quote,
+            "filters": [
+                { "filterType": "PRICE_FILTER", "minPrice": "0.00000001", "maxPrice": "1000000", "tickSize": "0.00000001" },
+                { "filterType": "LOT_SIZE", "minQty": "0.00001", "maxQty": "100000", "stepSize": "0.00001" },
+            ],
+        })
+    };
+    let info = json!({ "symbols": [symbol("BTCUSDT", "BTC", "USDT"), symbol("ETHUSDT", "ETH", "USDT"), symbol("ETHBTC", "ETH", "BTC")] });
+    ExchangeFilters::parse(&info.to_string()).unwrap()
+}
+
+// ETH is cheap in BTC, so USDT -> ETH -> BTC -> USDT returns about 1.4%
+fn detected() -> (Graph, Opportunity) {
+    let mut graph = Graph::new();
+    apply_ticker_data(
+        &mut graph,
+        &[book_ticker("BTCUSDT", 60_000.0, 60_010.0), book_ticker("ETHUSDT", 3_000.0, 3_000.5), book_ticker("ETHBTC", 0.0507, 0.05071)],
+    );
+    let found = NegativeCycleStrategy::new().on_update(&graph);
+    assert!(!found.is_empty(), "no cycle reported");
+    (graph, found.into_iter().next().unwrap())
+}
+
+// 1000 USDT worth of the cycle's first asset
+fn amount(graph: &Graph, opportunity: &Opportunity) -> Decimal {
+    let rate = graph.rate("USDT", &opportunity.path[0]).unwrap_or(1.0);
+    Decimal::try_from(1_000.0 * rate).unwrap().round_dp(5)
+}
+
+#[test]
+fn detected_cycle_is_planned_at_its_profit() {
+    let (graph, opportunity) = detected();
+    let profit = opportunity.profit.expect("cycle reported without its profit");
+    let amount = amount(&graph, &opportunity);
+
+    let plan = plan_cycle(&opportunity, amount, amount, None, &graph, &filters(), &MarginConfig::default()).unwrap();
+    assert!(plan.borrow.is_none());
+    assert_eq!(plan.orders.len(), 3);
+    assert_eq!(plan.net_profit, Decimal::try_from(profit).unwrap());
+    assert!(plan.net_profit > Decimal::new(1, 2), "net profit {}", plan.net_profit);
+}
+
+#[test]
+fn cycle_without_profit_is_planned_from_its_rates() {
+    let (graph, mut opportunity) = detected();
+    let gross = opportunity.profit.take().unwrap();
+    opportunity.rates = opportunity.path.windows(2).map(|leg| graph.rate(&leg[0], &leg[1]).unwrap()).collect();
+    let amount = amount(&graph, &opportunity);
+
+    // Borrowing all of it for under an hour costs one hour of interest
+    let hourly = Decimal::new(1, 4);
+    let plan = plan_cycle(&opportunity, amount, Decimal::ZERO, Some(hourly), &graph, &filters(), &MarginConfig::default()).unwrap();
+    assert_eq!(plan.borrow, Some((opportunity.path[0].clone(), amount)));
+    let expected = Decimal::try_from(gross).unwrap() - hourly;
+    assert!((plan.net_profit - expected).abs() < Decimal::new(1, 12), "net profit {} against {}", plan.net_profit, expected);
+}
+
+#[test]
+fn cycle_with_a_non_finite_profit_is_refused() {
+    let (graph, opportunity) = detected();
+    let amount = amount(&graph, &opportunity);
+    for profit in [f64::NAN, f64::INFINITY] {
+        let opportunity = opportunity.clone().with_profit(profit);
+        let result = plan_cycle(&opportunity, amount, amount, None, &graph, &filters(), &MarginConfig::default());
+        assert!(matches!(result, Err(MarginError::BadProfit(_))), "{:?}", result.map(|plan| plan.net_profit));
+    }
+}
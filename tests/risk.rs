@@ -0,0 +1,86 @@
+use std::time::Instant;
+
+use chrono::Utc;
+use hft3::ledger::Fill;
+use hft3::order::{OrderRequest, Side};
+use hft3::risk::{RiskConfig, RiskManager, RiskRejection};
+use hft3::{Graph, Opportunity};
+use rust_decimal::Decimal;
+
+fn buy_btc(quantity: Decimal) -> Fill {
+    Fill {
+        time: Utc::now(),
+        venue: "binance".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        base: "BTC".to_string(),
+        quote: "USDT".to_string(),
+        side: Side::Buy,
+        quantity,
+        price: Decimal::from(60_000),
+        fee: Decimal::ZERO,
+        fee_asset: String::new(),
+        order_id: None,
+    }
+}
+
+#[test]
+fn fills_count_towards_the_position_limit() {
+    let mut config = RiskConfig::new("USDT", Decimal::from(100));
+    config.max_asset_exposure = Some(Decimal::from(1_000));
+    let risk = RiskManager::new(config);
+    let mut graph = Graph::new();
+    graph.set_edge("BTC", "USDT", 60_000.0);
+    graph.set_edge("ETH", "USDT", 3_000.0);
+    let cycle = Opportunity::new(["USDT", "BTC", "ETH", "USDT"].map(String::from).to_vec());
+
+    assert_eq!(risk.check(&cycle, &graph, Instant::now()), Ok(()));
+    // 0.015 BTC is 900 USDT, plus the cycle's 100 is at the limit
+    risk.clone().record_fill(&buy_btc(Decimal::new(15, 3)));
+    assert_eq!(risk.check(&cycle, &graph, Instant::now()), Ok(()));
+    risk.record_fill(&buy_btc(Decimal::new(1, 3)));
+    assert!(matches!(risk.check(&cycle, &graph, Instant::now()), Err(RiskRejection::AssetExposure { asset, .. }) if asset == "BTC"));
+}
+
+fn market(side: Side, quantity: Decimal) -> OrderRequest {
+    OrderRequest { symbol: "BTCUSDT".to_string(), side, quantity, price: None }
+}
+
+#[test]
+fn signal_orders_are_held_to_the_limits() {
+    let mut config = RiskConfig::new("USDT", Decimal::from(100));
+    config.max_trade_notional = Some(Decimal::from(1_200));
+    config.max_asset_exposure = Some(Decimal::from(1_000));
+    config.max_trades_per_minute = Some(2);
+    let risk = RiskManager::new(config);
+    let mut graph = Graph::new();
+    graph.set_edge("BTC", "USDT", 60_000.0);
+    risk.record_fill(&buy_btc(Decimal::new(15, 3)));
+    let now = Instant::now();
+
+    // 0.03 BTC is 1800 USDT, over the notional cap
+    let rejected = risk.check_orders(&[market(Side::Sell, Decimal::new(3, 2))], &graph, now);
+    assert!(matches!(rejected, Err(RiskRejection::TradeNotional { .. })), "{:?}", rejected);
+    // Buying 210 USDT more takes BTC past 1000; selling as much brings it down
+    let rejected = risk.check_orders(&[market(Side::Buy, Decimal::new(35, 4))], &graph, now);
+    assert!(matches!(rejected, Err(RiskRejection::AssetExposure { asset, .. }) if asset == "BTC"));
+    assert_eq!(risk.check_orders(&[market(Side::Sell, Decimal::new(35, 4))], &graph, now), Ok(()));
+    // Approved orders count towards the trade rate like cycles do
+    let cycle = Opportunity::new(["USDT", "BTC", "USDT"].map(String::from).to_vec());
+    assert_eq!(risk.check(&cycle, &graph, now), Ok(()));
+    let rejected = risk.check_orders(&[market(Side::Sell, Decimal::new(1, 3))], &graph, now);
+    assert!(matches!(rejected, Err(RiskRejection::TradeRate { trades: 2, max: 2 })), "{:?}", rejected);
+}
+
+#[test]
+fn daily_loss_trips_the_kill_switch() {
+    let mut config = RiskConfig::new("USDT", Decimal::from(100));
+    config.daily_loss_limit = Some(50.0);
+    let risk = RiskManager::new(config);
+    let now = Utc::now();
+    risk.record_pnl(-30.0, now);
+    risk.record_pnl(5.0, now);
+    assert!(!risk.is_tripped());
+    risk.record_pnl(-25.0, now);
+    assert!(risk.is_tripped());
+    assert_eq!(risk.daily_pnl(), -50.0);
+}
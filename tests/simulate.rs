@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use hft3::sim::SimFeed;
+use hft3::{Engine, EngineEvent, NegativeCycleStrategy, Opportunity};
+
+// A triangle at par, then ETHBTC jumps, holds and jumps again
+const SCRIPT: &str = r#"# tickers as the exchange sends them
+[{"s":"BTCUSDT","c":"30000","b":"29999","a":"30001"},{"s":"ETHUSDT","c":"2000","b":"1999.9","a":"2000.1"},{"s":"ETHBTC","c":"0.066666","b":"0.066665","a":"0.066667"}]
+{"s":"ETHBTC","c":"0.0680","b":"0.0680","a":"0.06801"}
+{"s":"ETHBTC","c":"0.0680","b":"0.0680","a":"0.06801"}
+{"s":"ETHBTC","c":"0.0681","b":"0.0681","a":"0.06811"}
+"#;
+
+fn script(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("hft3-{}-{}.jsonl", name, std::process::id()));
+    std::fs::write(&path, SCRIPT).unwrap();
+    path
+}
+
+async fn opportunities(feed: SimFeed) -> Vec<Opportunity> {
+    let mut engine = Engine::new(feed).with_strategy(NegativeCycleStrategy::new());
+    let mut events = engine.subscribe();
+    engine.run().await;
+    let mut found = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if let EngineEvent::Opportunity(opportunity) = event {
+            found.push(opportunity);
+        }
+    }
+    found
+}
+
+#[tokio::test]
+async fn scripted_jumps_are_detected_at_simulated_times() {
+    let path = script("engine");
+    let found = opportunities(SimFeed::load(&path).unwrap().with_clock(1_000, 10)).await;
+    std::fs::remove_file(path).ok();
+
+    // The par triangle at step 0 has no edge; every step after it does
+    let times: Vec<_> = found.iter().map(|o| o.event_time).collect();
+    assert_eq!(times, [Some(1_010), Some(1_020), Some(1_030)]);
+    for opportunity in &found {
+        assert_eq!(opportunity.cycle_key(), "BTC>USDT>ETH");
+        // USDT buys ETH at the ask, which sells for BTC and then USDT at the bids
+        let gross: f64 = opportunity.rates.iter().product::<f64>() - 1.0;
+        assert!(gross > 0.019 && gross < 0.022, "gross return {}", gross);
+        let profit = opportunity.profit.expect("cycle reported without its profit");
+        assert!((profit - gross).abs() < 1e-12, "profit {} against gross return {}", profit, gross);
+    }
+    assert!(found[2].rates.iter().product::<f64>() > found[1].rates.iter().product::<f64>());
+}
+
+#[test]
+fn simulate_reports_every_detection_without_dedup_or_ttl() {
+    let path = script("cli");
+    let output = Command::new(env!("CARGO_BIN_EXE_hft3"))
+        .args(["--simulate", path.to_str().unwrap(), "--output", "jsonl", "--log-level", "error"])
+        .output()
+        .unwrap();
+    std::fs::remove_file(path).ok();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let lines: Vec<serde_json::Value> = String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let opportunities: Vec<_> = lines.iter().filter(|line| line["type"] == "opportunity").collect();
+    assert_eq!(opportunities.len(), 3);
+    for opportunity in opportunities {
+        assert_eq!(opportunity["cycle"], "BTC>USDT>ETH");
+        assert!(opportunity["expires_at"].is_null());
+        assert!(opportunity["profit_bps"].as_f64().is_some_and(|bps| bps > 190.0));
+    }
+    assert_eq!(lines.last().unwrap()["type"], "feed_closed");
+}
@@ -0,0 +1,54 @@
+use hft3::ticker::{apply_ticker_data, TickerData};
+use hft3::{Graph, NegativeCycleStrategy, Strategy};
+use serde_json::json;
+
+fn ticker(symbol: &str, last: f64) -> TickerData {
+    serde_json::from_value(json!({ "s": symbol, "c": last.to_string() })).unwrap()
+}
+
+fn book_ticker(symbol: &str, bid: f64, ask: f64) -> TickerData {
+    serde_json::from_value(json!({ "s": symbol, "c": bid.to_string(), "b": bid.to_string(), "a": ask.to_string() })).unwrap()
+}
+
+fn cycles(graph: &Graph) -> Vec<Vec<String>> {
+    NegativeCycleStrategy::new().on_update(graph).into_iter().map(|opportunity| opportunity.path).collect()
+}
+
+#[test]
+fn last_prices_price_both_directions() {
+    let mut graph = Graph::new();
+    apply_ticker_data(&mut graph, &[ticker("ETHBTC", 0.05)]);
+    assert_eq!(graph.rate("ETH", "BTC"), Some(0.05));
+    assert_eq!(graph.rate("BTC", "ETH"), Some(20.0));
+}
+
+#[test]
+fn book_tickers_price_the_reverse_at_the_ask() {
+    let mut graph = Graph::new();
+    apply_ticker_data(&mut graph, &[book_ticker("BTCUSDT", 60_000.0, 60_010.0)]);
+    assert_eq!(graph.rate("BTC", "USDT"), Some(60_000.0));
+    assert_eq!(graph.rate("USDT", "BTC"), Some(1.0 / 60_010.0));
+}
+
+#[test]
+fn three_asset_cycle_from_last_prices_is_reported() {
+    let mut graph = Graph::new();
+    // ETH is cheap in BTC: USDT -> ETH -> BTC -> USDT returns 1.5%
+    apply_ticker_data(&mut graph, &[ticker("BTCUSDT", 60_000.0), ticker("ETHUSDT", 3_000.0), ticker("ETHBTC", 0.0507)]);
+    let found = cycles(&graph);
+    assert!(!found.is_empty(), "no cycle reported");
+    let mut assets = found[0].clone();
+    assets.sort();
+    assets.dedup();
+    assert_eq!(assets, ["BTC", "ETH", "USDT"]);
+}
+
+#[test]
+fn consistent_prices_report_nothing() {
+    let mut graph = Graph::new();
+    apply_ticker_data(
+        &mut graph,
+        &[book_ticker("BTCUSDT", 59_990.0, 60_010.0), book_ticker("ETHUSDT", 2_999.0, 3_001.0), book_ticker("ETHBTC", 0.04998, 0.05002)],
+    );
+    assert!(cycles(&graph).is_empty());
+}
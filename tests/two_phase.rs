@@ -0,0 +1,37 @@
+use hft3::ticker::TickerData;
+use hft3::{Graph, MarketEvent, Strategy, TwoPhaseConfig, TwoPhaseStrategy};
+use serde_json::json;
+
+fn tickers(prices: &[(&str, &str)]) -> MarketEvent {
+    let tickers: Vec<TickerData> = prices
+        .iter()
+        .map(|(symbol, last)| serde_json::from_value(json!({ "s": symbol, "c": last })).unwrap())
+        .collect();
+    MarketEvent::Tickers(tickers)
+}
+
+fn strategy() -> TwoPhaseStrategy {
+    TwoPhaseStrategy::new(TwoPhaseConfig { coarse_bps: 10.0, fee_bps: 10.0, ..Default::default() })
+}
+
+#[test]
+fn unprofitable_triangle_is_dropped_before_the_exact_check() {
+    let mut strategy = strategy();
+    // At par: no gross edge, so 30 bps of fees short of break-even
+    let found = strategy.on_event(&Graph::new(), &tickers(&[("BTCUSDT", "60000"), ("ETHUSDT", "3000"), ("ETHBTC", "0.05")]));
+    assert!(found.is_empty());
+    let (coarse, exact) = strategy.stats();
+    assert!(coarse > 0, "triangle never reached phase one");
+    assert_eq!(exact, 0, "triangle reached the exact check");
+}
+
+#[test]
+fn profitable_triangle_is_reported() {
+    let mut strategy = strategy();
+    // ETH is 1.4% cheap in BTC
+    let found = strategy.on_event(&Graph::new(), &tickers(&[("BTCUSDT", "60000"), ("ETHUSDT", "3000"), ("ETHBTC", "0.0507")]));
+    assert_eq!(strategy.stats().1, 1);
+    assert_eq!(found.len(), 1);
+    let profit_bps = found[0].profit.unwrap() * 10_000.0;
+    assert!(profit_bps > 100.0 && profit_bps < 140.0, "profit {} bps", profit_bps);
+}